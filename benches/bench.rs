@@ -199,6 +199,48 @@ mod msm {
     }
 }
 
+#[divan::bench_group(sample_count = 5, sample_size = 1)]
+mod batch_msm {
+    use ark_bls12_381::{Fr, G1Affine, G1Projective};
+    use ark_ec::VariableBaseMSM;
+    use ark_ff::UniformRand;
+    use divan::Bencher;
+    use poly_multiproof::msm::batch_msm;
+    use rand::thread_rng;
+
+    /// Numbers of scalars and rows on either side of where a shared table starts to pay off
+    const CASES: [(usize, usize); 4] = [(256, 8), (256, 64), (4096, 16), (4096, 64)];
+
+    fn inputs(n: usize, rows: usize) -> (Vec<G1Affine>, Vec<Vec<Fr>>) {
+        let bases = (0..n)
+            .map(|_| G1Affine::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..rows)
+            .map(|_| (0..n).map(|_| Fr::rand(&mut thread_rng())).collect())
+            .collect();
+        (bases, scalars)
+    }
+
+    #[divan::bench(args = CASES)]
+    fn batch(bencher: Bencher, (n, rows): (usize, usize)) {
+        bencher
+            .with_inputs(|| inputs(n, rows))
+            .bench_refs(|(bases, scalars)| batch_msm::<G1Projective>(bases, scalars).unwrap());
+    }
+
+    #[divan::bench(args = CASES)]
+    fn msm_per_row(bencher: Bencher, (n, rows): (usize, usize)) {
+        bencher
+            .with_inputs(|| inputs(n, rows))
+            .bench_refs(|(bases, scalars)| {
+                scalars
+                    .iter()
+                    .map(|row| G1Projective::msm(bases, row).unwrap())
+                    .collect::<Vec<_>>()
+            });
+    }
+}
+
 fn main() {
     divan::main()
 }
@@ -23,8 +23,7 @@ use std::{
 
 type M1 = M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
 type M1Pc = method1::precompute::M1Precomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
-type M2 = method2::M2NoPrecomp<Bls12_381>;
-type M2Pc = method2::precompute::M2Precomp<Bls12_381>;
+type M2Pc = method2::precompute::M2Precomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
 type M1Blst = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
 type M1BlstPc = M1Precomp<Bls12_381, BlstMSMEngine>;
 type M1BlstCyclPc = M1CyclPrecomp<Bls12_381, BlstMSMEngine>;
@@ -45,12 +44,12 @@ impl<F: PrimeField> TestGrid<F> {
     pub(crate) fn gen_grid(width: usize, height: usize) -> Self {
         let degree = width - 1;
         let ev = Radix2EvaluationDomain::<F>::new(width).unwrap();
-        let points = ev.elements().into_iter().collect::<Vec<_>>();
+        let points = ev.elements().collect::<Vec<_>>();
         assert_eq!(points.len(), width);
         let coeffs = (0..height)
             .map(|_| DensePolynomial::<F>::rand(degree, &mut thread_rng()).coeffs)
             .collect::<Vec<_>>();
-        let evals: Vec<Vec<_>> = coeffs.iter().map(|p| ev.fft(&p)).collect();
+        let evals: Vec<Vec<_>> = coeffs.iter().map(|p| ev.fft(p)).collect();
         Self {
             points,
             coeffs,
@@ -68,10 +67,6 @@ lazy_static::lazy_static! {
         M1::new(WIDTH, WIDTH, &mut thread_rng())
     };
 
-    static ref M2_PMP: M2 = {
-        M2::new_from_affine(M1_PMP.powers_of_g1.clone(), M1BLST_PMP.powers_of_g2[0].into(), M1BLST_PMP.powers_of_g2[1].into())
-    };
-
     static ref M1BLST_PMP: M1Blst = {
         M1Blst::new_from_affine(M1_PMP.powers_of_g1.clone(), M1_PMP.powers_of_g2.clone())
     };
@@ -133,7 +128,7 @@ fn open_with_pmp<P: PolyMultiProof<Bls12_381>>(
 ) -> P::Proof {
     pmp.open(
         &mut Transcript::new(b"bench"),
-        &eval_selector(&pmp, grid, width, height),
+        &eval_selector(pmp, grid, width, height),
         &grid.coeffs[..height],
         0,
     )
@@ -155,8 +150,8 @@ fn verify_with_pmp<P: PolyMultiProof<Bls12_381>>(
             &mut transcript,
             &commits[..height],
             0,
-            &eval_selector(&pmp, grid, width, height),
-            &open
+            &eval_selector(pmp, grid, width, height),
+            open
         ),
         Ok(true)
     );
@@ -181,7 +176,7 @@ where
     fn open(&self) {
         open_with_pmp(
             self.backend.as_ref(),
-            &self.grid,
+            self.grid,
             self.width,
             self.height,
             self.eval_selector,
@@ -190,8 +185,8 @@ where
 
     fn verify(&self) {
         verify_with_pmp::<P>(
-            &self.backend.as_ref(),
-            &self.grid,
+            self.backend.as_ref(),
+            self.grid,
             &COMMITS,
             &self.opening,
             self.width,
@@ -314,13 +309,13 @@ lazy_static::lazy_static! {
 mod pmp_benches {
 
     use super::*;
-    #[divan::bench(args = INPUT_ARGS.deref())]
-    fn open_bench(arg: &Box<dyn Arg>) {
+    #[divan::bench(args = INPUT_ARGS.iter().map(Box::as_ref))]
+    fn open_bench(arg: &dyn Arg) {
         arg.open()
     }
 
-    #[divan::bench(args = INPUT_ARGS.deref())]
-    fn verify_bench(arg: &Box<dyn Arg>) {
+    #[divan::bench(args = INPUT_ARGS.iter().map(Box::as_ref))]
+    fn verify_bench(arg: &dyn Arg) {
         arg.verify()
     }
 }
@@ -0,0 +1,318 @@
+//! Commit, open and verify for each method 1 scheme over a grid of polynomial sizes, numbers of
+//! polynomials and numbers of points. Run with `--features print-sizes` to also print the sizes of
+//! the setups, commitments and proofs.
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalSerialize, Compress};
+use divan::Bencher;
+use merlin::Transcript;
+use poly_multiproof::{
+    m1_cycl::M1CyclPrecomp,
+    method1::{precompute::M1Precomp, M1NoPrecomp, Proof},
+    msm::{blst::BlstMSMEngine, ArkMSMEngine},
+    poly_ops::ev_points,
+    traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+    Commitment,
+};
+use rand::thread_rng;
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+};
+
+type M1Ark = M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
+type M1Blst = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+type M1BlstPc = M1Precomp<Bls12_381, BlstMSMEngine>;
+type M1BlstCyclPc = M1CyclPrecomp<Bls12_381, BlstMSMEngine>;
+
+const N_COEFFS: [usize; 3] = [256, 1024, 4096];
+const N_POLYS: [usize; 2] = [1, 16];
+const N_POINTS: [usize; 2] = [4, 16];
+// The cyclic point sets are subgroups of this size, it can be at most the smallest polynomial
+const CYCL_BASE_SIZE: usize = 256;
+
+struct Schemes {
+    m1_ark: M1Ark,
+    m1_blst: M1Blst,
+    // One point set for each number of points
+    m1_pc: M1BlstPc,
+    // One setup for each number of points, where point set 0 is used
+    m1_cycl: Vec<M1BlstCyclPc>,
+    // The points opened at for each number of points
+    points: Vec<Vec<Fr>>,
+}
+
+impl Schemes {
+    fn new() -> Self {
+        let max_coeffs = N_COEFFS[N_COEFFS.len() - 1];
+        let max_points = N_POINTS[N_POINTS.len() - 1];
+        let m1_blst = M1Blst::new(max_coeffs, max_points, &mut thread_rng());
+        let m1_ark =
+            M1Ark::new_from_affine(m1_blst.powers_of_g1.clone(), m1_blst.powers_of_g2.clone());
+        let m1_cycl = N_POINTS
+            .iter()
+            .map(|n| M1BlstCyclPc::from_inner(m1_blst.clone(), CYCL_BASE_SIZE, CYCL_BASE_SIZE / n))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        // The cyclic schemes can only open at their subgroups, so every scheme uses those points
+        let points = m1_cycl
+            .iter()
+            .map(|c| ev_points(&c.point_sets().subgroups()[0]))
+            .collect::<Vec<_>>();
+        let m1_pc = M1BlstPc::from_inner(m1_blst.clone(), points.clone()).unwrap();
+        Self {
+            m1_ark,
+            m1_blst,
+            m1_pc,
+            m1_cycl,
+            points,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SCHEMES: Schemes = Schemes::new();
+    static ref CASES: Vec<Case> = cases();
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Scheme {
+    Ark,
+    Blst,
+    BlstPc,
+    BlstCyclPc,
+}
+
+impl Display for Scheme {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Scheme::Ark => "m1",
+            Scheme::Blst => "m1blst",
+            Scheme::BlstPc => "m1blst_pc",
+            Scheme::BlstCyclPc => "m1blst_cycl",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Case {
+    scheme: Scheme,
+    n_coeffs: usize,
+    n_polys: usize,
+    // Index into N_POINTS
+    points_idx: usize,
+}
+
+impl Display for Case {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "method: {}, coeffs: {}, polys: {}, points: {}",
+            self.scheme, self.n_coeffs, self.n_polys, N_POINTS[self.points_idx]
+        )
+    }
+}
+
+fn cases() -> Vec<Case> {
+    let schemes = [
+        Scheme::Ark,
+        Scheme::Blst,
+        Scheme::BlstPc,
+        Scheme::BlstCyclPc,
+    ];
+    let mut cases = Vec::new();
+    for scheme in schemes {
+        for n_coeffs in N_COEFFS {
+            for n_polys in N_POLYS {
+                for points_idx in 0..N_POINTS.len() {
+                    cases.push(Case {
+                        scheme,
+                        n_coeffs,
+                        n_polys,
+                        points_idx,
+                    });
+                }
+            }
+        }
+    }
+    cases
+}
+
+struct Inputs {
+    polys: Vec<Vec<Fr>>,
+    evals: Vec<Vec<Fr>>,
+    commits: Vec<Commitment<Bls12_381>>,
+}
+
+impl Case {
+    fn points(&self) -> &'static [Fr] {
+        &SCHEMES.points[self.points_idx]
+    }
+
+    fn inputs(&self) -> Inputs {
+        let polys = (0..self.n_polys)
+            .map(|_| DensePolynomial::<Fr>::rand(self.n_coeffs - 1, &mut thread_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| {
+                let p = DensePolynomial::from_coefficients_slice(p);
+                self.points().iter().map(|x| p.evaluate(x)).collect()
+            })
+            .collect();
+        let commits = self.commit(&polys);
+        Inputs {
+            polys,
+            evals,
+            commits,
+        }
+    }
+
+    fn commit(&self, polys: &[Vec<Fr>]) -> Vec<Commitment<Bls12_381>> {
+        let commit = |p: &Vec<Fr>| match self.scheme {
+            Scheme::Ark => SCHEMES.m1_ark.commit(p),
+            Scheme::Blst => SCHEMES.m1_blst.commit(p),
+            Scheme::BlstPc => SCHEMES.m1_pc.commit(p),
+            Scheme::BlstCyclPc => SCHEMES.m1_cycl[self.points_idx].commit(p),
+        };
+        polys.iter().map(|p| commit(p).unwrap()).collect()
+    }
+
+    fn open(&self, inputs: &Inputs) -> Proof<Bls12_381> {
+        let transcript = &mut Transcript::new(b"bench");
+        let points = self.points();
+        match self.scheme {
+            Scheme::Ark => SCHEMES
+                .m1_ark
+                .open(transcript, &inputs.evals, &inputs.polys, points),
+            Scheme::Blst => SCHEMES
+                .m1_blst
+                .open(transcript, &inputs.evals, &inputs.polys, points),
+            Scheme::BlstPc => PolyMultiProof::open(
+                &SCHEMES.m1_pc,
+                transcript,
+                &inputs.evals,
+                &inputs.polys,
+                self.points_idx,
+            ),
+            Scheme::BlstCyclPc => PolyMultiProof::open(
+                &SCHEMES.m1_cycl[self.points_idx],
+                transcript,
+                &inputs.evals,
+                &inputs.polys,
+                0,
+            ),
+        }
+        .unwrap()
+    }
+
+    fn verify(&self, inputs: &Inputs, proof: &Proof<Bls12_381>) -> bool {
+        let transcript = &mut Transcript::new(b"bench");
+        let points = self.points();
+        match self.scheme {
+            Scheme::Ark => {
+                SCHEMES
+                    .m1_ark
+                    .verify(transcript, &inputs.commits, points, &inputs.evals, proof)
+            }
+            Scheme::Blst => {
+                SCHEMES
+                    .m1_blst
+                    .verify(transcript, &inputs.commits, points, &inputs.evals, proof)
+            }
+            Scheme::BlstPc => PolyMultiProof::verify(
+                &SCHEMES.m1_pc,
+                transcript,
+                &inputs.commits,
+                self.points_idx,
+                &inputs.evals,
+                proof,
+            ),
+            Scheme::BlstCyclPc => PolyMultiProof::verify(
+                &SCHEMES.m1_cycl[self.points_idx],
+                transcript,
+                &inputs.commits,
+                0,
+                &inputs.evals,
+                proof,
+            ),
+        }
+        .unwrap()
+    }
+}
+
+#[divan::bench_group(sample_size = 3, sample_count = 3)]
+mod scheme_benches {
+    use super::*;
+
+    #[divan::bench(args = CASES.deref())]
+    fn commit_bench(bencher: Bencher, case: &Case) {
+        bencher
+            .with_inputs(|| case.inputs().polys)
+            .bench_refs(|polys| case.commit(polys));
+    }
+
+    #[divan::bench(args = CASES.deref())]
+    fn open_bench(bencher: Bencher, case: &Case) {
+        bencher
+            .with_inputs(|| case.inputs())
+            .bench_refs(|inputs| case.open(inputs));
+    }
+
+    #[divan::bench(args = CASES.deref())]
+    fn verify_bench(bencher: Bencher, case: &Case) {
+        bencher
+            .with_inputs(|| {
+                let inputs = case.inputs();
+                let proof = case.open(&inputs);
+                (inputs, proof)
+            })
+            .bench_refs(|(inputs, proof)| assert!(case.verify(inputs, proof)));
+    }
+}
+
+/// Prints the compressed sizes of the setups, a commitment and a proof
+fn print_sizes() {
+    let schemes = SCHEMES.deref();
+    println!(
+        "m1 setup ({} coeffs, {} points): {} bytes",
+        N_COEFFS[N_COEFFS.len() - 1],
+        N_POINTS[N_POINTS.len() - 1],
+        schemes.m1_blst.serialized_size(Compress::Yes)
+    );
+    println!(
+        "m1 precompute setup: {} bytes",
+        schemes.m1_pc.serialized_size(Compress::Yes)
+    );
+    for (n, cycl) in N_POINTS.iter().zip(&schemes.m1_cycl) {
+        println!(
+            "m1 cyclic setup ({} points per set): {} bytes",
+            n,
+            cycl.serialized_size(Compress::Yes)
+        );
+    }
+    let case = Case {
+        scheme: Scheme::Blst,
+        n_coeffs: N_COEFFS[0],
+        n_polys: N_POLYS[N_POLYS.len() - 1],
+        points_idx: 0,
+    };
+    let inputs = case.inputs();
+    println!(
+        "commitment: {} bytes",
+        inputs.commits[0].serialized_size(Compress::Yes)
+    );
+    // Proofs are a single group element regardless of the number of polynomials and points
+    println!(
+        "m1 proof: {} bytes",
+        case.open(&inputs).serialized_size(Compress::Yes)
+    );
+}
+
+fn main() {
+    if cfg!(feature = "print-sizes") {
+        print_sizes();
+    }
+    divan::main()
+}
@@ -0,0 +1,104 @@
+#ifndef POLY_MULTIPROOF_H
+#define POLY_MULTIPROOF_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The size of a serialized scalar
+#define PMP_SCALAR_BYTES 32
+
+// The size of a serialized G1 point
+#define PMP_G1_BYTES 48
+
+// The result of a C API call
+typedef enum PmpStatus {
+  // The call succeeded
+  PMP_STATUS_OK = 0,
+  // A pointer was null, or a buffer didn't hold valid scalars or points
+  PMP_STATUS_INVALID_INPUT = 1,
+  // The inputs were well formed but the operation failed, e.g. a polynomial was too large
+  PMP_STATUS_FAILED = 2,
+} PmpStatus;
+
+// A method 1 setup over BLS12-381
+typedef struct PmpM1 PmpM1;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The [`crate::Error::code`] of the error behind the last [`PmpStatus::Failed`] returned on this
+// thread, or 0 if there hasn't been one
+uint32_t pmp_last_error_code(void);
+
+// Load a setup from its compressed serialization, checking that all its points are valid.
+// Returns null if the setup can't be loaded. The setup must be freed with [`pmp_m1_free`].
+//
+// # Safety
+// `data` must point to `len` readable bytes.
+struct PmpM1 *pmp_m1_load(const uint8_t *data, size_t len);
+
+// Free a setup loaded with [`pmp_m1_load`]
+//
+// # Safety
+// `pmp` must be null or a pointer returned by [`pmp_m1_load`] that hasn't been freed yet.
+void pmp_m1_free(struct PmpM1 *pmp);
+
+// Commit to a polynomial of `n_coeffs` coefficients, writing the commitment to `commit_out`
+//
+// # Safety
+// `pmp` must be a live setup, `coeffs` must point to `n_coeffs * PMP_SCALAR_BYTES` readable bytes
+// and `commit_out` to `PMP_G1_BYTES` writable bytes.
+enum PmpStatus pmp_m1_commit(const struct PmpM1 *pmp,
+                             const uint8_t *coeffs,
+                             size_t n_coeffs,
+                             uint8_t *commit_out);
+
+// Open `n_polys` polynomials of `n_coeffs` coefficients each at `n_points` points, writing the
+// proof to `proof_out`. `evals` holds the evaluations of each polynomial at the points. The
+// transcript is seeded with `domain`, which must match the one given to [`pmp_m1_verify`].
+//
+// # Safety
+// `pmp` must be a live setup, `domain` must point to `domain_len` readable bytes, `evals`,
+// `polys` and `points` to `n_polys * n_points`, `n_polys * n_coeffs` and `n_points` readable
+// scalars, and `proof_out` to `PMP_G1_BYTES` writable bytes.
+enum PmpStatus pmp_m1_open(const struct PmpM1 *pmp,
+                           const uint8_t *domain,
+                           size_t domain_len,
+                           const uint8_t *evals,
+                           const uint8_t *polys,
+                           size_t n_polys,
+                           size_t n_coeffs,
+                           const uint8_t *points,
+                           size_t n_points,
+                           uint8_t *proof_out);
+
+// Verify a proof from [`pmp_m1_open`] against `n_polys` commitments, writing whether it is
+// valid to `valid_out`
+//
+// # Safety
+// `pmp` must be a live setup, `domain` must point to `domain_len` readable bytes, `commits` to
+// `n_polys * PMP_G1_BYTES` readable bytes, `evals` and `points` to `n_polys * n_points` and
+// `n_points` readable scalars, `proof` to `PMP_G1_BYTES` readable bytes and `valid_out` to a
+// writable bool.
+enum PmpStatus pmp_m1_verify(const struct PmpM1 *pmp,
+                             const uint8_t *domain,
+                             size_t domain_len,
+                             const uint8_t *commits,
+                             const uint8_t *evals,
+                             size_t n_polys,
+                             const uint8_t *points,
+                             size_t n_points,
+                             const uint8_t *proof,
+                             bool *valid_out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POLY_MULTIPROOF_H */
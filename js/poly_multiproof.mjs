@@ -0,0 +1,94 @@
+// Uint8Array bindings for the module built with the `wasm` feature, see src/wasm.rs for the
+// byte layouts. Build the module with
+//
+//   cargo rustc --profile wasm --no-default-features --features wasm \
+//       --target wasm32-unknown-unknown --crate-type cdylib
+
+/** The size of a serialized scalar */
+export const SCALAR_BYTES = 32;
+/** The size of a serialized G1 point */
+export const G1_BYTES = 48;
+
+/** An error from the module, with the stable `Error::code` of its cause */
+export class PmpError extends Error {
+  constructor(code) {
+    super(`poly-multiproof error ${code}`);
+    this.code = code;
+  }
+}
+
+/** A method 1 setup over BLS12-381 loaded into a module instance */
+export class Setup {
+  constructor(exports, ptr) {
+    this.exports = exports;
+    this.ptr = ptr;
+  }
+
+  /**
+   * Instantiate the module from its bytes (or a compiled `WebAssembly.Module`) and load a setup
+   * from its compressed serialization into it
+   */
+  static async load(wasm, setup) {
+    const source = wasm instanceof WebAssembly.Module ? wasm : await WebAssembly.compile(wasm);
+    const { exports } = await WebAssembly.instantiate(source, {});
+    const ptr = call(exports, [setup], 4, (out, [data]) =>
+      exports.pmp_setup_load(data.ptr, data.len, out),
+    );
+    return new Setup(exports, new DataView(ptr.buffer).getUint32(0, true));
+  }
+
+  /** Commit to the polynomial with the concatenated coefficients `coeffs` */
+  commit(coeffs) {
+    return call(this.exports, [coeffs], G1_BYTES, (out, [c]) =>
+      this.exports.pmp_commit(this.ptr, c.ptr, c.len, out),
+    );
+  }
+
+  /**
+   * Open `nPolys` polynomials of the same length, concatenated in `polys`, at `points`. `evals`
+   * holds the evaluations of each polynomial at the points, row by row.
+   */
+  open(domain, evals, polys, nPolys, points) {
+    return call(this.exports, [domain, evals, polys, points], G1_BYTES, (out, [d, e, p, z]) =>
+      this.exports.pmp_open(this.ptr, d.ptr, d.len, e.ptr, e.len, p.ptr, p.len, nPolys, z.ptr, z.len, out),
+    );
+  }
+
+  /** Verify a proof from `open` against the concatenated `commits` */
+  verify(domain, commits, evals, points, proof) {
+    const valid = call(this.exports, [domain, commits, evals, points, proof], 1, (out, [d, c, e, z, p]) =>
+      this.exports.pmp_verify(this.ptr, d.ptr, d.len, c.ptr, c.len, e.ptr, e.len, z.ptr, z.len, p.ptr, p.len, out),
+    );
+    return valid[0] === 1;
+  }
+
+  /** Free the setup, after which it can't be used */
+  free() {
+    this.exports.pmp_setup_free(this.ptr);
+    this.ptr = 0;
+  }
+}
+
+// Copy `inputs` into the module's memory, run `f` with an `outLen` byte output buffer, and
+// return a copy of the output. Throws a `PmpError` if `f` fails.
+function call(exports, inputs, outLen, f) {
+  const bufs = inputs.map((bytes) => {
+    const ptr = exports.pmp_alloc(bytes.length);
+    // Allocating can grow the memory, which detaches any earlier views of it
+    new Uint8Array(exports.memory.buffer, ptr, bytes.length).set(bytes);
+    return { ptr, len: bytes.length };
+  });
+  const out = exports.pmp_alloc(outLen);
+  try {
+    const code = f(out, bufs);
+    if (code !== 0) {
+      throw new PmpError(code);
+    }
+    return new Uint8Array(exports.memory.buffer, out, outLen).slice();
+  } finally {
+    for (const { ptr, len } of bufs) {
+      exports.pmp_free(ptr, len);
+    }
+    exports.pmp_free(out, outLen);
+  }
+}
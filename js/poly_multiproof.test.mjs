@@ -0,0 +1,59 @@
+// Checks the wrapper against a module built as in poly_multiproof.mjs, using the fixture checked
+// by `wasm::tests::test_js_fixture`. Run with
+//
+//   node --test js/
+//
+// or set PMP_WASM to the path of a module built elsewhere.
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+
+import { G1_BYTES, PmpError, SCALAR_BYTES, Setup } from "./poly_multiproof.mjs";
+
+const wasm = readFileSync(
+  process.env.PMP_WASM ??
+    new URL("../target/wasm32-unknown-unknown/wasm/poly_multiproof.wasm", import.meta.url),
+);
+const fixture = JSON.parse(readFileSync(new URL("./fixture.json", import.meta.url)));
+const hex = (s) => new Uint8Array(Buffer.from(s, "hex"));
+const setupBytes = hex(fixture.setup);
+const polys = hex(fixture.polys);
+const points = hex(fixture.points);
+const evals = hex(fixture.evals);
+const commits = hex(fixture.commits);
+const domain = new TextEncoder().encode(fixture.domain);
+
+test("commit", async () => {
+  const setup = await Setup.load(wasm, setupBytes);
+  const polyLen = polys.length / fixture.n_polys;
+  for (let i = 0; i < fixture.n_polys; i++) {
+    const commit = setup.commit(polys.subarray(i * polyLen, (i + 1) * polyLen));
+    assert.deepEqual(commit, commits.subarray(i * G1_BYTES, (i + 1) * G1_BYTES));
+  }
+  setup.free();
+});
+
+test("open and verify", async () => {
+  const setup = await Setup.load(wasm, setupBytes);
+  const proof = setup.open(domain, evals, polys, fixture.n_polys, points);
+  assert.deepEqual(proof, hex(fixture.proof));
+  assert.equal(setup.verify(domain, commits, evals, points, proof), true);
+  const other = new TextEncoder().encode("other");
+  assert.equal(setup.verify(other, commits, evals, points, proof), false);
+  const swapped = new Uint8Array(commits.length);
+  swapped.set(commits.subarray(G1_BYTES));
+  swapped.set(commits.subarray(0, G1_BYTES), commits.length - G1_BYTES);
+  assert.equal(setup.verify(domain, swapped, evals, points, proof), false);
+  setup.free();
+});
+
+test("errors", async () => {
+  const setup = await Setup.load(wasm, setupBytes);
+  // A buffer which isn't whole scalars is a serialization error, code 5
+  const serialization = (e) => e instanceof PmpError && e.code === 5;
+  assert.throws(() => setup.commit(new Uint8Array(SCALAR_BYTES + 1)), serialization);
+  // More coefficients than the setup has powers for
+  assert.throws(() => setup.commit(new Uint8Array(SCALAR_BYTES * 1000)), PmpError);
+  await assert.rejects(Setup.load(wasm, setupBytes.subarray(1)), PmpError);
+  setup.free();
+});
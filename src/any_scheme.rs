@@ -0,0 +1,365 @@
+//! A method 1 scheme picked at runtime. The [`PolyMultiProof`] traits take `impl` arguments, so
+//! they can't be used as trait objects. [`AnyScheme`] is an enum over the method 1 schemes which
+//! implements the traits itself, so an application can choose the scheme from its config with
+//! [`SchemeConfig`] and use the same code for all of them.
+use ark_ec::pairing::Pairing;
+use ark_poly::{univariate::SparsePolynomial, Radix2EvaluationDomain};
+use ark_std::{rand::RngCore, vec::Vec};
+
+use crate::{
+    m1_cycl::M1CyclPrecomp,
+    method1::{precompute::M1Precomp, M1NoPrecomp, Proof},
+    poly_ops::evaluate_at_points,
+    traits::{
+        Committer, MSMEngine, MemoryEstimate, PolyMultiProof, PolyMultiProofNoPrecomp, SchemeInfo,
+        TranscriptProtocol,
+    },
+    Commitment, Error,
+};
+
+/// Which method 1 scheme to build, and the point sets it opens at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeConfig<F> {
+    /// [`M1NoPrecomp`], opening at the given point sets without precomputation
+    NoPrecomp(Vec<Vec<F>>),
+    /// [`M1Precomp`] with the given point sets
+    Precomp(Vec<Vec<F>>),
+    /// [`M1CyclPrecomp`] over a domain of `base_size` split into `num_point_sets` cosets
+    CyclPrecomp {
+        /// The size of the domain
+        base_size: usize,
+        /// The number of point sets the domain is split into
+        num_point_sets: usize,
+    },
+}
+
+/// One of the method 1 schemes. Point sets are referred to by index for every scheme, so
+/// [`M1NoPrecomp`] is held along with its point sets.
+#[derive(Clone)]
+pub enum AnyScheme<E: Pairing, M: MSMEngine<E = E>> {
+    /// Method 1 without precomputation
+    NoPrecomp {
+        /// The scheme
+        pmp: M1NoPrecomp<E, M>,
+        /// The point sets opened at
+        point_sets: Vec<Vec<E::ScalarField>>,
+    },
+    /// Method 1 with precomputed point sets
+    Precomp(M1Precomp<E, M>),
+    /// Method 1 with cyclic point sets
+    CyclPrecomp(M1CyclPrecomp<E, M>),
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> AnyScheme<E, M> {
+    /// Build the scheme described by `config` from a method 1 setup
+    pub fn from_config(
+        pmp: M1NoPrecomp<E, M>,
+        config: SchemeConfig<E::ScalarField>,
+    ) -> Result<Self, Error> {
+        match config {
+            SchemeConfig::NoPrecomp(point_sets) => {
+                for ps in &point_sets {
+                    pmp.check_n_points(ps.len())?;
+                }
+                Ok(Self::NoPrecomp { pmp, point_sets })
+            }
+            SchemeConfig::Precomp(point_sets) => {
+                M1Precomp::from_inner(pmp, point_sets).map(Self::Precomp)
+            }
+            SchemeConfig::CyclPrecomp {
+                base_size,
+                num_point_sets,
+            } => M1CyclPrecomp::from_inner(pmp, base_size, num_point_sets).map(Self::CyclPrecomp),
+        }
+    }
+
+    /// The method 1 setup underneath the scheme
+    pub fn inner(&self) -> &M1NoPrecomp<E, M> {
+        match self {
+            Self::NoPrecomp { pmp, .. } => pmp,
+            Self::Precomp(pmp) => &pmp.inner,
+            Self::CyclPrecomp(pmp) => &pmp.inner,
+        }
+    }
+
+    fn no_precomp_points(
+        point_sets: &[Vec<E::ScalarField>],
+        index: usize,
+    ) -> Result<&[E::ScalarField], Error> {
+        point_sets
+            .get(index)
+            .map(Vec::as_slice)
+            .ok_or(Error::PointSetIndexOutOfRange {
+                idx: index,
+                n_point_sets: point_sets.len(),
+            })
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> From<M1Precomp<E, M>> for AnyScheme<E, M> {
+    fn from(pmp: M1Precomp<E, M>) -> Self {
+        Self::Precomp(pmp)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> From<M1CyclPrecomp<E, M>> for AnyScheme<E, M> {
+    fn from(pmp: M1CyclPrecomp<E, M>) -> Self {
+        Self::CyclPrecomp(pmp)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for AnyScheme<E, M> {
+    fn max_coeffs(&self) -> usize {
+        self.inner().max_coeffs()
+    }
+
+    fn max_pts(&self) -> usize {
+        self.inner().max_pts()
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        match self {
+            Self::NoPrecomp { pmp, .. } => pmp.scheme_name(),
+            Self::Precomp(pmp) => pmp.scheme_name(),
+            Self::CyclPrecomp(pmp) => pmp.scheme_name(),
+        }
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        self.inner().proof_size_bytes()
+    }
+
+    /// The scheme isn't known until it's built, so this is the largest of each part of the
+    /// estimates of the method 1 schemes
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate {
+        M1NoPrecomp::<E, M>::memory_estimate(max_coeffs, max_pts, num_point_sets)
+            .max(M1Precomp::<E, M>::memory_estimate(
+                max_coeffs,
+                max_pts,
+                num_point_sets,
+            ))
+            .max(M1CyclPrecomp::<E, M>::memory_estimate(
+                max_coeffs,
+                max_pts,
+                num_point_sets,
+            ))
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for AnyScheme<E, M> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        self.inner().commit(poly)
+    }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner().commit_evals(evals, domain)
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner().commit_sparse(poly)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for AnyScheme<E, M> {
+    type Proof = Proof<E>;
+
+    fn open(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        match self {
+            Self::NoPrecomp { pmp, point_sets } => {
+                let points = Self::no_precomp_points(point_sets, point_set_index)?;
+                PolyMultiProofNoPrecomp::open(pmp, transcript, evals, polys, points)
+            }
+            Self::Precomp(pmp) => pmp.open(transcript, evals, polys, point_set_index),
+            Self::CyclPrecomp(pmp) => pmp.open(transcript, evals, polys, point_set_index),
+        }
+    }
+
+    fn open_points(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        poly: impl AsRef<[E::ScalarField]>,
+        point_set_indices: &[usize],
+    ) -> Result<Vec<(Vec<E::ScalarField>, Self::Proof)>, Error> {
+        match self {
+            Self::NoPrecomp { pmp, point_sets } => {
+                let points = point_set_indices
+                    .iter()
+                    .map(|i| Self::no_precomp_points(point_sets, *i))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                PolyMultiProofNoPrecomp::open_points(pmp, transcripts, poly, &points)
+            }
+            Self::Precomp(pmp) => pmp.open_points(transcripts, poly, point_set_indices),
+            Self::CyclPrecomp(pmp) => pmp.open_points(transcripts, poly, point_set_indices),
+        }
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        match self {
+            Self::NoPrecomp { pmp, point_sets } => {
+                let points = Self::no_precomp_points(point_sets, point_set_index)?;
+                PolyMultiProofNoPrecomp::verify(pmp, transcript, commits, points, evals, proof)
+            }
+            Self::Precomp(pmp) => pmp.verify(transcript, commits, point_set_index, evals, proof),
+            Self::CyclPrecomp(pmp) => {
+                pmp.verify(transcript, commits, point_set_index, evals, proof)
+            }
+        }
+    }
+
+    fn compute_evals(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error> {
+        match self {
+            Self::NoPrecomp { point_sets, .. } => {
+                let points = Self::no_precomp_points(point_sets, point_set_index)?;
+                Ok(polys
+                    .iter()
+                    .map(|p| evaluate_at_points(p.as_ref(), points))
+                    .collect())
+            }
+            Self::Precomp(pmp) => pmp.compute_evals(polys, point_set_index),
+            Self::CyclPrecomp(pmp) => pmp.compute_evals(polys, point_set_index),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            usize,
+            &[impl AsRef<[E::ScalarField]>],
+            &Self::Proof,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        match self {
+            Self::NoPrecomp { pmp, point_sets } => {
+                let items = items
+                    .iter()
+                    .map(|(commits, point_set_index, evals, proof)| {
+                        let points = Self::no_precomp_points(point_sets, *point_set_index)?;
+                        Ok((*commits, points, *evals, *proof))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                PolyMultiProofNoPrecomp::verify_batch(pmp, transcripts, &items, rng)
+            }
+            Self::Precomp(pmp) => pmp.verify_batch(transcripts, items, rng),
+            Self::CyclPrecomp(pmp) => pmp.verify_batch(transcripts, items, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+    use ark_std::{vec, vec::Vec};
+    use merlin::Transcript;
+
+    use super::{AnyScheme, SchemeConfig};
+    use crate::{
+        method1::M1NoPrecomp, msm::blst::BlstMSMEngine, poly_ops::ev_points, test_rng,
+        testing::gen_openings, traits::PolyMultiProof, Error,
+    };
+
+    #[test]
+    fn test_any_scheme() {
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        // The cyclic scheme with 4 point sets of 8 opens at the cosets of the subgroup of order 8
+        let domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        let all_points = ev_points(&domain);
+        let point_sets = (0..4)
+            .map(|i| all_points.iter().skip(i).step_by(4).cloned().collect())
+            .collect::<Vec<Vec<Fr>>>();
+        let configs = vec![
+            SchemeConfig::NoPrecomp(point_sets.clone()),
+            SchemeConfig::Precomp(point_sets.clone()),
+            SchemeConfig::CyclPrecomp {
+                base_size: 32,
+                num_point_sets: 4,
+            },
+        ];
+        let schemes = configs
+            .into_iter()
+            .map(|c| AnyScheme::from_config(pmp.clone(), c).unwrap())
+            .collect::<Vec<_>>();
+
+        let (polys, _, commits) = gen_openings(&pmp, 3, &[]);
+        for scheme in &schemes {
+            let (evals, proof) = scheme
+                .open_with_poly_evals(&mut Transcript::new(b"any"), &polys, 1)
+                .unwrap();
+            let set = scheme.compute_evals(&polys, 1).unwrap();
+            assert_eq!(evals, set);
+            for other in &schemes {
+                assert_eq!(
+                    Ok(true),
+                    other.verify(&mut Transcript::new(b"any"), &commits, 1, &evals, &proof)
+                );
+            }
+            assert_eq!(
+                Ok(true),
+                scheme.verify_batch(
+                    &mut [Transcript::new(b"any")],
+                    &[(&commits, 1, &evals, &proof)],
+                    &mut test_rng()
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                scheme.verify(&mut Transcript::new(b"any"), &commits, 2, &evals, &proof)
+            );
+
+            let opened = scheme
+                .open_points(&mut [Transcript::new(b"any")], &polys[0], &[3])
+                .unwrap();
+            let (evals, proof) = &opened[0];
+            assert_eq!(
+                Ok(true),
+                scheme.verify(
+                    &mut Transcript::new(b"any"),
+                    &commits[..1],
+                    3,
+                    &[evals],
+                    proof
+                )
+            );
+        }
+
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 4,
+                n_point_sets: 4
+            }),
+            schemes[0].compute_evals(&polys, 4)
+        );
+        assert!(matches!(
+            AnyScheme::from_config(pmp, SchemeConfig::NoPrecomp(vec![vec![Fr::from(0u64); 9]])),
+            Err(Error::TooManyPoints { .. })
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+//! Reproducible method 1 openings for auditing proofs offline. [`open_traced`] opens as
+//! [`PolyMultiProofNoPrecomp::open`] does, and also returns an [`OpeningTrace`] of every message
+//! the opening absorbed into the transcript and the challenge `gamma` it drew.
+//!
+//! Given the trace, [`verify_trace`] re-derives `gamma` from the recorded messages against the
+//! commitments and checks the proof with it, so a proof can be checked without the transcript it
+//! was made with. With the polynomials, the proof itself can be reproduced exactly with
+//! [`M1NoPrecomp::open_with_challenge`] at the trace's points and `gamma`.
+//!
+//! Only the steps of the opening are recorded: anything absorbed into the transcript beforehand,
+//! such as a [`DomainSeparator`](crate::transcript::DomainSeparator), is only reflected in the
+//! challenge bytes.
+use core::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+
+use crate::{
+    method1::{opening_challenge, M1NoPrecomp, Proof},
+    traits::{MSMEngine, TranscriptProtocol},
+    transcript::{RecordingTranscript, ReplayTranscript, TranscriptTrace},
+    Commitment, Error,
+};
+
+#[cfg(doc)]
+use crate::traits::PolyMultiProofNoPrecomp;
+
+/// The inputs to the Fiat-Shamir transcript of an opening and the challenge drawn from them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpeningTrace<F: PrimeField> {
+    /// Every step the opening took on the transcript, in order
+    pub transcript: TranscriptTrace,
+    /// The challenge the polynomials were combined with
+    pub gamma: F,
+    /// The points opened at
+    pub points: Vec<F>,
+    /// The evaluations of each polynomial at the points
+    pub evals: Vec<Vec<F>>,
+}
+
+/// The transcript steps, followed by `gamma <value>`
+impl<F: PrimeField> fmt::Display for OpeningTrace<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.transcript)?;
+        writeln!(f, "gamma {}", self.gamma)
+    }
+}
+
+/// Open `polys` at `points` as [`PolyMultiProofNoPrecomp::open`] does, returning the proof along
+/// with a trace of the opening
+pub fn open_traced<E: Pairing, M: MSMEngine<E = E>>(
+    srs: &M1NoPrecomp<E, M>,
+    transcript: &mut impl TranscriptProtocol,
+    evals: &[impl AsRef<[E::ScalarField]>],
+    polys: &[impl AsRef<[E::ScalarField]>],
+    points: &[E::ScalarField],
+) -> Result<(Proof<E>, OpeningTrace<E::ScalarField>), Error> {
+    srs.check_n_points(points.len())?;
+    let mut recording = RecordingTranscript::new(transcript);
+    let gamma = opening_challenge(
+        &mut recording,
+        &srs.transcript_commits(polys)?,
+        evals,
+        polys,
+        points,
+        srs.transcript_encoding(),
+    )?;
+    let proof = srs.open_with_challenge(polys, points, gamma)?;
+    let (_, trace) = recording.into_parts();
+    Ok((
+        proof,
+        OpeningTrace {
+            transcript: trace,
+            gamma,
+            points: points.to_vec(),
+            evals: evals.iter().map(|e| e.as_ref().to_vec()).collect(),
+        },
+    ))
+}
+
+/// Check `proof` against `commits` with the challenge in `trace`. Fails with
+/// [`Error::TranscriptReplayMismatch`] if replaying the recorded challenges against the
+/// commitments and the trace's points and evals doesn't absorb exactly the recorded messages and
+/// draw the recorded `gamma`, i.e. if the trace isn't of an opening of `commits`.
+pub fn verify_trace<E: Pairing, M: MSMEngine<E = E>>(
+    srs: &M1NoPrecomp<E, M>,
+    commits: &[Commitment<E>],
+    trace: &OpeningTrace<E::ScalarField>,
+    proof: &Proof<E>,
+) -> Result<bool, Error> {
+    let mut replay = ReplayTranscript::new(trace.transcript.challenges());
+    let gamma = srs.verifier_gamma(&mut replay, commits, &trace.points, &trace.evals)?;
+    if replay.finish()? != trace.transcript || gamma != trace.gamma {
+        return Err(Error::TranscriptReplayMismatch);
+    }
+    srs.verify_with_challenge(commits, &trace.points, &trace.evals, proof, gamma)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::{open_traced, verify_trace};
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::ArkMSMEngine,
+        poly_ops::evaluate_at_points,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        transcript::{DomainSeparator, TranscriptEncoding},
+        Error,
+    };
+
+    #[test]
+    fn test_open_traced() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(32, 4, &mut test_rng());
+        let polys = (0..3)
+            .map(|_| (0..32).map(|_| Fr::rand(&mut test_rng())).collect())
+            .collect::<Vec<Vec<_>>>();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| evaluate_at_points(p, &points))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+
+        let sep = DomainSeparator::new("audit");
+        let (proof, trace) =
+            open_traced(&s, &mut sep.transcript(), &evals, &polys, &points).unwrap();
+        // Same proof as an untraced opening, and the verifier accepts it
+        assert_eq!(
+            proof.0,
+            s.open(&mut sep.transcript(), &evals, &polys, &points)
+                .unwrap()
+                .0
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut sep.transcript(), &commits, &points, &evals, &proof)
+        );
+        assert_eq!(Ok(true), verify_trace(&s, &commits, &trace, &proof));
+        assert_eq!(
+            proof.0,
+            s.open_with_challenge(&polys, &trace.points, trace.gamma)
+                .unwrap()
+                .0
+        );
+        assert!(trace
+            .to_string()
+            .ends_with(&format!("gamma {}\n", trace.gamma)));
+
+        // A different proof is rejected against the trace
+        let other = s
+            .open(&mut Transcript::new(b"other"), &evals, &polys, &points)
+            .unwrap();
+        assert_eq!(Ok(false), verify_trace(&s, &commits, &trace, &other));
+
+        // Tampering with the trace is caught
+        let mut bad = trace.clone();
+        bad.evals[0][0] += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::TranscriptReplayMismatch),
+            verify_trace(&s, &commits, &bad, &proof)
+        );
+        let mut bad = trace.clone();
+        bad.gamma += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::TranscriptReplayMismatch),
+            verify_trace(&s, &commits, &bad, &proof)
+        );
+
+        // With V1 the commitments are absorbed, so the trace only replays against them
+        let v1 = s.clone().with_transcript_encoding(TranscriptEncoding::v1());
+        let (proof, trace) =
+            open_traced(&v1, &mut Transcript::new(b"v1"), &evals, &polys, &points).unwrap();
+        assert_eq!(Ok(true), verify_trace(&v1, &commits, &trace, &proof));
+        let mut swapped = commits.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            Err(Error::TranscriptReplayMismatch),
+            verify_trace(&v1, &swapped, &trace, &proof)
+        );
+    }
+}
@@ -0,0 +1,678 @@
+//! EIP-4844 blob commitments and proofs over BLS12-381, following the semantics of
+//! `c-kzg-4844`. A blob holds the evaluations of a polynomial over the size 4096 subgroup in
+//! bit-reversed order, so element `i` is the evaluation at $\omega^{\mathrm{brp}(i)}$. Field
+//! elements are 32 bytes big endian and must be canonical. Commitments and proofs are 48 byte
+//! compressed points in the ZCash format, so the byte level functions such as
+//! [`verify_kzg_proof_bytes`] can be mixed with `c-kzg-4844` or `rust-kzg` on the other side.
+//!
+//! The setup isn't fixed, so these work with any setup which has at least
+//! [`FIELD_ELEMENTS_PER_BLOB`] powers in G1 and two in G2, such as one loaded from the Ethereum
+//! KZG ceremony with [`M1NoPrecomp::new_from_affine`](crate::method1::M1NoPrecomp::new_from_affine).
+//!
+//! With the `blst` feature, which provides SHA-256, [`compute_blob_kzg_proof`],
+//! [`verify_blob_kzg_proof`] and [`verify_blob_kzg_proof_batch`] open each blob at the point
+//! [`compute_challenge`] derives from it and its commitment, and batch the checks with a random
+//! challenge derived the same way, as `c-kzg-4844`'s functions of the same names do.
+//!
+//! The tests pin vectors for a setup with a known secret and check them against that secret
+//! directly. Vectors from `c-kzg-4844` need the mainnet setup, which isn't shipped with this crate,
+//! so only the checks which don't depend on the secret are compared with it.
+use ark_bls12_381::{Bls12_381, Fr};
+#[cfg(feature = "blst")]
+use ark_bls12_381::{G1Affine, G1Projective};
+#[cfg(feature = "blst")]
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+#[cfg(feature = "blst")]
+use ark_ff::{One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+use crate::{
+    method1::Proof,
+    poly_ops::{evaluate_at_points, from_brp},
+    traits::{AsBytes, Committer, KZGProof, MSMEngine},
+    Commitment, Error,
+};
+
+/// The number of field elements in a blob
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The number of bytes in a serialized field element
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The number of bytes in a serialized blob
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// The number of bytes in a serialized commitment
+pub const BYTES_PER_COMMITMENT: usize = 48;
+/// The number of bytes in a serialized proof
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// The evaluations of a polynomial over the blob domain in bit-reversed order
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Blob(Vec<Fr>);
+
+impl Blob {
+    /// Make a blob from its [`FIELD_ELEMENTS_PER_BLOB`] evaluations in bit-reversed order
+    pub fn from_evals(evals: Vec<Fr>) -> Result<Self, Error> {
+        if evals.len() != FIELD_ELEMENTS_PER_BLOB {
+            return Err(Error::InvalidInputLength {
+                expected: FIELD_ELEMENTS_PER_BLOB,
+                got: evals.len(),
+            });
+        }
+        Ok(Self(evals))
+    }
+
+    /// Parse a blob from [`BYTES_PER_BLOB`] bytes, failing if any element isn't canonical
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != BYTES_PER_BLOB {
+            return Err(Error::InvalidInputLength {
+                expected: BYTES_PER_BLOB,
+                got: bytes.len(),
+            });
+        }
+        bytes
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .map(|c| bytes_to_field(c.try_into().expect("Chunks are the right size")))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+
+    /// Serialize the blob to [`BYTES_PER_BLOB`] bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(field_to_bytes).collect()
+    }
+
+    /// The evaluations in bit-reversed order
+    pub fn evals(&self) -> &[Fr] {
+        &self.0
+    }
+
+    /// The coefficients of the polynomial the blob holds the evaluations of
+    pub fn to_polynomial(&self) -> Vec<Fr> {
+        let domain = Radix2EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_BLOB)
+            .expect("Blob domain is valid");
+        domain.ifft(&from_brp(&self.0).expect("Blob size is a power of two"))
+    }
+}
+
+/// Parse a 32 byte big endian field element, failing if it isn't canonical
+pub fn bytes_to_field(bytes: &[u8; BYTES_PER_FIELD_ELEMENT]) -> Result<Fr, Error> {
+    let mut le = *bytes;
+    le.reverse();
+    Ok(Fr::deserialize_compressed(&le[..])?)
+}
+
+/// Serialize a field element to 32 bytes big endian
+pub fn field_to_bytes(f: &Fr) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+    let mut out = [0u8; BYTES_PER_FIELD_ELEMENT];
+    out.copy_from_slice(&f.into_bigint().to_bytes_be());
+    out
+}
+
+/// Serialize a commitment to 48 bytes
+pub fn commitment_to_bytes(commitment: &Commitment<Bls12_381>) -> [u8; BYTES_PER_COMMITMENT] {
+    commitment
+        .to_bytes()
+        .expect("A compressed point is 48 bytes")
+}
+
+/// Parse a 48 byte commitment, failing unless it's a point in the prime order subgroup
+pub fn bytes_to_commitment(
+    bytes: &[u8; BYTES_PER_COMMITMENT],
+) -> Result<Commitment<Bls12_381>, Error> {
+    Commitment::from_bytes(bytes)
+}
+
+/// Serialize a proof to 48 bytes
+pub fn proof_to_bytes(proof: &Proof<Bls12_381>) -> [u8; BYTES_PER_PROOF] {
+    proof.to_bytes().expect("A compressed point is 48 bytes")
+}
+
+/// Parse a 48 byte proof, failing unless it's a point in the prime order subgroup
+pub fn bytes_to_proof(bytes: &[u8; BYTES_PER_PROOF]) -> Result<Proof<Bls12_381>, Error> {
+    Proof::from_bytes(bytes)
+}
+
+/// Commit to the polynomial held by `blob`
+pub fn blob_to_kzg_commitment<S: Committer<Bls12_381>>(
+    srs: &S,
+    blob: &Blob,
+) -> Result<Commitment<Bls12_381>, Error> {
+    srs.commit(blob.to_polynomial())
+}
+
+/// Open the polynomial held by `blob` at `z`, returning the proof and the evaluation $y$
+pub fn compute_kzg_proof<S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>>(
+    srs: &S,
+    blob: &Blob,
+    z: Fr,
+) -> Result<(Proof<Bls12_381>, Fr), Error> {
+    let poly = blob.to_polynomial();
+    let y = evaluate_at_points(&poly, &[z])[0];
+    let witness = srs.compute_witness_polynomial(poly, z)?;
+    Ok((srs.open(witness)?, y))
+}
+
+/// Verify that the polynomial committed to by `commitment` evaluates to `y` at `z`
+pub fn verify_kzg_proof<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    commitment: &Commitment<Bls12_381>,
+    z: Fr,
+    y: Fr,
+    proof: &Proof<Bls12_381>,
+) -> Result<bool, Error> {
+    srs.verify::<M>(commitment, z, y, proof)
+}
+
+/// [`blob_to_kzg_commitment`] over serialized inputs and outputs
+pub fn blob_to_kzg_commitment_bytes<S: Committer<Bls12_381>>(
+    srs: &S,
+    blob: &[u8],
+) -> Result<[u8; BYTES_PER_COMMITMENT], Error> {
+    let commitment = blob_to_kzg_commitment(srs, &Blob::from_bytes(blob)?)?;
+    Ok(commitment_to_bytes(&commitment))
+}
+
+/// [`compute_kzg_proof`] over serialized inputs and outputs
+pub fn compute_kzg_proof_bytes<S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>>(
+    srs: &S,
+    blob: &[u8],
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+) -> Result<([u8; BYTES_PER_PROOF], [u8; BYTES_PER_FIELD_ELEMENT]), Error> {
+    let (proof, y) = compute_kzg_proof(srs, &Blob::from_bytes(blob)?, bytes_to_field(z)?)?;
+    Ok((proof_to_bytes(&proof), field_to_bytes(&y)))
+}
+
+/// [`verify_kzg_proof`] over serialized inputs
+pub fn verify_kzg_proof_bytes<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    commitment: &[u8; BYTES_PER_COMMITMENT],
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    y: &[u8; BYTES_PER_FIELD_ELEMENT],
+    proof: &[u8; BYTES_PER_PROOF],
+) -> Result<bool, Error> {
+    verify_kzg_proof::<M, S>(
+        srs,
+        &bytes_to_commitment(commitment)?,
+        bytes_to_field(z)?,
+        bytes_to_field(y)?,
+        &bytes_to_proof(proof)?,
+    )
+}
+
+/// The domain of the challenge at which a blob is opened by [`compute_blob_kzg_proof`]
+pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8; 16] = b"FSBLOBVERIFY_V1_";
+/// The domain of the challenge combining the checks of [`verify_blob_kzg_proof_batch`]
+pub const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8; 16] = b"RCKZGBATCH___V1_";
+
+#[cfg(feature = "blst")]
+/// The point at which [`compute_blob_kzg_proof`] opens `blob`, the SHA-256 hash of
+/// [`FIAT_SHAMIR_PROTOCOL_DOMAIN`], the blob size as 16 bytes big endian, the blob and its
+/// commitment, read big endian and reduced
+pub fn compute_challenge(blob: &Blob, commitment: &Commitment<Bls12_381>) -> Fr {
+    let mut data = Vec::with_capacity(32 + BYTES_PER_BLOB + BYTES_PER_COMMITMENT);
+    data.extend_from_slice(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    data.extend(blob.to_bytes());
+    data.extend_from_slice(&commitment_to_bytes(commitment));
+    hash_to_field(&data)
+}
+
+#[cfg(feature = "blst")]
+/// Open the polynomial held by `blob` at [`compute_challenge`], for checking against its
+/// `commitment` with [`verify_blob_kzg_proof`]
+pub fn compute_blob_kzg_proof<S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>>(
+    srs: &S,
+    blob: &Blob,
+    commitment: &Commitment<Bls12_381>,
+) -> Result<Proof<Bls12_381>, Error> {
+    Ok(compute_kzg_proof(srs, blob, compute_challenge(blob, commitment))?.0)
+}
+
+#[cfg(feature = "blst")]
+/// Verify a proof from [`compute_blob_kzg_proof`] that `commitment` is to the polynomial held by
+/// `blob`
+pub fn verify_blob_kzg_proof<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    blob: &Blob,
+    commitment: &Commitment<Bls12_381>,
+    proof: &Proof<Bls12_381>,
+) -> Result<bool, Error> {
+    let z = compute_challenge(blob, commitment);
+    let y = evaluate_at_points(&blob.to_polynomial(), &[z])[0];
+    verify_kzg_proof::<M, S>(srs, commitment, z, y, proof)
+}
+
+#[cfg(feature = "blst")]
+/// Verify proofs from [`compute_blob_kzg_proof`] for each blob and commitment at once. The
+/// checks are combined with the powers of a challenge $r$, the SHA-256 hash of
+/// [`RANDOM_CHALLENGE_KZG_BATCH_DOMAIN`], the blob size and number of blobs as 8 bytes big endian
+/// each, then each commitment, evaluation point, evaluation and proof.
+pub fn verify_blob_kzg_proof_batch<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    blobs: &[Blob],
+    commitments: &[Commitment<Bls12_381>],
+    proofs: &[Proof<Bls12_381>],
+) -> Result<bool, Error> {
+    for len in [commitments.len(), proofs.len()] {
+        if len != blobs.len() {
+            return Err(Error::InvalidInputLength {
+                expected: blobs.len(),
+                got: len,
+            });
+        }
+    }
+    let mut data = Vec::new();
+    data.extend_from_slice(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    data.extend_from_slice(&(blobs.len() as u64).to_be_bytes());
+    let mut openings = Vec::with_capacity(blobs.len());
+    for ((blob, commitment), proof) in blobs.iter().zip(commitments).zip(proofs) {
+        let z = compute_challenge(blob, commitment);
+        let y = evaluate_at_points(&blob.to_polynomial(), &[z])[0];
+        data.extend_from_slice(&commitment_to_bytes(commitment));
+        data.extend_from_slice(&field_to_bytes(&z));
+        data.extend_from_slice(&field_to_bytes(&y));
+        data.extend_from_slice(&proof_to_bytes(proof));
+        openings.push((z, y));
+    }
+    // e(sum r^i pi_i, [s]) = e(sum r^i (C_i - [y_i] + z_i pi_i), [1]) is a single opening of
+    // the combined left hand side to zero at zero
+    let r = hash_to_field(&data);
+    let (mut lhs, mut proof_sum) = (G1Projective::zero(), G1Projective::zero());
+    let mut r_i = Fr::one();
+    for ((commitment, proof), (z, y)) in commitments.iter().zip(proofs).zip(openings) {
+        lhs += (commitment.0.into_group() - G1Affine::generator() * y + proof.0 * z) * r_i;
+        proof_sum += proof.0 * r_i;
+        r_i *= r;
+    }
+    verify_kzg_proof::<M, S>(
+        srs,
+        &Commitment(lhs.into_affine()),
+        Fr::zero(),
+        Fr::zero(),
+        &Proof(proof_sum.into_affine()),
+    )
+}
+
+#[cfg(feature = "blst")]
+/// [`compute_blob_kzg_proof`] over serialized inputs and outputs
+pub fn compute_blob_kzg_proof_bytes<S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>>(
+    srs: &S,
+    blob: &[u8],
+    commitment: &[u8; BYTES_PER_COMMITMENT],
+) -> Result<[u8; BYTES_PER_PROOF], Error> {
+    let proof = compute_blob_kzg_proof(
+        srs,
+        &Blob::from_bytes(blob)?,
+        &bytes_to_commitment(commitment)?,
+    )?;
+    Ok(proof_to_bytes(&proof))
+}
+
+#[cfg(feature = "blst")]
+/// [`verify_blob_kzg_proof`] over serialized inputs
+pub fn verify_blob_kzg_proof_bytes<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    blob: &[u8],
+    commitment: &[u8; BYTES_PER_COMMITMENT],
+    proof: &[u8; BYTES_PER_PROOF],
+) -> Result<bool, Error> {
+    verify_blob_kzg_proof::<M, S>(
+        srs,
+        &Blob::from_bytes(blob)?,
+        &bytes_to_commitment(commitment)?,
+        &bytes_to_proof(proof)?,
+    )
+}
+
+#[cfg(feature = "blst")]
+/// [`verify_blob_kzg_proof_batch`] over serialized inputs
+pub fn verify_blob_kzg_proof_batch_bytes<
+    M: MSMEngine<E = Bls12_381>,
+    S: KZGProof<Bls12_381, Proof = Proof<Bls12_381>>,
+>(
+    srs: &S,
+    blobs: &[impl AsRef<[u8]>],
+    commitments: &[[u8; BYTES_PER_COMMITMENT]],
+    proofs: &[[u8; BYTES_PER_PROOF]],
+) -> Result<bool, Error> {
+    verify_blob_kzg_proof_batch::<M, S>(
+        srs,
+        &blobs
+            .iter()
+            .map(|b| Blob::from_bytes(b.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?,
+        &commitments
+            .iter()
+            .map(bytes_to_commitment)
+            .collect::<Result<Vec<_>, _>>()?,
+        &proofs
+            .iter()
+            .map(bytes_to_proof)
+            .collect::<Result<Vec<_>, _>>()?,
+    )
+}
+
+#[cfg(feature = "blst")]
+/// The SHA-256 hash of `data` read as a big endian integer and reduced
+fn hash_to_field(data: &[u8]) -> Fr {
+    let mut hash = [0u8; 32];
+    // SAFETY: `hash` is 32 writable bytes and `data` is `data.len()` readable bytes
+    unsafe { ::blst::blst_sha256(hash.as_mut_ptr(), data.as_ptr(), data.len()) };
+    Fr::from_be_bytes_mod_order(&hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{BigInteger, Field, PrimeField};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::{vec, vec::Vec, UniformRand};
+
+    use super::{
+        blob_to_kzg_commitment, blob_to_kzg_commitment_bytes, bytes_to_commitment, bytes_to_field,
+        compute_kzg_proof, compute_kzg_proof_bytes, field_to_bytes, verify_kzg_proof,
+        verify_kzg_proof_bytes, Blob, BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB,
+    };
+    #[cfg(feature = "blst")]
+    use super::{
+        commitment_to_bytes, compute_blob_kzg_proof, compute_blob_kzg_proof_bytes,
+        compute_challenge, hash_to_field, proof_to_bytes, verify_blob_kzg_proof,
+        verify_blob_kzg_proof_batch, verify_blob_kzg_proof_batch_bytes,
+        verify_blob_kzg_proof_bytes,
+    };
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::blst::BlstMSMEngine,
+        poly_ops::{ev_points, to_brp},
+        test_rng, Error,
+    };
+
+    #[test]
+    fn test_blob_proofs() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_insecure_for_testing(
+            0,
+            FIELD_ELEMENTS_PER_BLOB,
+            1,
+        );
+        // Element i of the blob is the evaluation of X at the bit-reversed root of unity, so it
+        // holds the polynomial X
+        let domain = Radix2EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+        let blob = Blob::from_evals(to_brp(&ev_points(&domain)).unwrap()).unwrap();
+        let commit = blob_to_kzg_commitment(&s, &blob).unwrap();
+        assert_eq!(s.powers_of_g1[1], commit.0);
+
+        let z = Fr::rand(&mut test_rng());
+        let (proof, y) = compute_kzg_proof(&s, &blob, z).unwrap();
+        assert_eq!(z, y);
+        // The quotient of X - z by X - z is one
+        assert_eq!(s.powers_of_g1[0], proof.0);
+        assert!(verify_kzg_proof::<BlstMSMEngine, _>(&s, &commit, z, y, &proof).unwrap());
+        assert!(!verify_kzg_proof::<BlstMSMEngine, _>(&s, &commit, z, y + z, &proof).unwrap());
+
+        // Opening at a point in the domain gives the blob element
+        let (proof, y) = compute_kzg_proof(&s, &blob, blob.evals()[1]).unwrap();
+        assert_eq!(blob.evals()[1], y);
+        assert!(
+            verify_kzg_proof::<BlstMSMEngine, _>(&s, &commit, blob.evals()[1], y, &proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_blob_bytes() {
+        let evals = (0..FIELD_ELEMENTS_PER_BLOB)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let blob = Blob::from_evals(evals).unwrap();
+        let bytes = blob.to_bytes();
+        assert_eq!(BYTES_PER_BLOB, bytes.len());
+        assert_eq!(blob, Blob::from_bytes(&bytes).unwrap());
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(Fr::from(1u64), bytes_to_field(&one).unwrap());
+        assert_eq!(one, field_to_bytes(&Fr::from(1u64)));
+        // The modulus isn't canonical
+        let modulus = Fr::MODULUS.to_bytes_be();
+        assert_eq!(
+            Err(Error::SerializationError),
+            bytes_to_field(&modulus.try_into().unwrap())
+        );
+        let mut bad = bytes.clone();
+        bad[..32].copy_from_slice(&[0xff; 32]);
+        assert_eq!(Err(Error::SerializationError), Blob::from_bytes(&bad));
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: BYTES_PER_BLOB,
+                got: BYTES_PER_BLOB - 1
+            }),
+            Blob::from_bytes(&bytes[1..])
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: FIELD_ELEMENTS_PER_BLOB,
+                got: 1
+            }),
+            Blob::from_evals(vec![Fr::from(1u64)])
+        );
+    }
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_interop_vectors() {
+        // These don't depend on the secret, so they match c-kzg-4844 with the mainnet setup
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_insecure_for_testing(
+            0,
+            FIELD_ELEMENTS_PER_BLOB,
+            1,
+        );
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let ones = one.repeat(FIELD_ELEMENTS_PER_BLOB);
+        let zeros = vec![0u8; BYTES_PER_BLOB];
+        let generator = from_hex(
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        );
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+
+        // The constant polynomial one commits to the generator, and zero to the identity
+        let commitment = blob_to_kzg_commitment_bytes(&s, &ones).unwrap();
+        assert_eq!(generator, commitment);
+        assert_eq!(infinity, blob_to_kzg_commitment_bytes(&s, &zeros).unwrap());
+
+        // The quotient of a constant polynomial is zero
+        let z = field_to_bytes(&Fr::from(1234u64));
+        let (proof, y) = compute_kzg_proof_bytes(&s, &ones, &z).unwrap();
+        assert_eq!(infinity, proof);
+        assert_eq!(one, y);
+        assert_eq!(
+            Ok(true),
+            verify_kzg_proof_bytes::<BlstMSMEngine, _>(&s, &commitment, &z, &y, &proof)
+        );
+        assert_eq!(
+            Ok(false),
+            verify_kzg_proof_bytes::<BlstMSMEngine, _>(&s, &commitment, &z, &[0u8; 32], &proof)
+        );
+
+        // Points off the curve or not compressed are rejected
+        let mut bad = infinity;
+        bad[0] = 0x80 | 0x01;
+        assert_eq!(
+            Err(Error::SerializationError),
+            bytes_to_commitment(&bad).map(|c| c.0)
+        );
+        assert_eq!(
+            Err(Error::SerializationError),
+            bytes_to_commitment(&[0u8; 48]).map(|c| c.0)
+        );
+    }
+
+    #[cfg(feature = "blst")]
+    /// The blob whose element `i` is the hash of `tag` and `i`
+    fn hashed_blob(tag: u8) -> Blob {
+        Blob::from_evals(
+            (0..FIELD_ELEMENTS_PER_BLOB as u64)
+                .map(|i| hash_to_field(&[&[tag][..], &i.to_be_bytes()].concat()))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[cfg(feature = "blst")]
+    /// Evaluate the polynomial holding `blob` at `x` outside the domain with the barycentric
+    /// formula $p(x) = (x^N - 1)/N \sum_i y_i \omega_i / (x - \omega_i)$
+    fn barycentric(blob: &Blob, x: Fr) -> Fr {
+        let domain = Radix2EvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+        let roots = to_brp(&ev_points(&domain)).unwrap();
+        let sum: Fr = blob
+            .evals()
+            .iter()
+            .zip(&roots)
+            .map(|(y, w)| *y * w / (x - w))
+            .sum();
+        let n = Fr::from(FIELD_ELEMENTS_PER_BLOB as u64);
+        (x.pow([FIELD_ELEMENTS_PER_BLOB as u64]) - Fr::from(1u64)) / n * sum
+    }
+
+    #[cfg(feature = "blst")]
+    #[test]
+    fn test_blob_kzg_proof_vectors() {
+        use ark_bls12_381::{G1Projective, G2Projective};
+        use ark_ec::{CurveGroup, Group};
+
+        // A setup with a known secret, so the vectors can be checked against the secret directly
+        let secret = Fr::from(0x1337_c0de_u64);
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_scalar(
+            secret,
+            G1Projective::generator(),
+            G2Projective::generator(),
+            FIELD_ELEMENTS_PER_BLOB,
+            1,
+        );
+        let blobs = [hashed_blob(0), hashed_blob(1), hashed_blob(2)];
+        let commitments = blobs
+            .iter()
+            .map(|b| blob_to_kzg_commitment(&s, b).unwrap())
+            .collect::<Vec<_>>();
+        let proofs = blobs
+            .iter()
+            .zip(&commitments)
+            .map(|(b, c)| compute_blob_kzg_proof(&s, b, c).unwrap())
+            .collect::<Vec<_>>();
+        let z = compute_challenge(&blobs[0], &commitments[0]);
+        let (_, y) = compute_kzg_proof(&s, &blobs[0], z).unwrap();
+        assert_eq!(
+            from_hex("8e696eb08dde608b5dcb1d8424ab5f8c2c5753d1ee17a938bbc87362fb9ba09f8d3b2597c72e173aac931c934ab81e1e"),
+            commitment_to_bytes(&commitments[0])
+        );
+        assert_eq!(
+            from_hex("5146c12fae01d9a8673d608536f2cd023f384e7cf9d3d624715bc69b0c937709"),
+            field_to_bytes(&z)
+        );
+        assert_eq!(
+            from_hex("664dace5e1d40c012358e6597da5f1d8c492ca832696463d499147ed62b6070d"),
+            field_to_bytes(&y)
+        );
+        assert_eq!(
+            from_hex("a53c900a584dff9bcb093d3661e5f2b527474c266586797591124a93ea2cf27c5ae8c0c26816da106c19783340254da4"),
+            proof_to_bytes(&proofs[0])
+        );
+
+        // Check them against the secret without the FFTs, MSMs and division the setup uses
+        let p_s = barycentric(&blobs[0], secret);
+        assert_eq!(y, barycentric(&blobs[0], z));
+        assert_eq!(
+            commitments[0].0,
+            (G1Projective::generator() * p_s).into_affine()
+        );
+        assert_eq!(
+            proofs[0].0,
+            (G1Projective::generator() * ((p_s - y) / (secret - z))).into_affine()
+        );
+
+        for ((blob, commitment), proof) in blobs.iter().zip(&commitments).zip(&proofs) {
+            assert_eq!(
+                Ok(true),
+                verify_blob_kzg_proof::<BlstMSMEngine, _>(&s, blob, commitment, proof)
+            );
+        }
+        assert_eq!(
+            Ok(false),
+            verify_blob_kzg_proof::<BlstMSMEngine, _>(&s, &blobs[0], &commitments[0], &proofs[1])
+        );
+        assert_eq!(
+            Ok(true),
+            verify_blob_kzg_proof_batch::<BlstMSMEngine, _>(&s, &blobs, &commitments, &proofs)
+        );
+        assert_eq!(
+            Ok(true),
+            verify_blob_kzg_proof_batch::<BlstMSMEngine, _>(&s, &[], &[], &[])
+        );
+        let swapped = [proofs[1].clone(), proofs[0].clone(), proofs[2].clone()];
+        assert_eq!(
+            Ok(false),
+            verify_blob_kzg_proof_batch::<BlstMSMEngine, _>(&s, &blobs, &commitments, &swapped)
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            verify_blob_kzg_proof_batch::<BlstMSMEngine, _>(&s, &blobs, &commitments[..2], &proofs)
+        );
+
+        let blob_bytes = blobs.iter().map(Blob::to_bytes).collect::<Vec<_>>();
+        let commitment_bytes = commitments
+            .iter()
+            .map(commitment_to_bytes)
+            .collect::<Vec<_>>();
+        let proof_bytes = blob_bytes
+            .iter()
+            .zip(&commitment_bytes)
+            .map(|(b, c)| compute_blob_kzg_proof_bytes(&s, b, c).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(proof_to_bytes(&proofs[0]), proof_bytes[0]);
+        assert_eq!(
+            Ok(true),
+            verify_blob_kzg_proof_bytes::<BlstMSMEngine, _>(
+                &s,
+                &blob_bytes[0],
+                &commitment_bytes[0],
+                &proof_bytes[0]
+            )
+        );
+        assert_eq!(
+            Ok(true),
+            verify_blob_kzg_proof_batch_bytes::<BlstMSMEngine, _>(
+                &s,
+                &blob_bytes,
+                &commitment_bytes,
+                &proof_bytes
+            )
+        );
+    }
+}
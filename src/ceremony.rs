@@ -0,0 +1,284 @@
+//! Powers of tau ceremonies. Each participant updates the setup by a secret $s$ of their own with
+//! [`contribute`], which multiplies the $i$th power by $s^i$ so the new secret is $\tau s$, and
+//! publishes the new setup with a [`ContributionProof`]. Anyone can check each update with
+//! [`verify_contribution`], and the final setup is secure as long as one participant discarded
+//! their secret.
+//!
+//! A ceremony can start from any valid setup, such as
+//! [`M1NoPrecomp::new_insecure_for_testing`], since the first honest contribution hides its
+//! secret.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::{rand::RngCore, vec::Vec, UniformRand};
+
+use crate::{check_setup_size, gen_powers, method1::M1NoPrecomp, traits::MSMEngine, Error};
+
+/// Proves that a contribution multiplied the previous secret by a secret $s$ its contributor
+/// knows. This is the standard update proof: $\[s\]_1$ and $\[s\]_2$, which must pair to the same
+/// value against the generators, and a Schnorr proof of knowledge of $s$ bound to the update.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<E: Pairing> {
+    /// The contributor's secret times the G1 generator of the setup
+    pub s_g1: E::G1Affine,
+    /// The contributor's secret times the G2 generator of the setup
+    pub s_g2: E::G2Affine,
+    /// The commitment $\[k\]_1$ to the nonce of the proof of knowledge
+    pub pok_commit: E::G1Affine,
+    /// The response $k + c s$ of the proof of knowledge to its challenge $c$
+    pub pok_response: E::ScalarField,
+}
+
+/// Update `srs` with a random secret from `rng`. Returns the new setup along with the proof of
+/// the update. The secret is wiped from memory afterwards with the `zeroize` feature. Fails with
+/// [`Error::SetupTooSmall`] if `srs` doesn't have the powers the proof is checked against, two
+/// in G1 and one in G2.
+pub fn contribute<E: Pairing, M: MSMEngine<E = E>>(
+    srs: &M1NoPrecomp<E, M>,
+    rng: &mut impl RngCore,
+) -> Result<(M1NoPrecomp<E, M>, ContributionProof<E>), Error> {
+    check_contributed_sizes(srs)?;
+    #[allow(unused_mut)]
+    let mut s = E::ScalarField::rand(rng);
+    #[allow(unused_mut)]
+    let mut k = E::ScalarField::rand(rng);
+    #[allow(unused_mut)]
+    let mut s_powers = gen_powers(
+        s,
+        core::cmp::max(srs.powers_of_g1.len(), srs.powers_of_g2.len()),
+    );
+    let powers_of_g1 = E::G1::normalize_batch(
+        &srs.powers_of_g1
+            .iter()
+            .zip(&s_powers)
+            .map(|(p, s)| *p * s)
+            .collect::<Vec<_>>(),
+    );
+    let powers_of_g2 = E::G2::normalize_batch(
+        &srs.powers_of_g2
+            .iter()
+            .zip(&s_powers)
+            .map(|(p, s)| *p * s)
+            .collect::<Vec<_>>(),
+    );
+    let s_g1 = (srs.powers_of_g1[0] * s).into_affine();
+    let s_g2 = (srs.powers_of_g2[0] * s).into_affine();
+    let pok_commit = (srs.powers_of_g1[0] * k).into_affine();
+    let c = pok_challenge::<E>(
+        &srs.powers_of_g1[1],
+        &powers_of_g1[1],
+        &s_g1,
+        &s_g2,
+        &pok_commit,
+    );
+    let proof = ContributionProof {
+        s_g1,
+        s_g2,
+        pok_commit,
+        pok_response: k + c * s,
+    };
+    #[cfg(feature = "zeroize")]
+    {
+        zeroize::Zeroize::zeroize(&mut s);
+        zeroize::Zeroize::zeroize(&mut k);
+        zeroize::Zeroize::zeroize(&mut s_powers);
+    }
+    let new = M1NoPrecomp::new_from_affine(powers_of_g1, powers_of_g2);
+    Ok((new, proof))
+}
+
+/// Check that `new` is a valid setup which updates `old` by the secret in `proof`, and that the
+/// contributor knew it. The validity of `new` is checked with [`M1NoPrecomp::validate`] using
+/// `rng`.
+pub fn verify_contribution<E: Pairing, M: MSMEngine<E = E>>(
+    old: &M1NoPrecomp<E, M>,
+    new: &M1NoPrecomp<E, M>,
+    proof: &ContributionProof<E>,
+    rng: &mut impl RngCore,
+) -> Result<(), Error> {
+    check_contributed_sizes(old)?;
+    if new.powers_of_g1.len() != old.powers_of_g1.len() {
+        return Err(Error::InvalidInputLength {
+            expected: old.powers_of_g1.len(),
+            got: new.powers_of_g1.len(),
+        });
+    }
+    if new.powers_of_g2.len() != old.powers_of_g2.len() {
+        return Err(Error::InvalidInputLength {
+            expected: old.powers_of_g2.len(),
+            got: new.powers_of_g2.len(),
+        });
+    }
+    new.validate(rng)?;
+    let g1 = old.powers_of_g1[0];
+    let g2 = old.powers_of_g2[0];
+    // The generators don't change, and a zero secret would erase the setup
+    if new.powers_of_g1[0] != g1
+        || new.powers_of_g2[0] != g2
+        || proof.s_g1.is_zero()
+        || proof.s_g2.is_zero()
+        || proof.s_g1.check().is_err()
+        || proof.s_g2.check().is_err()
+        || proof.pok_commit.check().is_err()
+    {
+        return Err(Error::InvalidContribution);
+    }
+    // e([s]_1, [1]_2) == e([1]_1, [s]_2), so both are the same secret
+    if !M::pairing_eq_check(proof.s_g1, g2, g1, proof.s_g2) {
+        return Err(Error::InvalidContribution);
+    }
+    // The contributor knows s: [z]_1 == [k]_1 + c [s]_1
+    let c = pok_challenge::<E>(
+        &old.powers_of_g1[1],
+        &new.powers_of_g1[1],
+        &proof.s_g1,
+        &proof.s_g2,
+        &proof.pok_commit,
+    );
+    if g1 * proof.pok_response != proof.pok_commit.into_group() + proof.s_g1 * c {
+        return Err(Error::InvalidContribution);
+    }
+    // e([tau s]_1, [1]_2) == e([tau]_1, [s]_2)
+    if !M::pairing_eq_check(new.powers_of_g1[1], g2, old.powers_of_g1[1], proof.s_g2) {
+        return Err(Error::InvalidContribution);
+    }
+    Ok(())
+}
+
+/// A setup is updated and checked through its generators and $[\tau]_1$
+fn check_contributed_sizes<E: Pairing, M: MSMEngine<E = E>>(
+    srs: &M1NoPrecomp<E, M>,
+) -> Result<(), Error> {
+    check_setup_size(1, 2, srs.powers_of_g1.len())?;
+    check_setup_size(2, 1, srs.powers_of_g2.len())
+}
+
+/// The challenge of the proof of knowledge of the secret, which binds it to the update from
+/// `old_tau_g1` to `new_tau_g1` so it can't be replayed for another one
+fn pok_challenge<E: Pairing>(
+    old_tau_g1: &E::G1Affine,
+    new_tau_g1: &E::G1Affine,
+    s_g1: &E::G1Affine,
+    s_g2: &E::G2Affine,
+    pok_commit: &E::G1Affine,
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    for p in [old_tau_g1, new_tau_g1, s_g1, pok_commit] {
+        p.serialize_compressed(&mut bytes)
+            .expect("Writing to a vec doesn't fail");
+    }
+    s_g2.serialize_compressed(&mut bytes)
+        .expect("Writing to a vec doesn't fail");
+    let mut transcript = merlin::Transcript::new(b"poly-multiproof ceremony contribution");
+    transcript.append_message(b"update", &bytes);
+    let mut challenge = [0u8; 64];
+    transcript.challenge_bytes(b"pok challenge", &mut challenge);
+    E::ScalarField::from_le_bytes_mod_order(&challenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_std::vec::Vec;
+
+    use super::{contribute, verify_contribution, ContributionProof};
+    use crate::{
+        method1::M1NoPrecomp, msm::blst::BlstMSMEngine, test_rng, testing::test_basic_no_precomp,
+        Error,
+    };
+
+    #[test]
+    fn test_ceremony() {
+        let start = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_insecure_for_testing(0, 64, 31);
+        let (first, first_proof) = contribute(&start, &mut test_rng()).unwrap();
+        let (second, second_proof) = contribute(&first, &mut test_rng()).unwrap();
+        assert_eq!(
+            Ok(()),
+            verify_contribution(&start, &first, &first_proof, &mut test_rng())
+        );
+        assert_eq!(
+            Ok(()),
+            verify_contribution(&first, &second, &second_proof, &mut test_rng())
+        );
+        assert_ne!(start.powers_of_g1[1], second.powers_of_g1[1]);
+        test_basic_no_precomp(&second);
+
+        // Proofs are tied to their update
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&first, &second, &first_proof, &mut test_rng())
+        );
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&start, &second, &second_proof, &mut test_rng())
+        );
+        let zero = ContributionProof {
+            s_g2: ark_bls12_381::G2Affine::zero(),
+            ..second_proof.clone()
+        };
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&first, &second, &zero, &mut test_rng())
+        );
+
+        // [s]_1 has to match [s]_2, and the proof of knowledge has to check out
+        let mismatched = ContributionProof {
+            s_g1: first_proof.s_g1,
+            ..second_proof.clone()
+        };
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&first, &second, &mismatched, &mut test_rng())
+        );
+        let mut bad_response = second_proof.clone();
+        bad_response.pok_response += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&first, &second, &bad_response, &mut test_rng())
+        );
+        let bad_commit = ContributionProof {
+            pok_commit: first_proof.pok_commit,
+            ..second_proof.clone()
+        };
+        assert_eq!(
+            Err(Error::InvalidContribution),
+            verify_contribution(&first, &second, &bad_commit, &mut test_rng())
+        );
+
+        let mut bad = second.clone();
+        bad.powers_of_g1[5] = (bad.powers_of_g1[5] + bad.powers_of_g1[0]).into_affine();
+        assert_eq!(
+            Err(Error::InconsistentSrs { group: 1 }),
+            verify_contribution(&first, &bad, &second_proof, &mut test_rng())
+        );
+        let mut short = second;
+        short.truncate(32, 31).unwrap();
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 64,
+                got: 32
+            }),
+            verify_contribution(&first, &short, &second_proof, &mut test_rng())
+        );
+
+        let empty =
+            M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_affine(Vec::new(), Vec::new());
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 2,
+                got: 0
+            }),
+            contribute(&empty, &mut test_rng()).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 2,
+                got: 0
+            }),
+            verify_contribution(&empty, &empty, &second_proof, &mut test_rng())
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! A least recently used cache of commitments, for callers which commit to the same
+//! polynomials over and over, like block builders committing to unchanged rows of each candidate
+//! block. [`CommitCache`] wraps any [`Committer`] and implements [`Committer`] itself, so it can
+//! be used anywhere the wrapped setup is.
+//!
+//! Entries are looked up by a fast keyed hash of the coefficients. The coefficients are stored
+//! alongside the commitment and compared on a hit, so a hash collision is only a cache miss.
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::BuildHasher,
+    sync::Mutex,
+};
+
+use ark_ec::pairing::Pairing;
+use ark_poly::Radix2EvaluationDomain;
+use ark_std::vec::Vec;
+
+use crate::{traits::Committer, Commitment, Error};
+
+/// The number of lookups a [`CommitCache`] has answered from the cache and by committing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups which committed to the polynomial
+    pub misses: u64,
+}
+
+struct Entry<E: Pairing> {
+    coeffs: Vec<E::ScalarField>,
+    commit: Commitment<E>,
+    last_used: u64,
+}
+
+struct Lru<E: Pairing> {
+    entries: HashMap<u64, Entry<E>>,
+    // The key of each entry by when it was last used, so the oldest is first
+    by_use: BTreeMap<u64, u64>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+/// A [`Committer`] which remembers the commitments to the last `capacity` distinct polynomials
+pub struct CommitCache<E: Pairing, C> {
+    inner: C,
+    capacity: usize,
+    hasher: RandomState,
+    lru: Mutex<Lru<E>>,
+}
+
+impl<E: Pairing, C: Committer<E>> CommitCache<E, C> {
+    /// Cache the commitments made with `inner` to up to `capacity` polynomials
+    pub fn new(inner: C, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            hasher: RandomState::new(),
+            lru: Mutex::new(Lru {
+                entries: HashMap::new(),
+                by_use: BTreeMap::new(),
+                tick: 0,
+                stats: CacheStats::default(),
+            }),
+        }
+    }
+
+    /// The wrapped committer
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Take the wrapped committer out of the cache
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// The most polynomials the cache holds commitments to
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of polynomials the cache holds commitments to
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The hits and misses since the cache was made or last cleared
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drop every cached commitment and reset the stats
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        lru.entries.clear();
+        lru.by_use.clear();
+        lru.stats = CacheStats::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru<E>> {
+        // A panic while holding the lock can't leave the cache inconsistent in a way that gives
+        // wrong commitments, since entries are checked against the coefficients
+        self.lru.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn key(&self, coeffs: &[E::ScalarField]) -> u64 {
+        self.hasher.hash_one(coeffs)
+    }
+}
+
+impl<E: Pairing, C: Committer<E>> Committer<E> for CommitCache<E, C> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        let coeffs = poly.as_ref();
+        let key = self.key(coeffs);
+        {
+            let mut lru = self.lock();
+            let lru = &mut *lru;
+            lru.tick += 1;
+            let tick = lru.tick;
+            if let Some(entry) = lru.entries.get_mut(&key) {
+                if entry.coeffs == coeffs {
+                    lru.by_use.remove(&entry.last_used);
+                    lru.by_use.insert(tick, key);
+                    entry.last_used = tick;
+                    lru.stats.hits += 1;
+                    return Ok(entry.commit.clone());
+                }
+            }
+            lru.stats.misses += 1;
+        }
+
+        // Commit without holding the lock, so other threads can still hit the cache
+        let commit = self.inner.commit(coeffs)?;
+        if self.capacity == 0 {
+            return Ok(commit);
+        }
+        let mut lru = self.lock();
+        let lru = &mut *lru;
+        lru.tick += 1;
+        let tick = lru.tick;
+        let entry = Entry {
+            coeffs: coeffs.to_vec(),
+            commit: commit.clone(),
+            last_used: tick,
+        };
+        if let Some(old) = lru.entries.insert(key, entry) {
+            lru.by_use.remove(&old.last_used);
+        }
+        lru.by_use.insert(tick, key);
+        while lru.entries.len() > self.capacity {
+            let (_, oldest) = lru.by_use.pop_first().expect("Every entry has a use");
+            lru.entries.remove(&oldest);
+        }
+        Ok(commit)
+    }
+
+    /// Commitments to evaluations aren't cached, and go straight to the wrapped committer so it
+    /// can use its lagrange basis
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_evals(evals, domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{vec::Vec, UniformRand};
+
+    use super::{CacheStats, CommitCache};
+    use crate::{method1::M1NoPrecomp, msm::blst::BlstMSMEngine, test_rng, traits::Committer};
+
+    #[test]
+    fn test_commit_cache() {
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(16, 2, &mut test_rng());
+        let polys = (0..3)
+            .map(|_| {
+                (0..16)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let cache = CommitCache::new(pmp, 2);
+        assert!(cache.is_empty());
+
+        for p in &polys[..2] {
+            assert_eq!(
+                cache.inner().commit(p).unwrap().0,
+                cache.commit(p).unwrap().0
+            );
+        }
+        assert_eq!(
+            cache.commit(&polys[0]).unwrap().0,
+            cache.inner().commit(&polys[0]).unwrap().0
+        );
+        assert_eq!(CacheStats { hits: 1, misses: 2 }, cache.stats());
+        assert_eq!(2, cache.len());
+
+        // polys[1] is the least recently used, so it's evicted
+        cache.commit(&polys[2]).unwrap();
+        assert_eq!(2, cache.len());
+        cache.commit(&polys[0]).unwrap();
+        assert_eq!(CacheStats { hits: 2, misses: 3 }, cache.stats());
+        cache.commit(&polys[1]).unwrap();
+        assert_eq!(CacheStats { hits: 2, misses: 4 }, cache.stats());
+
+        // A changed polynomial isn't served from the cache
+        let mut changed = polys[1].clone();
+        changed[3] += Fr::from(1u64);
+        assert_eq!(
+            cache.inner().commit(&changed).unwrap().0,
+            cache.commit(&changed).unwrap().0
+        );
+        assert_eq!(CacheStats { hits: 2, misses: 5 }, cache.stats());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(CacheStats::default(), cache.stats());
+    }
+}
@@ -0,0 +1,210 @@
+//! Type aliases for the schemes over specific curves. BLS12-381 uses [`BlstMSMEngine`] and is
+//! enabled with the `blst` feature, BN254 and BLS12-377 use [`ArkMSMEngine`] and are enabled with
+//! the `bn254` and `bls12-377` features.
+//!
+//! The largest radix 2 domain differs between the scalar fields, see
+//! [`max_domain_size`](crate::poly_ops::max_domain_size). BN254 only supports domains up to
+//! $2^{28}$, and bigger domains fail with [`Error::DomainTooLarge`](crate::Error::DomainTooLarge).
+//!
+//! [`BlstMSMEngine`]: crate::msm::blst::BlstMSMEngine
+//! [`ArkMSMEngine`]: crate::msm::ArkMSMEngine
+#[allow(unused_macros)]
+macro_rules! curve_aliases {
+    ($e:ty, $m:ty) => {
+        /// The pairing engine of the curve
+        pub type Curve = $e;
+        /// The scalar field of the curve
+        pub type Fr = <$e as ark_ec::pairing::Pairing>::ScalarField;
+        /// A commitment over the curve
+        pub type Commitment = crate::Commitment<$e>;
+        /// Method 1 without precomputation
+        pub type M1NoPrecomp = crate::method1::M1NoPrecomp<$e, $m>;
+        /// Method 1 with precomputed point sets
+        pub type M1Precomp = crate::method1::precompute::M1Precomp<$e, $m>;
+        /// Method 1 with cyclic point sets
+        pub type M1CyclPrecomp = crate::m1_cycl::M1CyclPrecomp<$e, $m>;
+        /// One of the method 1 schemes, picked at runtime
+        pub type AnyScheme = crate::any_scheme::AnyScheme<$e, $m>;
+        /// A method 1 proof
+        pub type M1Proof = crate::method1::Proof<$e>;
+        /// Method 2 without precomputation
+        pub type M2NoPrecomp = crate::method2::M2NoPrecomp<$e, $m>;
+        /// Method 2 with precomputed point sets
+        pub type M2Precomp = crate::method2::precompute::M2Precomp<$e, $m>;
+        /// A method 2 proof
+        pub type M2Proof = crate::method2::Proof<$e>;
+    };
+}
+
+/// BLS12-381 with the blst MSM engine
+#[cfg(feature = "blst")]
+pub mod bls12_381 {
+    curve_aliases!(ark_bls12_381::Bls12_381, crate::msm::blst::BlstMSMEngine);
+}
+
+/// BN254 with the arkworks MSM engine
+#[cfg(feature = "bn254")]
+pub mod bn254 {
+    curve_aliases!(ark_bn254::Bn254, crate::msm::ArkMSMEngine<ark_bn254::Bn254>);
+}
+
+/// BLS12-377 with the arkworks MSM engine
+#[cfg(feature = "bls12-377")]
+pub mod bls12_377 {
+    curve_aliases!(
+        ark_bls12_377::Bls12_377,
+        crate::msm::ArkMSMEngine<ark_bls12_377::Bls12_377>
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::pairing::Pairing;
+    use ark_std::{vec::Vec, UniformRand};
+
+    use crate::{
+        m1_cycl::M1CyclPrecomp,
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        method2::{precompute::M2Precomp, M2NoPrecomp},
+        msm::ArkMSMEngine,
+        poly_ops::{ev_points, max_domain_size},
+        test_rng,
+        testing::{test_basic_no_precomp, test_basic_precomp, test_batch_precomp},
+        traits::SchemeInfo,
+        Error,
+    };
+
+    fn check_curve<E: Pairing>() {
+        let point_sets = (0..2)
+            .map(|_| {
+                (0..10)
+                    .map(|_| E::ScalarField::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let m1 = M1NoPrecomp::<E, ArkMSMEngine<E>>::new(256, 32, &mut test_rng());
+        test_basic_no_precomp(&m1);
+        let cycl = M1CyclPrecomp::from_inner(m1.clone(), 32, 4).unwrap();
+        let cycl_sets = cycl.point_sets().subgroups();
+        let cycl_sets = cycl_sets.iter().map(ev_points).collect::<Vec<_>>();
+        test_basic_precomp(&cycl, &cycl_sets[0]);
+        test_batch_precomp(&cycl, &cycl_sets);
+        let m1 = M1Precomp::from_inner(m1, point_sets.clone()).unwrap();
+        test_basic_precomp(&m1, &point_sets[0]);
+        test_batch_precomp(&m1, &point_sets);
+
+        let m2 = M2NoPrecomp::<E, ArkMSMEngine<E>>::new(256, &mut test_rng());
+        test_basic_no_precomp(&m2);
+        let m2 = M2Precomp::from_inner(m2, point_sets.clone()).unwrap();
+        test_basic_precomp(&m2, &point_sets[0]);
+        test_batch_precomp(&m2, &point_sets);
+    }
+
+    #[test]
+    fn test_bn254() {
+        type Bn = ark_bn254::Bn254;
+        check_curve::<Bn>();
+        assert_eq!(1 << 28, max_domain_size::<ark_bn254::Fr>());
+        // Fails on the domain size before the setup size
+        let m1 = M1NoPrecomp::<Bn, ArkMSMEngine<Bn>>::new(16, 4, &mut test_rng());
+        assert!(matches!(
+            M1CyclPrecomp::from_inner(m1, 1 << 29, 4),
+            Err(Error::DomainTooLarge {
+                size: 536870912,
+                max: 268435456
+            })
+        ));
+    }
+
+    #[test]
+    fn test_bls12_377() {
+        check_curve::<ark_bls12_377::Bls12_377>();
+        assert_eq!(1 << 47, max_domain_size::<ark_bls12_377::Fr>());
+    }
+
+    #[test]
+    fn test_scheme_info() {
+        type Bls = ark_bls12_381::Bls12_381;
+        let m1 = M1NoPrecomp::<Bls, ArkMSMEngine<Bls>>::new(64, 8, &mut test_rng());
+        assert_eq!(64, SchemeInfo::max_coeffs(&m1));
+        assert_eq!(8, SchemeInfo::max_pts(&m1));
+        assert_eq!("m1-no-precomp", m1.scheme_name());
+        assert_eq!(48, m1.proof_size_bytes());
+        assert_eq!(Ok(()), m1.check_capacity(64, 8));
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 65,
+                expected_max: 64
+            }),
+            m1.check_capacity(65, 8)
+        );
+        assert_eq!(
+            Err(Error::TooManyPoints {
+                n_points: 9,
+                max_points: 8
+            }),
+            m1.check_capacity(64, 9)
+        );
+        let cycl = M1CyclPrecomp::from_inner(m1.clone(), 32, 4).unwrap();
+        assert_eq!("m1-cycl-precomp", cycl.scheme_name());
+        assert_eq!(8, cycl.max_pts());
+        let precomp = M1Precomp::from_inner(m1, Vec::<Vec<_>>::new()).unwrap();
+        assert_eq!("m1-precomp", precomp.scheme_name());
+        assert_eq!(64, precomp.max_coeffs());
+
+        let m2 = M2NoPrecomp::<Bls, ArkMSMEngine<Bls>>::new(64, &mut test_rng());
+        assert_eq!("m2-no-precomp", m2.scheme_name());
+        assert_eq!(96, m2.proof_size_bytes());
+        assert_eq!(Ok(()), m2.check_capacity(64, 1000));
+        let m2 = M2Precomp::from_inner(m2, Vec::<Vec<_>>::new()).unwrap();
+        assert_eq!("m2-precomp", m2.scheme_name());
+
+        type Bn = ark_bn254::Bn254;
+        let bn = M1NoPrecomp::<Bn, ArkMSMEngine<Bn>>::new(16, 4, &mut test_rng());
+        assert_eq!(32, bn.proof_size_bytes());
+    }
+
+    #[test]
+    fn test_memory_estimate() {
+        use core::mem::size_of;
+
+        use crate::{any_scheme::AnyScheme, traits::MemoryEstimate};
+
+        type Bls = ark_bls12_381::Bls12_381;
+        type M1 = M1NoPrecomp<Bls, ArkMSMEngine<Bls>>;
+        let est = M1::memory_estimate(64, 8, 4);
+        // The affine powers are half of the setup, the rest is the prepared copy
+        let m1 = M1::new(64, 8, &mut test_rng());
+        let powers = m1.powers_of_g1.len() * size_of::<ark_bls12_381::G1Affine>()
+            + m1.powers_of_g2.len() * size_of::<ark_bls12_381::G2Affine>();
+        assert_eq!(2 * powers, est.srs);
+        assert_eq!(0, est.precompute);
+        assert_eq!(est.srs + est.per_open, est.total());
+
+        // Precomputation grows with the point sets, and doesn't change the setup
+        type Precomp = M1Precomp<Bls, ArkMSMEngine<Bls>>;
+        let one = Precomp::memory_estimate(64, 8, 1);
+        let many = Precomp::memory_estimate(64, 8, 10);
+        assert_eq!(est.srs, one.srs);
+        assert_eq!(10 * one.precompute, many.precompute);
+        assert!(one.per_open < est.per_open);
+
+        let any = AnyScheme::<Bls, ArkMSMEngine<Bls>>::memory_estimate(64, 8, 10);
+        assert_eq!(
+            MemoryEstimate {
+                srs: est.srs,
+                precompute: many.precompute,
+                per_open: est.per_open,
+            },
+            any
+        );
+        let m2 = M2NoPrecomp::<Bls, ArkMSMEngine<Bls>>::memory_estimate(64, 8, 10);
+        assert!(m2.srs < est.srs);
+    }
+
+    #[test]
+    fn test_bls12_381() {
+        assert_eq!(1 << 32, max_domain_size::<ark_bls12_381::Fr>());
+    }
+}
@@ -0,0 +1,310 @@
+//! Setups from several runs of a ceremony. Networks which periodically re-run their ceremony
+//! commit and prove with the newest setup, but still need to check proofs made with older ones.
+//! [`EpochSetups`] holds a setup per [`SrsEpoch`], tags the commitments and proofs it makes with
+//! the epoch of the setup they were made with, and verifies tagged values with that same setup.
+use ark_ec::pairing::Pairing;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::vec::Vec;
+
+use crate::{
+    traits::{Committer, PolyMultiProofNoPrecomp, TranscriptProtocol},
+    Commitment, Error,
+};
+
+/// The id of one run of a ceremony. Later runs have larger ids.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct SrsEpoch(pub u64);
+
+/// A commitment or proof along with the epoch of the setup it was made with. It serializes as
+/// the epoch followed by the value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tagged<T> {
+    /// The epoch of the setup `inner` was made with
+    pub epoch: SrsEpoch,
+    /// The commitment or proof
+    pub inner: T,
+}
+
+impl<T: CanonicalSerialize> CanonicalSerialize for Tagged<T> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.epoch.serialize_with_mode(&mut writer, compress)?;
+        self.inner.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.epoch.serialized_size(compress) + self.inner.serialized_size(compress)
+    }
+}
+
+impl<T: Valid> Valid for Tagged<T> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.inner.check()
+    }
+}
+
+impl<T: CanonicalDeserialize> CanonicalDeserialize for Tagged<T> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let epoch = SrsEpoch::deserialize_with_mode(&mut reader, compress, validate)?;
+        let inner = T::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self { epoch, inner })
+    }
+}
+
+/// A setup per epoch, which commits and opens with the latest one
+#[derive(Clone, Debug)]
+pub struct EpochSetups<S> {
+    /// The setups sorted by epoch, so the latest is last
+    setups: Vec<(SrsEpoch, S)>,
+}
+
+impl<S> EpochSetups<S> {
+    /// Start with the setup `setup` from `epoch`
+    pub fn new(epoch: SrsEpoch, setup: S) -> Self {
+        Self {
+            setups: ark_std::vec![(epoch, setup)],
+        }
+    }
+
+    /// Add the setup from a new run of the ceremony, which becomes the one used to commit and
+    /// open. Its epoch must be later than every epoch already held.
+    pub fn rotate(&mut self, epoch: SrsEpoch, setup: S) -> Result<(), Error> {
+        let current = self.current_epoch();
+        if epoch <= current {
+            return Err(Error::StaleEpoch {
+                epoch: epoch.0,
+                current: current.0,
+            });
+        }
+        self.setups.push((epoch, setup));
+        Ok(())
+    }
+
+    /// Stop verifying values from `epoch`, returning its setup. The current epoch can't be
+    /// retired.
+    pub fn retire(&mut self, epoch: SrsEpoch) -> Result<S, Error> {
+        let i = self.index(epoch)?;
+        if i + 1 == self.setups.len() {
+            return Err(Error::StaleEpoch {
+                epoch: epoch.0,
+                current: epoch.0,
+            });
+        }
+        Ok(self.setups.remove(i).1)
+    }
+
+    /// The epoch commitments and proofs are made with
+    pub fn current_epoch(&self) -> SrsEpoch {
+        self.setups.last().expect("There's always a setup").0
+    }
+
+    /// The setup commitments and proofs are made with
+    pub fn current(&self) -> &S {
+        &self.setups.last().expect("There's always a setup").1
+    }
+
+    /// The epochs held, oldest first
+    pub fn epochs(&self) -> impl Iterator<Item = SrsEpoch> + '_ {
+        self.setups.iter().map(|(e, _)| *e)
+    }
+
+    /// The setup from `epoch`
+    pub fn get(&self, epoch: SrsEpoch) -> Result<&S, Error> {
+        Ok(&self.setups[self.index(epoch)?].1)
+    }
+
+    fn index(&self, epoch: SrsEpoch) -> Result<usize, Error> {
+        self.setups
+            .binary_search_by_key(&epoch, |(e, _)| *e)
+            .map_err(|_| Error::UnknownEpoch(epoch.0))
+    }
+
+    /// The setup from `epoch`, which every one of `tags` must also be from
+    fn get_matching(
+        &self,
+        epoch: SrsEpoch,
+        tags: impl IntoIterator<Item = SrsEpoch>,
+    ) -> Result<&S, Error> {
+        if let Some(other) = tags.into_iter().find(|e| *e != epoch) {
+            return Err(Error::EpochMismatch {
+                expected: epoch.0,
+                got: other.0,
+            });
+        }
+        self.get(epoch)
+    }
+}
+
+impl<S> EpochSetups<S> {
+    /// Commit to `poly` with the current setup
+    pub fn commit<E: Pairing>(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+    ) -> Result<Tagged<Commitment<E>>, Error>
+    where
+        S: Committer<E>,
+    {
+        Ok(Tagged {
+            epoch: self.current_epoch(),
+            inner: self.current().commit(poly)?,
+        })
+    }
+
+    /// Open `polys` at `points` with the current setup, as in [`PolyMultiProofNoPrecomp::open`]
+    pub fn open<E: Pairing>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Tagged<S::Proof>, Error>
+    where
+        S: PolyMultiProofNoPrecomp<E>,
+    {
+        Ok(Tagged {
+            epoch: self.current_epoch(),
+            inner: self.current().open(transcript, evals, polys, points)?,
+        })
+    }
+
+    /// Verify `proof` with the setup of its epoch, as in [`PolyMultiProofNoPrecomp::verify`].
+    /// Fails with [`Error::EpochMismatch`] if a commitment is from another epoch, and
+    /// [`Error::UnknownEpoch`] if the epoch's setup isn't held.
+    pub fn verify<E: Pairing>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Tagged<Commitment<E>>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Tagged<S::Proof>,
+    ) -> Result<bool, Error>
+    where
+        S: PolyMultiProofNoPrecomp<E>,
+    {
+        let setup = self.get_matching(proof.epoch, commits.iter().map(|c| c.epoch))?;
+        let commits = commits.iter().map(|c| c.inner.clone()).collect::<Vec<_>>();
+        setup.verify(transcript, &commits, points, evals, &proof.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use super::{EpochSetups, SrsEpoch, Tagged};
+    use crate::{
+        method1::M1NoPrecomp, msm::ArkMSMEngine, poly_ops::evaluate_at_points, test_rng,
+        Commitment, Error,
+    };
+
+    type M1 = M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
+
+    #[test]
+    fn test_epoch_rotation() {
+        let mut setups = EpochSetups::new(SrsEpoch(1), M1::new_insecure_for_testing(1, 64, 8));
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = (0..32)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = [evaluate_at_points(&poly, &points)];
+
+        let old_commit = setups.commit(&poly).unwrap();
+        let old_proof = setups
+            .open(&mut Transcript::new(b"epoch"), &evals, &[&poly], &points)
+            .unwrap();
+        assert_eq!(SrsEpoch(1), old_proof.epoch);
+        let mut bytes = Vec::new();
+        old_commit.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(56, bytes.len());
+        let read = Tagged::<Commitment<Bls12_381>>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(
+            (old_commit.epoch, old_commit.inner.0),
+            (read.epoch, read.inner.0)
+        );
+
+        assert_eq!(
+            Err(Error::StaleEpoch {
+                epoch: 1,
+                current: 1
+            }),
+            setups.rotate(SrsEpoch(1), M1::new_insecure_for_testing(2, 64, 8))
+        );
+        setups
+            .rotate(SrsEpoch(2), M1::new_insecure_for_testing(2, 64, 8))
+            .unwrap();
+        assert_eq!(SrsEpoch(2), setups.current_epoch());
+        let new_commit = setups.commit(&poly).unwrap();
+        assert_ne!(old_commit.inner.0, new_commit.inner.0);
+        let new_proof = setups
+            .open(&mut Transcript::new(b"epoch"), &evals, &[&poly], &points)
+            .unwrap();
+
+        // Both epochs verify with their own setup
+        for (commit, proof) in [(&old_commit, &old_proof), (&new_commit, &new_proof)] {
+            assert_eq!(
+                Ok(true),
+                setups.verify(
+                    &mut Transcript::new(b"epoch"),
+                    core::slice::from_ref(commit),
+                    &points,
+                    &evals,
+                    proof
+                )
+            );
+        }
+        assert_eq!(
+            Err(Error::EpochMismatch {
+                expected: 2,
+                got: 1
+            }),
+            setups.verify(
+                &mut Transcript::new(b"epoch"),
+                core::slice::from_ref(&old_commit),
+                &points,
+                &evals,
+                &new_proof
+            )
+        );
+
+        setups.retire(SrsEpoch(1)).unwrap();
+        assert_eq!(vec![SrsEpoch(2)], setups.epochs().collect::<Vec<_>>());
+        assert_eq!(
+            Err(Error::UnknownEpoch(1)),
+            setups.verify(
+                &mut Transcript::new(b"epoch"),
+                &[old_commit],
+                &points,
+                &evals,
+                &old_proof
+            )
+        );
+        assert!(setups.retire(SrsEpoch(2)).is_err());
+    }
+}
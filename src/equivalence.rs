@@ -0,0 +1,354 @@
+//! Proofs of equivalence between a KZG commitment and data committed to some other way, such as
+//! a Merkle root or a blob hash. Both commitments are absorbed into the transcript to derive a
+//! challenge point $z$, and the polynomial is opened at $z$ to $y$. A verifier holding the data
+//! behind the other commitment computes the evaluation at $z$ itself, e.g. with
+//! [`evaluate_data_at`] for data in evaluation form, and checks that it's also $y$. Since $z$
+//! depends on both commitments, they can only agree there if they commit to the same
+//! polynomial, except with negligible probability.
+//!
+//! The root is opaque bytes, so any hash or tree construction can be used.
+//!
+//! The same argument shows two KZG commitments to the same data agree, e.g. when a protocol
+//! migrates to a new trusted setup and has to show its data carried over. The commitments can be
+//! under setups with different trapdoors, or over different pairings with the same scalar field.
+//! [`prove_same_evaluation`] opens both at a given point, and [`prove_migration`] derives the
+//! point from both commitments, which shows they commit to the same polynomial.
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+use crate::{
+    get_challenge, get_field_size,
+    method1::Proof,
+    poly_ops::evaluate_at_points,
+    traits::{Committer, KZGProof, MSMEngine, TranscriptProtocol},
+    transcribe_generic, Commitment, Error,
+};
+
+/// An opening of a committed polynomial at the challenge point derived from it and a data root
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EquivalenceProof<E: Pairing> {
+    /// The evaluation $y$ at the challenge point
+    pub value: E::ScalarField,
+    /// The KZG proof of the evaluation
+    pub proof: Proof<E>,
+}
+
+/// Derive the challenge point $z$ from the data root and the KZG commitment
+pub fn equivalence_challenge<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    data_root: &[u8],
+    commit: &Commitment<E>,
+) -> Result<E::ScalarField, Error> {
+    transcript.append_message(b"equivalence root", data_root);
+    transcribe_generic(transcript, b"equivalence commit", &commit.0)?;
+    let field_size_bytes = get_field_size::<E::ScalarField>();
+    Ok(get_challenge(
+        transcript,
+        b"equivalence point",
+        field_size_bytes,
+    ))
+}
+
+/// Commit to `poly` and prove that it's equivalent to the data under `data_root`. Returns the
+/// commitment along with the proof.
+pub fn prove_equivalence<E: Pairing, S: KZGProof<E, Proof = Proof<E>> + Committer<E>>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    data_root: &[u8],
+    poly: &[E::ScalarField],
+) -> Result<(Commitment<E>, EquivalenceProof<E>), Error> {
+    let commit = srs.commit(poly)?;
+    let point = equivalence_challenge(transcript, data_root, &commit)?;
+    let value = evaluate_at_points(poly, &[point])[0];
+    let witness = srs.compute_witness_polynomial(poly.to_vec(), point)?;
+    let proof = srs.open(witness)?;
+    Ok((commit, EquivalenceProof { value, proof }))
+}
+
+/// Verify the KZG side of an equivalence proof, returning the challenge point if it's valid. The
+/// data side is checked by evaluating the data under `data_root` at the challenge point and
+/// comparing it to `proof.value`.
+pub fn verify_equivalence<E: Pairing, M: MSMEngine<E = E>, S: KZGProof<E, Proof = Proof<E>>>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    data_root: &[u8],
+    commit: &Commitment<E>,
+    proof: &EquivalenceProof<E>,
+) -> Result<Option<E::ScalarField>, Error> {
+    let point = equivalence_challenge(transcript, data_root, commit)?;
+    let valid = srs.verify::<M>(commit, point, proof.value, &proof.proof)?;
+    Ok(valid.then_some(point))
+}
+
+/// Openings of two commitments to the same polynomial, under different setups, to the same value
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SameEvaluationProof<E1: Pairing, E2: Pairing<ScalarField = E1::ScalarField>> {
+    /// The evaluation $y$ of both commitments at the point
+    pub value: E1::ScalarField,
+    /// The KZG proof of the evaluation under the first setup
+    pub proof_a: Proof<E1>,
+    /// The KZG proof of the evaluation under the second setup
+    pub proof_b: Proof<E2>,
+}
+
+/// Prove that `poly` committed to under `srs_a` and under `srs_b` evaluates to the same value at
+/// `point`
+pub fn prove_same_evaluation<E1, S1, E2, S2>(
+    srs_a: &S1,
+    srs_b: &S2,
+    poly: &[E1::ScalarField],
+    point: E1::ScalarField,
+) -> Result<SameEvaluationProof<E1, E2>, Error>
+where
+    E1: Pairing,
+    S1: KZGProof<E1, Proof = Proof<E1>>,
+    E2: Pairing<ScalarField = E1::ScalarField>,
+    S2: KZGProof<E2, Proof = Proof<E2>>,
+{
+    let value = evaluate_at_points(poly, &[point])[0];
+    let proof_a = srs_a.open(srs_a.compute_witness_polynomial(poly.to_vec(), point)?)?;
+    let proof_b = srs_b.open(srs_b.compute_witness_polynomial(poly.to_vec(), point)?)?;
+    Ok(SameEvaluationProof {
+        value,
+        proof_a,
+        proof_b,
+    })
+}
+
+/// Verify that `commit_a` under `srs_a` and `commit_b` under `srs_b` both evaluate to
+/// `proof.value` at `point`
+pub fn verify_same_evaluation<E1, M1, S1, E2, M2, S2>(
+    srs_a: &S1,
+    srs_b: &S2,
+    commit_a: &Commitment<E1>,
+    commit_b: &Commitment<E2>,
+    point: E1::ScalarField,
+    proof: &SameEvaluationProof<E1, E2>,
+) -> Result<bool, Error>
+where
+    E1: Pairing,
+    M1: MSMEngine<E = E1>,
+    S1: KZGProof<E1, Proof = Proof<E1>>,
+    E2: Pairing<ScalarField = E1::ScalarField>,
+    M2: MSMEngine<E = E2>,
+    S2: KZGProof<E2, Proof = Proof<E2>>,
+{
+    Ok(
+        srs_a.verify::<M1>(commit_a, point, proof.value, &proof.proof_a)?
+            && srs_b.verify::<M2>(commit_b, point, proof.value, &proof.proof_b)?,
+    )
+}
+
+/// Derive the challenge point $z$ from the commitments under the old and new setups
+pub fn migration_challenge<E1: Pairing, E2: Pairing<ScalarField = E1::ScalarField>>(
+    transcript: &mut impl TranscriptProtocol,
+    commit_a: &Commitment<E1>,
+    commit_b: &Commitment<E2>,
+) -> Result<E1::ScalarField, Error> {
+    transcribe_generic(transcript, b"migration commit a", &commit_a.0)?;
+    transcribe_generic(transcript, b"migration commit b", &commit_b.0)?;
+    let field_size_bytes = get_field_size::<E1::ScalarField>();
+    Ok(get_challenge(
+        transcript,
+        b"migration point",
+        field_size_bytes,
+    ))
+}
+
+/// Commit to `poly` under both setups and prove that the commitments are to the same
+/// polynomial. Returns the commitments along with the proof.
+#[allow(clippy::type_complexity)]
+pub fn prove_migration<E1, S1, E2, S2>(
+    srs_a: &S1,
+    srs_b: &S2,
+    transcript: &mut impl TranscriptProtocol,
+    poly: &[E1::ScalarField],
+) -> Result<(Commitment<E1>, Commitment<E2>, SameEvaluationProof<E1, E2>), Error>
+where
+    E1: Pairing,
+    S1: KZGProof<E1, Proof = Proof<E1>> + Committer<E1>,
+    E2: Pairing<ScalarField = E1::ScalarField>,
+    S2: KZGProof<E2, Proof = Proof<E2>> + Committer<E2>,
+{
+    let commit_a = srs_a.commit(poly)?;
+    let commit_b = srs_b.commit(poly)?;
+    let point = migration_challenge(transcript, &commit_a, &commit_b)?;
+    let proof = prove_same_evaluation(srs_a, srs_b, poly, point)?;
+    Ok((commit_a, commit_b, proof))
+}
+
+/// Verify a proof made with [`prove_migration`]
+pub fn verify_migration<E1, M1, S1, E2, M2, S2>(
+    srs_a: &S1,
+    srs_b: &S2,
+    transcript: &mut impl TranscriptProtocol,
+    commit_a: &Commitment<E1>,
+    commit_b: &Commitment<E2>,
+    proof: &SameEvaluationProof<E1, E2>,
+) -> Result<bool, Error>
+where
+    E1: Pairing,
+    M1: MSMEngine<E = E1>,
+    S1: KZGProof<E1, Proof = Proof<E1>>,
+    E2: Pairing<ScalarField = E1::ScalarField>,
+    M2: MSMEngine<E = E2>,
+    S2: KZGProof<E2, Proof = Proof<E2>>,
+{
+    let point = migration_challenge(transcript, commit_a, commit_b)?;
+    verify_same_evaluation::<E1, M1, S1, E2, M2, S2>(srs_a, srs_b, commit_a, commit_b, point, proof)
+}
+
+/// Evaluate the polynomial with evaluations `evals` over `domain` at `point`, without
+/// interpolating it. This is how the data side computes its evaluation at the challenge point.
+pub fn evaluate_data_at<F: PrimeField>(
+    evals: &[F],
+    domain: &Radix2EvaluationDomain<F>,
+    point: F,
+) -> Result<F, Error> {
+    if evals.len() != domain.size() {
+        return Err(Error::InvalidInputLength {
+            expected: domain.size(),
+            got: evals.len(),
+        });
+    }
+    Ok(domain
+        .evaluate_all_lagrange_coefficients(point)
+        .iter()
+        .zip(evals)
+        .fold(F::zero(), |acc, (l, e)| acc + *l * e))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::{vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::{
+        evaluate_data_at, prove_equivalence, prove_migration, prove_same_evaluation,
+        verify_equivalence, verify_migration, verify_same_evaluation, EquivalenceProof,
+    };
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        traits::Committer,
+        Error,
+    };
+
+    #[test]
+    fn test_equivalence() {
+        type M = BlstMSMEngine;
+        let s = M1NoPrecomp::<Bls12_381, M>::new(64, 4, &mut test_rng());
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let data = (0..64)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let root = b"merkle root of the data";
+        let poly = domain.ifft(&data);
+
+        let (commit, proof) =
+            prove_equivalence(&s, &mut Transcript::new(b"eq"), root, &poly).unwrap();
+        let point =
+            verify_equivalence::<_, M, _>(&s, &mut Transcript::new(b"eq"), root, &commit, &proof)
+                .unwrap()
+                .expect("Proof should verify");
+        // The data side agrees on the evaluation
+        assert_eq!(
+            proof.value,
+            evaluate_data_at(&data, &domain, point).unwrap()
+        );
+
+        // A different root gives a different point, where the proof doesn't hold
+        assert_eq!(
+            Ok(None),
+            verify_equivalence::<_, M, _>(
+                &s,
+                &mut Transcript::new(b"eq"),
+                b"another root",
+                &commit,
+                &proof
+            )
+        );
+        let wrong_value = EquivalenceProof {
+            value: proof.value + Fr::from(1u64),
+            proof: proof.proof.clone(),
+        };
+        assert_eq!(
+            Ok(None),
+            verify_equivalence::<_, M, _>(
+                &s,
+                &mut Transcript::new(b"eq"),
+                root,
+                &commit,
+                &wrong_value
+            )
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 64,
+                got: 63
+            }),
+            evaluate_data_at(&data[..63], &domain, point)
+        );
+    }
+
+    #[test]
+    fn test_migration() {
+        type M = BlstMSMEngine;
+        type A = ArkMSMEngine<Bls12_381>;
+        // The old and new setups have different trapdoors
+        let old = M1NoPrecomp::<Bls12_381, M>::new(64, 4, &mut test_rng());
+        let new = M1NoPrecomp::<Bls12_381, A>::new(64, 4, &mut test_rng());
+        let poly = (0..64)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+
+        let (commit_a, commit_b, proof) =
+            prove_migration(&old, &new, &mut Transcript::new(b"mig"), &poly).unwrap();
+        assert_ne!(commit_a.0, commit_b.0);
+        assert_eq!(
+            Ok(true),
+            verify_migration::<_, M, _, _, A, _>(
+                &old,
+                &new,
+                &mut Transcript::new(b"mig"),
+                &commit_a,
+                &commit_b,
+                &proof
+            )
+        );
+
+        // A commitment to other data under the new setup can't be shown to carry over
+        let mut other = poly.clone();
+        other[3] += Fr::from(1u64);
+        let other_commit = new.commit(&other).unwrap();
+        assert_eq!(
+            Ok(false),
+            verify_migration::<_, M, _, _, A, _>(
+                &old,
+                &new,
+                &mut Transcript::new(b"mig"),
+                &commit_a,
+                &other_commit,
+                &proof
+            )
+        );
+
+        // At a given point
+        let point = Fr::rand(&mut test_rng());
+        let proof = prove_same_evaluation(&old, &new, &poly, point).unwrap();
+        let verify = |commit_b, point| {
+            verify_same_evaluation::<_, M, _, _, A, _>(
+                &old, &new, &commit_a, commit_b, point, &proof,
+            )
+        };
+        assert_eq!(Ok(true), verify(&commit_b, point));
+        assert_eq!(Ok(false), verify(&other_commit, point));
+        assert_eq!(Ok(false), verify(&commit_b, point + Fr::from(1u64)));
+    }
+}
@@ -0,0 +1,367 @@
+//! Evaluations of several polynomials at several points, stored either a row per polynomial
+//! (poly-major, the layout of `evals: &[impl AsRef<[F]>]` everywhere else) or a column per point
+//! (point-major, the layout data availability samples usually come in). An [`EvalMatrix`] borrows
+//! the evaluations in either layout, and method 1 can open and verify with it directly rather
+//! than transposing point-major data into rows first.
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, One};
+use ark_std::vec::Vec;
+
+use crate::{
+    field_ops::mul_add_assign,
+    gen_powers,
+    lagrange::LagrangeInterpContext,
+    method1::{M1NoPrecomp, Proof},
+    traits::{MSMEngine, TranscriptProtocol},
+    transcribe_evals_with, transcribe_points,
+    transcript::TranscriptEncoding,
+    vanishing_polynomial, Commitment, Error,
+};
+
+/// How the evaluations of an [`EvalMatrix`] are stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvalLayout {
+    /// The evaluations of each polynomial at all the points are contiguous
+    PolyMajor,
+    /// The evaluations of all the polynomials at each point are contiguous
+    PointMajor,
+}
+
+/// A borrowed matrix of the evaluations of `n_polys` polynomials at `n_points` points
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalMatrix<'a, F> {
+    data: &'a [F],
+    n_polys: usize,
+    n_points: usize,
+    layout: EvalLayout,
+}
+
+impl<'a, F: Field> EvalMatrix<'a, F> {
+    /// A matrix of `data` in `layout`, which must have `n_polys * n_points` elements
+    pub fn new(
+        data: &'a [F],
+        n_polys: usize,
+        n_points: usize,
+        layout: EvalLayout,
+    ) -> Result<Self, Error> {
+        if data.len() != n_polys * n_points {
+            return Err(Error::InvalidInputLength {
+                expected: n_polys * n_points,
+                got: data.len(),
+            });
+        }
+        Ok(Self {
+            data,
+            n_polys,
+            n_points,
+            layout,
+        })
+    }
+
+    /// A matrix of `data` holding the evaluations of each polynomial in turn
+    pub fn poly_major(data: &'a [F], n_polys: usize, n_points: usize) -> Result<Self, Error> {
+        Self::new(data, n_polys, n_points, EvalLayout::PolyMajor)
+    }
+
+    /// A matrix of `data` holding the evaluations at each point in turn
+    pub fn point_major(data: &'a [F], n_polys: usize, n_points: usize) -> Result<Self, Error> {
+        Self::new(data, n_polys, n_points, EvalLayout::PointMajor)
+    }
+
+    /// The number of polynomials
+    pub fn n_polys(&self) -> usize {
+        self.n_polys
+    }
+
+    /// The number of points
+    pub fn n_points(&self) -> usize {
+        self.n_points
+    }
+
+    /// How the evaluations are stored
+    pub fn layout(&self) -> EvalLayout {
+        self.layout
+    }
+
+    /// The evaluation of polynomial `poly` at point `point`
+    pub fn get(&self, poly: usize, point: usize) -> Option<F> {
+        if poly >= self.n_polys || point >= self.n_points {
+            return None;
+        }
+        Some(self.data[self.index(poly, point)])
+    }
+
+    /// The evaluations of polynomial `poly`, if they're contiguous
+    pub fn row(&self, poly: usize) -> Option<&'a [F]> {
+        match self.layout {
+            EvalLayout::PolyMajor if poly < self.n_polys => {
+                Some(&self.data[poly * self.n_points..(poly + 1) * self.n_points])
+            }
+            _ => None,
+        }
+    }
+
+    /// The evaluations at point `point`, if they're contiguous
+    pub fn column(&self, point: usize) -> Option<&'a [F]> {
+        match self.layout {
+            EvalLayout::PointMajor if point < self.n_points => {
+                Some(&self.data[point * self.n_polys..(point + 1) * self.n_polys])
+            }
+            _ => None,
+        }
+    }
+
+    /// The transposed matrix over the same data, whose polynomials are this matrix's points
+    pub fn transpose(&self) -> Self {
+        Self {
+            data: self.data,
+            n_polys: self.n_points,
+            n_points: self.n_polys,
+            layout: match self.layout {
+                EvalLayout::PolyMajor => EvalLayout::PointMajor,
+                EvalLayout::PointMajor => EvalLayout::PolyMajor,
+            },
+        }
+    }
+
+    /// Copies the evaluations into a row per polynomial, for the methods which take
+    /// `evals: &[impl AsRef<[F]>]`
+    pub fn to_rows(&self) -> Vec<Vec<F>> {
+        (0..self.n_polys)
+            .map(|i| {
+                (0..self.n_points)
+                    .map(|j| self.data[self.index(i, j)])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Computes $\sum_i \gamma_i y_{i, j}$ at each point $j$, as
+    /// [`linear_combination_of_evals`](crate::lagrange::linear_combination_of_evals) does for rows
+    pub(crate) fn linear_combination(&self, scalars: &[F]) -> Vec<F> {
+        match self.layout {
+            EvalLayout::PolyMajor => {
+                let mut targets = ark_std::vec![F::zero(); self.n_points];
+                for (row, scalar) in self.data.chunks_exact(self.n_points.max(1)).zip(scalars) {
+                    mul_add_assign(&mut targets, *scalar, row);
+                }
+                targets
+            }
+            EvalLayout::PointMajor => self
+                .data
+                .chunks_exact(self.n_polys.max(1))
+                .take(self.n_points)
+                .map(|col| col.iter().zip(scalars).map(|(y, s)| *y * s).sum())
+                .collect(),
+        }
+    }
+
+    /// Commits the points and evals to the transcript in the same format as rows are
+    pub(crate) fn transcribe<E: Pairing<ScalarField = F>>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[F],
+        encoding: &TranscriptEncoding,
+    ) -> Result<(), Error> {
+        encoding.absorb_commits(transcript, commits)?;
+        transcribe_evals_with(
+            transcript,
+            self.n_polys,
+            self.n_points,
+            |i, j| self.data[self.index(i, j)],
+            encoding,
+        )?;
+        transcribe_points(transcript, points, encoding)
+    }
+
+    /// Checks the matrix has a row for each of `n_rows` polynomials or commitments, and a column
+    /// for each of `n_points` points
+    fn check_shape(&self, n_rows: usize, n_points: usize) -> Result<(), Error> {
+        if self.n_polys != n_rows {
+            return Err(Error::EvalsAndPolysDifferentSizes {
+                n_eval_rows: self.n_polys,
+                n_polys: n_rows,
+            });
+        }
+        if self.n_points != n_points {
+            return Err(Error::EvalsAndPointsDifferentSizes {
+                n_evals: self.n_points,
+                n_points,
+            });
+        }
+        Ok(())
+    }
+
+    fn index(&self, poly: usize, point: usize) -> usize {
+        match self.layout {
+            EvalLayout::PolyMajor => poly * self.n_points + point,
+            EvalLayout::PointMajor => point * self.n_polys + poly,
+        }
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
+    /// Open the polynomials at `points` with their evaluations in a matrix of either layout.
+    /// The transcript and proof are the same as opening with the rows of `evals`.
+    pub fn open_eval_matrix(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &EvalMatrix<E::ScalarField>,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        evals.check_shape(polys.len(), points.len())?;
+        self.check_n_points(points.len())?;
+        evals.transcribe(
+            transcript,
+            &self.transcript_commits(polys)?,
+            points,
+            &self.transcript_encoding,
+        )?;
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+        self.open_with_gamma(polys, gamma, &vanishing_polynomial(points))
+    }
+
+    /// Verify an opening at `points` with the evaluations in a matrix of either layout
+    pub fn verify_eval_matrix(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &EvalMatrix<E::ScalarField>,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if evals.n_polys != commits.len() {
+            return Err(Error::EvalsAndCommitsDifferentSizes {
+                n_evals: evals.n_polys,
+                n_commits: commits.len(),
+            });
+        }
+        evals.check_shape(commits.len(), points.len())?;
+        self.check_n_points(points.len())?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        evals.transcribe(transcript, commits, points, &self.transcript_encoding)?;
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+        let gammas = gen_powers(gamma, commits.len());
+        let targets = evals.linear_combination(&gammas);
+        let gamma_ris = lag_ctx
+            .lagrange_interp_linear_combo(&[targets], &[E::ScalarField::one()])?
+            .coeffs;
+        let lhs = self.verifier_lhs_with_interp(commits, gammas, gamma_ris)?;
+        let g2_zeros =
+            M::multi_scalar_mul_g2(&self.g2_precomp, &vanishing_polynomial(points).coeffs)?;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            self.powers_of_g2[0],
+            proof.0,
+            g2_zeros.into_affine(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    use super::{EvalLayout, EvalMatrix};
+    use crate::{
+        method1::M1NoPrecomp, msm::ArkMSMEngine, test_rng, testing::gen_openings,
+        traits::PolyMultiProofNoPrecomp, Error,
+    };
+
+    #[test]
+    fn test_eval_matrix_views() {
+        let data = (0..6u64).map(Fr::from).collect::<Vec<_>>();
+        let m = EvalMatrix::point_major(&data, 2, 3).unwrap();
+        assert_eq!(Some(Fr::from(3u64)), m.get(1, 1));
+        assert_eq!(None, m.get(2, 0));
+        assert_eq!(None, m.row(0));
+        assert_eq!(Some(&data[2..4]), m.column(1));
+        let rows = m.to_rows();
+        assert_eq!(vec![data[0], data[2], data[4]], rows[0]);
+
+        let t = m.transpose();
+        assert_eq!(EvalLayout::PolyMajor, t.layout());
+        assert_eq!((3, 2), (t.n_polys(), t.n_points()));
+        assert_eq!(Some(&data[2..4]), t.row(1));
+        assert_eq!(m, t.transpose());
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 4,
+                got: 6
+            }),
+            EvalMatrix::poly_major(&data, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_eval_matrix_open_verify() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 30, &mut test_rng());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, &points)
+            .unwrap();
+
+        let poly_major = evals.concat();
+        let point_major = (0..points.len())
+            .flat_map(|j| evals.iter().map(move |e| e[j]))
+            .collect::<Vec<_>>();
+        let matrices = [
+            EvalMatrix::poly_major(&poly_major, 3, 10).unwrap(),
+            EvalMatrix::point_major(&point_major, 3, 10).unwrap(),
+        ];
+        for m in matrices {
+            assert_eq!(evals, m.to_rows());
+            let matrix_proof = s
+                .open_eval_matrix(&mut Transcript::new(b"test"), &m, &coeffs, &points)
+                .unwrap();
+            assert_eq!(proof.0, matrix_proof.0);
+            assert_eq!(
+                Ok(true),
+                s.verify_eval_matrix(&mut Transcript::new(b"test"), &commits, &points, &m, &proof)
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_eval_matrix(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    &points,
+                    &m.transpose().transpose(),
+                    &crate::method1::Proof(commits[0].0)
+                )
+            );
+            assert_eq!(
+                Err(Error::EvalsAndCommitsDifferentSizes {
+                    n_evals: 3,
+                    n_commits: 2
+                }),
+                s.verify_eval_matrix(
+                    &mut Transcript::new(b"test"),
+                    &commits[..2],
+                    &points,
+                    &m,
+                    &proof
+                )
+            );
+        }
+
+        let mut tampered = point_major.clone();
+        tampered[4] += Fr::from(1u64);
+        let m = EvalMatrix::point_major(&tampered, 3, 10).unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_eval_matrix(&mut Transcript::new(b"test"), &commits, &points, &m, &proof)
+        );
+    }
+}
@@ -0,0 +1,512 @@
+//! Verifying method 1 proofs over BN254 on Ethereum, with the `ecAdd`, `ecMul` and pairing
+//! precompiles at `0x06`, `0x07` and `0x08`.
+//!
+//! The merlin transcript isn't practical to run on chain, so proofs for the EVM are opened with
+//! an [`EvmTranscript`], which hashes with keccak256, and a scheme using
+//! [`TranscriptEncoding::big_endian`] to match the EVM's word layout. The transcript starts from
+//! the commitments, so the challenge is bound to them without the caller absorbing them.
+//!
+//! An [`EvmVerifier`] is made for one point set of an [`M1Precomp`]. It generates a reference
+//! Solidity contract with the point set's constants built in, the calldata for that contract, and
+//! the input of the pairing check the contract makes. Points are encoded as in EIP-196 and
+//! EIP-197: big endian 32 byte words, with the imaginary part of each G2 coordinate first and the
+//! point at infinity as zeros.
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{fmt::Write, string::String, vec::Vec};
+
+use crate::{
+    check_verify_sizes, curve_msm, gen_powers,
+    lagrange::linear_combination_of_evals,
+    method1::{precompute::M1Precomp, Proof},
+    traits::{MSMEngine, TranscriptProtocol},
+    transcript::TranscriptEncoding,
+    Commitment, Error,
+};
+
+/// The size of a pairing check input, two G1 and G2 pairs
+pub const PAIRING_INPUT_SIZE: usize = 384;
+
+/// The keccak256 hash of `data`, as the EVM's `KECCAK256` opcode computes it
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE: usize = 136;
+    fn absorb(state: &mut [u64; 25], block: &[u8]) {
+        for (lane, word) in state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(word.try_into().expect("Chunks are 8 bytes"));
+        }
+        keccak::f1600(state);
+    }
+
+    let mut state = [0u64; 25];
+    let mut blocks = data.chunks_exact(RATE);
+    for block in &mut blocks {
+        absorb(&mut state, block);
+    }
+    let rem = blocks.remainder();
+    let mut last = [0u8; RATE];
+    last[..rem.len()].copy_from_slice(rem);
+    last[rem.len()] ^= 0x01;
+    last[RATE - 1] ^= 0x80;
+    absorb(&mut state, &last);
+
+    let mut out = [0u8; 32];
+    for (word, lane) in out.chunks_exact_mut(8).zip(state) {
+        word.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+/// A transcript which the EVM can replay. Messages are concatenated, ignoring their labels, and a
+/// challenge is the keccak256 hash of everything absorbed since the last one and the last
+/// challenge, so the contract gets the same challenge as `keccak256(abi.encodePacked(..))`.
+#[derive(Clone, Debug)]
+pub struct EvmTranscript {
+    state: Vec<u8>,
+}
+
+impl EvmTranscript {
+    /// Start a transcript from the commitments being opened
+    pub fn new(commits: &[Commitment<Bn254>]) -> Self {
+        let mut state = Vec::with_capacity(64 * commits.len());
+        for c in commits {
+            state.extend_from_slice(&encode_g1(&c.0));
+        }
+        Self { state }
+    }
+}
+
+impl TranscriptProtocol for EvmTranscript {
+    fn append_message(&mut self, _label: &'static [u8], message: &[u8]) {
+        self.state.extend_from_slice(message);
+    }
+
+    fn challenge_bytes(&mut self, _label: &'static [u8], dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let hash = keccak256(&self.state);
+            chunk.copy_from_slice(&hash[..chunk.len()]);
+            self.state = hash.to_vec();
+        }
+    }
+}
+
+fn encode_field<F: PrimeField>(f: &F, out: &mut [u8]) {
+    out.copy_from_slice(&f.into_bigint().to_bytes_be());
+}
+
+fn encode_fq2(f: &Fq2, out: &mut [u8]) {
+    encode_field(&f.c1, &mut out[..32]);
+    encode_field(&f.c0, &mut out[32..]);
+}
+
+/// Encode a scalar as a big endian word
+pub fn encode_fr(f: &Fr) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    encode_field(f, &mut out);
+    out
+}
+
+/// Encode a G1 point as its big endian x and y coordinates
+pub fn encode_g1(p: &G1Affine) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    if let Some((x, y)) = p.xy() {
+        encode_field::<Fq>(x, &mut out[..32]);
+        encode_field::<Fq>(y, &mut out[32..]);
+    }
+    out
+}
+
+/// Encode a G2 point as its x and y coordinates, each with the imaginary part first
+pub fn encode_g2(p: &G2Affine) -> [u8; 128] {
+    let mut out = [0u8; 128];
+    if let Some((x, y)) = p.xy() {
+        encode_fq2(x, &mut out[..64]);
+        encode_fq2(y, &mut out[64..]);
+    }
+    out
+}
+
+/// Encode a proof as the `uint256[2]` the Solidity verifier takes
+pub fn encode_proof_for_evm(proof: &Proof<Bn254>) -> [u8; 64] {
+    encode_g1(&proof.0)
+}
+
+/// Verifies method 1 proofs over BN254 at one point set, the way the EVM does
+#[derive(Clone, Debug)]
+pub struct EvmVerifier {
+    points: Vec<Fr>,
+    g2: G2Affine,
+    g2_zeros: G2Affine,
+    lagrange_g1: Vec<G1Affine>,
+}
+
+impl EvmVerifier {
+    /// A verifier for the point set at `point_set_index` of `pmp`. Proofs must be opened with an
+    /// [`EvmTranscript`] by a scheme with [`TranscriptEncoding::big_endian`].
+    pub fn new<M: MSMEngine<E = Bn254>>(
+        pmp: &M1Precomp<Bn254, M>,
+        point_set_index: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            g2_zeros: pmp.g2_zeros(point_set_index)?,
+            lagrange_g1: pmp.lagrange_g1(point_set_index)?.to_vec(),
+            points: pmp.point_sets()[point_set_index].clone(),
+            g2: pmp.inner.powers_of_g2[0],
+        })
+    }
+
+    /// The points proofs are opened at
+    pub fn points(&self) -> &[Fr] {
+        &self.points
+    }
+
+    /// The challenge $\gamma$ the contract draws for an opening of `commits` to `evals`
+    pub fn challenge(
+        &self,
+        commits: &[Commitment<Bn254>],
+        evals: &[impl AsRef<[Fr]>],
+    ) -> Result<Fr, Error> {
+        check_verify_sizes(commits, evals, self.points.len())?;
+        let encoding = TranscriptEncoding::big_endian();
+        let mut transcript = EvmTranscript::new(commits);
+        encoding.absorb_evals(&mut transcript, evals, self.points.len())?;
+        encoding.absorb_points(&mut transcript, &self.points)?;
+        Ok(encoding.squeeze_gamma(&mut transcript))
+    }
+
+    /// The input to the pairing precompile which checks the proof,
+    /// $e(\sum \gamma^i [f_i(x) - r_i(x)]_1, \[1\]_2) \cdot e(-\pi, [Z(x)]_2) = 1$.
+    /// This is [`PAIRING_INPUT_SIZE`] bytes.
+    pub fn pairing_calldata(
+        &self,
+        commits: &[Commitment<Bn254>],
+        evals: &[impl AsRef<[Fr]>],
+        proof: &Proof<Bn254>,
+    ) -> Result<Vec<u8>, Error> {
+        let gamma = self.challenge(commits, evals)?;
+        let gammas = gen_powers(gamma, commits.len());
+        let targets = linear_combination_of_evals(evals, &gammas, self.points.len());
+        let cms = commits.iter().map(|c| c.0).collect::<Vec<_>>();
+        let lhs = curve_msm::<G1Projective>(&cms, &gammas)?
+            - curve_msm::<G1Projective>(&self.lagrange_g1, &targets)?;
+
+        let mut out = Vec::with_capacity(PAIRING_INPUT_SIZE);
+        out.extend_from_slice(&encode_g1(&lhs.into_affine()));
+        out.extend_from_slice(&encode_g2(&self.g2));
+        out.extend_from_slice(&encode_g1(&(-proof.0.into_group()).into_affine()));
+        out.extend_from_slice(&encode_g2(&self.g2_zeros));
+        Ok(out)
+    }
+
+    /// The ABI encoded call of the contract's
+    /// `verify(uint256[] commits, uint256[] evals, uint256[2] proof)`, with the commitments as
+    /// x, y pairs and the evaluations of each polynomial one after the other
+    pub fn verify_calldata(
+        &self,
+        commits: &[Commitment<Bn254>],
+        evals: &[impl AsRef<[Fr]>],
+        proof: &Proof<Bn254>,
+    ) -> Result<Vec<u8>, Error> {
+        check_verify_sizes(commits, evals, self.points.len())?;
+        let commit_words = 2 * commits.len();
+        let eval_words = self.points.len() * evals.len();
+        let mut out = Vec::with_capacity(4 + 32 * (6 + commit_words + eval_words));
+        out.extend_from_slice(&keccak256(b"verify(uint256[],uint256[],uint256[2])")[..4]);
+        // The head is the offsets of the two arrays and the proof inline
+        out.extend_from_slice(&encode_fr(&Fr::from(4 * 32u64)));
+        out.extend_from_slice(&encode_fr(&Fr::from((5 + commit_words as u64) * 32)));
+        out.extend_from_slice(&encode_proof_for_evm(proof));
+        out.extend_from_slice(&encode_fr(&Fr::from(commit_words as u64)));
+        for c in commits {
+            out.extend_from_slice(&encode_g1(&c.0));
+        }
+        out.extend_from_slice(&encode_fr(&Fr::from(eval_words as u64)));
+        for e in evals.iter().flat_map(|e| e.as_ref()) {
+            out.extend_from_slice(&encode_fr(e));
+        }
+        Ok(out)
+    }
+
+    /// A reference Solidity verifier named `contract_name` with this point set built in
+    pub fn solidity(&self, contract_name: &str) -> String {
+        let mut points = String::new();
+        for (i, p) in self.points.iter().enumerate() {
+            let _ = writeln!(points, "        p[{}] = {};", i, hex_word(&encode_fr(p)));
+        }
+        let mut lagrange = String::new();
+        for (i, l) in self.lagrange_g1.iter().enumerate() {
+            let words = encode_g1(l);
+            let _ = writeln!(
+                lagrange,
+                "        l[{}] = [uint256({}), {}];",
+                i,
+                hex_word(&words[..32]),
+                hex_word(&words[32..])
+            );
+        }
+        let mut constants = String::new();
+        for (name, p) in [("G2", &self.g2), ("ZEROS", &self.g2_zeros)] {
+            let words = encode_g2(p);
+            for (j, part) in ["X1", "X0", "Y1", "Y0"].iter().enumerate() {
+                let _ = writeln!(
+                    constants,
+                    "    uint256 internal constant {}_{} = {};",
+                    name,
+                    part,
+                    hex_word(&words[32 * j..32 * (j + 1)])
+                );
+            }
+        }
+        SOLIDITY_TEMPLATE
+            .replace("__NAME__", contract_name)
+            .replace("__N__", &self.points.len().to_string())
+            .replace("__CONSTANTS__\n", &constants)
+            .replace("__POINTS__\n", &points)
+            .replace("__LAGRANGE__\n", &lagrange)
+    }
+}
+
+fn hex_word(word: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + 2 * word.len());
+    out.push_str("0x");
+    for b in word {
+        let _ = write!(out, "{:02x}", b);
+    }
+    out
+}
+
+const SOLIDITY_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.19;
+
+/// @notice Verifies poly-multiproof method 1 openings over BN254 at a fixed set of __N__ points.
+/// Generated by `poly_multiproof::evm::EvmVerifier::solidity`.
+contract __NAME__ {
+    uint256 internal constant R = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    uint256 internal constant Q = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+    uint256 internal constant N_POINTS = __N__;
+__CONSTANTS__
+
+    /// @param commits The commitments as x, y pairs
+    /// @param evals The evaluations of each polynomial at each point, one polynomial after another
+    /// @param proof The proof as an x, y pair
+    function verify(uint256[] calldata commits, uint256[] calldata evals, uint256[2] calldata proof)
+        external
+        view
+        returns (bool)
+    {
+        uint256 nPolys = commits.length / 2;
+        require(nPolys > 0 && commits.length == 2 * nPolys, "bad commits length");
+        require(evals.length == nPolys * N_POINTS, "bad evals length");
+        for (uint256 k = 0; k < evals.length; k++) {
+            require(evals[k] < R, "eval not in field");
+        }
+        uint256 gamma = uint256(keccak256(abi.encodePacked(commits, evals, points()))) % R;
+        return pairing(combine(commits, evals, gamma), proof);
+    }
+
+    /// sum_i gamma^i [f_i(x)]_1 - sum_j (sum_i gamma^i f_i(z_j)) [L_j(x)]_1
+    function combine(uint256[] calldata commits, uint256[] calldata evals, uint256 gamma)
+        internal
+        view
+        returns (uint256[2] memory lhs)
+    {
+        uint256[__N__] memory targets;
+        uint256 g = 1;
+        for (uint256 i = 0; i < commits.length / 2; i++) {
+            lhs = ecAdd(lhs, ecMul([commits[2 * i], commits[2 * i + 1]], g));
+            for (uint256 j = 0; j < N_POINTS; j++) {
+                targets[j] = addmod(targets[j], mulmod(g, evals[i * N_POINTS + j], R), R);
+            }
+            g = mulmod(g, gamma, R);
+        }
+        uint256[2][__N__] memory lagrange = lagrangeCommits();
+        for (uint256 j = 0; j < N_POINTS; j++) {
+            lhs = ecAdd(lhs, ecMul(lagrange[j], R - targets[j]));
+        }
+    }
+
+    /// e(lhs, [1]_2) * e(-proof, [Z(x)]_2) == 1
+    function pairing(uint256[2] memory lhs, uint256[2] calldata proof) internal view returns (bool) {
+        uint256[12] memory input = [
+            lhs[0],
+            lhs[1],
+            G2_X1,
+            G2_X0,
+            G2_Y1,
+            G2_Y0,
+            proof[0],
+            (Q - proof[1]) % Q,
+            ZEROS_X1,
+            ZEROS_X0,
+            ZEROS_Y1,
+            ZEROS_Y0
+        ];
+        uint256[1] memory out;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x08, input, 384, out, 32)
+        }
+        require(ok, "pairing failed");
+        return out[0] == 1;
+    }
+
+    function ecAdd(uint256[2] memory a, uint256[2] memory b) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [a[0], a[1], b[0], b[1]];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x06, input, 128, r, 64)
+        }
+        require(ok, "ecAdd failed");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x07, input, 96, r, 64)
+        }
+        require(ok, "ecMul failed");
+    }
+
+    function points() internal pure returns (uint256[__N__] memory p) {
+__POINTS__
+    }
+
+    function lagrangeCommits() internal pure returns (uint256[2][__N__] memory l) {
+__LAGRANGE__
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+    use ark_ec::{pairing::Pairing, AffineRepr};
+    use ark_ff::{Field, One, PrimeField, Zero};
+    use ark_std::{vec::Vec, UniformRand};
+
+    use super::{
+        encode_fr, encode_proof_for_evm, keccak256, EvmTranscript, EvmVerifier, PAIRING_INPUT_SIZE,
+    };
+    use crate::{
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        msm::ArkMSMEngine,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        transcript::TranscriptEncoding,
+    };
+
+    fn decode_fq(word: &[u8]) -> Fq {
+        Fq::from_be_bytes_mod_order(word)
+    }
+
+    fn decode_g1(words: &[u8]) -> G1Affine {
+        let (x, y) = (decode_fq(&words[..32]), decode_fq(&words[32..]));
+        if x.is_zero() && y.is_zero() {
+            return G1Affine::zero();
+        }
+        G1Affine::new(x, y)
+    }
+
+    fn decode_g2(words: &[u8]) -> G2Affine {
+        let fq2 = |w: &[u8]| Fq2::new(decode_fq(&w[32..]), decode_fq(&w[..32]));
+        G2Affine::new(fq2(&words[..64]), fq2(&words[64..]))
+    }
+
+    // What the pairing precompile computes
+    fn pairing_precompile(input: &[u8]) -> bool {
+        let (g1s, g2s): (Vec<_>, Vec<_>) = input
+            .chunks_exact(192)
+            .map(|pair| (decode_g1(&pair[..64]), decode_g2(&pair[64..])))
+            .unzip();
+        Bn254::multi_pairing(g1s, g2s).0.is_one()
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            &super::hex_word(&keccak256(b""))[2..]
+        );
+        assert_eq!(
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            &super::hex_word(&keccak256(b"abc"))[2..]
+        );
+        // Crossing the 136 byte rate
+        let long = [0x61u8; 200];
+        assert_ne!(keccak256(&long[..136]), keccak256(&long[..137]));
+    }
+
+    #[test]
+    fn test_evm_verifier() {
+        type Pmp = M1NoPrecomp<Bn254, ArkMSMEngine<Bn254>>;
+        let inner = Pmp::new(32, 4, &mut test_rng())
+            .with_transcript_encoding(TranscriptEncoding::big_endian());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let pmp = M1Precomp::from_inner(inner, vec![points.clone()]).unwrap();
+        let evm = EvmVerifier::new(&pmp, 0).unwrap();
+        assert_eq!(points, evm.points());
+        assert!(EvmVerifier::new(&pmp, 1).is_err());
+
+        let polys = (0..3)
+            .map(|_| {
+                (0..32)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::poly_ops::evaluate_at_points(p, &points))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| pmp.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let proof = pmp
+            .inner
+            .open(&mut EvmTranscript::new(&commits), &evals, &polys, &points)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            pmp.inner.verify(
+                &mut EvmTranscript::new(&commits),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+
+        let input = evm.pairing_calldata(&commits, &evals, &proof).unwrap();
+        assert_eq!(PAIRING_INPUT_SIZE, input.len());
+        assert!(pairing_precompile(&input));
+        let mut bad_evals = evals.clone();
+        bad_evals[1][2] += Fr::ONE;
+        let input = evm.pairing_calldata(&commits, &bad_evals, &proof).unwrap();
+        assert!(!pairing_precompile(&input));
+        assert!(evm.pairing_calldata(&commits[..2], &evals, &proof).is_err());
+
+        let calldata = evm.verify_calldata(&commits, &evals, &proof).unwrap();
+        assert_eq!(4 + 32 * (4 + 1 + 6 + 1 + 12), calldata.len());
+        assert_eq!(encode_fr(&Fr::from(128u64)), calldata[4..36]);
+        assert_eq!(encode_proof_for_evm(&proof), calldata[68..132]);
+        assert_eq!(encode_fr(&Fr::from(6u64)), calldata[132..164]);
+        assert_eq!(encode_fr(&evals[2][3]), calldata[calldata.len() - 32..]);
+
+        let sol = evm.solidity("PmpVerifier");
+        assert!(sol.contains("contract PmpVerifier {"));
+        assert!(sol.contains("uint256 internal constant N_POINTS = 4;"));
+        assert!(sol.contains(&format!(
+            "p[3] = {};",
+            super::hex_word(&encode_fr(&points[3]))
+        )));
+        assert!(sol.contains("uint256 internal constant ZEROS_Y0 = 0x"));
+        assert!(!sol.contains("__"));
+        // The generator's x coordinate is 1
+        let g1 = super::encode_g1(&G1Affine::generator());
+        assert_eq!(1, g1[31]);
+    }
+}
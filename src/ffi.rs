@@ -0,0 +1,362 @@
+//! A C API for method 1 over BLS12-381, enabled with the `capi` feature.
+//!
+//! Everything crosses the boundary as byte buffers in the arkworks compressed serialization:
+//! scalars are [`PMP_SCALAR_BYTES`] bytes little endian, and G1 points (commitments and proofs)
+//! are [`PMP_G1_BYTES`] bytes. Lists of scalars are concatenated, and matrices are row-major with
+//! one row per polynomial. The header is in `include/poly_multiproof.h`, and is regenerated with
+//! ```bash
+//! cbindgen --config cbindgen.toml --output include/poly_multiproof.h
+//! ```
+//! When a call returns [`PmpStatus::Failed`], [`pmp_last_error_code`] gives the
+//! [`crate::Error::code`] of the cause.
+//!
+//! To build a library to link against, run
+//! ```bash
+//! cargo rustc --release --features capi --crate-type staticlib
+//! ```
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{boxed::Box, slice, vec::Vec};
+use merlin::Transcript;
+use std::cell::Cell;
+
+use crate::{
+    method1::{M1NoPrecomp, Proof},
+    msm::blst::BlstMSMEngine,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Error,
+};
+
+/// The size of a serialized scalar
+pub const PMP_SCALAR_BYTES: usize = 32;
+/// The size of a serialized G1 point
+pub const PMP_G1_BYTES: usize = 48;
+
+/// A method 1 setup over BLS12-381
+pub struct PmpM1(M1NoPrecomp<Bls12_381, BlstMSMEngine>);
+
+/// The result of a C API call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PmpStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// A pointer was null, or a buffer didn't hold valid scalars or points
+    InvalidInput = 1,
+    /// The inputs were well formed but the operation failed, e.g. a polynomial was too large
+    Failed = 2,
+}
+
+std::thread_local! {
+    static LAST_ERROR: Cell<u32> = const { Cell::new(0) };
+}
+
+/// The [`crate::Error::code`] of the error behind the last [`PmpStatus::Failed`] returned on this
+/// thread, or 0 if there hasn't been one
+#[no_mangle]
+pub extern "C" fn pmp_last_error_code() -> u32 {
+    LAST_ERROR.with(Cell::get)
+}
+
+/// Record `err` as the last error on this thread
+fn failed(err: Error) -> PmpStatus {
+    LAST_ERROR.with(|e| e.set(err.code()));
+    PmpStatus::Failed
+}
+
+/// Load a setup from its compressed serialization, checking that all its points are valid.
+/// Returns null if the setup can't be loaded. The setup must be freed with [`pmp_m1_free`].
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pmp_m1_load(data: *const u8, len: usize) -> *mut PmpM1 {
+    let Some(data) = bytes(data, len) else {
+        return core::ptr::null_mut();
+    };
+    match M1NoPrecomp::deserialize_compressed(data) {
+        Ok(s) => Box::into_raw(Box::new(PmpM1(s))),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Free a setup loaded with [`pmp_m1_load`]
+///
+/// # Safety
+/// `pmp` must be null or a pointer returned by [`pmp_m1_load`] that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn pmp_m1_free(pmp: *mut PmpM1) {
+    if !pmp.is_null() {
+        drop(Box::from_raw(pmp));
+    }
+}
+
+/// Commit to a polynomial of `n_coeffs` coefficients, writing the commitment to `commit_out`
+///
+/// # Safety
+/// `pmp` must be a live setup, `coeffs` must point to `n_coeffs * PMP_SCALAR_BYTES` readable bytes
+/// and `commit_out` to `PMP_G1_BYTES` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pmp_m1_commit(
+    pmp: *const PmpM1,
+    coeffs: *const u8,
+    n_coeffs: usize,
+    commit_out: *mut u8,
+) -> PmpStatus {
+    let (Some(pmp), Some(coeffs)) = (pmp.as_ref(), scalars(coeffs, n_coeffs)) else {
+        return PmpStatus::InvalidInput;
+    };
+    match pmp.0.commit(coeffs) {
+        Ok(c) => write_g1(&c.0, commit_out),
+        Err(e) => failed(e),
+    }
+}
+
+/// Open `n_polys` polynomials of `n_coeffs` coefficients each at `n_points` points, writing the
+/// proof to `proof_out`. `evals` holds the evaluations of each polynomial at the points. The
+/// transcript is seeded with `domain`, which must match the one given to [`pmp_m1_verify`].
+///
+/// # Safety
+/// `pmp` must be a live setup, `domain` must point to `domain_len` readable bytes, `evals`,
+/// `polys` and `points` to `n_polys * n_points`, `n_polys * n_coeffs` and `n_points` readable
+/// scalars, and `proof_out` to `PMP_G1_BYTES` writable bytes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn pmp_m1_open(
+    pmp: *const PmpM1,
+    domain: *const u8,
+    domain_len: usize,
+    evals: *const u8,
+    polys: *const u8,
+    n_polys: usize,
+    n_coeffs: usize,
+    points: *const u8,
+    n_points: usize,
+    proof_out: *mut u8,
+) -> PmpStatus {
+    let (Some(pmp), Some(mut transcript), Some(evals), Some(polys), Some(points)) = (
+        pmp.as_ref(),
+        transcript(domain, domain_len),
+        scalars(evals, n_polys.saturating_mul(n_points)),
+        scalars(polys, n_polys.saturating_mul(n_coeffs)),
+        scalars(points, n_points),
+    ) else {
+        return PmpStatus::InvalidInput;
+    };
+    let evals = rows(&evals, n_polys, n_points);
+    let polys = rows(&polys, n_polys, n_coeffs);
+    match pmp.0.open(&mut transcript, &evals, &polys, &points) {
+        Ok(p) => write_g1(&p.0, proof_out),
+        Err(e) => failed(e),
+    }
+}
+
+/// Verify a proof from [`pmp_m1_open`] against `n_polys` commitments, writing whether it is
+/// valid to `valid_out`
+///
+/// # Safety
+/// `pmp` must be a live setup, `domain` must point to `domain_len` readable bytes, `commits` to
+/// `n_polys * PMP_G1_BYTES` readable bytes, `evals` and `points` to `n_polys * n_points` and
+/// `n_points` readable scalars, `proof` to `PMP_G1_BYTES` readable bytes and `valid_out` to a
+/// writable bool.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn pmp_m1_verify(
+    pmp: *const PmpM1,
+    domain: *const u8,
+    domain_len: usize,
+    commits: *const u8,
+    evals: *const u8,
+    n_polys: usize,
+    points: *const u8,
+    n_points: usize,
+    proof: *const u8,
+    valid_out: *mut bool,
+) -> PmpStatus {
+    let (Some(pmp), Some(mut transcript), Some(commits), Some(evals), Some(points), Some(proof)) = (
+        pmp.as_ref(),
+        transcript(domain, domain_len),
+        g1s(commits, n_polys),
+        scalars(evals, n_polys.saturating_mul(n_points)),
+        scalars(points, n_points),
+        g1s(proof, 1),
+    ) else {
+        return PmpStatus::InvalidInput;
+    };
+    if valid_out.is_null() {
+        return PmpStatus::InvalidInput;
+    }
+    let commits = commits.into_iter().map(Commitment).collect::<Vec<_>>();
+    let evals = rows(&evals, n_polys, n_points);
+    match pmp
+        .0
+        .verify(&mut transcript, &commits, &points, &evals, &Proof(proof[0]))
+    {
+        Ok(valid) => {
+            *valid_out = valid;
+            PmpStatus::Ok
+        }
+        Err(e) => failed(e),
+    }
+}
+
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if data.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(data, len))
+    }
+}
+
+unsafe fn scalars(data: *const u8, n: usize) -> Option<Vec<Fr>> {
+    bytes(data, n.checked_mul(PMP_SCALAR_BYTES)?)?
+        .chunks(PMP_SCALAR_BYTES)
+        .map(|b| Fr::deserialize_compressed(b).ok())
+        .collect()
+}
+
+unsafe fn g1s(data: *const u8, n: usize) -> Option<Vec<G1Affine>> {
+    bytes(data, n.checked_mul(PMP_G1_BYTES)?)?
+        .chunks(PMP_G1_BYTES)
+        .map(|b| G1Affine::deserialize_compressed(b).ok())
+        .collect()
+}
+
+unsafe fn transcript(domain: *const u8, len: usize) -> Option<Transcript> {
+    let mut t = Transcript::new(b"poly-multiproof capi");
+    t.append_message(b"domain", bytes(domain, len)?);
+    Some(t)
+}
+
+unsafe fn write_g1(p: &G1Affine, out: *mut u8) -> PmpStatus {
+    if out.is_null() {
+        return PmpStatus::InvalidInput;
+    }
+    let out = slice::from_raw_parts_mut(out, PMP_G1_BYTES);
+    match p.serialize_compressed(out) {
+        Ok(()) => PmpStatus::Ok,
+        Err(e) => failed(e.into()),
+    }
+}
+
+/// Split a flat row-major matrix into `n_rows` rows of `row_len`
+fn rows(flat: &[Fr], n_rows: usize, row_len: usize) -> Vec<&[Fr]> {
+    (0..n_rows)
+        .map(|i| &flat[i * row_len..(i + 1) * row_len])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::{vec, vec::Vec};
+
+    use super::*;
+    use crate::{method1::M1NoPrecomp, msm::blst::BlstMSMEngine, test_rng};
+
+    fn to_bytes(items: &[impl CanonicalSerialize]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for i in items {
+            i.serialize_compressed(&mut out).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_open_verify() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let mut setup = Vec::new();
+        s.serialize_compressed(&mut setup).unwrap();
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, _) = crate::testing::gen_openings(&s, 2, &points);
+        let n_coeffs = coeffs[0].len();
+        let points = to_bytes(&points);
+        let evals = to_bytes(&evals.concat());
+        let coeffs = to_bytes(&coeffs.concat());
+
+        unsafe {
+            let pmp = pmp_m1_load(setup.as_ptr(), setup.len());
+            assert!(!pmp.is_null());
+
+            let mut commits = vec![0u8; 2 * PMP_G1_BYTES];
+            for (c, out) in coeffs
+                .chunks(n_coeffs * PMP_SCALAR_BYTES)
+                .zip(commits.chunks_mut(PMP_G1_BYTES))
+            {
+                let status = pmp_m1_commit(pmp, c.as_ptr(), n_coeffs, out.as_mut_ptr());
+                assert_eq!(PmpStatus::Ok, status);
+            }
+
+            let domain = b"test";
+            let mut proof = [0u8; PMP_G1_BYTES];
+            let status = pmp_m1_open(
+                pmp,
+                domain.as_ptr(),
+                domain.len(),
+                evals.as_ptr(),
+                coeffs.as_ptr(),
+                2,
+                n_coeffs,
+                points.as_ptr(),
+                4,
+                proof.as_mut_ptr(),
+            );
+            assert_eq!(PmpStatus::Ok, status);
+
+            let verify = |domain: &[u8], commits: &[u8]| {
+                let mut valid = false;
+                let status = pmp_m1_verify(
+                    pmp,
+                    domain.as_ptr(),
+                    domain.len(),
+                    commits.as_ptr(),
+                    evals.as_ptr(),
+                    2,
+                    points.as_ptr(),
+                    4,
+                    proof.as_ptr(),
+                    &mut valid,
+                );
+                (status, valid)
+            };
+            assert_eq!((PmpStatus::Ok, true), verify(domain, &commits));
+            assert_eq!((PmpStatus::Ok, false), verify(b"other", &commits));
+            let mut swapped = commits[PMP_G1_BYTES..].to_vec();
+            swapped.extend_from_slice(&commits[..PMP_G1_BYTES]);
+            assert_eq!((PmpStatus::Ok, false), verify(domain, &swapped));
+            assert_eq!(
+                PmpStatus::InvalidInput,
+                verify(domain, &[0u8; 2 * PMP_G1_BYTES]).0
+            );
+
+            // Too many coefficients for the setup
+            let big = to_bytes(&vec![Fr::from(1u64); 100]);
+            let mut out = [0u8; PMP_G1_BYTES];
+            assert_eq!(0, pmp_last_error_code());
+            assert_eq!(
+                PmpStatus::Failed,
+                pmp_m1_commit(pmp, big.as_ptr(), 100, out.as_mut_ptr())
+            );
+            let expected = crate::Error::TooManyScalars {
+                n_coeffs: 100,
+                expected_max: 64,
+            };
+            assert_eq!(expected.code(), pmp_last_error_code());
+            assert_eq!(
+                PmpStatus::InvalidInput,
+                pmp_m1_commit(pmp, core::ptr::null(), 1, out.as_mut_ptr())
+            );
+            pmp_m1_free(pmp);
+        }
+        unsafe {
+            assert!(pmp_m1_load(setup.as_ptr(), setup.len() - 1).is_null());
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Batched field arithmetic for the lagrange interpolation and linear combination loops, which
+//! make up much of verifying large matrices of evaluations.
+//!
+//! With the `simd` feature, multiply-adds are done in lanes of independent multiplications, and
+//! on x86_64 CPUs with AVX2 (detected at runtime) the lanes are compiled with AVX2 enabled. This
+//! composes with the `asm` feature, which makes ark-ff's multiplication itself use assembly.
+use ark_ff::Field;
+
+use crate::Error;
+
+/// The number of independent multiplications done together
+#[cfg(feature = "simd")]
+const LANES: usize = 8;
+
+/// The field arithmetic paths in use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FieldCapabilities {
+    /// Multiply-adds run the AVX2 path, which needs the `simd` feature and an x86_64 CPU with AVX2
+    pub simd: bool,
+    /// Multiplication uses ark-ff's assembly, which needs the `asm` feature and building with the
+    /// `bmi2` and `adx` target features
+    pub asm: bool,
+}
+
+/// Detects which field arithmetic paths are in use on this machine
+pub fn capabilities() -> FieldCapabilities {
+    FieldCapabilities {
+        simd: has_avx2(),
+        asm: cfg!(all(
+            feature = "asm",
+            target_feature = "bmi2",
+            target_feature = "adx"
+        )),
+    }
+}
+
+/// Whether the `simd` feature is on and the CPU has AVX2
+fn has_avx2() -> bool {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    {
+        false
+    }
+}
+
+/// Sets `out[i] += scalar * xs[i]` for each `i` in both
+pub(crate) fn mul_add_assign<F: Field>(out: &mut [F], scalar: F, xs: &[F]) {
+    #[cfg(feature = "simd")]
+    {
+        #[cfg(target_arch = "x86_64")]
+        if has_avx2() {
+            // Safe since the CPU has AVX2
+            #[allow(unsafe_code)]
+            unsafe {
+                mul_add_assign_avx2(out, scalar, xs)
+            };
+            return;
+        }
+        mul_add_assign_lanes(out, scalar, xs)
+    }
+    #[cfg(not(feature = "simd"))]
+    for (o, x) in out.iter_mut().zip(xs) {
+        *o += scalar * x;
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[allow(unsafe_code)]
+#[target_feature(enable = "avx2")]
+unsafe fn mul_add_assign_avx2<F: Field>(out: &mut [F], scalar: F, xs: &[F]) {
+    mul_add_assign_lanes(out, scalar, xs)
+}
+
+/// Does the products of each lane before adding them, so the multiplications don't depend on
+/// each other and can be interleaved
+#[cfg(feature = "simd")]
+#[inline(always)]
+fn mul_add_assign_lanes<F: Field>(out: &mut [F], scalar: F, xs: &[F]) {
+    let n = out.len().min(xs.len());
+    let mut outs = out[..n].chunks_exact_mut(LANES);
+    let mut ins = xs[..n].chunks_exact(LANES);
+    for (o, x) in (&mut outs).zip(&mut ins) {
+        let mut prods = [F::zero(); LANES];
+        for (p, x) in prods.iter_mut().zip(x) {
+            *p = scalar * x;
+        }
+        for (o, p) in o.iter_mut().zip(prods) {
+            *o += p;
+        }
+    }
+    for (o, x) in outs.into_remainder().iter_mut().zip(ins.remainder()) {
+        *o += scalar * x;
+    }
+}
+
+/// Inverts every element of `values` with a single field inversion
+pub(crate) fn batch_inverse<F: Field>(values: &mut [F]) -> Result<(), Error> {
+    if values.iter().any(|v| v.is_zero()) {
+        return Err(Error::DivisorIsZero);
+    }
+    ark_ff::batch_inversion(values);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, Zero};
+    use ark_std::UniformRand;
+
+    use super::{batch_inverse, capabilities, mul_add_assign};
+    use crate::{test_rng, Error};
+
+    #[test]
+    fn test_mul_add_assign() {
+        let scalar = Fr::rand(&mut test_rng());
+        for (n_out, n_in) in [(0, 0), (1, 3), (8, 8), (19, 17), (17, 19), (64, 64)] {
+            let out = (0..n_out)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let xs = (0..n_in)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let mut expected = out.clone();
+            for (o, x) in expected.iter_mut().zip(&xs) {
+                *o += scalar * x;
+            }
+            let mut got = out;
+            mul_add_assign(&mut got, scalar, &xs);
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        let values = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let mut inverses = values.clone();
+        batch_inverse(&mut inverses).unwrap();
+        for (v, i) in values.iter().zip(&inverses) {
+            assert_eq!(v.inverse().unwrap(), *i);
+        }
+        let mut with_zero = vec![Fr::from(2u64), Fr::zero()];
+        assert_eq!(Err(Error::DivisorIsZero), batch_inverse(&mut with_zero));
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let caps = capabilities();
+        if cfg!(not(feature = "simd")) {
+            assert!(!caps.simd);
+        }
+        if cfg!(not(feature = "asm")) {
+            assert!(!caps.asm);
+        }
+    }
+}
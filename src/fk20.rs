@@ -0,0 +1,249 @@
+//! FK20 style amortized openings. Computes the proof for every point set of an [`M1CyclPrecomp`]
+//! for a single polynomial at once with $O(n \log n)$ group operations, rather than one quotient
+//! msm per point set.
+//!
+//! For a coset $\omega^j H$ with $|H| = m$ the vanishing polynomial is $x^m - c_j$ with
+//! $c_j = \psi^j$, where $\psi = \omega^m$. Dividing $f$ by it gives the quotient commitment
+//! $\pi_j = \sum_{t \geq 1} c_j^{t-1} h_t$ with $h_t = \sum_{i \geq tm} f_i [\tau^{i - tm}]_1$.
+//! The $h_t$ don't depend on the point set and are a toeplitz matrix-vector product, which is
+//! computed with FFTs over the precomputed powers of tau. The proofs are then a single FFT of the
+//! $h_t$ over the point set index. When the base domain is a coset with offset $o$, $c_j$ picks up
+//! a factor of $o^m$, so $h_t$ is scaled by $o^{m(t-1)}$ first.
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, One, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    cfg_iter,
+    m1_cycl::M1CyclPrecomp,
+    method1::Proof,
+    poly_ops::{check_domain_size, exact_domain},
+    traits::MSMEngine,
+    Error,
+};
+
+/// Precomputed FFTs of the powers of tau for computing all the point set openings of polynomials
+/// with up to `max_coeffs` coefficients
+#[derive(Clone)]
+pub struct Fk20<E: Pairing, M: MSMEngine<E = E>> {
+    max_coeffs: usize,
+    point_set_size: usize,
+    n_rows: usize,
+    toeplitz_domain: Radix2EvaluationDomain<E::ScalarField>,
+    point_set_domain: GeneralEvaluationDomain<E::ScalarField>,
+    /// $o^m$ for the base domain offset $o$
+    offset_pow_size: E::ScalarField,
+    /// For each evaluation of the toeplitz FFT, the bases for each residue mod the point set size
+    toeplitz_ffts: Vec<M::G1Prepared>,
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Fk20<E, M> {
+    /// Precompute the toeplitz FFTs for the point sets of `pmp` and polynomials with up to
+    /// `max_coeffs` coefficients
+    pub fn new(pmp: &M1CyclPrecomp<E, M>, max_coeffs: usize) -> Result<Self, Error> {
+        let powers_of_g1 = &pmp.inner.powers_of_g1;
+        if max_coeffs > powers_of_g1.len() {
+            return Err(Error::TooManyScalars {
+                n_coeffs: max_coeffs,
+                expected_max: powers_of_g1.len(),
+            });
+        }
+        let n_point_sets = pmp.point_sets().n_splits();
+        let point_set_size = pmp.point_sets().base().size() / n_point_sets;
+        let point_set_domain = exact_domain(n_point_sets, E::ScalarField::one())
+            .ok_or(Error::DomainConstructionFailed(n_point_sets))?;
+        // Row u holds the coefficients u * m..(u + 1) * m
+        let n_rows = max_coeffs.div_ceil(point_set_size).max(1);
+        let toeplitz_size = 2 * n_rows;
+        check_domain_size::<E::ScalarField>(toeplitz_size)?;
+        let toeplitz_domain = Radix2EvaluationDomain::new(toeplitz_size)
+            .ok_or(Error::DomainConstructionFailed(toeplitz_size))?;
+
+        // For residue r, the column [tau^{sm + r}] for s in 0..n_rows, reversed and padded
+        let columns = (0..point_set_size).collect::<Vec<_>>();
+        let column_ffts = cfg_iter!(columns)
+            .map(|(_, r)| {
+                let mut col = vec![E::G1::zero(); toeplitz_domain.size()];
+                for s in 0..n_rows {
+                    let i = s * point_set_size + r;
+                    if i < max_coeffs {
+                        col[n_rows - 1 - s] = powers_of_g1[i].into();
+                    }
+                }
+                toeplitz_domain.fft_in_place(&mut col);
+                col
+            })
+            .collect::<Vec<_>>();
+        let evals = (0..toeplitz_domain.size()).collect::<Vec<_>>();
+        let toeplitz_ffts = cfg_iter!(evals)
+            .map(|(_, i)| {
+                let bases = column_ffts.iter().map(|c| c[*i]).collect::<Vec<_>>();
+                M::prepare_g1(E::G1::normalize_batch(&bases))
+            })
+            .collect();
+
+        Ok(Self {
+            max_coeffs,
+            point_set_size,
+            n_rows,
+            toeplitz_domain,
+            point_set_domain,
+            offset_pow_size: pmp.offset().pow([point_set_size as u64]),
+            toeplitz_ffts,
+        })
+    }
+
+    /// The maximum number of coefficients of the polynomials that can be opened
+    pub fn max_coeffs(&self) -> usize {
+        self.max_coeffs
+    }
+
+    /// Compute the proof of `poly` at every point set, in point set order. Each proof is the same
+    /// as the one from [`M1CyclPrecomp`]'s [`open`](crate::traits::PolyMultiProof::open) with
+    /// `poly` as the only polynomial, and verifies with its
+    /// [`verify`](crate::traits::PolyMultiProof::verify).
+    pub fn open_all(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Vec<Proof<E>>, Error> {
+        let poly = poly.as_ref();
+        if poly.len() > self.max_coeffs {
+            return Err(Error::TooManyScalars {
+                n_coeffs: poly.len(),
+                expected_max: self.max_coeffs,
+            });
+        }
+        let m = self.point_set_size;
+
+        // For residue r, the FFT of the coefficients f_{um + r}
+        let residues = (0..m).collect::<Vec<_>>();
+        let coeff_ffts = cfg_iter!(residues)
+            .map(|(_, r)| {
+                let mut row = (0..self.n_rows)
+                    .map(|u| poly.get(u * m + r).copied().unwrap_or_else(Zero::zero))
+                    .collect::<Vec<_>>();
+                self.toeplitz_domain.fft_in_place(&mut row);
+                row
+            })
+            .collect::<Vec<_>>();
+        let mut h = cfg_iter!(self.toeplitz_ffts)
+            .map(|(i, bases)| {
+                let scalars = coeff_ffts.iter().map(|c| c[i]).collect::<Vec<_>>();
+                M::multi_scalar_mul_g1(bases, &scalars)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.toeplitz_domain.ifft_in_place(&mut h);
+
+        // h_t sits at n_rows - 1 + t, fold the powers of psi^j since psi^(jk) = 1
+        let n_point_sets = self.point_set_domain.size();
+        let mut proofs = vec![E::G1::zero(); n_point_sets];
+        let mut offset_pow = E::ScalarField::one();
+        for t in 1..self.n_rows {
+            proofs[(t - 1) % n_point_sets] += h[self.n_rows - 1 + t] * offset_pow;
+            offset_pow *= self.offset_pow_size;
+        }
+        self.point_set_domain.fft_in_place(&mut proofs);
+        Ok(E::G1::normalize_batch(&proofs)
+            .into_iter()
+            .map(Proof)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_std::vec::Vec;
+    use merlin::Transcript;
+
+    use super::Fk20;
+    use crate::{
+        m1_cycl::M1CyclPrecomp,
+        method1::M1NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        poly_ops::evaluate_over_coset,
+        test_rng,
+        traits::{Committer, MSMEngine, PolyMultiProof},
+        Error,
+    };
+
+    fn check_open_all<M: MSMEngine<E = Bls12_381>>(
+        pmp: &M1CyclPrecomp<Bls12_381, M>,
+        max_coeffs: usize,
+        n_coeffs: usize,
+    ) {
+        let fk = Fk20::new(pmp, max_coeffs).unwrap();
+        let poly = (0..n_coeffs)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let commit = pmp.commit(&poly).unwrap();
+        let evals = evaluate_over_coset(&poly, pmp.point_sets().base());
+        let proofs = fk.open_all(&poly).unwrap();
+        assert_eq!(pmp.point_sets().n_splits(), proofs.len());
+        for (i, proof) in proofs.iter().enumerate() {
+            let set_evals = pmp.point_sets().take_subgroup_indices(i, &evals).unwrap();
+            let expected = pmp
+                .open(&mut Transcript::new(b"fk20"), &[&set_evals], &[&poly], i)
+                .unwrap();
+            assert_eq!(expected.0, proof.0);
+            assert_eq!(
+                Ok(true),
+                pmp.verify(
+                    &mut Transcript::new(b"fk20"),
+                    core::slice::from_ref(&commit),
+                    i,
+                    &[&set_evals],
+                    proof
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_all() {
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(512, 256, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner(pmp, 256, 8).unwrap();
+        // Enough coefficients for the quotient to wrap around the point sets more than once
+        check_open_all(&pmp, 512, 512);
+        check_open_all(&pmp, 512, 200);
+        check_open_all(&pmp, 256, 256);
+
+        let pmp = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 64, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner(pmp, 64, 4).unwrap();
+        check_open_all(&pmp, 100, 100);
+        check_open_all(&pmp, 16, 16);
+
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner_coset(pmp, 128, 4, Fr::from(7u64)).unwrap();
+        check_open_all(&pmp, 256, 256);
+
+        // Mixed radix point sets and base domain
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner_coset(pmp, 96, 6, Fr::from(7u64)).unwrap();
+        check_open_all(&pmp, 256, 200);
+    }
+
+    #[test]
+    fn test_errors() {
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 64, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner(pmp, 64, 4).unwrap();
+        assert!(matches!(
+            Fk20::new(&pmp, 66),
+            Err(Error::TooManyScalars {
+                n_coeffs: 66,
+                expected_max: 65
+            })
+        ));
+        let fk = Fk20::new(&pmp, 32).unwrap();
+        assert_eq!(32, fk.max_coeffs());
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 33,
+                expected_max: 32
+            }),
+            fk.open_all([Fr::from(1u64); 33]).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,430 @@
+//! A 2d grid of field elements for data availability. Each row is the evaluations of a polynomial
+//! over a radix-2 domain, which is committed to on its own. Cells of the grid are opened with
+//! [`M1CyclPrecomp`], where each point set is a cyclic subgroup of the row domain.
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    cfg_iter, get_field_size,
+    m1_cycl::M1CyclPrecomp,
+    method1::Proof,
+    poly_ops::check_domain_size,
+    traits::{Committer, MSMEngine, PolyMultiProof, TranscriptProtocol},
+    Commitment, Error,
+};
+
+/// A grid of field elements with a power of two width, stored as rows of evaluations along with
+/// the coefficients of the row polynomials
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<F: FftField> {
+    domain: Radix2EvaluationDomain<F>,
+    evals: Vec<Vec<F>>,
+    polys: Vec<Vec<F>>,
+}
+
+impl<F: FftField> Grid<F> {
+    /// Make a grid from its rows, which must all have the same power of two length
+    pub fn from_rows(rows: Vec<Vec<F>>) -> Result<Self, Error> {
+        let width = rows.first().map(|r| r.len()).ok_or(Error::EmptyEvals)?;
+        if !width.is_power_of_two() {
+            return Err(Error::NotPowerOfTwo(width));
+        }
+        if let Some(row) = rows.iter().find(|r| r.len() != width) {
+            return Err(Error::InvalidInputLength {
+                expected: width,
+                got: row.len(),
+            });
+        }
+        check_domain_size::<F>(width)?;
+        let domain =
+            Radix2EvaluationDomain::new(width).ok_or(Error::DomainConstructionFailed(width))?;
+        let polys = cfg_iter!(rows).map(|(_, r)| domain.ifft(r)).collect();
+        Ok(Self {
+            domain,
+            evals: rows,
+            polys,
+        })
+    }
+
+    /// The number of elements in each row
+    pub fn width(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// The number of rows
+    pub fn height(&self) -> usize {
+        self.evals.len()
+    }
+
+    /// The domain the rows are evaluated over
+    pub fn domain(&self) -> &Radix2EvaluationDomain<F> {
+        &self.domain
+    }
+
+    /// The rows of the grid
+    pub fn rows(&self) -> &[Vec<F>] {
+        &self.evals
+    }
+
+    /// The coefficients of each row polynomial
+    pub fn polys(&self) -> &[Vec<F>] {
+        &self.polys
+    }
+
+    /// Get the element at row `i` and column `j`
+    pub fn get(&self, i: usize, j: usize) -> Option<&F> {
+        self.evals.get(i)?.get(j)
+    }
+
+    /// Erasure extend every row by evaluating its polynomial over a domain `factor` times larger.
+    /// The row polynomials, and so their commitments, don't change. The original elements of a
+    /// row end up at every `factor`th column of the extended row.
+    pub fn extend_rows(&self, factor: usize) -> Result<Self, Error> {
+        if !factor.is_power_of_two() {
+            return Err(Error::NotPowerOfTwo(factor));
+        }
+        let ext_size = self.width() * factor;
+        check_domain_size::<F>(ext_size)?;
+        let domain = Radix2EvaluationDomain::new(ext_size)
+            .ok_or(Error::DomainConstructionFailed(ext_size))?;
+        let evals = cfg_iter!(self.polys).map(|(_, p)| domain.fft(p)).collect();
+        Ok(Self {
+            domain,
+            evals,
+            polys: self.polys.clone(),
+        })
+    }
+
+    /// The domain the columns are evaluated over, which needs a power of two height
+    pub fn column_domain(&self) -> Result<Radix2EvaluationDomain<F>, Error> {
+        radix2_domain(self.height())
+    }
+
+    /// The coefficients of each column polynomial, interpolated over [`Grid::column_domain`]
+    pub fn column_polys(&self) -> Result<Vec<Vec<F>>, Error> {
+        let domain = self.column_domain()?;
+        let cols = (0..self.width()).collect::<Vec<_>>();
+        Ok(cfg_iter!(cols)
+            .map(|(_, j)| domain.ifft(&self.column(*j)))
+            .collect())
+    }
+
+    /// Erasure extend every column by evaluating its polynomial over a domain `factor` times
+    /// larger, as [`Grid::extend_rows`] does for rows. The original rows end up at every
+    /// `factor`th row of the extended grid.
+    pub fn extend_columns(&self, factor: usize) -> Result<Self, Error> {
+        if !factor.is_power_of_two() {
+            return Err(Error::NotPowerOfTwo(factor));
+        }
+        let column_polys = self.column_polys()?;
+        let ext_size = self.height() * factor;
+        let ext_domain = radix2_domain::<F>(ext_size)?;
+        let ext_cols = cfg_iter!(column_polys)
+            .map(|(_, p)| ext_domain.fft(p))
+            .collect::<Vec<_>>();
+        let rows = (0..ext_size)
+            .map(|i| ext_cols.iter().map(|c| c[i]).collect())
+            .collect();
+        Self::from_rows(rows)
+    }
+
+    fn column(&self, j: usize) -> Vec<F> {
+        self.evals.iter().map(|r| r[j]).collect()
+    }
+}
+
+impl<F: PrimeField> Grid<F> {
+    /// Pack bytes into a grid of the given width. The bytes are chunked into groups one byte
+    /// smaller than a field element so that each chunk fits, and the last row is padded with
+    /// zeros.
+    pub fn from_bytes(data: &[u8], width: usize) -> Result<Self, Error> {
+        let chunk_size = get_field_size::<F>() - 1;
+        let elems = data
+            .chunks(chunk_size)
+            .map(F::from_be_bytes_mod_order)
+            .collect::<Vec<_>>();
+        if width == 0 {
+            return Err(Error::NotPowerOfTwo(width));
+        }
+        let rows = elems
+            .chunks(width)
+            .map(|row| {
+                let mut row = row.to_vec();
+                row.resize(width, F::zero());
+                row
+            })
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            return Self::from_rows(vec![vec![F::zero(); width]]);
+        }
+        Self::from_rows(rows)
+    }
+
+    /// Commit to each row polynomial
+    pub fn commit<E: Pairing<ScalarField = F>>(
+        &self,
+        c: &(impl Committer<E> + Sync),
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        cfg_iter!(self.polys)
+            .map(|(_, p)| c.commit(p))
+            .collect::<Result<Vec<_>, Error>>()
+    }
+
+    /// Commit to each column polynomial
+    pub fn commit_columns<E: Pairing<ScalarField = F>>(
+        &self,
+        c: &(impl Committer<E> + Sync),
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let column_polys = self.column_polys()?;
+        cfg_iter!(column_polys)
+            .map(|(_, p)| c.commit(p))
+            .collect::<Result<Vec<_>, Error>>()
+    }
+
+    /// The elements of row `i` in the point set `point_set` of `pmp`
+    pub fn point_set_evals<E: Pairing<ScalarField = F>, M: MSMEngine<E = E>>(
+        &self,
+        pmp: &M1CyclPrecomp<E, M>,
+        i: usize,
+        point_set: usize,
+    ) -> Result<Vec<F>, Error> {
+        let row = self.evals.get(i).ok_or(Error::InvalidInputLength {
+            expected: self.height(),
+            got: i,
+        })?;
+        pmp.point_sets().take_subgroup_indices(point_set, row)
+    }
+
+    /// Open the cell of row `i` in the point set `point_set` of `pmp`
+    pub fn open_cell<E: Pairing<ScalarField = F>, M: MSMEngine<E = E>>(
+        &self,
+        pmp: &M1CyclPrecomp<E, M>,
+        transcript: &mut impl TranscriptProtocol,
+        i: usize,
+        point_set: usize,
+    ) -> Result<Proof<E>, Error> {
+        self.open_rows(pmp, transcript, i..i + 1, point_set)
+    }
+
+    /// Open the column of every row in the point set `point_set` of `pmp` with a single proof
+    pub fn open_column<E: Pairing<ScalarField = F>, M: MSMEngine<E = E>>(
+        &self,
+        pmp: &M1CyclPrecomp<E, M>,
+        transcript: &mut impl TranscriptProtocol,
+        point_set: usize,
+    ) -> Result<Proof<E>, Error> {
+        self.open_rows(pmp, transcript, 0..self.height(), point_set)
+    }
+
+    fn open_rows<E: Pairing<ScalarField = F>, M: MSMEngine<E = E>>(
+        &self,
+        pmp: &M1CyclPrecomp<E, M>,
+        transcript: &mut impl TranscriptProtocol,
+        rows: core::ops::Range<usize>,
+        point_set: usize,
+    ) -> Result<Proof<E>, Error> {
+        let polys = self
+            .polys
+            .get(rows.clone())
+            .ok_or(Error::InvalidInputLength {
+                expected: self.height(),
+                got: rows.end,
+            })?;
+        let evals = rows
+            .map(|i| self.point_set_evals(pmp, i, point_set))
+            .collect::<Result<Vec<_>, Error>>()?;
+        pmp.open(transcript, &evals, polys, point_set)
+    }
+}
+
+/// A radix-2 domain of `size` elements, which must be a power of two
+pub(crate) fn radix2_domain<F: FftField>(size: usize) -> Result<Radix2EvaluationDomain<F>, Error> {
+    if !size.is_power_of_two() {
+        return Err(Error::NotPowerOfTwo(size));
+    }
+    check_domain_size::<F>(size)?;
+    Radix2EvaluationDomain::new(size).ok_or(Error::DomainConstructionFailed(size))
+}
+
+/// Verify an opening of a single cell made with [`Grid::open_cell`]
+pub fn verify_cell<E: Pairing, M: MSMEngine<E = E>>(
+    pmp: &M1CyclPrecomp<E, M>,
+    transcript: &mut impl TranscriptProtocol,
+    commit: &Commitment<E>,
+    point_set: usize,
+    evals: &[E::ScalarField],
+    proof: &Proof<E>,
+) -> Result<bool, Error> {
+    pmp.verify(
+        transcript,
+        core::slice::from_ref(commit),
+        point_set,
+        &[evals],
+        proof,
+    )
+}
+
+/// Verify an opening of a column made with [`Grid::open_column`]
+pub fn verify_column<E: Pairing, M: MSMEngine<E = E>>(
+    pmp: &M1CyclPrecomp<E, M>,
+    transcript: &mut impl TranscriptProtocol,
+    commits: &[Commitment<E>],
+    point_set: usize,
+    evals: &[impl AsRef<[E::ScalarField]>],
+    proof: &Proof<E>,
+) -> Result<bool, Error> {
+    pmp.verify(transcript, commits, point_set, evals, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::{vec, vec::Vec};
+    use merlin::Transcript;
+    use rand::RngCore;
+
+    use super::{verify_cell, verify_column, Grid};
+    use crate::{
+        m1_cycl::M1CyclPrecomp, method1::M1NoPrecomp, msm::blst::BlstMSMEngine, test_rng,
+        traits::Committer, Error,
+    };
+
+    fn rand_grid(width: usize, height: usize) -> Grid<Fr> {
+        let rows = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Grid::from_rows(rows).unwrap()
+    }
+
+    #[test]
+    fn test_from_rows() {
+        assert_eq!(
+            Err(Error::NotPowerOfTwo(3)),
+            Grid::from_rows(vec![vec![Fr::from(1u64); 3]])
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 4,
+                got: 2
+            }),
+            Grid::from_rows(vec![vec![Fr::from(1u64); 4], vec![Fr::from(1u64); 2]])
+        );
+        let grid = rand_grid(16, 4);
+        assert_eq!(16, grid.width());
+        assert_eq!(4, grid.height());
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        assert_eq!(grid.rows()[2], domain.fft(&grid.polys()[2]));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let mut data = vec![0u8; 31 * 20];
+        test_rng().fill_bytes(&mut data);
+        let grid = Grid::<Fr>::from_bytes(&data, 8).unwrap();
+        assert_eq!(3, grid.height());
+        assert_eq!(
+            Some(&Fr::from_be_bytes_mod_order(&data[31..62])),
+            grid.get(0, 1)
+        );
+        assert_eq!(Some(&Fr::from(0u64)), grid.get(2, 7));
+    }
+
+    #[test]
+    fn test_extend_rows() {
+        let grid = rand_grid(16, 4);
+        let ext = grid.extend_rows(2).unwrap();
+        assert_eq!(32, ext.width());
+        assert_eq!(grid.polys(), ext.polys());
+        for (row, ext_row) in grid.rows().iter().zip(ext.rows()) {
+            let evens = ext_row.iter().step_by(2).cloned().collect::<Vec<_>>();
+            assert_eq!(row, &evens);
+        }
+        assert_eq!(Err(Error::NotPowerOfTwo(3)), grid.extend_rows(3));
+    }
+
+    #[test]
+    fn test_extend_columns() {
+        let grid = rand_grid(8, 4);
+        let ext = grid.extend_columns(2).unwrap();
+        assert_eq!(8, ext.width());
+        assert_eq!(8, ext.height());
+        for (i, row) in grid.rows().iter().enumerate() {
+            assert_eq!(row, &ext.rows()[2 * i]);
+        }
+        let col_domain = Radix2EvaluationDomain::<Fr>::new(4).unwrap();
+        assert_eq!(
+            grid.rows().iter().map(|r| r[3]).collect::<Vec<_>>(),
+            col_domain.fft(&grid.column_polys().unwrap()[3])
+        );
+        assert_eq!(Err(Error::NotPowerOfTwo(3)), grid.extend_columns(3));
+        assert_eq!(
+            Err(Error::NotPowerOfTwo(3)),
+            rand_grid(8, 3).column_polys().map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_open_verify() {
+        let grid = rand_grid(16, 4).extend_rows(2).unwrap();
+        let pmp = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(32, 32, &mut test_rng());
+        let pmp = M1CyclPrecomp::from_inner(pmp, 32, 4).unwrap();
+        let commits = grid.commit(&pmp).unwrap();
+        assert_eq!(pmp.commit(&grid.polys()[1]).unwrap().0, commits[1].0);
+
+        let evals = grid.point_set_evals(&pmp, 1, 2).unwrap();
+        let proof = grid
+            .open_cell(&pmp, &mut Transcript::new(b"grid"), 1, 2)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            verify_cell(
+                &pmp,
+                &mut Transcript::new(b"grid"),
+                &commits[1],
+                2,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            verify_cell(
+                &pmp,
+                &mut Transcript::new(b"grid"),
+                &commits[0],
+                2,
+                &evals,
+                &proof
+            )
+        );
+
+        let evals = (0..grid.height())
+            .map(|i| grid.point_set_evals(&pmp, i, 3).unwrap())
+            .collect::<Vec<_>>();
+        let proof = grid
+            .open_column(&pmp, &mut Transcript::new(b"grid"), 3)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            verify_column(
+                &pmp,
+                &mut Transcript::new(b"grid"),
+                &commits,
+                3,
+                &evals,
+                &proof
+            )
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Proofs that the column commitments of a [`Grid`] are consistent with its row commitments, as
+//! used by data availability networks which sample both rows and columns of a 2d erasure coded
+//! grid.
+//!
+//! The rows $r_i(X)$ and the columns $c_j(Y)$ are both restrictions of a bivariate polynomial
+//! $P(X, Y)$. Since commitments are linear, the commitment to $P(X, z)$ is the combination of the
+//! row commitments with the lagrange coefficients of the column domain at $z$, and the commitment
+//! to $P(x, Y)$ is the combination of the column commitments with the lagrange coefficients of
+//! the row domain at $x$. The challenges $x$ and $z$ are derived from all of the commitments, and
+//! both combinations are opened to the same value $P(x, z)$. A prover can only manage this when
+//! the rows and columns interpolate the same grid, except with negligible probability.
+//!
+//! The same linearity means the commitments to the rows added by [`Grid::extend_columns`] don't
+//! need the grid at all, [`Commitment::extend_commitments`] computes them from the original row
+//! commitments.
+use ark_ec::pairing::Pairing;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+use crate::{
+    get_challenge, get_field_size,
+    grid::{radix2_domain, Grid},
+    linear_combination, linear_combination_of_commitments,
+    method1::Proof,
+    poly_ops::evaluate_at_points,
+    traits::{KZGProof, MSMEngine, TranscriptProtocol},
+    transcribe_generic, Commitment, Error,
+};
+
+/// Openings of the combined row and column commitments to the same value
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ConsistencyProof<E: Pairing> {
+    /// The evaluation $P(x, z)$ of the grid polynomial at the challenges
+    pub value: E::ScalarField,
+    /// The KZG proof of the combined row commitment at $x$
+    pub row_proof: Proof<E>,
+    /// The KZG proof of the combined column commitment at $z$
+    pub column_proof: Proof<E>,
+}
+
+/// Derive the row challenge $x$ and the column challenge $z$ from the row and column commitments
+pub fn consistency_challenges<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    row_commits: &[Commitment<E>],
+    column_commits: &[Commitment<E>],
+) -> Result<(E::ScalarField, E::ScalarField), Error> {
+    let rows = row_commits.iter().map(|c| c.0).collect::<Vec<_>>();
+    let columns = column_commits.iter().map(|c| c.0).collect::<Vec<_>>();
+    transcribe_generic(transcript, b"consistency rows", &rows)?;
+    transcribe_generic(transcript, b"consistency columns", &columns)?;
+    let field_size_bytes = get_field_size::<E::ScalarField>();
+    let x = get_challenge(transcript, b"consistency x", field_size_bytes);
+    let z = get_challenge(transcript, b"consistency z", field_size_bytes);
+    Ok((x, z))
+}
+
+/// Prove that `column_commits`, as made by [`Grid::commit_columns`], are consistent with
+/// `row_commits`, as made by [`Grid::commit`]
+pub fn prove_consistency<E: Pairing, S: KZGProof<E, Proof = Proof<E>>>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    grid: &Grid<E::ScalarField>,
+    row_commits: &[Commitment<E>],
+    column_commits: &[Commitment<E>],
+) -> Result<ConsistencyProof<E>, Error> {
+    check_commit_counts(row_commits, grid.height(), column_commits, grid.width())?;
+    let column_polys = grid.column_polys()?;
+    let (x, z) = consistency_challenges(transcript, row_commits, column_commits)?;
+
+    let row_coeffs = grid.column_domain()?.evaluate_all_lagrange_coefficients(z);
+    let row_poly = linear_combination(grid.polys(), &row_coeffs).ok_or(Error::EmptyEvals)?;
+    let column_coeffs = grid.domain().evaluate_all_lagrange_coefficients(x);
+    let column_poly = linear_combination(&column_polys, &column_coeffs).ok_or(Error::EmptyEvals)?;
+
+    let value = evaluate_at_points(&row_poly, &[x])[0];
+    let row_proof = srs.open(srs.compute_witness_polynomial(row_poly, x)?)?;
+    let column_proof = srs.open(srs.compute_witness_polynomial(column_poly, z)?)?;
+    Ok(ConsistencyProof {
+        value,
+        row_proof,
+        column_proof,
+    })
+}
+
+/// Verify a proof made with [`prove_consistency`]. The number of row and column commitments are
+/// the height and width of the grid, and must both be powers of two.
+pub fn verify_consistency<E: Pairing, M: MSMEngine<E = E>, S: KZGProof<E, Proof = Proof<E>>>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    row_commits: &[Commitment<E>],
+    column_commits: &[Commitment<E>],
+    proof: &ConsistencyProof<E>,
+) -> Result<bool, Error> {
+    let row_domain = radix2_domain::<E::ScalarField>(column_commits.len())?;
+    let column_domain = radix2_domain::<E::ScalarField>(row_commits.len())?;
+    let (x, z) = consistency_challenges(transcript, row_commits, column_commits)?;
+
+    let row_commit = combine_commits(row_commits, &column_domain, z)?;
+    let column_commit = combine_commits(column_commits, &row_domain, x)?;
+    Ok(
+        srs.verify::<M>(&row_commit, x, proof.value, &proof.row_proof)?
+            && srs.verify::<M>(&column_commit, z, proof.value, &proof.column_proof)?,
+    )
+}
+
+fn combine_commits<E: Pairing>(
+    commits: &[Commitment<E>],
+    domain: &Radix2EvaluationDomain<E::ScalarField>,
+    point: E::ScalarField,
+) -> Result<Commitment<E>, Error> {
+    linear_combination_of_commitments(commits, &domain.evaluate_all_lagrange_coefficients(point))
+}
+
+fn check_commit_counts<E: Pairing>(
+    row_commits: &[Commitment<E>],
+    height: usize,
+    column_commits: &[Commitment<E>],
+    width: usize,
+) -> Result<(), Error> {
+    if row_commits.len() != height {
+        return Err(Error::InvalidInputLength {
+            expected: height,
+            got: row_commits.len(),
+        });
+    }
+    if column_commits.len() != width {
+        return Err(Error::InvalidInputLength {
+            expected: width,
+            got: column_commits.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_std::vec::Vec;
+    use merlin::Transcript;
+
+    use super::{prove_consistency, verify_consistency};
+    use crate::{
+        grid::Grid, method1::M1NoPrecomp, msm::blst::BlstMSMEngine, test_rng, Commitment, Error,
+    };
+
+    type S = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+
+    #[test]
+    fn test_consistency() {
+        let rows = (0..4)
+            .map(|_| {
+                (0..8)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let grid = Grid::from_rows(rows).unwrap();
+        let srs = S::new(16, 2, &mut test_rng());
+        let commits = grid.commit(&srs).unwrap();
+
+        let grid = grid.extend_rows(2).unwrap().extend_columns(2).unwrap();
+        let row_commits = grid.commit(&srs).unwrap();
+        let extended = Commitment::extend_commitments(&commits, 8).unwrap();
+        assert!(row_commits.iter().zip(&extended).all(|(a, b)| a.0 == b.0));
+        let column_commits = grid.commit_columns(&srs).unwrap();
+        assert_eq!(16, column_commits.len());
+
+        let proof = prove_consistency(
+            &srs,
+            &mut Transcript::new(b"grid"),
+            &grid,
+            &row_commits,
+            &column_commits,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(true),
+            verify_consistency::<_, BlstMSMEngine, _>(
+                &srs,
+                &mut Transcript::new(b"grid"),
+                &row_commits,
+                &column_commits,
+                &proof
+            )
+        );
+
+        let mut bad_columns = column_commits.clone();
+        bad_columns.swap(0, 1);
+        assert_eq!(
+            Ok(false),
+            verify_consistency::<_, BlstMSMEngine, _>(
+                &srs,
+                &mut Transcript::new(b"grid"),
+                &row_commits,
+                &bad_columns,
+                &proof
+            )
+        );
+        let mut bad_rows = row_commits.clone();
+        bad_rows[3] = commits[0].clone();
+        assert_eq!(
+            Ok(false),
+            verify_consistency::<_, BlstMSMEngine, _>(
+                &srs,
+                &mut Transcript::new(b"grid"),
+                &bad_rows,
+                &column_commits,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::NotPowerOfTwo(7)),
+            verify_consistency::<_, BlstMSMEngine, _>(
+                &srs,
+                &mut Transcript::new(b"grid"),
+                &row_commits[..7],
+                &column_commits,
+                &proof
+            )
+        );
+        assert!(matches!(
+            prove_consistency(
+                &srs,
+                &mut Transcript::new(b"grid"),
+                &grid,
+                &row_commits[..4],
+                &column_commits,
+            ),
+            Err(Error::InvalidInputLength {
+                expected: 8,
+                got: 4
+            })
+        ));
+    }
+}
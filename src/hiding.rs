@@ -0,0 +1,513 @@
+//! Hiding KZG commitments, as in the hiding variant of KZG10. A hiding commitment to $f$ is
+//! $f(\tau) G + r(\tau) \gamma G$ for a random blinding polynomial $r$, where the setup holds the
+//! powers $\tau^i \gamma G$ and nobody knows $\gamma$. Openings reveal $r(z)$, so a commitment
+//! stays hiding for up to the hiding bound (the degree of $r$) openings at distinct points.
+//!
+//! [`HidingKzg::open_zk`] opens hiding commitments at a set of points as in method 1. The
+//! blinding polynomials are combined and divided by the vanishing polynomial just like the
+//! committed ones, so the quotient is blinded by the hiding generator, and the proof reveals the
+//! combined blinding polynomial at the points. Nothing leaks beyond the claimed evaluations as
+//! long as there are no more points than the hiding bound.
+//!
+//! The randomness for blinding is drawn from the `RngCore` passed to
+//! [`HidingKzg::commit_hiding`], so it can come from any source the application trusts.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::RngCore, vec::Vec, UniformRand};
+
+use crate::{
+    check_setup_size, gen_curve_powers, gen_powers,
+    lagrange::LagrangeInterpContext,
+    method1::{combine_polys, compute_quotient, opening_challenge, M1NoPrecomp},
+    poly_div_q_r_in_place,
+    poly_ops::{div_by_linear, evaluate_at_points},
+    traits::{Committer, MSMEngine, TranscriptProtocol},
+    vanishing_polynomial, Commitment, Error,
+};
+
+/// A setup for hiding commitments and their single point openings
+#[derive(Clone)]
+pub struct HidingKzg<E: Pairing, M: MSMEngine<E = E>> {
+    /// The setup for the committed polynomials
+    pub inner: M1NoPrecomp<E, M>,
+    /// The powers of tau times the hiding generator $\gamma G$
+    pub powers_of_gamma_g1: Vec<E::G1Affine>,
+    gamma_g1_precomp: M::G1Prepared,
+}
+
+/// The blinding polynomial of a hiding commitment, which is needed to open it
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Blinding<F: CanonicalSerialize + CanonicalDeserialize>(pub Vec<F>);
+
+/// An opening of a hiding commitment at a single point
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingProof<E: Pairing> {
+    /// The commitment to the witness polynomials of the committed and blinding polynomials
+    pub witness: E::G1Affine,
+    /// The evaluation of the blinding polynomial at the point
+    pub blinding_eval: E::ScalarField,
+}
+
+/// A zero knowledge opening of hiding commitments at a set of points
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZkProof<E: Pairing> {
+    /// The commitment to the quotients of the combined committed and blinding polynomials
+    pub witness: E::G1Affine,
+    /// The evaluations of the combined blinding polynomial at each point
+    pub blinding_evals: Vec<E::ScalarField>,
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> HidingKzg<E, M> {
+    /// Make a new random setup for polynomials with up to `max_coeffs` coefficients, which stay
+    /// hidden for up to `hiding_bound` openings. There are enough powers in G2 for
+    /// [`HidingKzg::open_zk`] at up to `hiding_bound` points.
+    pub fn new(max_coeffs: usize, hiding_bound: usize, rng: &mut impl RngCore) -> Self {
+        #[allow(unused_mut)]
+        let mut x = E::ScalarField::rand(rng);
+        #[allow(unused_mut)]
+        let mut gamma = E::ScalarField::rand(rng);
+        let g1 = E::G1::rand(rng);
+        let g2 = E::G2::rand(rng);
+        let inner = M1NoPrecomp::new_from_scalar_ref(&x, g1, g2, max_coeffs, hiding_bound.max(1));
+        #[allow(unused_mut)]
+        let mut x_powers = gen_powers(x, hiding_bound + 1);
+        let powers_of_gamma_g1 = gen_curve_powers::<E::G1>(&x_powers, g1 * gamma);
+        #[cfg(feature = "zeroize")]
+        {
+            zeroize::Zeroize::zeroize(&mut x);
+            zeroize::Zeroize::zeroize(&mut gamma);
+            zeroize::Zeroize::zeroize(&mut x_powers);
+        }
+        Self::from_parts(inner, powers_of_gamma_g1)
+    }
+
+    /// Make a setup from a method 1 setup and the powers of tau times the hiding generator for
+    /// the same tau. The hiding bound is one less than the number of hiding powers. Fails with
+    /// [`Error::SetupTooSmall`] if there isn't a power in G1 and a hiding power to verify
+    /// openings with, or fewer than two powers in G2.
+    pub fn new_from_affine(
+        inner: M1NoPrecomp<E, M>,
+        powers_of_gamma_g1: Vec<E::G1Affine>,
+    ) -> Result<Self, Error> {
+        check_setup_size(1, 1, inner.powers_of_g1.len())?;
+        check_setup_size(1, 1, powers_of_gamma_g1.len())?;
+        check_setup_size(2, 2, inner.powers_of_g2.len())?;
+        Ok(Self::from_parts(inner, powers_of_gamma_g1))
+    }
+
+    fn from_parts(inner: M1NoPrecomp<E, M>, powers_of_gamma_g1: Vec<E::G1Affine>) -> Self {
+        Self {
+            gamma_g1_precomp: M::prepare_g1(powers_of_gamma_g1.clone()),
+            inner,
+            powers_of_gamma_g1,
+        }
+    }
+
+    /// The number of openings at distinct points a commitment stays hidden for
+    pub fn hiding_bound(&self) -> usize {
+        self.powers_of_gamma_g1.len().saturating_sub(1)
+    }
+
+    /// Commit to `poly` with a random blinding polynomial drawn from `rng`. Returns the commitment
+    /// along with the blinding, which must be kept to open the commitment.
+    pub fn commit_hiding(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        rng: &mut impl RngCore,
+    ) -> Result<(Commitment<E>, Blinding<E::ScalarField>), Error> {
+        let blinding = Blinding(
+            (0..self.powers_of_gamma_g1.len())
+                .map(|_| E::ScalarField::rand(rng))
+                .collect(),
+        );
+        let commit = self.commit_with_blinding(poly, &blinding)?;
+        Ok((commit, blinding))
+    }
+
+    /// Commit to `poly` with the given blinding polynomial
+    pub fn commit_with_blinding(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        blinding: &Blinding<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        let commit = self.inner.commit(poly)?;
+        let blind = self.blinding_msm(&blinding.0)?;
+        Ok(Commitment((commit.0 + blind).into_affine()))
+    }
+
+    /// Open the hiding commitment to `poly` with `blinding` at `point`
+    pub fn open_hiding(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        blinding: &Blinding<E::ScalarField>,
+        point: E::ScalarField,
+    ) -> Result<HidingProof<E>, Error> {
+        let witness = self
+            .inner
+            .commit(div_by_linear(poly.as_ref(), point))?
+            .0
+            .into_group();
+        let blind_witness = self.blinding_msm(&div_by_linear(&blinding.0, point))?;
+        Ok(HidingProof {
+            witness: (witness + blind_witness).into_affine(),
+            blinding_eval: evaluate_at_points(&blinding.0, &[point])[0],
+        })
+    }
+
+    /// Verify that the hiding commitment `commit` opens to `value` at `point`
+    pub fn verify_hiding(
+        &self,
+        commit: &Commitment<E>,
+        point: E::ScalarField,
+        value: E::ScalarField,
+        proof: &HidingProof<E>,
+    ) -> Result<bool, Error> {
+        let g1 = self.inner.powers_of_g1[0];
+        let gamma_g1 = self.powers_of_gamma_g1[0];
+        let g2 = self.inner.powers_of_g2[0];
+        let lhs = E::G1::msm_unchecked(
+            &[commit.0, g1, gamma_g1],
+            &[E::ScalarField::from(1u64), -value, -proof.blinding_eval],
+        );
+        let rhs_g2 = self.inner.powers_of_g2[1].into_group() - g2 * point;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            g2,
+            proof.witness,
+            rhs_g2.into_affine(),
+        ))
+    }
+
+    /// Open the hiding commitments to `polys`, made with `blindings`, at `points` in zero
+    /// knowledge. `evals` has the evaluations of each polynomial at the points, as in
+    /// [`PolyMultiProofNoPrecomp::open`](crate::traits::PolyMultiProofNoPrecomp::open). There
+    /// can be at most [`HidingKzg::hiding_bound`] points.
+    pub fn open_zk(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        blindings: &[Blinding<E::ScalarField>],
+        points: &[E::ScalarField],
+    ) -> Result<ZkProof<E>, Error> {
+        self.check_zk_points(points.len())?;
+        if blindings.len() != polys.len() {
+            return Err(Error::InvalidInputLength {
+                expected: polys.len(),
+                got: blindings.len(),
+            });
+        }
+        let commits = self
+            .inner
+            .transcript_encoding
+            .prover_commits(polys.len(), |i| {
+                self.commit_with_blinding(&polys[i], &blindings[i])
+            })?;
+        let gamma = opening_challenge(
+            transcript,
+            &commits,
+            evals,
+            polys,
+            points,
+            &self.inner.transcript_encoding,
+        )?;
+        let gammas = gen_powers(gamma, polys.len());
+        let vp = vanishing_polynomial(points);
+
+        let q = compute_quotient(polys, &gammas, &vp)?;
+        let witness = self.inner.commit(&q.coeffs)?.0.into_group();
+        let blindings = blindings.iter().map(|b| &b.0).collect::<Vec<_>>();
+        let mut blinding = combine_polys(&blindings, &gammas)?;
+        let blinding_evals = evaluate_at_points(&blinding, points);
+        let blind_q = poly_div_q_r_in_place(&mut blinding, &vp.coeffs)?;
+        let blind_witness = self.blinding_msm(&blind_q)?;
+        Ok(ZkProof {
+            witness: (witness + blind_witness).into_affine(),
+            blinding_evals,
+        })
+    }
+
+    /// Verify a proof made with [`HidingKzg::open_zk`] that the hiding commitments `commits`
+    /// open to `evals` at `points`
+    pub fn verify_zk(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &ZkProof<E>,
+    ) -> Result<bool, Error> {
+        self.check_zk_points(points.len())?;
+        if proof.blinding_evals.len() != points.len() {
+            return Err(Error::InvalidInputLength {
+                expected: points.len(),
+                got: proof.blinding_evals.len(),
+            });
+        }
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let lhs = self
+            .inner
+            .verifier_lhs_with_lag_ctx(transcript, commits, points, evals, &lag_ctx)?;
+        let blinding_interp = lag_ctx.lagrange_interp_linear_combo(
+            &[&proof.blinding_evals],
+            &[E::ScalarField::from(1u64)],
+        )?;
+        let lhs = lhs - self.blinding_msm(&blinding_interp.coeffs)?;
+
+        let vp = vanishing_polynomial(points);
+        let g2_zeros = M::multi_scalar_mul_g2(&self.inner.g2_precomp, &vp.coeffs)?;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            self.inner.powers_of_g2[0],
+            proof.witness,
+            g2_zeros.into_affine(),
+        ))
+    }
+
+    fn check_zk_points(&self, n_points: usize) -> Result<(), Error> {
+        if n_points > self.hiding_bound() {
+            return Err(Error::TooManyPoints {
+                n_points,
+                max_points: self.hiding_bound(),
+            });
+        }
+        self.inner.check_n_points(n_points)
+    }
+
+    fn blinding_msm(&self, blinding: &[E::ScalarField]) -> Result<E::G1, Error> {
+        if blinding.len() > self.powers_of_gamma_g1.len() {
+            return Err(Error::InvalidInputLength {
+                expected: self.powers_of_gamma_g1.len(),
+                got: blinding.len(),
+            });
+        }
+        M::multi_scalar_mul_g1(&self.gamma_g1_precomp, blinding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::{Blinding, HidingKzg};
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        poly_ops::evaluate_at_points,
+        test_rng,
+        traits::{Committer, MSMEngine},
+        transcript::TranscriptEncoding,
+        Error,
+    };
+
+    fn check_hiding<M: MSMEngine<E = Bls12_381>>() {
+        let s = HidingKzg::<Bls12_381, M>::new(32, 2, &mut test_rng());
+        assert_eq!(2, s.hiding_bound());
+        let poly = (0..32)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (commit, blinding) = s.commit_hiding(&poly, &mut test_rng()).unwrap();
+        let (commit2, _) = s.commit_hiding(&poly, &mut test_rng()).unwrap();
+        // The commitments are randomized, and differ from the non-hiding commitment
+        assert_ne!(commit.0, commit2.0);
+        assert_ne!(commit.0, s.inner.commit(&poly).unwrap().0);
+        assert_eq!(
+            commit.0,
+            s.commit_with_blinding(&poly, &blinding).unwrap().0
+        );
+
+        let point = Fr::rand(&mut test_rng());
+        let value = evaluate_at_points(&poly, &[point])[0];
+        let proof = s.open_hiding(&poly, &blinding, point).unwrap();
+        assert_eq!(Ok(true), s.verify_hiding(&commit, point, value, &proof));
+        assert_eq!(
+            Ok(false),
+            s.verify_hiding(&commit, point, value + Fr::from(1u64), &proof)
+        );
+        assert_eq!(Ok(false), s.verify_hiding(&commit2, point, value, &proof));
+        let mut wrong_blinding = proof.clone();
+        wrong_blinding.blinding_eval += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_hiding(&commit, point, value, &wrong_blinding)
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 4
+            }),
+            s.commit_with_blinding(&poly, &Blinding(vec![Fr::from(1u64); 4]))
+                .map(|c| c.0)
+        );
+    }
+
+    fn check_zk<M: MSMEngine<E = Bls12_381>>() {
+        let s = HidingKzg::<Bls12_381, M>::new(32, 4, &mut test_rng());
+        let polys = (0..3)
+            .map(|_| {
+                (0..32)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let (commits, blindings): (Vec<_>, Vec<_>) = polys
+            .iter()
+            .map(|p| s.commit_hiding(p, &mut test_rng()).unwrap())
+            .unzip();
+        let points = (0..4u64).map(Fr::from).collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| evaluate_at_points(p, &points))
+            .collect::<Vec<_>>();
+
+        let proof = s
+            .open_zk(
+                &mut Transcript::new(b"zk"),
+                &evals,
+                &polys,
+                &blindings,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_zk(
+                &mut Transcript::new(b"zk"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        let mut wrong_evals = evals.clone();
+        wrong_evals[1][2] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_zk(
+                &mut Transcript::new(b"zk"),
+                &commits,
+                &points,
+                &wrong_evals,
+                &proof
+            )
+        );
+        let mut wrong_blinding = proof.clone();
+        wrong_blinding.blinding_evals[0] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_zk(
+                &mut Transcript::new(b"zk"),
+                &commits,
+                &points,
+                &evals,
+                &wrong_blinding
+            )
+        );
+
+        // With a transcript version absorbing them, the prover absorbs the hiding commitments
+        let mut v1 = s.clone();
+        v1.inner = v1.inner.with_transcript_encoding(TranscriptEncoding::v1());
+        let v1_proof = v1
+            .open_zk(
+                &mut Transcript::new(b"zk"),
+                &evals,
+                &polys,
+                &blindings,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            v1.verify_zk(
+                &mut Transcript::new(b"zk"),
+                &commits,
+                &points,
+                &evals,
+                &v1_proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_zk(
+                &mut Transcript::new(b"zk"),
+                &commits,
+                &points,
+                &evals,
+                &v1_proof
+            )
+        );
+
+        // Opening at more points than the hiding bound would reveal the blinding
+        let too_many = (0..5u64).map(Fr::from).collect::<Vec<_>>();
+        assert_eq!(
+            Err(Error::TooManyPoints {
+                n_points: 5,
+                max_points: 4
+            }),
+            s.open_zk(
+                &mut Transcript::new(b"zk"),
+                &evals,
+                &polys,
+                &blindings,
+                &too_many
+            )
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            s.open_zk(
+                &mut Transcript::new(b"zk"),
+                &evals,
+                &polys,
+                &blindings[..2],
+                &points
+            )
+        );
+    }
+
+    #[test]
+    fn test_zk() {
+        check_zk::<BlstMSMEngine>();
+        check_zk::<ArkMSMEngine<Bls12_381>>();
+    }
+
+    #[test]
+    fn test_hiding() {
+        check_hiding::<BlstMSMEngine>();
+        check_hiding::<ArkMSMEngine<Bls12_381>>();
+    }
+
+    #[test]
+    fn test_new_from_affine_checks_sizes() {
+        let s = HidingKzg::<Bls12_381, BlstMSMEngine>::new(32, 2, &mut test_rng());
+        assert_eq!(
+            Ok(()),
+            HidingKzg::new_from_affine(s.inner.clone(), s.powers_of_gamma_g1.clone()).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 1,
+                got: 0
+            }),
+            HidingKzg::new_from_affine(s.inner.clone(), Vec::new()).map(|_| ())
+        );
+        let one_g2 = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_affine(
+            s.inner.powers_of_g1.clone(),
+            s.inner.powers_of_g2[..1].to_vec(),
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 2,
+                needed: 2,
+                got: 1
+            }),
+            HidingKzg::new_from_affine(one_g2, s.powers_of_gamma_g1.clone()).map(|_| ())
+        );
+    }
+}
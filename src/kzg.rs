@@ -2,17 +2,15 @@
 use core::ops::Mul;
 
 use ark_ec::{pairing::Pairing, AffineRepr};
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
 
 use crate::{
     m1_cycl::M1CyclPrecomp,
     method1::{precompute::M1Precomp, M1NoPrecomp},
-    poly_div_q_r,
+    poly_ops::div_by_linear,
     traits::{Committer, KZGProof, MSMEngine},
     Error,
 };
 use ark_std::vec::Vec;
-use ark_std::vec;
 
 impl<E: Pairing, A: Committer<E> + WithSrs<E>> KZGProof<E> for A {
     type Proof = crate::method1::Proof<E>;
@@ -22,11 +20,7 @@ impl<E: Pairing, A: Committer<E> + WithSrs<E>> KZGProof<E> for A {
         poly: Vec<<E as Pairing>::ScalarField>,
         point: <E as Pairing>::ScalarField,
     ) -> Result<Vec<<E as Pairing>::ScalarField>, Error> {
-        use ark_ff::One;
-        let poly = DensePolynomial::from_coefficients_vec(poly);
-        let divisor = DensePolynomial::from_coefficients_vec(vec![-point, E::ScalarField::one()]);
-        let (q, _r) = poly_div_q_r(poly.into(), divisor.into())?;
-        Ok(q)
+        Ok(div_by_linear(&poly, point))
     }
 
     fn open(&self, witness_poly: Vec<<E as Pairing>::ScalarField>) -> Result<Self::Proof, Error> {
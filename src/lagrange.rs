@@ -1,5 +1,6 @@
 use ark_ff::FftField;
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{vec, vec::Vec};
 use core::ops::Mul;
 
@@ -7,9 +8,12 @@ use crate::cfg_iter;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::Error;
+use crate::{
+    field_ops::{batch_inverse, mul_add_assign},
+    Error,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub(crate) struct LagrangeInterpContext<F: FftField> {
     lag_polys: Vec<DensePolynomial<F>>,
 }
@@ -35,20 +39,25 @@ impl<F: FftField> LagrangeInterpContext<F> {
             })
             .collect();
 
-        let lag_polys = cfg_iter!(non_normalized_polys)
-            .map(|(i, non_normed)| {
-                // Evaluate at target, divide by result
-                // so that the polynomial evaluates to 1 at the target
-                non_normed
-                    .evaluate(&points[i])
-                    .inverse()
-                    .ok_or(Error::DivisorIsZero)
-                    .map(|v| non_normed * v)
-            })
-            .collect::<Result<Vec<_>, Error>>()?;
+        // Evaluate at target, divide by result so that the polynomial evaluates to 1 at the
+        // target. The results are inverted together.
+        let mut norms = cfg_iter!(non_normalized_polys)
+            .map(|(i, non_normed)| non_normed.evaluate(&points[i]))
+            .collect::<Vec<_>>();
+        batch_inverse(&mut norms)?;
+        let lag_polys = non_normalized_polys
+            .iter()
+            .zip(norms)
+            .map(|(non_normed, v)| non_normed * v)
+            .collect();
         Ok(Self { lag_polys })
     }
 
+    /// The lagrange polynomials $L_j$, which are one at $x_j$ and zero at the other points
+    pub fn lag_polys(&self) -> &[DensePolynomial<F>] {
+        &self.lag_polys
+    }
+
     /// Given evals $((y_{1, 1}, \ldots y_{1_k}), \ldots (y_{l, 1}, \ldots y_{l, k}))$, points
     /// $(x_1, \ldots x_k)$, and scalars $(\gamma_1, \ldots, \gamma_l)$, this method
     /// computes $\sum_{i=1}^l \gamma_i r_i$ where $r_i$ is the unique degree $k$ polynomial such that
@@ -58,21 +67,104 @@ impl<F: FftField> LagrangeInterpContext<F> {
         evals: &[impl AsRef<[F]>],
         scalars: &[F],
     ) -> Result<DensePolynomial<F>, Error> {
-        let mut targets = vec![F::zero(); self.lag_polys.len()];
-        for i in 0..evals.len() {
-            let eval = evals[i].as_ref();
-            for j in 0..eval.len() {
-                // Our target at x_j is \sum gamma_i * y_{i, j}
-                // Does this as_ref() call introduce any overhead?
-                targets[j] += scalars[i] * eval[j];
+        if self.lag_polys.is_empty() {
+            return Err(Error::NoPointsGiven);
+        }
+        // Interpolate to the combined evaluation at each point
+        let targets = linear_combination_of_evals(evals, scalars, self.lag_polys.len());
+        let len = self.lag_polys.iter().map(|l| l.coeffs.len()).max();
+        let mut coeffs = vec![F::zero(); len.unwrap_or(0)];
+        for (l, target) in self.lag_polys.iter().zip(targets) {
+            mul_add_assign(&mut coeffs, target, &l.coeffs);
+        }
+        Ok(DensePolynomial::from_coefficients_vec(coeffs))
+    }
+
+    /// Does [`LagrangeInterpContext::lagrange_interp_linear_combo`] for each set of evals with
+    /// its scalars. The lagrange polynomials are normalized with a single batch inversion when
+    /// the context is made, so every set shares it, e.g. the openings of a batch which are all at
+    /// the same points.
+    pub fn lagrange_interp_many(
+        &self,
+        evals_sets: &[&[impl AsRef<[F]>]],
+        scalars_sets: &[impl AsRef<[F]>],
+    ) -> Result<Vec<DensePolynomial<F>>, Error> {
+        if evals_sets.len() != scalars_sets.len() {
+            return Err(Error::InvalidInputLength {
+                expected: evals_sets.len(),
+                got: scalars_sets.len(),
+            });
+        }
+        evals_sets
+            .iter()
+            .zip(scalars_sets)
+            .map(|(evals, scalars)| self.lagrange_interp_linear_combo(evals, scalars.as_ref()))
+            .collect()
+    }
+}
+
+/// Computes $\sum_{i=1}^l \gamma_i y_{i, j}$ for each of the `n_points` points $x_j$, which are the
+/// evaluations of $\sum_{i=1}^l \gamma_i r_i$ at the points
+pub(crate) fn linear_combination_of_evals<F: FftField>(
+    evals: &[impl AsRef<[F]>],
+    scalars: &[F],
+    n_points: usize,
+) -> Vec<F> {
+    let mut targets = vec![F::zero(); n_points];
+    for (eval, scalar) in evals.iter().zip(scalars) {
+        // Our target at x_j is \sum gamma_i * y_{i, j}
+        mul_add_assign(&mut targets, *scalar, eval.as_ref());
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_poly::Polynomial;
+    use ark_std::UniformRand;
+
+    use super::LagrangeInterpContext;
+    use crate::{test_rng, Error};
+
+    #[test]
+    fn test_lagrange_interp_many() {
+        let points = (0..6)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let ctx = LagrangeInterpContext::new_from_points(&points).unwrap();
+        let rand_vec = |n: usize| {
+            (0..n)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>()
+        };
+        let evals_sets = (0..3)
+            .map(|i| (0..=i).map(|_| rand_vec(6)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let scalars_sets = (0..3).map(|i| rand_vec(i + 1)).collect::<Vec<_>>();
+        let evals_refs = evals_sets.iter().map(|e| e.as_slice()).collect::<Vec<_>>();
+
+        let many = ctx
+            .lagrange_interp_many(&evals_refs, &scalars_sets)
+            .unwrap();
+        assert_eq!(3, many.len());
+        for ((r, evals), scalars) in many.iter().zip(&evals_sets).zip(&scalars_sets) {
+            assert_eq!(
+                &ctx.lagrange_interp_linear_combo(evals, scalars).unwrap(),
+                r
+            );
+            for (j, x) in points.iter().enumerate() {
+                let expected = evals.iter().zip(scalars).map(|(e, s)| e[j] * s).sum::<Fr>();
+                assert_eq!(expected, r.evaluate(x));
             }
         }
-        // Now we just interpolate to targets
-        targets
-            .into_iter()
-            .enumerate()
-            .map(|(j, target)| self.lag_polys[j].mul(target))
-            .reduce(|x, y| x + y)
-            .ok_or(Error::NoPointsGiven)
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            ctx.lagrange_interp_many(&evals_refs, &scalars_sets[..2])
+        );
     }
 }
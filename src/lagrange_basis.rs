@@ -0,0 +1,187 @@
+//! The SRS in lagrange form, used to commit to polynomials given in evaluation form
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::vec::Vec;
+
+use crate::{traits::MSMEngine, Commitment, Error};
+
+/// Commitments to the lagrange basis polynomials $[L_i(x)]_1$ of a radix-2 domain. Committing to
+/// evaluations against these is the same as committing to the polynomial interpolated from them,
+/// without the IFFT.
+#[derive(Clone, Debug)]
+pub struct LagrangeBasis<E: Pairing, M: MSMEngine<E = E>> {
+    domain: Radix2EvaluationDomain<E::ScalarField>,
+    /// The lagrange basis in G1, in the order of the domain elements
+    pub lagrange_g1: Vec<E::G1Affine>,
+    prepared: M::G1Prepared,
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> LagrangeBasis<E, M> {
+    /// Compute the lagrange basis for `domain` from the powers of tau in G1. This is a single
+    /// IFFT over the group elements.
+    pub fn new(
+        powers_of_g1: &[E::G1Affine],
+        domain: Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Self, Error> {
+        let n = domain.size();
+        if powers_of_g1.len() < n {
+            return Err(Error::TooManyScalars {
+                n_coeffs: n,
+                expected_max: powers_of_g1.len(),
+            });
+        }
+        let mut basis = powers_of_g1[..n]
+            .iter()
+            .map(|p| (*p).into())
+            .collect::<Vec<E::G1>>();
+        domain.ifft_in_place(&mut basis);
+        let lagrange_g1 = E::G1::normalize_batch(&basis);
+        Ok(Self {
+            domain,
+            prepared: M::prepare_g1(lagrange_g1.clone()),
+            lagrange_g1,
+        })
+    }
+
+    /// The domain this basis is over
+    pub fn domain(&self) -> &Radix2EvaluationDomain<E::ScalarField> {
+        &self.domain
+    }
+
+    /// Commit to the polynomial with the given evaluations over the domain
+    pub fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+    ) -> Result<Commitment<E>, Error> {
+        check_evals_len(evals.as_ref(), &self.domain)?;
+        let res = M::multi_scalar_mul_g1(&self.prepared, evals.as_ref())?;
+        Ok(Commitment(res.into_affine()))
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalSerialize for LagrangeBasis<E, M> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.domain.serialize_with_mode(&mut writer, compress)?;
+        self.lagrange_g1.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.domain.serialized_size(compress) + self.lagrange_g1.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Valid for LagrangeBasis<E, M> {
+    fn check(&self) -> Result<(), SerializationError> {
+        if self.lagrange_g1.len() != self.domain.size() {
+            return Err(SerializationError::InvalidData);
+        }
+        self.lagrange_g1.check()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalDeserialize for LagrangeBasis<E, M> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let domain =
+            Radix2EvaluationDomain::deserialize_with_mode(&mut reader, compress, validate)?;
+        let lagrange_g1 = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        if lagrange_g1.len() != domain.size() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self {
+            domain,
+            prepared: M::prepare_g1(lagrange_g1.clone()),
+            lagrange_g1,
+        })
+    }
+}
+
+pub(crate) fn check_evals_len<F: ark_ff::FftField>(
+    evals: &[F],
+    domain: &Radix2EvaluationDomain<F>,
+) -> Result<(), Error> {
+    if evals.len() != domain.size() {
+        return Err(Error::InvalidInputLength {
+            expected: domain.size(),
+            got: evals.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Finds the basis for `domain` in `bases` if there is one
+pub(crate) fn find_basis<'a, E: Pairing, M: MSMEngine<E = E>>(
+    bases: &'a [LagrangeBasis<E, M>],
+    domain: &Radix2EvaluationDomain<E::ScalarField>,
+) -> Option<&'a LagrangeBasis<E, M>> {
+    bases.iter().find(|b| b.domain() == domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::vec::Vec;
+
+    use crate::{
+        method1::M1NoPrecomp,
+        method2::M2NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        traits::Committer,
+        Error,
+    };
+
+    fn check_commit_evals(s: &impl Committer<Bls12_381>, domain: &Radix2EvaluationDomain<Fr>) {
+        let evals = (0..domain.size())
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let expected = s.commit(domain.ifft(&evals)).unwrap();
+        assert_eq!(expected.0, s.commit_evals(&evals, domain).unwrap().0);
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: domain.size(),
+                got: domain.size() - 1
+            }),
+            s.commit_evals(&evals[1..], domain).map(|c| c.0)
+        );
+    }
+
+    #[test]
+    fn test_commit_evals() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let other = Radix2EvaluationDomain::<Fr>::new(32).unwrap();
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 4, &mut test_rng());
+        // Without a basis this falls back to the ifft
+        check_commit_evals(&s, &domain);
+        let s = s.with_lagrange_basis(domain).unwrap();
+        check_commit_evals(&s, &domain);
+        check_commit_evals(&s, &other);
+
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 4, &mut test_rng())
+            .with_lagrange_basis(domain)
+            .unwrap();
+        check_commit_evals(&s, &domain);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, &mut test_rng())
+            .with_lagrange_basis(domain)
+            .unwrap();
+        check_commit_evals(&s, &domain);
+
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(32, 4, &mut test_rng());
+        assert!(s.with_lagrange_basis(domain).is_err());
+    }
+}
@@ -9,12 +9,81 @@
 //! pre-compute lagrange polynomials and vanishing polynomials for the points, which can speed up
 //! proof generation by a significant amount, especially for larger proof sizes.
 //!
+//! Verifiers of method 1 proofs don't need the powers of tau in G1, the
+//! `method1::verifier_key::VerifierKey` from `M1NoPrecomp::verifier_key` holds just the G1
+//! generator and the powers of tau in G2.
+//!
+//! The schemes commit and prove in G1. To commit and prove in G2 instead, use the pairing
+//! `swapped::Swapped<E>` in place of `E`.
+//!
+//! Openings at a grid of points $y_j \omega^i$, a union of cosets of a subgroup, can use
+//! `tensor_grid::TensorGrid` to divide and interpolate with the grid's product structure.
+//!
+//! To show a smaller commitment holds a range of coefficients of a committed polynomial, use
+//! `sub_poly::prove_sub_poly` and `sub_poly::verify_sub_poly`.
+//!
+//! Setups from ceremonies with more powers in G2 than a scheme opens at, or with other
+//! generators, can be loaded into an `srs::Srs` and made into each scheme with `Srs::to_method1`
+//! and `Srs::to_method2`.
+//!
+//! The traits for the schemes aren't object safe, so to pick a method 1 scheme at runtime use
+//! `any_scheme::AnyScheme`, which is built from an `any_scheme::SchemeConfig`.
+//!
+//! Building the precomputed schemes can take minutes for many point sets. Their
+//! `*_with_progress` constructors take a `progress::Progress` to report each step and to stop
+//! early on shutdown.
+//!
 //! ### Features
-//! * `blst` enables a specific `bls12-381` implementation which uses `blst` for curve msm.
+//! * `blst` enables a specific `bls12-381` implementation which uses `blst` for curve msm. Code
+//!   that's generic over the curve can use `msm::AnyMSMEngine`, which picks blst on BLS12-381.
 //! * `parallel` enables parallel computation for
 //!   * PMP setup generation
 //!   * operations in the `data_availability_grid` example
-//! * `print-trace` enables some tracing that shows the time certain things take to execute
+//!   * verifying against many commitments with `M1CyclPrecomp`
+//!
+//!   The thread pool and chunk sizes are chosen with `parallel::ParallelismConfig`, which can be
+//!   attached to a method 1 setup with `M1NoPrecomp::with_parallelism`.
+//! * `print-trace` prints nested timings of committing, opening, verifying and building the
+//!   precomputed schemes, along with the msms and ffts inside them and their sizes, using
+//!   `ark-std`'s timers
+//! * `print-sizes` prints the sizes of setups, commitments and proofs when running the
+//!   `scheme_bench` benchmark
+//! * `capi` exports a C API for method 1 over BLS12-381 from the `ffi` module, with the header in
+//!   `include/poly_multiproof.h`
+//! * `wasm` adds the `wasm` module, a byte buffer API for method 1 over BLS12-381 which is
+//!   exported to JavaScript on `wasm32` and wrapped with `Uint8Array`s in `js/poly_multiproof.mjs`.
+//!   Build it with the size-optimized `wasm` profile.
+//! * `python` builds a python module for the cyclic precompute of method 1 over BLS12-381 with
+//!   `pyo3`. Build it with `maturin develop --release`, which uses `pyproject.toml`.
+//! * `bn254` and `bls12-377` add type aliases for the schemes over those curves in the `curves`
+//!   module, alongside the BLS12-381 aliases enabled by `blst`
+//! * `serde` implements `Serialize` and `Deserialize` for commitments, proofs, and setups using
+//!   their compressed serialization, hex encoded for human readable formats
+//! * `async` adds `commit_async`, `open_async` and `verify_async` in the `nonblocking` module,
+//!   which run on a user supplied executor (such as tokio's `spawn_blocking`) and return futures
+//! * `commit-cache` adds `commit_cache::CommitCache`, which wraps a setup and remembers the
+//!   commitments to the polynomials it committed to most recently
+//! * `mmap` adds `storage::MmapStorage` on unix, which backs a `storage::StoredM1` setup with a
+//!   memory mapped file of the powers of tau in G1 instead of holding them in memory
+//! * `test-vectors` adds `testing::vectors`, which writes deterministic JSON test vectors of an
+//!   opening by each scheme for other implementations, and checks them
+//! * `test-harness` adds `testing::equivalence`, which checks that schemes give identical
+//!   commitments and proofs, for forks adding their own schemes or MSM engines
+//! * `evm` adds the `evm` module, which generates a Solidity verifier for method 1 proofs over
+//!   BN254 and encodes proofs and calldata for it
+//! * `simd` runs the multiply-adds of lagrange interpolation and linear combinations in lanes,
+//!   compiled with AVX2 when the CPU has it, see `field_ops::capabilities`
+//! * `audit` adds `audit::open_traced`, which returns an `audit::OpeningTrace` of everything a
+//!   method 1 opening absorbed and the challenge it drew alongside the proof, and
+//!   `audit::verify_trace` to check a proof against one offline
+//! * `zeroize` wipes the secret scalar and its powers from memory once a setup has been generated,
+//!   and adds `new_from_secret` constructors which borrow the secret in a `Zeroizing` wrapper
+//! * `std` (on by default) uses the standard library. Without it the crate is `no_std` and builds
+//!   for `wasm32-unknown-unknown` and embedded targets, as long as `blst` and `parallel` are also off:
+//!   ```bash
+//!   cargo build --no-default-features --target wasm32-unknown-unknown
+//!   ```
+//!   Randomness is always passed in as an `RngCore`, so there is no dependency on an OS rng.
 //!
 //! See [the `poly-multiproof` documentation](https://docs.rs/poly-multiproof) for more details.
 //!
@@ -61,12 +130,13 @@
 use ark_ec::{scalar_mul::fixed_base::FixedBase, CurveGroup, ScalarMul};
 use ark_ff::{Field, PrimeField};
 use ark_poly::{
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    univariate::{DensePolynomial, SparsePolynomial},
     DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError};
 use ark_std::{vec, vec::Vec};
-use merlin::Transcript;
+use traits::TranscriptProtocol;
+use transcript::TranscriptEncoding;
 #[cfg(test)]
 use rand::thread_rng as test_rng;
 
@@ -76,32 +146,80 @@ pub use ark_poly;
 pub use ark_serialize;
 pub use ark_ec::pairing::Pairing;
 pub use merlin;
+#[cfg(feature = "zeroize")]
+pub use zeroize;
 
+pub mod any_scheme;
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "ark-bls12-381")]
+pub mod blob;
+pub mod ceremony;
+#[cfg(feature = "commit-cache")]
+pub mod commit_cache;
+pub mod curves;
+pub mod epoch;
+pub mod equivalence;
+pub mod eval_matrix;
+pub mod field_ops;
+#[cfg(feature = "evm")]
+pub mod evm;
 pub mod m1_cycl;
 pub mod method1;
 pub mod method2;
 
+#[cfg(feature = "capi")]
+pub mod ffi;
+pub mod fk20;
+pub mod grid;
+pub mod grid_consistency;
+pub mod hiding;
 pub mod kzg;
 
 pub(crate) mod lagrange;
+pub mod lagrange_basis;
+pub mod srs;
+pub mod storage;
+pub mod streaming;
+pub mod sub_poly;
+pub mod swapped;
+pub mod tensor_grid;
 
 pub mod traits;
+pub mod transcript;
+pub mod vc;
 
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod point_set;
 pub mod poly_ops;
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "serde")]
+mod serde_impls;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub mod msm;
+#[cfg(feature = "async")]
+pub mod nonblocking;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-vectors", feature = "test-harness"))]
 pub mod testing;
 
-/// Crate error type
+/// Crate error type. Each variant has a stable numeric code from [`Error::code`] for FFI and
+/// logging, and a broad cause from [`Error::kind`].
 #[derive(Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
 pub enum Error {
     /// Too many scalars were given. This normally happens when you initialize a `PMP` with too few
     /// points for the degree of the polyomial you want to commit/open to.
-    #[cfg_attr(feature = "std", error("Polynomial given is too large"))]
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_coeffs} scalars, but at most {expected_max} are supported")
+    )]
     TooManyScalars {
         /// The number of scalars given
         n_coeffs: usize,
@@ -116,7 +234,10 @@ pub enum Error {
     NoPolynomialsGiven,
     /// The evaluations given to a method did not match with the expected number for the size of
     /// the polynomial.
-    #[cfg_attr(feature = "std", error("Given evaluations were the incorrect size"))]
+    #[cfg_attr(
+        feature = "std",
+        error("Polynomial {poly} has {n_evals} evaluations, expected {expected}")
+    )]
     EvalsIncorrectSize {
         /// The index of the polynomial that had incorrect evals
         poly: usize,
@@ -150,6 +271,30 @@ pub enum Error {
         /// The number of eval rows
         n_evals: usize,
     },
+    /// A polynomial had more coefficients than the setup has powers of tau in G1
+    #[cfg_attr(
+        feature = "std",
+        error("Polynomial {index} has {n_coeffs} coefficients, but at most {max_coeffs} are supported")
+    )]
+    PolynomialTooLarge {
+        /// The index of the polynomial
+        index: usize,
+        /// The number of coefficients it has
+        n_coeffs: usize,
+        /// The most coefficients supported by the setup
+        max_coeffs: usize,
+    },
+    /// More points were given than the setup has powers of tau in G2 for
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_points} points, but at most {max_points} are supported")
+    )]
+    TooManyPoints {
+        /// The number of points given
+        n_points: usize,
+        /// The most points supported by the setup
+        max_points: usize,
+    },
     /// Evals and commits had different sizes
     #[cfg_attr(
         feature = "std",
@@ -186,6 +331,321 @@ pub enum Error {
         /// Actual length
         got: usize,
     },
+    /// A size which must be a power of two was not
+    #[cfg_attr(feature = "std", error("{0} is not a power of two"))]
+    NotPowerOfTwo(usize),
+    /// A domain was larger than the largest power of two subgroup of the scalar field
+    #[cfg_attr(
+        feature = "std",
+        error("Domain of size {size} is larger than the field's maximum of {max}")
+    )]
+    DomainTooLarge {
+        /// The requested size
+        size: usize,
+        /// The largest supported size
+        max: usize,
+    },
+    /// No evaluations were given
+    #[cfg_attr(feature = "std", error("Expected evaluations, none were given"))]
+    EmptyEvals,
+    /// Point set index was out of range
+    #[cfg_attr(
+        feature = "std",
+        error("Point set index {idx} is out of range for {n_point_sets} point sets")
+    )]
+    PointSetIndexOutOfRange {
+        /// Index
+        idx: usize,
+        /// Number of point sets
+        n_point_sets: usize,
+    },
+    /// The same point set was given more than once
+    #[cfg_attr(feature = "std", error("Point set index {0} was given more than once"))]
+    DuplicatePointSetIndex(usize),
+    /// Commitment index was out of range
+    #[cfg_attr(
+        feature = "std",
+        error("Commitment index {idx} is out of range for {n_commits} commitments")
+    )]
+    CommitmentIndexOutOfRange {
+        /// Index
+        idx: usize,
+        /// Number of commitments
+        n_commits: usize,
+    },
+    /// The same commitment was given more than once
+    #[cfg_attr(
+        feature = "std",
+        error("Commitment index {0} was given more than once")
+    )]
+    DuplicateCommitmentIndex(usize),
+    /// A point in the setup is zero or not in the prime order subgroup
+    #[cfg_attr(
+        feature = "std",
+        error("Power {index} of tau in G{group} is zero or not in the prime order subgroup")
+    )]
+    InvalidSrsPoint {
+        /// The group the point is in, 1 or 2
+        group: u8,
+        /// The index of the point in the powers of tau
+        index: usize,
+    },
+    /// The powers of tau in a group are not successive powers of the same secret
+    #[cfg_attr(feature = "std", error("Powers of tau in G{group} are inconsistent"))]
+    InconsistentSrs {
+        /// The group with inconsistent powers, 1 or 2
+        group: u8,
+    },
+    /// A ceremony contribution doesn't update the previous setup by the secret in its proof
+    #[cfg_attr(feature = "std", error("Contribution doesn't update the previous setup"))]
+    InvalidContribution,
+    /// A degree bound can't be proven with the powers of tau in the setup
+    #[cfg_attr(
+        feature = "std",
+        error("Degree bound {bound} is outside of the supported range {min}..={max}")
+    )]
+    DegreeBoundOutOfRange {
+        /// The requested degree bound
+        bound: usize,
+        /// The smallest supported bound
+        min: usize,
+        /// The largest supported bound
+        max: usize,
+    },
+    /// A thread pool couldn't be made with the requested number of threads
+    #[cfg_attr(feature = "std", error("Unable to make a thread pool with that many threads"))]
+    InvalidThreadCount,
+    /// A position is past the end of a committed vector
+    #[cfg_attr(
+        feature = "std",
+        error("Position {idx} is out of range for a vector of length {len}")
+    )]
+    PositionOutOfRange {
+        /// The requested position
+        idx: usize,
+        /// The length of the vector
+        len: usize,
+    },
+    /// The same position was opened more than once
+    #[cfg_attr(feature = "std", error("Position {0} was given more than once"))]
+    DuplicatePosition(usize),
+    /// A setup has fewer powers of tau than its sizes need
+    #[cfg_attr(
+        feature = "std",
+        error("Setup has {got} powers of tau in G{group}, but needs at least {needed}")
+    )]
+    SetupTooSmall {
+        /// The group with too few powers, 1 or 2
+        group: u8,
+        /// The number of powers needed
+        needed: usize,
+        /// The number of powers given
+        got: usize,
+    },
+    /// Work handed to a blocking executor panicked or was dropped before it finished
+    #[cfg_attr(feature = "std", error("Blocking task failed before producing a result"))]
+    BlockingTaskFailed,
+    /// A setup file couldn't be opened or mapped
+    #[cfg_attr(feature = "std", error("Unable to open or map the setup file"))]
+    SrsFileUnreadable,
+    /// A setup file had a bad header, the wrong size, or invalid points
+    #[cfg_attr(feature = "std", error("Invalid setup file"))]
+    InvalidSrsFile,
+    /// A replayed transcript was asked for challenges which didn't match the recorded ones
+    #[cfg_attr(feature = "std", error("Replayed challenges did not match the recording"))]
+    TranscriptReplayMismatch,
+    /// No point set of the setup has the given hash
+    #[cfg_attr(feature = "std", error("No point set has the given hash"))]
+    UnknownPointSet,
+    /// Too few evaluations were given to recover erasure coded data
+    #[cfg_attr(
+        feature = "std",
+        error("Given {got} evaluations, but at least {needed} are needed to recover")
+    )]
+    TooFewEvals {
+        /// The number of evaluations given
+        got: usize,
+        /// The number of evaluations needed
+        needed: usize,
+    },
+    /// The evaluations given to recover erasure coded data aren't of a low enough degree
+    /// polynomial, so they aren't from an extension
+    #[cfg_attr(feature = "std", error("Evaluations aren't of an extension"))]
+    InconsistentEvals,
+    /// A shift of a tensor grid is zero or its coset overlaps an earlier shift's, so the grid's
+    /// points aren't distinct
+    #[cfg_attr(
+        feature = "std",
+        error("Shift {0} is zero or its coset overlaps an earlier shift's")
+    )]
+    OverlappingCosets(usize),
+    /// No setup is held for an epoch
+    #[cfg_attr(feature = "std", error("No setup is held for epoch {0}"))]
+    UnknownEpoch(u64),
+    /// Values from different epochs were used together
+    #[cfg_attr(
+        feature = "std",
+        error("Expected values from epoch {expected}, but got one from epoch {got}")
+    )]
+    EpochMismatch {
+        /// The epoch of the proof
+        expected: u64,
+        /// The epoch of the value which didn't match it
+        got: u64,
+    },
+    /// An epoch was added which isn't after the current one, or the current one was retired
+    #[cfg_attr(
+        feature = "std",
+        error("Epoch {epoch} can't be added or retired while epoch {current} is current")
+    )]
+    StaleEpoch {
+        /// The epoch added or retired
+        epoch: u64,
+        /// The current epoch
+        current: u64,
+    },
+    /// A precomputation was cancelled before it finished
+    #[cfg_attr(feature = "std", error("The precomputation was cancelled"))]
+    Cancelled,
+    /// A point set opened as part of a union shares a point with an earlier one
+    #[cfg_attr(
+        feature = "std",
+        error("Point set {0} shares a point with an earlier point set")
+    )]
+    OverlappingPointSets(usize),
+    /// A row of evals had a different size to the points
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_points} points, but eval row {row} has {n_evals} evals")
+    )]
+    EvalRowAndPointsDifferentSizes {
+        /// The index of the eval row
+        row: usize,
+        /// The number of points
+        n_points: usize,
+        /// The number of evals in the row
+        n_evals: usize,
+    },
+}
+
+/// The broad cause of an [`Error`], for callers which handle failures by category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Inputs had the wrong number of elements, or didn't match each other's sizes
+    Shape,
+    /// An index, position, or point set was out of range or given more than once
+    Index,
+    /// The setup is too small for the input, invalid, or couldn't be loaded
+    Setup,
+    /// An evaluation domain couldn't be made with the requested size
+    Domain,
+    /// Something couldn't be serialized or deserialized
+    Serialization,
+    /// A transcript couldn't be replayed
+    Transcript,
+    /// A computation failed, such as a division by zero or work on another thread
+    Computation,
+}
+
+impl Error {
+    /// A stable numeric code for the error. Codes start at 1, so 0 can mean success across an
+    /// FFI boundary, and are never changed or reused once assigned.
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::TooManyScalars { .. } => 1,
+            Error::DivisorIsZero => 2,
+            Error::NoPolynomialsGiven => 3,
+            Error::EvalsIncorrectSize { .. } => 4,
+            Error::SerializationError => 5,
+            Error::NoPointsGiven => 6,
+            Error::EvalsAndPolysDifferentSizes { .. } => 7,
+            Error::EvalsAndPointsDifferentSizes { .. } => 8,
+            Error::PolynomialTooLarge { .. } => 9,
+            Error::TooManyPoints { .. } => 10,
+            Error::EvalsAndCommitsDifferentSizes { .. } => 11,
+            Error::DomainConstructionFailed(_) => 12,
+            Error::InvalidSubgroupIndex { .. } => 13,
+            Error::InvalidInputLength { .. } => 14,
+            Error::NotPowerOfTwo(_) => 15,
+            Error::DomainTooLarge { .. } => 16,
+            Error::EmptyEvals => 17,
+            Error::PointSetIndexOutOfRange { .. } => 18,
+            Error::DuplicatePointSetIndex(_) => 19,
+            Error::CommitmentIndexOutOfRange { .. } => 20,
+            Error::DuplicateCommitmentIndex(_) => 21,
+            Error::InvalidSrsPoint { .. } => 22,
+            Error::InconsistentSrs { .. } => 23,
+            Error::InvalidContribution => 24,
+            Error::DegreeBoundOutOfRange { .. } => 25,
+            Error::InvalidThreadCount => 26,
+            Error::PositionOutOfRange { .. } => 27,
+            Error::DuplicatePosition(_) => 28,
+            Error::SetupTooSmall { .. } => 29,
+            Error::BlockingTaskFailed => 30,
+            Error::SrsFileUnreadable => 31,
+            Error::InvalidSrsFile => 32,
+            Error::TranscriptReplayMismatch => 33,
+            Error::UnknownPointSet => 34,
+            Error::TooFewEvals { .. } => 35,
+            Error::InconsistentEvals => 36,
+            Error::OverlappingCosets(_) => 37,
+            Error::UnknownEpoch(_) => 38,
+            Error::EpochMismatch { .. } => 39,
+            Error::StaleEpoch { .. } => 40,
+            Error::Cancelled => 41,
+            Error::OverlappingPointSets(_) => 42,
+            Error::EvalRowAndPointsDifferentSizes { .. } => 43,
+        }
+    }
+
+    /// The broad cause of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::TooManyScalars { .. }
+            | Error::NoPolynomialsGiven
+            | Error::EvalsIncorrectSize { .. }
+            | Error::NoPointsGiven
+            | Error::EvalsAndPolysDifferentSizes { .. }
+            | Error::EvalsAndPointsDifferentSizes { .. }
+            | Error::EvalRowAndPointsDifferentSizes { .. }
+            | Error::EvalsAndCommitsDifferentSizes { .. }
+            | Error::InvalidInputLength { .. }
+            | Error::EmptyEvals
+            | Error::TooFewEvals { .. } => ErrorKind::Shape,
+            Error::InvalidSubgroupIndex { .. }
+            | Error::PointSetIndexOutOfRange { .. }
+            | Error::DuplicatePointSetIndex(_)
+            | Error::CommitmentIndexOutOfRange { .. }
+            | Error::DuplicateCommitmentIndex(_)
+            | Error::PositionOutOfRange { .. }
+            | Error::DuplicatePosition(_)
+            | Error::UnknownPointSet
+            | Error::OverlappingCosets(_)
+            | Error::OverlappingPointSets(_)
+            | Error::UnknownEpoch(_)
+            | Error::EpochMismatch { .. }
+            | Error::StaleEpoch { .. } => ErrorKind::Index,
+            Error::PolynomialTooLarge { .. }
+            | Error::TooManyPoints { .. }
+            | Error::InvalidSrsPoint { .. }
+            | Error::InconsistentSrs { .. }
+            | Error::InvalidContribution
+            | Error::DegreeBoundOutOfRange { .. }
+            | Error::SetupTooSmall { .. }
+            | Error::SrsFileUnreadable
+            | Error::InvalidSrsFile => ErrorKind::Setup,
+            Error::DomainConstructionFailed(_)
+            | Error::NotPowerOfTwo(_)
+            | Error::DomainTooLarge { .. } => ErrorKind::Domain,
+            Error::SerializationError => ErrorKind::Serialization,
+            Error::TranscriptReplayMismatch => ErrorKind::Transcript,
+            Error::DivisorIsZero
+            | Error::InvalidThreadCount
+            | Error::BlockingTaskFailed
+            | Error::InconsistentEvals
+            | Error::Cancelled => ErrorKind::Computation,
+        }
+    }
 }
 
 impl From<SerializationError> for Error {
@@ -221,6 +681,73 @@ impl<E: Pairing> Commitment<E> {
             .map(|x| Commitment(x.into()))
             .collect::<Vec<_>>())
     }
+
+    /// The commitment to the sum of the two committed polynomials
+    pub fn add(&self, other: &Self) -> Self {
+        Commitment((self.0 + other.0).into())
+    }
+
+    /// The commitment to the committed polynomial multiplied by `scalar`
+    pub fn scale(&self, scalar: E::ScalarField) -> Self {
+        Commitment((self.0 * scalar).into())
+    }
+}
+
+#[cfg(feature = "ark-bls12-381")]
+impl Commitment<ark_bls12_381::Bls12_381> {
+    /// Serialize to the 48 byte compressed encoding of the point
+    pub fn to_bytes_compressed(&self) -> [u8; 48] {
+        g1_to_bytes_compressed(&self.0)
+    }
+
+    /// Parse the 48 byte compressed encoding of a point, failing unless it's in the prime order
+    /// subgroup
+    pub fn from_bytes_compressed(bytes: &[u8; 48]) -> Result<Self, Error> {
+        g1_from_bytes_compressed(bytes, ark_serialize::Validate::Yes).map(Self)
+    }
+
+    /// Parse the 48 byte compressed encoding of a point without the subgroup check, which is
+    /// most of the cost. Only use this for bytes from a trusted source, e.g. ones this process
+    /// wrote itself, since a point outside the subgroup can break verification.
+    pub fn from_bytes_compressed_unchecked(bytes: &[u8; 48]) -> Result<Self, Error> {
+        g1_from_bytes_compressed(bytes, ark_serialize::Validate::No).map(Self)
+    }
+}
+
+#[cfg(feature = "ark-bls12-381")]
+pub(crate) fn g1_to_bytes_compressed(p: &ark_bls12_381::G1Affine) -> [u8; 48] {
+    let mut out = [0u8; 48];
+    p.serialize_compressed(&mut out[..])
+        .expect("A compressed point is 48 bytes");
+    out
+}
+
+#[cfg(feature = "ark-bls12-381")]
+pub(crate) fn g1_from_bytes_compressed(
+    bytes: &[u8; 48],
+    validate: ark_serialize::Validate,
+) -> Result<ark_bls12_381::G1Affine, Error> {
+    Ok(ark_bls12_381::G1Affine::deserialize_with_mode(
+        &bytes[..],
+        Compress::Yes,
+        validate,
+    )?)
+}
+
+/// Computes the commitment to $\sum_i s_i f_i$ from the commitments to the polynomials $f_i$ and
+/// the scalars $s_i$, such as the commitments to the extended rows of an erasure coded grid.
+pub fn linear_combination_of_commitments<E: Pairing>(
+    commits: &[Commitment<E>],
+    scalars: &[E::ScalarField],
+) -> Result<Commitment<E>, Error> {
+    if commits.len() != scalars.len() {
+        return Err(Error::InvalidInputLength {
+            expected: commits.len(),
+            got: scalars.len(),
+        });
+    }
+    let bases = commits.iter().map(|c| c.0).collect::<Vec<_>>();
+    Ok(Commitment(curve_msm::<E::G1>(&bases, scalars)?.into()))
 }
 
 pub(crate) fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
@@ -256,10 +783,12 @@ pub(crate) fn vanishing_polynomial<F: Field>(points: impl AsRef<[F]>) -> DensePo
         .fold(one, |x, y| x.naive_mul(&y))
 }
 
-/// Does polynomial division, returning q, r
+/// Does polynomial division, returning q, r. This is the arkworks division, which the in place
+/// division is checked against.
+#[cfg(test)]
 pub(crate) fn poly_div_q_r<F: Field>(
-    num: DenseOrSparsePolynomial<F>,
-    denom: DenseOrSparsePolynomial<F>,
+    num: ark_poly::univariate::DenseOrSparsePolynomial<F>,
+    denom: ark_poly::univariate::DenseOrSparsePolynomial<F>,
 ) -> Result<(Vec<F>, Vec<F>), Error> {
     if denom.is_zero() {
         return Err(Error::DivisorIsZero);
@@ -268,18 +797,93 @@ pub(crate) fn poly_div_q_r<F: Field>(
     Ok((q.coeffs, r.coeffs))
 }
 
+/// Divides `num` by `denom` in place, returning the quotient and leaving the remainder in `num`.
+/// This is long division like arkworks does, without copying the numerator.
+pub(crate) fn poly_div_q_r_in_place<F: Field>(
+    num: &mut Vec<F>,
+    denom: &[F],
+) -> Result<Vec<F>, Error> {
+    let d = match denom.iter().rposition(|c| !c.is_zero()) {
+        Some(d) => d,
+        None => return Err(Error::DivisorIsZero),
+    };
+    trim_zeros(num);
+    if num.len() <= d {
+        return Ok(Vec::new());
+    }
+    let lead_inv = denom[d].inverse().ok_or(Error::DivisorIsZero)?;
+    let mut q = vec![F::zero(); num.len() - d];
+    for i in (0..q.len()).rev() {
+        let c = num[i + d] * lead_inv;
+        q[i] = c;
+        for (n, dj) in num[i..=i + d].iter_mut().zip(&denom[..=d]) {
+            *n -= c * dj;
+        }
+    }
+    num.truncate(d);
+    trim_zeros(num);
+    Ok(q)
+}
+
+fn trim_zeros<F: Field>(coeffs: &mut Vec<F>) {
+    while coeffs.last().is_some_and(|c| c.is_zero()) {
+        coeffs.pop();
+    }
+}
+
 pub(crate) fn linear_combination<F: Field>(
     polynomials: &[impl AsRef<[F]>],
     challenges: &[F],
 ) -> Option<Vec<F>> {
-    polynomials
-        .as_ref()
+    if polynomials.is_empty() || challenges.is_empty() {
+        return None;
+    }
+    let mut out = Vec::new();
+    linear_combination_into(&mut out, polynomials, challenges);
+    Some(out)
+}
+
+/// Writes the linear combination of `polynomials` with `challenges` to `out`, reusing its
+/// allocation rather than making a polynomial per term. Trailing zeros are trimmed.
+pub(crate) fn linear_combination_into<F: Field>(
+    out: &mut Vec<F>,
+    polynomials: &[impl AsRef<[F]>],
+    challenges: &[F],
+) {
+    out.clear();
+    let len = polynomials
         .iter()
-        .zip(challenges.iter())
-        .map(|(p, &c)| &DensePolynomial::from_coefficients_slice(p.as_ref()) * c)
-        .reduce(|x, y| x + y)?
-        .coeffs
-        .into()
+        .zip(challenges)
+        .map(|(p, _)| p.as_ref().len())
+        .max()
+        .unwrap_or(0);
+    out.resize(len, F::zero());
+    for (p, c) in polynomials.iter().zip(challenges) {
+        field_ops::mul_add_assign(out, *c, p.as_ref());
+    }
+    trim_zeros(out);
+}
+
+/// Computes $\sum_i c_i f_i$ for sparse polynomials $f_i$ without making them dense, so the
+/// result can be committed to with [`traits::Committer::commit_sparse`]. Returns `None` when
+/// there are no polynomials or challenges.
+pub fn sparse_linear_combination<F: Field>(
+    polynomials: &[SparsePolynomial<F>],
+    challenges: &[F],
+) -> Option<SparsePolynomial<F>> {
+    if polynomials.is_empty() || challenges.is_empty() {
+        return None;
+    }
+    let mut terms = ark_std::collections::BTreeMap::<usize, F>::new();
+    for (p, c) in polynomials.iter().zip(challenges) {
+        for (i, x) in p.iter() {
+            *terms.entry(*i).or_insert_with(F::zero) += *c * x;
+        }
+    }
+    // Dropping the terms which cancelled is done by the constructor
+    Some(SparsePolynomial::from_coefficients_vec(
+        terms.into_iter().collect(),
+    ))
 }
 
 pub(crate) fn gen_curve_powers_proj<G: ScalarMul + CurveGroup>(
@@ -303,14 +907,26 @@ pub(crate) fn get_field_size<F: Field + CanonicalSerialize>() -> usize {
     F::zero().serialized_size(Compress::Yes)
 }
 
-pub(crate) fn transcribe_points_and_evals<F: CanonicalSerialize>(
-    transcript: &mut Transcript,
-    points: &[F],
+/// Transcribes the commitments if the encoding's version absorbs them, then the evals and points
+pub(crate) fn transcribe_points_and_evals<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    commits: &[Commitment<E>],
+    points: &[E::ScalarField],
+    evals: &[impl AsRef<[E::ScalarField]>],
+    encoding: &TranscriptEncoding,
+) -> Result<(), Error> {
+    encoding.absorb_commits(transcript, commits)?;
+    transcribe_evals(transcript, evals, points.len(), encoding)?;
+    transcribe_points(transcript, points, encoding)
+}
+
+/// Transcribes the evals, which must all have `n_points` evaluations
+pub(crate) fn transcribe_evals<F: Field>(
+    transcript: &mut impl TranscriptProtocol,
     evals: &[impl AsRef<[F]>],
-    field_size_bytes: usize,
+    n_points: usize,
+    encoding: &TranscriptEncoding,
 ) -> Result<(), Error> {
-    let n_points = points.len();
-    let mut eval_bytes = vec![0u8; field_size_bytes * n_points * evals.len()];
     for (i, e) in evals.iter().enumerate() {
         if e.as_ref().len() != n_points {
             return Err(Error::EvalsIncorrectSize {
@@ -319,22 +935,51 @@ pub(crate) fn transcribe_points_and_evals<F: CanonicalSerialize>(
                 expected: n_points,
             });
         }
-        for (j, p) in e.as_ref().iter().enumerate() {
-            let start = (i * n_points + j) * field_size_bytes;
-            p.serialize_compressed(&mut eval_bytes[start..start + field_size_bytes])?;
-        }
+    }
+    transcribe_evals_with(
+        transcript,
+        evals.len(),
+        n_points,
+        |i, j| evals[i].as_ref()[j],
+        encoding,
+    )
+}
+
+/// Commits the evaluation `eval(i, j)` of each polynomial `i` at each point `j` to the
+/// transcript, in the same format as [`transcribe_evals`] whatever layout they're stored in
+pub(crate) fn transcribe_evals_with<F: Field>(
+    transcript: &mut impl TranscriptProtocol,
+    n_polys: usize,
+    n_points: usize,
+    eval: impl Fn(usize, usize) -> F,
+    encoding: &TranscriptEncoding,
+) -> Result<(), Error> {
+    let field_size_bytes = get_field_size::<F>();
+    let mut eval_bytes = vec![0u8; field_size_bytes * n_points * n_polys];
+    for (k, chunk) in eval_bytes.chunks_exact_mut(field_size_bytes).enumerate() {
+        encoding.write_field(&eval(k / n_points, k % n_points), chunk)?;
     }
     transcript.append_message(b"open evals", &eval_bytes);
+    Ok(())
+}
+
+pub(crate) fn transcribe_points<F: Field>(
+    transcript: &mut impl TranscriptProtocol,
+    points: &[F],
+    encoding: &TranscriptEncoding,
+) -> Result<(), Error> {
+    let field_size_bytes = get_field_size::<F>();
+    let n_points = points.len();
     let mut point_bytes = vec![0u8; field_size_bytes * n_points];
     for (i, p) in points.iter().enumerate() {
-        p.serialize_compressed(&mut point_bytes[i * field_size_bytes..(i + 1) * field_size_bytes])?;
+        encoding.write_field(p, &mut point_bytes[i * field_size_bytes..(i + 1) * field_size_bytes])?;
     }
     transcript.append_message(b"open points", &point_bytes);
     Ok(())
 }
 
 pub(crate) fn transcribe_generic<F: CanonicalSerialize>(
-    transcript: &mut Transcript,
+    transcript: &mut impl TranscriptProtocol,
     label: &'static [u8],
     f: &F,
 ) -> Result<(), Error> {
@@ -346,7 +991,7 @@ pub(crate) fn transcribe_generic<F: CanonicalSerialize>(
 }
 
 pub(crate) fn get_challenge<F: PrimeField>(
-    transcript: &mut Transcript,
+    transcript: &mut impl TranscriptProtocol,
     label: &'static [u8],
     field_size_bytes: usize,
 ) -> F {
@@ -366,9 +1011,10 @@ pub(crate) fn check_opening_sizes<F>(
             n_polys: polys.len(),
         });
     }
-    for e in evals {
+    for (row, e) in evals.iter().enumerate() {
         if e.as_ref().len() != n_points {
-            return Err(Error::EvalsAndPointsDifferentSizes {
+            return Err(Error::EvalRowAndPointsDifferentSizes {
+                row,
                 n_evals: e.as_ref().len(),
                 n_points,
             });
@@ -388,9 +1034,10 @@ pub(crate) fn check_verify_sizes<F, C>(
             n_commits: commits.len(),
         });
     }
-    for e in evals {
+    for (row, e) in evals.iter().enumerate() {
         if e.as_ref().len() != n_points {
-            return Err(Error::EvalsAndPointsDifferentSizes {
+            return Err(Error::EvalRowAndPointsDifferentSizes {
+                row,
                 n_evals: e.as_ref().len(),
                 n_points,
             });
@@ -399,6 +1046,55 @@ pub(crate) fn check_verify_sizes<F, C>(
     Ok(())
 }
 
+/// Checks that no polynomial has more than `max_coeffs` coefficients
+pub(crate) fn check_poly_sizes<F>(
+    polys: &[impl AsRef<[F]>],
+    max_coeffs: usize,
+) -> Result<(), Error> {
+    for (index, p) in polys.iter().enumerate() {
+        if p.as_ref().len() > max_coeffs {
+            return Err(Error::PolynomialTooLarge {
+                index,
+                n_coeffs: p.as_ref().len(),
+                max_coeffs,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a setup has at least `needed` powers of tau in G`group`
+pub(crate) fn check_setup_size(group: u8, needed: usize, got: usize) -> Result<(), Error> {
+    if got < needed {
+        return Err(Error::SetupTooSmall { group, needed, got });
+    }
+    Ok(())
+}
+
+/// Selects the items at `indices`, which must be in range and distinct
+pub(crate) fn select_indices<'a, T>(
+    items: &'a [T],
+    indices: &[usize],
+) -> Result<Vec<&'a T>, Error> {
+    let mut seen = vec![false; items.len()];
+    indices
+        .iter()
+        .map(|&idx| {
+            match seen.get_mut(idx) {
+                None => {
+                    return Err(Error::CommitmentIndexOutOfRange {
+                        idx,
+                        n_commits: items.len(),
+                    })
+                }
+                Some(true) => return Err(Error::DuplicateCommitmentIndex(idx)),
+                Some(s) => *s = true,
+            }
+            Ok(&items[idx])
+        })
+        .collect()
+}
+
 /// This macro is used to iterate over a slice in parallel if the `parallel` feature is enabled.
 #[macro_export]
 macro_rules! cfg_iter {
@@ -412,3 +1108,249 @@ macro_rules! cfg_iter {
         result
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        linear_combination, linear_combination_into, poly_div_q_r, poly_div_q_r_in_place,
+        sparse_linear_combination, test_rng, vanishing_polynomial, Error, ErrorKind,
+    };
+    use ark_bls12_381::Fr;
+    use ark_poly::{
+        univariate::{DensePolynomial, SparsePolynomial},
+        DenseUVPolynomial,
+    };
+    use ark_std::{string::ToString, vec, vec::Vec, UniformRand};
+
+    #[test]
+    fn test_in_place_division() {
+        let points = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = vanishing_polynomial(&points);
+        for len in [0, 3, 5, 6, 64] {
+            let num = (0..len)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let (q, r) = poly_div_q_r(
+                DensePolynomial::from_coefficients_slice(&num).into(),
+                (&vp).into(),
+            )
+            .unwrap();
+            let mut rem = num.clone();
+            assert_eq!(q, poly_div_q_r_in_place(&mut rem, &vp.coeffs).unwrap());
+            assert_eq!(r, rem);
+        }
+        // Trailing zeros of the divisor are ignored
+        let mut rem = vec![Fr::from(3u64), Fr::from(1u64)];
+        let quotient = poly_div_q_r_in_place(&mut rem, &[Fr::from(2u64), Fr::from(0u64)]);
+        assert_eq!(
+            Ok(vec![
+                Fr::from(3u64) / Fr::from(2u64),
+                Fr::from(1u64) / Fr::from(2u64)
+            ]),
+            quotient
+        );
+        assert!(rem.is_empty());
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            poly_div_q_r_in_place(&mut rem, &[Fr::from(0u64)])
+        );
+    }
+
+    #[test]
+    fn test_linear_combination_into() {
+        let polys = [
+            vec![Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)],
+            vec![Fr::from(4u64)],
+            vec![Fr::from(5u64), Fr::from(6u64), -Fr::from(1u64)],
+        ];
+        let scalars = [Fr::from(2u64), Fr::from(3u64), Fr::from(6u64)];
+        let expected = vec![Fr::from(44u64), Fr::from(40u64)];
+        // The top coefficient cancels and is trimmed, and the old contents are overwritten
+        let mut out = vec![Fr::from(7u64); 10];
+        linear_combination_into(&mut out, &polys, &scalars);
+        assert_eq!(expected, out);
+        assert_eq!(Some(expected), linear_combination(&polys, &scalars));
+        assert_eq!(None, linear_combination::<Fr>(&[] as &[Vec<Fr>], &[]));
+    }
+
+    #[test]
+    fn test_sparse_linear_combination() {
+        let polys = [
+            SparsePolynomial::from_coefficients_vec(vec![(0, Fr::from(1u64)), (9, Fr::from(2u64))]),
+            SparsePolynomial::from_coefficients_vec(vec![(4, Fr::from(3u64)), (9, Fr::from(1u64))]),
+        ];
+        let scalars = [Fr::from(1u64), -Fr::from(2u64)];
+        // The degree 9 terms cancel
+        let expected =
+            SparsePolynomial::from_coefficients_vec(vec![(0, Fr::from(1u64)), (4, -Fr::from(6u64))]);
+        assert_eq!(Some(expected), sparse_linear_combination(&polys, &scalars));
+        let dense = polys
+            .iter()
+            .map(|p| DensePolynomial::from(p.clone()).coeffs)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            linear_combination(&dense, &scalars),
+            sparse_linear_combination(&polys, &scalars).map(|p| DensePolynomial::from(p).coeffs)
+        );
+        assert_eq!(None, sparse_linear_combination::<Fr>(&[], &scalars));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        // One of each variant, in code order
+        let errors = [
+            Error::TooManyScalars {
+                n_coeffs: 2,
+                expected_max: 1,
+            },
+            Error::DivisorIsZero,
+            Error::NoPolynomialsGiven,
+            Error::EvalsIncorrectSize {
+                poly: 0,
+                n_evals: 1,
+                expected: 2,
+            },
+            Error::SerializationError,
+            Error::NoPointsGiven,
+            Error::EvalsAndPolysDifferentSizes {
+                n_eval_rows: 1,
+                n_polys: 2,
+            },
+            Error::EvalsAndPointsDifferentSizes {
+                n_points: 1,
+                n_evals: 2,
+            },
+            Error::PolynomialTooLarge {
+                index: 0,
+                n_coeffs: 2,
+                max_coeffs: 1,
+            },
+            Error::TooManyPoints {
+                n_points: 2,
+                max_points: 1,
+            },
+            Error::EvalsAndCommitsDifferentSizes {
+                n_evals: 1,
+                n_commits: 2,
+            },
+            Error::DomainConstructionFailed(3),
+            Error::InvalidSubgroupIndex {
+                idx: 2,
+                n_splits: 2,
+            },
+            Error::InvalidInputLength {
+                expected: 1,
+                got: 2,
+            },
+            Error::NotPowerOfTwo(3),
+            Error::DomainTooLarge { size: 2, max: 1 },
+            Error::EmptyEvals,
+            Error::PointSetIndexOutOfRange {
+                idx: 2,
+                n_point_sets: 2,
+            },
+            Error::DuplicatePointSetIndex(0),
+            Error::CommitmentIndexOutOfRange {
+                idx: 2,
+                n_commits: 2,
+            },
+            Error::DuplicateCommitmentIndex(0),
+            Error::InvalidSrsPoint { group: 1, index: 0 },
+            Error::InconsistentSrs { group: 1 },
+            Error::InvalidContribution,
+            Error::DegreeBoundOutOfRange {
+                bound: 0,
+                min: 1,
+                max: 2,
+            },
+            Error::InvalidThreadCount,
+            Error::PositionOutOfRange { idx: 2, len: 2 },
+            Error::DuplicatePosition(0),
+            Error::SetupTooSmall {
+                group: 2,
+                needed: 2,
+                got: 1,
+            },
+            Error::BlockingTaskFailed,
+            Error::SrsFileUnreadable,
+            Error::InvalidSrsFile,
+            Error::TranscriptReplayMismatch,
+            Error::UnknownPointSet,
+            Error::TooFewEvals { got: 1, needed: 2 },
+            Error::InconsistentEvals,
+            Error::OverlappingCosets(1),
+            Error::UnknownEpoch(1),
+            Error::EpochMismatch {
+                expected: 2,
+                got: 1,
+            },
+            Error::StaleEpoch {
+                epoch: 1,
+                current: 2,
+            },
+            Error::Cancelled,
+            Error::OverlappingPointSets(1),
+            Error::EvalRowAndPointsDifferentSizes {
+                row: 0,
+                n_points: 1,
+                n_evals: 2,
+            },
+        ];
+        let codes = errors.iter().map(Error::code).collect::<Vec<_>>();
+        assert_eq!((1..=errors.len() as u32).collect::<Vec<_>>(), codes);
+
+        assert_eq!(ErrorKind::Index, errors[17].kind());
+        assert_eq!(ErrorKind::Setup, errors[28].kind());
+        assert_eq!(ErrorKind::Shape, errors[13].kind());
+        assert_eq!(ErrorKind::Transcript, errors[32].kind());
+        assert_eq!(
+            "Given 2 scalars, but at most 1 are supported",
+            errors[0].to_string()
+        );
+    }
+
+    #[cfg(feature = "ark-bls12-381")]
+    #[test]
+    fn test_compressed_bytes() {
+        use super::{method1::Proof, Commitment};
+        use ark_bls12_381::{Bls12_381, Fq, G1Affine};
+        use ark_ec::AffineRepr;
+        use ark_serialize::CanonicalSerialize;
+
+        let point = (G1Affine::generator() * Fr::rand(&mut test_rng())).into();
+        let commit = Commitment::<Bls12_381>(point);
+        let bytes = commit.to_bytes_compressed();
+        assert_eq!(
+            point,
+            Commitment::<Bls12_381>::from_bytes_compressed(&bytes)
+                .unwrap()
+                .0
+        );
+        let proof = Proof::<Bls12_381>(point);
+        assert_eq!(bytes, proof.to_bytes_compressed());
+        assert_eq!(
+            point,
+            Proof::<Bls12_381>::from_bytes_compressed_unchecked(&bytes)
+                .unwrap()
+                .0
+        );
+
+        // A point on the curve but outside the prime order subgroup only parses unchecked
+        let outside = (1u64..)
+            .filter_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), true))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let mut bytes = [0u8; 48];
+        outside.serialize_compressed(&mut bytes[..]).unwrap();
+        assert!(Commitment::<Bls12_381>::from_bytes_compressed(&bytes).is_err());
+        assert!(Proof::<Bls12_381>::from_bytes_compressed(&bytes).is_err());
+        assert_eq!(
+            outside,
+            Commitment::<Bls12_381>::from_bytes_compressed_unchecked(&bytes)
+                .unwrap()
+                .0
+        );
+    }
+}
@@ -1,42 +1,76 @@
-//! Precomputation for Method 1 where each point set is a cyclic subgroup of the evaluation domain, with blst optimizations
+//! Precomputation for Method 1 where each point set is a cyclic subgroup of the evaluation domain.
+//! This works over any pairing with an [`MSMEngine`]: use `BlstMSMEngine` for the blst optimized
+//! BLS12-381 path, or `ArkMSMEngine` for other curves like BN254. The engine defaults to
+//! [`AnyMSMEngine`], which uses blst on BLS12-381 when the `blst` feature is on and arkworks
+//! otherwise, so `M1CyclPrecomp<E>` also builds for targets without blst like wasm.
+//!
+//! The base size and the number of point sets don't need to be powers of two. Any base size
+//! with an exact domain over the field works, e.g. $3 \cdot 2^k$ over BLS12-381, see
+//! [`exact_domain`](crate::poly_ops::exact_domain).
 use ark_ec::pairing::Pairing;
-use ark_ec::CurveGroup;
-use ark_ff::Zero;
-use core::ops::Deref;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{FftField, One, Zero};
+use core::{mem::size_of, ops::Deref};
 
-use ark_poly::univariate::DensePolynomial;
-use ark_poly::{DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain};
-use ark_std::vec::Vec;
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{end_timer, rand::RngCore, start_timer, vec::Vec};
 use core::ops::Mul;
-use merlin::Transcript;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::method1::{M1NoPrecomp, Proof};
-use crate::poly_ops::{ev_points, SplitEvalDomain};
-use crate::traits::{Committer, MSMEngine, PolyMultiProof};
+use crate::method1::{
+    batched_sets_challenges, check_batched_sets_sizes, check_open_all_sizes, combine_polys,
+    concat_set_evals, opening_challenge, union_of_point_sets, BatchedSetsProof, M1NoPrecomp,
+    PointSetUnion, Proof,
+};
+use crate::msm::AnyMSMEngine;
+use crate::poly_ops::{
+    check_domain_size, div_by_vanishing_poly, ev_points, evaluate_over_coset, SplitEvalDomain,
+};
+use crate::progress::Progress;
+use crate::traits::{
+    check_batch_sizes, g1_compressed_size, Committer, MSMEngine, MemoryEstimate, PolyMultiProof,
+    SchemeInfo, TranscriptProtocol,
+};
 use crate::{
-    cfg_iter, check_opening_sizes, check_verify_sizes, gen_powers, get_challenge, get_field_size,
-    linear_combination, transcribe_points_and_evals, Commitment, Error,
+    cfg_iter, check_opening_sizes, check_poly_sizes, check_verify_sizes, gen_powers,
+    transcribe_points_and_evals, Commitment, Error,
 };
 
-/// Method 1 with blst optimization and precomputed lagrange polynomials/vanishing polys
+/// Computes the quotient of $\sum_i \gamma_i f_i$ by the vanishing polynomial of the (coset)
+/// domain `subgroup`, as in [`compute_quotient`](crate::method1::compute_quotient) but without a
+/// general polynomial division.
+pub fn compute_quotient_cyclic<F: FftField>(
+    polys: &[impl AsRef<[F]>],
+    gammas: &[F],
+    subgroup: &impl EvaluationDomain<F>,
+) -> Result<DensePolynomial<F>, Error> {
+    let fsum = combine_polys(polys, gammas)?;
+    Ok(DensePolynomial::from_coefficients_vec(
+        div_by_vanishing_poly(&fsum, subgroup),
+    ))
+}
+
+/// Method 1 with precomputed vanishing polys for cyclic point sets, generic over the MSM engine
 #[derive(Clone)]
-pub struct M1CyclPrecomp<E: Pairing, M: MSMEngine<E = E>> {
+pub struct M1CyclPrecomp<E: Pairing, M: MSMEngine<E = E> = AnyMSMEngine<E>> {
     /// The inner method 1 object without precomputation
     pub inner: M1NoPrecomp<E, M>,
     split_domain: SplitEvalDomain<E::ScalarField>,
-    point_set_groups: Vec<Radix2EvaluationDomain<E::ScalarField>>,
+    point_set_groups: Vec<GeneralEvaluationDomain<E::ScalarField>>,
     num_point_sets: usize,
     base_size: usize,
     g2_zeros: Vec<E::G2Affine>,
 }
 
-fn is_power_of_two(n: usize) -> bool {
-    n != 0 && (n & (n - 1)) == 0
-}
-
 impl<E: Pairing, M: MSMEngine<E = E>> M1CyclPrecomp<E, M> {
     /// Make a precompute-optimized version of a method 1 object for the given sets of points
     pub fn from_inner(
@@ -44,35 +78,75 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1CyclPrecomp<E, M> {
         base_size: usize,
         num_point_sets: usize,
     ) -> Result<Self, Error> {
-        if !is_power_of_two(base_size) || !is_power_of_two(num_point_sets) {
-            todo!() // return error
-        }
+        Self::from_inner_coset(inner, base_size, num_point_sets, E::ScalarField::one())
+    }
+
+    /// Make a precompute-optimized version of a method 1 object where the base domain is the coset
+    /// of the size `base_size` subgroup with `offset`, such as the extension of erasure coded
+    /// data. Each point set is a coset of the size `base_size / num_point_sets` subgroup, so
+    /// `num_point_sets` must divide `base_size`.
+    pub fn from_inner_coset(
+        inner: M1NoPrecomp<E, M>,
+        base_size: usize,
+        num_point_sets: usize,
+        offset: E::ScalarField,
+    ) -> Result<Self, Error> {
+        Self::from_inner_coset_with_progress(
+            inner,
+            base_size,
+            num_point_sets,
+            offset,
+            &Progress::new(),
+        )
+    }
+
+    /// Make a precompute-optimized version as in [`M1CyclPrecomp::from_inner_coset`], reporting
+    /// each point set as its vanishing polynomial is committed to `progress`. Fails with
+    /// [`Error::Cancelled`] if `progress` is cancelled first.
+    pub fn from_inner_coset_with_progress(
+        inner: M1NoPrecomp<E, M>,
+        base_size: usize,
+        num_point_sets: usize,
+        offset: E::ScalarField,
+        progress: &Progress,
+    ) -> Result<Self, Error> {
+        check_domain_size::<E::ScalarField>(base_size)?;
         if inner.powers_of_g1.len() < base_size {
             return Err(Error::DomainConstructionFailed(base_size));
         }
-        let split_domain = SplitEvalDomain::<E::ScalarField>::new(base_size, num_point_sets)
-            .ok_or(Error::DomainConstructionFailed(inner.powers_of_g1.len()))?;
+        let split_domain =
+            SplitEvalDomain::<E::ScalarField>::new_coset(base_size, num_point_sets, offset)
+                .ok_or(Error::DomainConstructionFailed(base_size))?;
+        inner.check_n_points(base_size / num_point_sets)?;
         let point_set_groups = split_domain.subgroups();
-        let vanishing_polys: Vec<_> = cfg_iter!(point_set_groups)
-            .map(|(_, sg)| sg.vanishing_polynomial())
-            .collect();
-        let g2_zeros = cfg_iter!(vanishing_polys)
-            .map(|(_, p)| {
-                let coeffs = p.deref();
-                let mut accum = E::G2::zero();
-                for (i0, p0) in coeffs {
-                    accum += inner
-                        .powers_of_g2
-                        .get(*i0)
-                        .ok_or(Error::TooManyScalars {
-                            n_coeffs: inner.powers_of_g1.len(),
-                            expected_max: i0 + 1,
-                        })?
-                        .mul(p0);
-                }
-                Ok(accum.into_affine())
-            })
-            .collect::<Result<Vec<_>, Error>>()?;
+        let tracker = progress.tracker(point_set_groups.len());
+        let timer =
+            start_timer!(|| ark_std::format!("precompute {} cyclic point sets", num_point_sets));
+        let g2_zeros = inner.install(|| {
+            let vanishing_polys: Vec<_> = cfg_iter!(point_set_groups)
+                .map(|(_, sg)| sg.vanishing_polynomial())
+                .collect();
+            cfg_iter!(vanishing_polys)
+                .map(|(_, p)| {
+                    tracker.step(|| {
+                        let coeffs = p.deref();
+                        let mut accum = E::G2::zero();
+                        for (i0, p0) in coeffs {
+                            accum += inner
+                                .powers_of_g2
+                                .get(*i0)
+                                .ok_or(Error::TooManyScalars {
+                                    n_coeffs: inner.powers_of_g1.len(),
+                                    expected_max: i0 + 1,
+                                })?
+                                .mul(p0);
+                        }
+                        Ok(accum.into_affine())
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        });
+        end_timer!(timer);
 
         Ok(Self {
             inner,
@@ -80,104 +154,665 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1CyclPrecomp<E, M> {
             split_domain,
             point_set_groups,
             num_point_sets,
-            g2_zeros,
+            g2_zeros: g2_zeros?,
         })
     }
 
+    /// The offset of the base domain, which is one unless it's a coset
+    pub fn offset(&self) -> E::ScalarField {
+        self.split_domain.base().coset_offset()
+    }
+
     /// Returns the SplitEvalDomain beign used for the multiproof scheme.
     /// In order to figure out which points map to which evaluation index, you should use this
     /// object
     pub fn point_sets(&self) -> &SplitEvalDomain<E::ScalarField> {
         &self.split_domain
     }
-}
 
-impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1CyclPrecomp<E, M> {
-    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        self.inner.commit(poly)
+    pub(crate) fn point_set_group(
+        &self,
+        point_set_index: usize,
+    ) -> Result<&GeneralEvaluationDomain<E::ScalarField>, Error> {
+        self.point_set_groups
+            .get(point_set_index)
+            .ok_or(Error::PointSetIndexOutOfRange {
+                idx: point_set_index,
+                n_point_sets: self.num_point_sets,
+            })
     }
-}
 
-impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M> {
-    type Proof = Proof<E>;
+    /// Verify an opening as in [`PolyMultiProof::verify`], running on the thread pool of
+    /// `config`
+    #[cfg(feature = "parallel")]
+    pub fn verify_with_config(
+        &self,
+        config: &crate::parallel::ParallelismConfig,
+        transcript: &mut (impl TranscriptProtocol + Send),
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]> + Sync],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        config.install(|| self.verify(transcript, commits, point_set_index, evals, proof))
+    }
 
-    fn open(
+    /// Open the polynomials at point set `point_set_index` with a challenge `gamma` drawn by the
+    /// caller rather than from a transcript, as in [`M1NoPrecomp::open_with_challenge`]. `gamma`
+    /// must meet the soundness requirements listed there.
+    pub fn open_with_challenge(
         &self,
-        transcript: &mut Transcript,
-        evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         point_set_index: usize,
-    ) -> Result<Self::Proof, Error> {
-        check_opening_sizes(evals, polys, self.base_size / self.num_point_sets)?;
+        gamma: E::ScalarField,
+    ) -> Result<Proof<E>, Error> {
+        let subgroup = self.point_set_group(point_set_index)?;
+        self.open_with_gamma(polys, gamma, subgroup)
+    }
 
-        // Commit the evals and the points to the transcript
-        // TODO: better error
-        let subgroup = self
-            .point_set_groups
-            .get(point_set_index)
-            .ok_or(Error::NoPointsGiven)?;
-        let points = ev_points(subgroup);
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, &points, evals, field_size_bytes)?;
+    /// Verify a proof made with [`M1CyclPrecomp::open_with_challenge`] against the same `gamma`
+    pub fn verify_with_challenge(
+        &self,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma: E::ScalarField,
+    ) -> Result<bool, Error> {
+        check_verify_sizes(commits, evals, self.base_size / self.num_point_sets)?;
+        let subgroup = self.point_set_group(point_set_index)?;
+        let lhs = self.verifier_lhs_with_gamma(commits, subgroup, evals, gamma)?;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            self.inner.powers_of_g2[0],
+            proof.0,
+            self.g2_zeros[point_set_index],
+        ))
+    }
+
+    fn union_of_point_sets(
+        &self,
+        point_set_indices: &[usize],
+    ) -> Result<PointSetUnion<E::ScalarField>, Error> {
+        union_of_point_sets(point_set_indices, self.num_point_sets, |i| {
+            let subgroup = &self.point_set_groups[i];
+            (ev_points(subgroup), subgroup.vanishing_polynomial().into())
+        })
+    }
+
+    /// Open the polynomials at the union of the point sets in `point_set_indices` with a single
+    /// proof. This isn't an aggregate of the proofs at each set: it's one multiproof at every
+    /// point of the sets, so it costs as much as an opening at that many points, and the inner
+    /// scheme needs enough powers of G2 for them. The rows of `evals` are the evaluations of each
+    /// polynomial over each point set in the order given, concatenated. The sets can't share any
+    /// points.
+    pub fn open_union(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_indices: &[usize],
+    ) -> Result<Proof<E>, Error> {
+        let (points, vp) = self.union_of_point_sets(point_set_indices)?;
+        self.inner
+            .open_with_vanishing_poly(transcript, evals, polys, &points, &vp)
+    }
+
+    /// Verify a proof made with [`M1CyclPrecomp::open_union`]
+    pub fn verify_union(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_indices: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let (points, vp) = self.union_of_point_sets(point_set_indices)?;
+        self.inner
+            .verify_with_vanishing_poly(transcript, commits, &points, evals, proof, &vp)
+    }
+
+    /// Open the polynomials at each point set in `point_set_indices`, drawing the challenge for
+    /// every set from the one transcript, as in
+    /// [`M1Precomp::open_batched_sets`](crate::method1::precompute::M1Precomp::open_batched_sets).
+    /// With `union`, a single proof at the union of the sets is made too, as in
+    /// [`M1CyclPrecomp::open_union`].
+    pub fn open_batched_sets<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals_per_set: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+        point_set_indices: &[usize],
+        union: bool,
+    ) -> Result<BatchedSetsProof<E>, Error> {
+        let (subgroups, points) = self.batched_sets_points(point_set_indices)?;
+        let point_refs = points.iter().map(|p| &p[..]).collect::<Vec<_>>();
+        check_batched_sets_sizes(evals_per_set, &point_refs, |evals, n_points| {
+            check_opening_sizes(evals, polys, n_points)
+        })?;
+        let union_set = union
+            .then(|| self.union_of_point_sets(point_set_indices))
+            .transpose()?;
+        if let Some((union_points, _)) = &union_set {
+            self.inner.check_n_points(union_points.len())?;
+        }
+        let (gammas, union_gamma) = batched_sets_challenges(
+            transcript,
+            &self.inner.transcript_commits(polys)?,
+            evals_per_set,
+            &point_refs,
+            union,
+            &self.inner.transcript_encoding,
+        )?;
+        let proofs = self.inner.install(|| {
+            cfg_iter!(gammas)
+                .map(|(i, gamma)| self.open_with_gamma(polys, *gamma, subgroups[i]))
+                .collect::<Result<Vec<_>, Error>>()
+        })?;
+        let union = match (union_set, union_gamma) {
+            (Some((_, vp)), Some(gamma)) => Some(self.inner.open_with_gamma(polys, gamma, &vp)?),
+            _ => None,
+        };
+        Ok(BatchedSetsProof { proofs, union })
+    }
+
+    /// Verify proofs made with [`M1CyclPrecomp::open_batched_sets`], including the union
+    /// proof if there is one
+    pub fn verify_batched_sets<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals_per_set: &[impl AsRef<[T]>],
+        point_set_indices: &[usize],
+        proof: &BatchedSetsProof<E>,
+    ) -> Result<bool, Error> {
+        let (subgroups, points) = self.batched_sets_points(point_set_indices)?;
+        let point_refs = points.iter().map(|p| &p[..]).collect::<Vec<_>>();
+        check_batched_sets_sizes(evals_per_set, &point_refs, |evals, n_points| {
+            check_verify_sizes(commits, evals, n_points)
+        })?;
+        if proof.proofs.len() != point_set_indices.len() {
+            return Err(Error::InvalidInputLength {
+                expected: point_set_indices.len(),
+                got: proof.proofs.len(),
+            });
+        }
+        let (gammas, union_gamma) = batched_sets_challenges(
+            transcript,
+            commits,
+            evals_per_set,
+            &point_refs,
+            proof.union.is_some(),
+            &self.inner.transcript_encoding,
+        )?;
+        for (i, gamma) in gammas.into_iter().enumerate() {
+            let lhs = self.verifier_lhs_with_gamma(
+                commits,
+                subgroups[i],
+                evals_per_set[i].as_ref(),
+                gamma,
+            )?;
+            if !M::pairing_eq_check(
+                lhs.into_affine(),
+                self.inner.powers_of_g2[0],
+                proof.proofs[i].0,
+                self.g2_zeros[point_set_indices[i]],
+            ) {
+                return Ok(false);
+            }
+        }
+        match (&proof.union, union_gamma) {
+            (Some(union), Some(gamma)) => {
+                let (union_points, _) = self.union_of_point_sets(point_set_indices)?;
+                let evals = concat_set_evals(evals_per_set, commits.len());
+                self.inner
+                    .verify_with_challenge(commits, &union_points, &evals, union, gamma)
+            }
+            _ => Ok(true),
+        }
+    }
 
-        // Read the challenge
-        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
+    /// The subgroups and points of each of the point sets at `point_set_indices`
+    #[allow(clippy::type_complexity)]
+    fn batched_sets_points(
+        &self,
+        point_set_indices: &[usize],
+    ) -> Result<
+        (
+            Vec<&GeneralEvaluationDomain<E::ScalarField>>,
+            Vec<Vec<E::ScalarField>>,
+        ),
+        Error,
+    > {
+        let subgroups = point_set_indices
+            .iter()
+            .map(|i| self.point_set_group(*i))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let points = subgroups.iter().map(|sg| ev_points(*sg)).collect();
+        Ok((subgroups, points))
+    }
+
+    fn open_with_gamma(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        gamma: E::ScalarField,
+        subgroup: &GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        check_poly_sizes(polys, self.inner.powers_of_g1.len())?;
         // Make the gamma powers
-        let gammas = gen_powers::<E::ScalarField>(gamma, self.inner.powers_of_g1.len());
-        // Take a linear combo of gammas with the polynomials
-        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
-            .ok_or(Error::NoPolynomialsGiven)?;
-
-        // Polynomial divide, the remained would contain the gamma * ri_s,
-        // The result is the correct quotient
-        let (q, _) = DensePolynomial::from_coefficients_vec(fsum)
-            .divide_by_vanishing_poly(*subgroup)
-            .expect("This always succeeds");
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let q = compute_quotient_cyclic(polys, &gammas, subgroup)?;
         // Open to the resulting polynomial
-        Ok(Proof(
-            M::multi_scalar_mul_g1(&self.inner.g1_precomp, &q.deref())?.into_affine(),
-        ))
+        let proof = self.inner.install(|| self.inner.g1_msm(&q.coeffs))?;
+        Ok(Proof(proof.into_affine()))
     }
 
-    fn verify(
+    /// Open polynomials given by their evaluations over the base domain at point set
+    /// `point_set_index`, returning the evaluations at the point set along with the proof. The
+    /// proof is the same as [`PolyMultiProof::open`] gives for the polynomials' coefficients.
+    ///
+    /// The evaluations at the point set are read out of `domain_evals` and the polynomials are
+    /// combined pointwise, so there's a single inverse FFT for the combined polynomial rather
+    /// than one per polynomial. Its division by the point set's vanishing polynomial
+    /// $X^m - c$ then takes linear time. With the legacy transcript encoding the commitments
+    /// aren't absorbed, otherwise each polynomial is interpolated to commit to it.
+    #[allow(clippy::type_complexity)]
+    pub fn open_domain_evals(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        domain_evals: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Proof<E>), Error> {
+        let subgroup = self.point_set_group(point_set_index)?;
+        check_poly_sizes(domain_evals, self.inner.powers_of_g1.len())?;
+        for (i, e) in domain_evals.iter().enumerate() {
+            if e.as_ref().len() != self.base_size {
+                return Err(Error::EvalsIncorrectSize {
+                    poly: i,
+                    n_evals: e.as_ref().len(),
+                    expected: self.base_size,
+                });
+            }
+        }
+        let base = self.split_domain.base();
+        let evals = domain_evals
+            .iter()
+            .map(|e| self.split_domain.take_subgroup_indices(point_set_index, e))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let commits = self
+            .inner
+            .transcript_encoding
+            .prover_commits(domain_evals.len(), |i| {
+                self.inner.commit(base.ifft(domain_evals[i].as_ref()))
+            })?;
+        let gamma = opening_challenge(
+            transcript,
+            &commits,
+            &evals,
+            domain_evals,
+            &ev_points(subgroup),
+            &self.inner.transcript_encoding,
+        )?;
+
+        let gammas = gen_powers::<E::ScalarField>(gamma, domain_evals.len());
+        let mut fsum = combine_polys(domain_evals, &gammas)?;
+        let timer = start_timer!(|| ark_std::format!("ifft of {} evals", self.base_size));
+        base.ifft_in_place(&mut fsum);
+        end_timer!(timer);
+        let q = div_by_vanishing_poly(&fsum, subgroup);
+        let proof = self.inner.install(|| self.inner.g1_msm(&q))?;
+        Ok((evals, Proof(proof.into_affine())))
+    }
+
+    /// Open the polynomials at every point set, with one transcript per point set. `evals[i]`
+    /// holds the evaluations over point set `i`. The transcripts are written to in order, then
+    /// the divisions and msms for each point set are done in parallel with the `parallel`
+    /// feature.
+    pub fn open_all<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_open_all_sizes(transcripts, evals, self.num_point_sets)?;
+        let commits = self.inner.transcript_commits(polys)?;
+        self.open_all_absorbing(transcripts, &commits, evals, polys)
+    }
+
+    /// Open the polynomials at every point set as in [`M1CyclPrecomp::open_all`], absorbing the caller's
+    /// `commits` to them rather than committing to every polynomial again, see
+    /// [`M1NoPrecomp::open_with_commits`](crate::method1::M1NoPrecomp::open_with_commits)
+    pub fn open_all_with_commits<T: AsRef<[E::ScalarField]>>(
         &self,
-        transcript: &mut Transcript,
+        transcripts: &mut [impl TranscriptProtocol],
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_open_all_sizes(transcripts, evals, self.num_point_sets)?;
+        for e in evals {
+            if e.as_ref().len() != commits.len() {
+                return Err(Error::EvalsAndCommitsDifferentSizes {
+                    n_evals: e.as_ref().len(),
+                    n_commits: commits.len(),
+                });
+            }
+        }
+        self.open_all_absorbing(transcripts, commits, evals, polys)
+    }
+
+    /// Opens the polynomials at every point set, absorbing `commits` if the transcript version
+    /// absorbs commitments
+    fn open_all_absorbing<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        let gammas = transcripts
+            .iter_mut()
+            .zip(evals)
+            .zip(&self.point_set_groups)
+            .map(|((transcript, evals), subgroup)| {
+                opening_challenge(
+                    transcript,
+                    commits,
+                    evals.as_ref(),
+                    polys,
+                    &ev_points(subgroup),
+                    &self.inner.transcript_encoding,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.inner.install(|| {
+            cfg_iter!(gammas)
+                .map(|(i, gamma)| self.open_with_gamma(polys, *gamma, &self.point_set_groups[i]))
+                .collect()
+        })
+    }
+
+    /// Computes the G1 element which is paired with the G2 generator on the left hand side of the
+    /// verification equation
+    fn verifier_lhs(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         point_set_index: usize,
         evals: &[impl AsRef<[E::ScalarField]>],
-        proof: &Self::Proof,
-    ) -> Result<bool, Error> {
+    ) -> Result<E::G1, Error> {
         check_verify_sizes(commits, evals, self.base_size / self.num_point_sets)?;
 
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        // TODO: better error
-        let subgroup = self
-            .point_set_groups
-            .get(point_set_index)
-            .ok_or(Error::NoPointsGiven)?;
+        let encoding = &self.inner.transcript_encoding;
+        let subgroup = self.point_set_group(point_set_index)?;
         let points = ev_points(subgroup);
-        transcribe_points_and_evals(transcript, &points, evals, field_size_bytes)?;
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
+        transcribe_points_and_evals(transcript, commits, &points, evals, encoding)?;
+        let gamma = encoding.challenge(transcript, b"open gamma");
+        self.verifier_lhs_with_gamma(commits, subgroup, evals, gamma)
+    }
+
+    /// Computes the same as [`M1CyclPrecomp::verifier_lhs`] once the challenge `gamma` has been
+    /// drawn, the sizes must already have been checked
+    fn verifier_lhs_with_gamma(
+        &self,
+        commits: &[Commitment<E>],
+        subgroup: &GeneralEvaluationDomain<E::ScalarField>,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        gamma: E::ScalarField,
+    ) -> Result<E::G1, Error> {
         // Aggregate the r_is and then do a single msm of just the ri's and gammas
         let gammas = gen_powers(gamma, evals.len());
 
         // We first get the values of sum_i gamma^i-1 r_i,j (z_j)
-        let mut gamma_ris = linear_combination(&evals, &gammas).expect("TODO");
+        #[cfg(not(feature = "parallel"))]
+        let mut gamma_ris = crate::linear_combination(evals, &gammas).ok_or(Error::EmptyEvals)?;
+        #[cfg(feature = "parallel")]
+        let mut gamma_ris = {
+            let rows = evals.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+            crate::parallel::linear_combination(
+                &rows,
+                &gammas,
+                self.inner.parallelism().min_chunk_len(),
+            )
+            .ok_or(Error::EmptyEvals)?
+        };
         // Then we find the coefficients
+        let timer = start_timer!(|| ark_std::format!("ifft of {} evals", gamma_ris.len()));
         subgroup.ifft_in_place(&mut gamma_ris);
-        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.inner.g1_precomp, &gamma_ris)?;
+        end_timer!(timer);
+        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
+
+        // Then do a single msm of the gammas and commitments, split across threads when there
+        // are many commitments
+        #[cfg(not(feature = "parallel"))]
+        let (gamma_ris_pt, gamma_cm_pt) = (
+            M::multi_scalar_mul_g1(&self.inner.g1_precomp, &gamma_ris)?,
+            M::multi_scalar_mul_g1(&M::prepare_g1(cms), &gammas)?,
+        );
+        #[cfg(feature = "parallel")]
+        let (gamma_ris_pt, gamma_cm_pt) = {
+            let (ris, cm) = rayon::join(
+                || M::multi_scalar_mul_g1(&self.inner.g1_precomp, &gamma_ris),
+                || {
+                    crate::parallel::split_msm_g1::<M>(
+                        &cms,
+                        &gammas,
+                        self.inner.parallelism().min_chunk_len(),
+                    )
+                },
+            );
+            (ris?, cm?)
+        };
+
+        Ok(gamma_cm_pt - gamma_ris_pt)
+    }
+}
+
+/// The point sets are recomputed from their sizes and the base offset on deserialization, only
+/// the inner scheme and the G2 zeros are stored
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalSerialize for M1CyclPrecomp<E, M> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.inner.serialize_with_mode(&mut writer, compress)?;
+        self.base_size.serialize_with_mode(&mut writer, compress)?;
+        self.num_point_sets
+            .serialize_with_mode(&mut writer, compress)?;
+        self.offset().serialize_with_mode(&mut writer, compress)?;
+        self.g2_zeros.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.inner.serialized_size(compress)
+            + self.base_size.serialized_size(compress)
+            + self.num_point_sets.serialized_size(compress)
+            + self.offset().serialized_size(compress)
+            + self.g2_zeros.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Valid for M1CyclPrecomp<E, M> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.inner.check()?;
+        self.g2_zeros.check()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalDeserialize for M1CyclPrecomp<E, M> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let inner = M1NoPrecomp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let base_size = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let num_point_sets = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let offset = E::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g2_zeros: Vec<E::G2Affine> =
+            Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        if g2_zeros.len() != num_point_sets {
+            return Err(SerializationError::InvalidData);
+        }
+        let split_domain =
+            SplitEvalDomain::<E::ScalarField>::new_coset(base_size, num_point_sets, offset)
+                .ok_or(SerializationError::InvalidData)?;
+        Ok(Self {
+            inner,
+            point_set_groups: split_domain.subgroups(),
+            split_domain,
+            num_point_sets,
+            base_size,
+            g2_zeros,
+        })
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for M1CyclPrecomp<E, M> {
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
+    }
+
+    fn max_pts(&self) -> usize {
+        self.inner.max_pts()
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m1-cycl-precomp"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        g1_compressed_size::<E>()
+    }
+
+    /// Each point set only holds its subgroup and the commitment to its vanishing polynomial in
+    /// G2. Openings hold the combined polynomial and its quotient, and the evaluations over the
+    /// point set.
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate {
+        let per_point_set =
+            size_of::<E::G2Affine>() + size_of::<GeneralEvaluationDomain<E::ScalarField>>();
+        MemoryEstimate {
+            precompute: num_point_sets * per_point_set,
+            per_open: (2 * max_coeffs + max_pts) * size_of::<E::ScalarField>(),
+            ..M1NoPrecomp::<E, M>::memory_estimate(max_coeffs, max_pts, num_point_sets)
+        }
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1CyclPrecomp<E, M> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        self.inner.commit(poly)
+    }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_evals(evals, domain)
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_sparse(poly)
+    }
+}
 
-        // Then do a single msm of the gammas and commitments
-        let cms_prep = M::prepare_g1(commits.into_iter().map(|i| i.0).collect());
-        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, &gammas)?;
+impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M> {
+    type Proof = Proof<E>;
+
+    fn open(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        let subgroup = self.point_set_group(point_set_index)?;
+        let timer = start_timer!(|| {
+            ark_std::format!(
+                "open {} polys at cyclic point set {}",
+                polys.len(),
+                point_set_index
+            )
+        });
+        let gamma = opening_challenge(
+            transcript,
+            &self.inner.transcript_commits(polys)?,
+            evals,
+            polys,
+            &ev_points(subgroup),
+            &self.inner.transcript_encoding,
+        )?;
+        let res = self.open_with_gamma(polys, gamma, subgroup);
+        end_timer!(timer);
+        res
+    }
 
+    fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
         let g2 = self.inner.powers_of_g2[0];
+        let timer = start_timer!(|| {
+            ark_std::format!(
+                "verify {} commits at cyclic point set {}",
+                commits.len(),
+                point_set_index
+            )
+        });
 
-        let lhsg1 = (gamma_cm_pt - gamma_ris_pt).into_affine();
+        let lhsg1 = self
+            .verifier_lhs(transcript, commits, point_set_index, evals)?
+            .into_affine();
         let lhsg2 = g2;
         let rhsg1 = proof.0;
         let rhsg2 = self.g2_zeros[point_set_index];
-        Ok(M::pairing_eq_check(lhsg1, lhsg2, rhsg1, rhsg2))
+        let res = M::pairing_eq_check(lhsg1, lhsg2, rhsg1, rhsg2);
+        end_timer!(timer);
+        Ok(res)
+    }
+
+    fn compute_evals(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error> {
+        let subgroup = self.point_set_group(point_set_index)?;
+        Ok(polys
+            .iter()
+            .map(|p| evaluate_over_coset(p.as_ref(), subgroup))
+            .collect())
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            usize,
+            &[impl AsRef<[E::ScalarField]>],
+            &Self::Proof,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        let terms = transcripts
+            .iter_mut()
+            .zip(items)
+            .map(|(transcript, (commits, point_set_index, evals, proof))| {
+                let lhs = self.verifier_lhs(transcript, commits, *point_set_index, evals)?;
+                Ok((lhs, proof.0, self.g2_zeros[*point_set_index].into_group()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(self.inner.batch_pairing_check(&terms, rng))
     }
 }
 
@@ -185,16 +820,23 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M>
 mod tests {
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
     use merlin::Transcript;
 
-    use super::M1CyclPrecomp;
+    use super::{compute_quotient_cyclic, M1CyclPrecomp};
     use crate::{
+        gen_powers,
+        method1::compute_quotient,
         method1::M1NoPrecomp,
-        msm::ArkMSMEngine,
-        poly_ops::ev_points,
+        msm::{blst::BlstMSMEngine, AnyMSMEngine, ArkMSMEngine},
+        poly_ops::{ev_points, evaluate_over_coset},
+        progress::Progress,
         test_rng,
-        testing::test_basic_precomp,
+        testing::{gen_openings, test_basic_precomp, test_batch_precomp},
         traits::{Committer, PolyMultiProof},
+        Error,
     };
 
     #[test]
@@ -205,6 +847,428 @@ mod tests {
         test_basic_precomp(&s, &points);
     }
 
+    #[test]
+    fn test_compute_evals() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(256, 64, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        // A coset, with polynomials longer than it
+        let points = ev_points(&s.point_set_groups[3]);
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        assert!(coeffs[0].len() > points.len());
+        assert_eq!(evals, s.compute_evals(&coeffs, 3).unwrap());
+        let (computed, proof) = s
+            .open_with_poly_evals(&mut Transcript::new(b"test"), &coeffs, 3)
+            .unwrap();
+        assert_eq!(evals, computed);
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut Transcript::new(b"test"), &commits, 3, &evals, &proof)
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 4,
+                n_point_sets: 4
+            }),
+            s.compute_evals(&coeffs, 4)
+        );
+    }
+
+    #[test]
+    fn test_compute_quotient_cyclic() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(256, 64, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner_coset(s, 64, 4, Fr::from(3u64)).unwrap();
+        let subgroup = &s.point_set_groups[1];
+        let (coeffs, _, _) = gen_openings(&s, 3, &ev_points(subgroup));
+        let gammas = gen_powers(Fr::from(11u64), 3);
+        assert_eq!(
+            compute_quotient(&coeffs, &gammas, &subgroup.vanishing_polynomial().into()),
+            compute_quotient_cyclic(&coeffs, &gammas, subgroup)
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_works() {
+        let s = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let point_sets = s.point_set_groups.iter().map(ev_points).collect::<Vec<_>>();
+        test_batch_precomp(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(256, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let mut bytes = Vec::new();
+        s.serialize_uncompressed(&mut bytes).unwrap();
+        let loaded =
+            M1CyclPrecomp::<Bls12_381, BlstMSMEngine>::deserialize_uncompressed(&bytes[..])
+                .expect("Failed to deserialize");
+        assert_eq!(s.point_set_groups, loaded.point_set_groups);
+        let point_sets = loaded
+            .point_set_groups
+            .iter()
+            .map(ev_points)
+            .collect::<Vec<_>>();
+        test_batch_precomp(&loaded, &point_sets);
+    }
+
+    #[test]
+    fn test_coset_works() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(256, 64, &mut test_rng());
+        let offset = Fr::from(5u64);
+        let s = M1CyclPrecomp::from_inner_coset(s, 128, 4, offset).expect("Failed to construct");
+        assert_eq!(offset, s.offset());
+        let base_points = ev_points(s.split_domain.base());
+        assert_eq!(offset, base_points[0]);
+        let point_sets = (0..4)
+            .map(|i| {
+                s.split_domain
+                    .take_subgroup_indices(i, &base_points)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(point_sets[1], ev_points(&s.point_set_groups[1]));
+        test_basic_precomp(&s, &point_sets[0]);
+        test_batch_precomp(&s, &point_sets);
+
+        // Evaluating over the whole coset then splitting matches the point set evals
+        let (coeffs, _, _) = gen_openings(&s, 2, &point_sets[2]);
+        let base_evals = coeffs
+            .iter()
+            .map(|p| evaluate_over_coset(p, s.split_domain.base()))
+            .collect::<Vec<_>>();
+        let split_evals = base_evals
+            .iter()
+            .map(|e| s.split_domain.take_subgroup_indices(2, e).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(split_evals, s.compute_evals(&coeffs, 2).unwrap());
+
+        let mut bytes = Vec::new();
+        s.serialize_compressed(&mut bytes).unwrap();
+        let loaded = M1CyclPrecomp::<Bls12_381, BlstMSMEngine>::deserialize_compressed(&bytes[..])
+            .expect("Failed to deserialize");
+        assert_eq!(s.point_set_groups, loaded.point_set_groups);
+        assert_eq!(offset, loaded.offset());
+
+        assert!(matches!(
+            M1CyclPrecomp::from_inner_coset(s.inner, 128, 4, Fr::from(0u64)),
+            Err(Error::DomainConstructionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_open_all() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(512, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let (coeffs, all_evals, commits) = gen_openings(&s, 3, &ev_points(s.split_domain.base()));
+        let evals = (0..4)
+            .map(|i| {
+                all_evals
+                    .iter()
+                    .map(|row| s.split_domain.take_subgroup_indices(i, row).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut transcripts = vec![Transcript::new(b"test"); 4];
+        let proofs = s.open_all(&mut transcripts, &evals, &coeffs).unwrap();
+        for (i, proof) in proofs.iter().enumerate() {
+            assert_eq!(
+                Ok(true),
+                s.verify(&mut Transcript::new(b"test"), &commits, i, &evals[i], proof)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_verify_with_config() {
+        use crate::parallel::ParallelismConfig;
+
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(128, 32, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 128, 4).expect("Failed to construct");
+        let points = ev_points(&s.point_set_groups[1]);
+        let (coeffs, evals, commits) = gen_openings(&s, 300, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, 1)
+            .unwrap();
+        for config in [
+            ParallelismConfig::global(),
+            ParallelismConfig::with_max_threads(2).unwrap(),
+        ] {
+            assert_eq!(
+                Ok(true),
+                s.verify_with_config(
+                    &config,
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    1,
+                    &evals,
+                    &proof
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_with_config(
+                    &config,
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    2,
+                    &evals,
+                    &proof
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_union_works() {
+        let s = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let indices = [3, 1];
+        let points = indices
+            .iter()
+            .flat_map(|&i| ev_points(&s.point_set_groups[i]))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open_union(&mut Transcript::new(b"test"), &evals, &coeffs, &indices)
+            .expect("Failed to open");
+        assert_eq!(
+            Ok(true),
+            s.verify_union(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &indices,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_union(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &[1, 3],
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let s = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 256, &mut test_rng());
+        // 255 isn't of the form 3^a 2^b, and 3 doesn't divide 256
+        assert_eq!(
+            Some(Error::DomainConstructionFailed(255)),
+            M1CyclPrecomp::from_inner(s.clone(), 255, 1).err()
+        );
+        assert_eq!(
+            Some(Error::DomainConstructionFailed(256)),
+            M1CyclPrecomp::from_inner(s.clone(), 256, 3).err()
+        );
+        // Point sets of 128 points need more than the 32 points the setup supports
+        let small =
+            <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 32, &mut test_rng());
+        assert_eq!(
+            Some(Error::TooManyPoints {
+                n_points: 128,
+                max_points: 32
+            }),
+            M1CyclPrecomp::from_inner(small, 256, 2).err()
+        );
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let polys = vec![vec![Fr::from(1u64); 64]];
+        let evals = vec![vec![Fr::from(1u64); 64]];
+        let commits = vec![s.commit(&polys[0]).unwrap()];
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 4,
+                n_point_sets: 4
+            }),
+            s.open(&mut Transcript::new(b"test"), &evals, &polys, 4)
+                .map(|_| ())
+        );
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &polys, 0)
+            .unwrap();
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 4,
+                n_point_sets: 4
+            }),
+            s.verify(&mut Transcript::new(b"test"), &commits, 4, &evals, &proof)
+        );
+        let no_evals: Vec<Vec<Fr>> = vec![];
+        assert_eq!(
+            Err(Error::EmptyEvals),
+            s.verify(&mut Transcript::new(b"test"), &[], 0, &no_evals, &proof)
+        );
+    }
+
+    #[test]
+    fn test_mixed_radix_works() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(192, 64, &mut test_rng());
+        for (base_size, num_point_sets) in [(96, 3), (96, 6), (192, 4), (48, 2)] {
+            let s = M1CyclPrecomp::from_inner_coset(
+                s.clone(),
+                base_size,
+                num_point_sets,
+                Fr::from(7u64),
+            )
+            .expect("Failed to construct");
+            let base_points = ev_points(s.split_domain.base());
+            let point_sets = (0..num_point_sets)
+                .map(|i| {
+                    s.split_domain
+                        .take_subgroup_indices(i, &base_points)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            for (set, group) in point_sets.iter().zip(&s.point_set_groups) {
+                assert_eq!(set, &ev_points(group));
+            }
+            test_basic_precomp(&s, &point_sets[0]);
+            test_batch_precomp(&s, &point_sets);
+
+            let (coeffs, evals, _) = gen_openings(&s, 2, &point_sets[num_point_sets - 1]);
+            assert_eq!(evals, s.compute_evals(&coeffs, num_point_sets - 1).unwrap());
+
+            let mut bytes = Vec::new();
+            s.serialize_compressed(&mut bytes).unwrap();
+            let loaded =
+                M1CyclPrecomp::<Bls12_381, BlstMSMEngine>::deserialize_compressed(&bytes[..])
+                    .expect("Failed to deserialize");
+            assert_eq!(s.point_set_groups, loaded.point_set_groups);
+        }
+
+        // BN254 has no small subgroup, so only powers of two work
+        type Bn = ark_bn254::Bn254;
+        let s = <M1NoPrecomp<Bn, ArkMSMEngine<Bn>>>::new(96, 8, &mut test_rng());
+        assert_eq!(
+            Some(Error::DomainConstructionFailed(96)),
+            M1CyclPrecomp::from_inner(s, 96, 3).err()
+        );
+    }
+
+    #[test]
+    fn test_bn254_works() {
+        type Bn = ark_bn254::Bn254;
+        let s = <M1NoPrecomp<Bn, ArkMSMEngine<Bn>>>::new(256, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 4).expect("Failed to construct");
+        let point_sets = s.point_set_groups.iter().map(ev_points).collect::<Vec<_>>();
+        test_basic_precomp(&s, &point_sets[0]);
+        test_batch_precomp(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_default_engine() {
+        // The default engine picks blst for BLS12-381 and arkworks for other curves
+        let s = M1NoPrecomp::<Bls12_381, AnyMSMEngine<Bls12_381>>::new(64, 16, &mut test_rng());
+        let s: M1CyclPrecomp<Bls12_381> = M1CyclPrecomp::from_inner(s, 64, 4).unwrap();
+        test_basic_precomp(&s, &ev_points(&s.point_set_groups[0]));
+
+        type Bn = ark_bn254::Bn254;
+        let s = M1NoPrecomp::<Bn, AnyMSMEngine<Bn>>::new(64, 16, &mut test_rng());
+        let s: M1CyclPrecomp<Bn> = M1CyclPrecomp::from_inner(s, 64, 4).unwrap();
+        test_basic_precomp(&s, &ev_points(&s.point_set_groups[0]));
+    }
+
+    #[test]
+    fn test_external_challenge() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(64, 16, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner_coset(s, 64, 4, Fr::from(3u64)).unwrap();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(63, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let evals = s.compute_evals(&polys, 1).unwrap();
+        let gamma = Fr::rand(&mut test_rng());
+
+        let proof = s.open_with_challenge(&polys, 1, gamma).unwrap();
+        // The same proof as the inner scheme makes at the point set's points
+        let points = ev_points(&s.point_set_groups[1]);
+        assert_eq!(
+            s.inner
+                .open_with_challenge(&polys, &points, gamma)
+                .unwrap()
+                .0,
+            proof.0
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify_with_challenge(&commits, 1, &evals, &proof, gamma)
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_with_challenge(&commits, 1, &evals, &proof, gamma + Fr::from(1u64))
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_with_challenge(&commits, 2, &evals, &proof, gamma)
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 4,
+                n_point_sets: 4
+            }),
+            s.open_with_challenge(&polys, 4, gamma).map(|p| p.0)
+        );
+    }
+
+    #[test]
+    fn test_open_batched_sets() {
+        let s = <M1NoPrecomp<Bls12_381, BlstMSMEngine>>::new(64, 33, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner_coset(s, 64, 4, Fr::from(3u64)).unwrap();
+        let indices = [1, 3];
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(63, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let evals = indices
+            .iter()
+            .map(|i| s.compute_evals(&polys, *i).unwrap())
+            .collect::<Vec<_>>();
+        for union in [false, true] {
+            let proof = s
+                .open_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &polys,
+                    &indices,
+                    union,
+                )
+                .unwrap();
+            assert_eq!(union, proof.union.is_some());
+            assert_eq!(
+                Ok(true),
+                s.verify_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &evals,
+                    &indices,
+                    &proof
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &evals,
+                    &[3, 1],
+                    &proof
+                )
+            );
+        }
+    }
+
     #[test]
     fn test_complex_open_works() {
         let s = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 256, &mut test_rng());
@@ -234,11 +1298,7 @@ mod tests {
         for gi in 0..s.point_set_groups.len() {
             let trimmed_evals: Vec<_> = naive_evals
                 .iter()
-                .map(|ev| {
-                    s.split_domain
-                        .take_subgroup_indices(gi, ev.clone())
-                        .unwrap()
-                })
+                .map(|ev| s.split_domain.take_subgroup_indices(gi, ev).unwrap())
                 .collect();
             let proof = s
                 .open(&mut Transcript::new(b"test"), &trimmed_evals, &polys, gi)
@@ -255,4 +1315,89 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_from_inner_coset_with_progress() {
+        let inner = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(64, 16, &mut test_rng());
+        let last = AtomicUsize::new(0);
+        let callback = |done: usize, total: usize| {
+            assert_eq!(4, total);
+            last.fetch_max(done, Ordering::Relaxed);
+        };
+        let s = M1CyclPrecomp::from_inner_coset_with_progress(
+            inner.clone(),
+            64,
+            4,
+            Fr::from(7u64),
+            &Progress::new().with_callback(&callback),
+        )
+        .unwrap();
+        assert_eq!(4, last.load(Ordering::Relaxed));
+        let points = ev_points(&s.point_set_groups[0]);
+        test_basic_precomp(&s, &points);
+
+        let cancel = AtomicBool::new(true);
+        assert_eq!(
+            Err(Error::Cancelled),
+            M1CyclPrecomp::from_inner_coset_with_progress(
+                inner,
+                64,
+                4,
+                Fr::from(7u64),
+                &Progress::new().with_cancel(&cancel)
+            )
+            .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_open_domain_evals() {
+        let inner = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(64, 17, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner_coset(inner, 64, 4, Fr::from(5u64)).unwrap();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(63, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let domain_evals = polys
+            .iter()
+            .map(|p| evaluate_over_coset(p, s.split_domain.base()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        for i in 0..4 {
+            let (evals, proof) = s
+                .open_domain_evals(&mut Transcript::new(b"testing"), &domain_evals, i)
+                .unwrap();
+            assert_eq!(s.compute_evals(&polys, i).unwrap(), evals);
+            let expected = s
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, i)
+                .unwrap();
+            assert_eq!(expected.0, proof.0);
+            assert_eq!(
+                Ok(true),
+                s.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    i,
+                    &evals,
+                    &proof
+                )
+            );
+        }
+
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 1,
+                n_evals: 32,
+                expected: 64
+            }),
+            s.open_domain_evals(
+                &mut Transcript::new(b"testing"),
+                &[&domain_evals[0][..], &domain_evals[1][..32]],
+                0
+            )
+            .map(|_| ())
+        );
+    }
 }
@@ -1,27 +1,47 @@
 //! # BDFG Method 1
 //! This contains a pure ark implementation of BDFG21 method 1
 use crate::{
-    check_opening_sizes, check_verify_sizes,
-    lagrange::LagrangeInterpContext,
-    traits::{Committer, MSMEngine, PolyMultiProofNoPrecomp},
+    check_opening_sizes, check_poly_sizes, check_setup_size, check_verify_sizes,
+    lagrange::{linear_combination_of_evals, LagrangeInterpContext},
+    lagrange_basis::{find_basis, LagrangeBasis},
+    msm::FixedBaseTable,
+    poly_ops::{div_by_linear, evaluate_at_points},
+    streaming::StreamingCommitter,
+    traits::{
+        check_batch_sizes, g1_compressed_size, Committer, MSMEngine, MemoryEstimate,
+        PolyMultiProofNoPrecomp, SchemeInfo, TranscriptProtocol,
+    },
+    transcript::TranscriptEncoding,
 };
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{rand::RngCore, vec::Vec, UniformRand, marker::PhantomData};
-use merlin::Transcript;
+use ark_ff::{FftField, One, PrimeField, Zero};
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    DenseUVPolynomial, Radix2EvaluationDomain,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
+    collections::BTreeMap, end_timer, marker::PhantomData, rand::RngCore, start_timer, vec,
+    vec::Vec, UniformRand,
+};
+use core::mem::size_of;
 
-use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
 
-use crate::{get_challenge, get_field_size, transcribe_points_and_evals, Commitment};
+use crate::{transcribe_points_and_evals, Commitment};
 
 use super::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r_in_place, vanishing_polynomial,
+    Error, ErrorKind,
 };
 
 pub mod precompute;
+pub mod verifier_key;
 
 /// A method 1 proof scheme with no precomputation of lagrange polynomials
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct M1NoPrecomp<E: Pairing, M: MSMEngine<E = E>> {
     /// The given powers tau in G1
     pub powers_of_g1: Vec<E::G1Affine>,
@@ -33,35 +53,376 @@ pub struct M1NoPrecomp<E: Pairing, M: MSMEngine<E = E>> {
     pub(crate) g1_precomp: M::G1Prepared,
     pub(crate) g2_precomp: M::G2Prepared,
 
+    /// Lagrange bases used for committing to polynomials in evaluation form
+    pub lagrange_bases: Vec<LagrangeBasis<E, M>>,
+
+    /// A table of the powers of tau in G1 for committing and opening with, if one was made with
+    /// [`Self::with_fixed_base_table`]
+    fixed_base: Option<FixedBaseTable<E::G1>>,
+
+    /// The seed the secret was derived from, if this is an insecure testing setup
+    insecure_seed: Option<u64>,
+
+    /// How points and evaluations are written into the opening transcript
+    pub(crate) transcript_encoding: TranscriptEncoding,
+
+    /// The thread pool and chunk sizes used for parallel work
+    #[cfg(feature = "parallel")]
+    parallelism: crate::parallel::ParallelismConfig,
+
     _marker: PhantomData<M>,
 }
 
+/// Insecure testing setups are marked with their seed, the prepared points aren't shown
+impl<E: Pairing, M: MSMEngine<E = E>> core::fmt::Debug for M1NoPrecomp<E, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let lagrange_domain_sizes = self
+            .lagrange_bases
+            .iter()
+            .map(|b| b.domain().size)
+            .collect::<Vec<_>>();
+        f.debug_struct("M1NoPrecomp")
+            .field("insecure_seed", &self.insecure_seed)
+            .field("powers_of_g1", &self.powers_of_g1)
+            .field("powers_of_g2", &self.powers_of_g2)
+            .field("lagrange_domain_sizes", &lagrange_domain_sizes)
+            .field(
+                "fixed_base_copies",
+                &self.fixed_base.as_ref().map(FixedBaseTable::copies),
+            )
+            .field("transcript_encoding", &self.transcript_encoding)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalSerialize for M1NoPrecomp<E, M> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.powers_of_g1
+            .serialize_with_mode(&mut writer, compress)?;
+        self.powers_of_g2
+            .serialize_with_mode(&mut writer, compress)?;
+        self.lagrange_bases
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.powers_of_g1.serialized_size(compress)
+            + self.powers_of_g2.serialized_size(compress)
+            + self.lagrange_bases.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Valid for M1NoPrecomp<E, M> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.powers_of_g1.check()?;
+        self.powers_of_g2.check()?;
+        self.lagrange_bases.check()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalDeserialize for M1NoPrecomp<E, M> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let powers_of_g1 = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let powers_of_g2 = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let lagrange_bases = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self {
+            lagrange_bases,
+            ..Self::new_from_affine(powers_of_g1, powers_of_g2)
+        })
+    }
+}
+
 /// A method 1 proof
 #[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<E: Pairing>(pub E::G1Affine);
 
+impl<E: Pairing> Proof<E> {
+    /// The sum of two proofs. For proofs of single polynomials at the same point set, this is the
+    /// proof of their sum at that point set.
+    pub fn add(&self, other: &Self) -> Self {
+        Proof((self.0 + other.0).into())
+    }
+
+    /// The proof multiplied by `scalar`. For a proof of a single polynomial, this is the proof of
+    /// the polynomial multiplied by `scalar`.
+    pub fn scale(&self, scalar: E::ScalarField) -> Self {
+        Proof((self.0 * scalar).into())
+    }
+}
+
+/// Proofs of the same polynomials at several point sets with challenges drawn from a single
+/// transcript, as made by [`precompute::M1Precomp::open_batched_sets`] and
+/// [`M1CyclPrecomp::open_batched_sets`](crate::m1_cycl::M1CyclPrecomp::open_batched_sets)
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchedSetsProof<E: Pairing> {
+    /// The proof at each point set, in the order the sets were given
+    pub proofs: Vec<Proof<E>>,
+    /// A single proof at the union of the point sets, if it was asked for
+    pub union: Option<Proof<E>>,
+}
+
+#[cfg(feature = "ark-bls12-381")]
+impl Proof<ark_bls12_381::Bls12_381> {
+    /// Serialize to the 48 byte compressed encoding of the point
+    pub fn to_bytes_compressed(&self) -> [u8; 48] {
+        crate::g1_to_bytes_compressed(&self.0)
+    }
+
+    /// Parse the 48 byte compressed encoding of a point, failing unless it's in the prime order
+    /// subgroup
+    pub fn from_bytes_compressed(bytes: &[u8; 48]) -> Result<Self, Error> {
+        crate::g1_from_bytes_compressed(bytes, Validate::Yes).map(Self)
+    }
+
+    /// Parse the 48 byte compressed encoding of a point without the subgroup check, as in
+    /// [`Commitment::from_bytes_compressed_unchecked`]. Only use this for trusted bytes.
+    pub fn from_bytes_compressed_unchecked(bytes: &[u8; 48]) -> Result<Self, Error> {
+        crate::g1_from_bytes_compressed(bytes, Validate::No).map(Self)
+    }
+}
+
+/// Computes $\sum_i s_i \pi_i$ for proofs $\pi_i$ and scalars $s_i$. When each proof opens a single
+/// polynomial $f_i$ at the same point set, the result is the proof of $\sum_i s_i f_i$ at that
+/// point set, which verifies against [`crate::linear_combination_of_commitments`].
+pub fn linear_combination_of_proofs<E: Pairing>(
+    proofs: &[Proof<E>],
+    scalars: &[E::ScalarField],
+) -> Result<Proof<E>, Error> {
+    if proofs.len() != scalars.len() {
+        return Err(Error::InvalidInputLength {
+            expected: proofs.len(),
+            got: scalars.len(),
+        });
+    }
+    let bases = proofs.iter().map(|p| p.0).collect::<Vec<_>>();
+    Ok(Proof(crate::curve_msm::<E::G1>(&bases, scalars)?.into()))
+}
+
+/// Computes the quotient of $\sum_i \gamma_i f_i$ by the vanishing polynomial `vp` of the
+/// opened points, where `gammas` holds one power of the opening challenge per polynomial. A proof
+/// is the commitment to this quotient, so it can be cached and reused when the same polynomials
+/// are opened with the same challenge, e.g. for proof recursion.
+pub fn compute_quotient<F: FftField>(
+    polys: &[impl AsRef<[F]>],
+    gammas: &[F],
+    vp: &DensePolynomial<F>,
+) -> Result<DensePolynomial<F>, Error> {
+    let mut fsum = combine_polys(polys, gammas)?;
+    // The remainder holds the combination of the r_is, the quotient is what gets committed to
+    let q = poly_div_q_r_in_place(&mut fsum, &vp.coeffs)?;
+    Ok(DensePolynomial::from_coefficients_vec(q))
+}
+
+/// Takes the linear combination of `polys` with `gammas`, which must be the same length
+pub(crate) fn combine_polys<F: FftField>(
+    polys: &[impl AsRef<[F]>],
+    gammas: &[F],
+) -> Result<Vec<F>, Error> {
+    if polys.len() != gammas.len() {
+        return Err(Error::InvalidInputLength {
+            expected: polys.len(),
+            got: gammas.len(),
+        });
+    }
+    linear_combination::<F>(polys, gammas).ok_or(Error::NoPolynomialsGiven)
+}
+
+/// The values the method 1 verifier computes from the transcript, commitments and evaluations
+/// before its final pairing check, so a circuit can redo the check without the Fiat-Shamir
+/// transcript. The opening is valid when
+/// $e(C - I, \[1\]_2) = e(\pi, [Z(x)]_2)$ for the aggregated commitment $C$ and interpolation
+/// commitment $I$.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierCircuitInputs<E: Pairing> {
+    /// The challenge $\gamma$ drawn from the transcript
+    pub gamma: E::ScalarField,
+    /// $\sum_i \gamma^i C_i$ for the commitments $C_i$
+    pub aggregated_commit: E::G1Affine,
+    /// $[\sum_i \gamma^i r_i(x)]_1$ for the polynomials $r_i$ interpolating the evaluations
+    pub interpolation_commit: E::G1Affine,
+    /// The G2 generator $\[1\]_2$
+    pub g2: E::G2Affine,
+    /// $[Z(x)]_2$ for the vanishing polynomial $Z$ of the points
+    pub g2_zeros: E::G2Affine,
+    /// The proof $\pi$
+    pub proof: E::G1Affine,
+}
+
+impl<E: Pairing> VerifierCircuitInputs<E> {
+    /// Does the pairing check natively
+    pub fn check<M: MSMEngine<E = E>>(&self) -> bool {
+        M::pairing_eq_check(
+            (self.aggregated_commit.into_group() - self.interpolation_commit).into_affine(),
+            self.g2,
+            self.proof,
+            self.g2_zeros,
+        )
+    }
+}
+
+/// Why an opening did or didn't verify, from [`M1NoPrecomp::verify_explain`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyDiagnosis<E: Pairing> {
+    /// The opening verified
+    Valid,
+    /// The commitments, points and evals didn't have matching sizes, so nothing was checked
+    ShapeMismatch(Error),
+    /// The verifier drew a different challenge than the prover, so the prover's and verifier's
+    /// transcripts saw different messages, e.g. different labels, commitments, or points
+    TranscriptMismatch {
+        /// The challenge the prover drew
+        prover_gamma: E::ScalarField,
+        /// What the verifier computed, including its own challenge
+        inputs: VerifierCircuitInputs<E>,
+    },
+    /// The challenges agreed (or the prover's wasn't given) but the pairing check failed, so the
+    /// evals, commitments, or proof are wrong
+    PairingMismatch(VerifierCircuitInputs<E>),
+}
+
+impl<E: Pairing> VerifyDiagnosis<E> {
+    /// Whether the opening verified
+    pub fn is_valid(&self) -> bool {
+        matches!(self, VerifyDiagnosis::Valid)
+    }
+
+    /// Makes the diagnosis from the verifier's values, `inputs` being an [`Err`] if computing
+    /// them failed
+    fn diagnose<M: MSMEngine<E = E>>(
+        inputs: Result<VerifierCircuitInputs<E>, Error>,
+        prover_gamma: Option<E::ScalarField>,
+    ) -> Result<Self, Error> {
+        let inputs = match inputs {
+            Ok(inputs) => inputs,
+            Err(e) if e.kind() == ErrorKind::Shape => return Ok(VerifyDiagnosis::ShapeMismatch(e)),
+            Err(e) => return Err(e),
+        };
+        if inputs.check::<M>() {
+            return Ok(VerifyDiagnosis::Valid);
+        }
+        Ok(match prover_gamma {
+            Some(prover_gamma) if prover_gamma != inputs.gamma => {
+                VerifyDiagnosis::TranscriptMismatch {
+                    prover_gamma,
+                    inputs,
+                }
+            }
+            _ => VerifyDiagnosis::PairingMismatch(inputs),
+        })
+    }
+}
+
+/// A proof that committed polynomials have fewer than some number of coefficients $d$. This is
+/// their random linear combination shifted to the top of the setup, $[x^{N-d} f(x)]_1$, which
+/// can only be committed to when $f$ has fewer than $d$ coefficients.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DegreeBoundProof<E: Pairing>(pub E::G1Affine);
+
 impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
     /// Make a new random scheme
     pub fn new(max_coeffs: usize, max_pts: usize, rng: &mut impl RngCore) -> Self {
-        let x = E::ScalarField::rand(rng);
+        #[allow(unused_mut)]
+        let mut x = E::ScalarField::rand(rng);
         let g1 = E::G1::rand(rng);
         let g2 = E::G2::rand(rng);
+        let res = Self::new_from_scalar_ref(&x, g1, g2, max_coeffs, max_pts);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x);
+        res
+    }
+
+    /// Make a new scheme from a secret scalar without copying it. The powers of the secret are
+    /// wiped from memory once the setup is generated, and the secret itself when `x` is dropped.
+    #[cfg(feature = "zeroize")]
+    pub fn new_from_secret(
+        x: &zeroize::Zeroizing<E::ScalarField>,
+        g1: E::G1,
+        g2: E::G2,
+        max_coeffs: usize,
+        max_pts: usize,
+    ) -> Self {
+        Self::new_from_scalar_ref(x, g1, g2, max_coeffs, max_pts)
+    }
+
+    /// Make a new scheme whose secret is derived from `seed` with the standard generators. Anyone
+    /// who knows the seed can forge proofs, so this is INSECURE and only for tests. The same seed
+    /// always gives the same setup, so it can be shared as a fixture between crates. The seed is
+    /// shown in the `Debug` output, and returned by [`Self::insecure_seed`].
+    pub fn new_insecure_for_testing(seed: u64, max_coeffs: usize, max_pts: usize) -> Self {
+        let mut transcript = merlin::Transcript::new(b"poly-multiproof insecure testing setup");
+        transcript.append_u64(b"seed", seed);
+        let x = seeded_scalar(&mut transcript, b"secret");
+        Self {
+            insecure_seed: Some(seed),
+            ..Self::new_from_scalar(
+                x,
+                E::G1::generator(),
+                E::G2::generator(),
+                max_coeffs,
+                max_pts,
+            )
+        }
+    }
+
+    /// The seed of a setup made with [`Self::new_insecure_for_testing`]. This isn't serialized, so
+    /// it's `None` for deserialized setups.
+    pub fn insecure_seed(&self) -> Option<u64> {
+        self.insecure_seed
+    }
+
+    /// Make a new scheme whose secret and generators are derived from `seed`, using a merlin
+    /// transcript as the key derivation function. Nodes of a development network can each derive
+    /// the same setup from a shared seed, rather than copying the setup between machines.
+    /// Anyone who knows the seed knows the secret and can forge proofs, so this is INSECURE
+    /// outside of development networks and tests.
+    pub fn new_from_seed(seed: &[u8], max_coeffs: usize, max_pts: usize) -> Self {
+        let mut transcript = merlin::Transcript::new(b"poly-multiproof seeded setup");
+        transcript.append_message(b"seed", seed);
+        let x = seeded_scalar(&mut transcript, b"secret");
+        let g1 = E::G1::generator() * seeded_scalar::<E::ScalarField>(&mut transcript, b"g1");
+        let g2 = E::G2::generator() * seeded_scalar::<E::ScalarField>(&mut transcript, b"g2");
         Self::new_from_scalar(x, g1, g2, max_coeffs, max_pts)
     }
 
-    /// Make a new scheme from a given secret scalar
+    /// Make a new scheme from a given secret scalar. With the `zeroize` feature, the scalar passed
+    /// in is wiped once the setup is generated, but the caller's copy isn't, see
+    /// `new_from_secret`.
     pub fn new_from_scalar(
-        x: E::ScalarField,
+        #[allow(unused_mut)] mut x: E::ScalarField,
+        g1: E::G1,
+        g2: E::G2,
+        max_coeffs: usize,
+        max_pts: usize,
+    ) -> Self {
+        let res = Self::new_from_scalar_ref(&x, g1, g2, max_coeffs, max_pts);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x);
+        res
+    }
+
+    pub(crate) fn new_from_scalar_ref(
+        x: &E::ScalarField,
         g1: E::G1,
         g2: E::G2,
         max_coeffs: usize,
         max_pts: usize,
     ) -> Self {
         let n_g2_powers = max_pts + 1;
-        let x_powers = gen_powers(x, core::cmp::max(max_coeffs, n_g2_powers));
+        #[allow(unused_mut)]
+        let mut x_powers = gen_powers(*x, core::cmp::max(max_coeffs, n_g2_powers));
 
         let powers_of_g1 = gen_curve_powers::<E::G1>(x_powers.as_ref(), g1);
         let powers_of_g2 = gen_curve_powers::<E::G2>(x_powers[..n_g2_powers].as_ref(), g2);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x_powers);
 
         Self::new_from_affine(powers_of_g1, powers_of_g2)
     }
@@ -74,146 +435,2320 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
         )
     }
 
-    /// Make a new scheme from the given powers in affine form
+    /// Make a new scheme from the given powers in affine form. The sizes aren't checked, see
+    /// [`Self::new_from_affine_checked`]
     pub fn new_from_affine(powers_of_g1: Vec<E::G1Affine>, powers_of_g2: Vec<E::G2Affine>) -> Self {
         Self {
             g1_precomp: M::prepare_g1(powers_of_g1.clone()),
             g2_precomp: M::prepare_g2(powers_of_g2.clone()),
             powers_of_g1,
             powers_of_g2,
+            lagrange_bases: Vec::new(),
+            fixed_base: None,
+            insecure_seed: None,
+            transcript_encoding: TranscriptEncoding::default(),
+            #[cfg(feature = "parallel")]
+            parallelism: Default::default(),
             _marker: PhantomData,
         }
     }
 
-    fn open_with_vanishing_poly(
+    /// Make a new scheme from the given powers in affine form, checking that there are enough of
+    /// them for polynomials with up to `max_coeffs` coefficients opened at up to `max_pts`
+    /// points, as [`Self::new`] makes. A setup that's too small fails here with
+    /// [`Error::SetupTooSmall`], rather than when it's used.
+    pub fn new_from_affine_checked(
+        powers_of_g1: Vec<E::G1Affine>,
+        powers_of_g2: Vec<E::G2Affine>,
+        max_coeffs: usize,
+        max_pts: usize,
+    ) -> Result<Self, Error> {
+        let n_g2_powers = max_pts + 1;
+        let n_g1_powers = core::cmp::max(max_coeffs, n_g2_powers);
+        check_setup_size(1, n_g1_powers, powers_of_g1.len())?;
+        check_setup_size(2, n_g2_powers, powers_of_g2.len())?;
+        Ok(Self::new_from_affine(powers_of_g1, powers_of_g2))
+    }
+
+    /// The most coefficients a committed polynomial can have
+    pub fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
+    }
+
+    /// The most points a proof can be verified at, one less than the number of powers in G2
+    pub fn max_pts(&self) -> usize {
+        self.powers_of_g2.len().saturating_sub(1)
+    }
+
+    /// Write points and evaluations into the opening transcript, and read its challenge, with
+    /// `encoding`. Schemes built on this setup and its [`Self::verifier_key`] use it too. This
+    /// isn't serialized, so it's the default for deserialized setups.
+    pub fn with_transcript_encoding(mut self, encoding: TranscriptEncoding) -> Self {
+        self.transcript_encoding = encoding;
+        self
+    }
+
+    /// The encoding of the opening transcript
+    pub fn transcript_encoding(&self) -> &TranscriptEncoding {
+        &self.transcript_encoding
+    }
+
+    /// Run parallel work for this setup, such as its msms and the precomputation of schemes built
+    /// on it, with `config`
+    #[cfg(feature = "parallel")]
+    pub fn with_parallelism(mut self, config: crate::parallel::ParallelismConfig) -> Self {
+        self.parallelism = config;
+        self
+    }
+
+    /// The config parallel work for this setup runs with
+    #[cfg(feature = "parallel")]
+    pub fn parallelism(&self) -> &crate::parallel::ParallelismConfig {
+        &self.parallelism
+    }
+
+    /// Run `f` on the thread pool of the setup's parallelism config
+    pub(crate) fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "parallel")]
+        return self.parallelism.install(f);
+        #[cfg(not(feature = "parallel"))]
+        f()
+    }
+
+    /// Hold a [`FixedBaseTable`] of up to `copies` copies of the powers of tau in G1, which
+    /// commitments and openings use in place of the MSM engine. More copies are faster and take
+    /// more memory, up to one per window of the table: with 4096 powers over BLS12-381, a copy
+    /// for every window takes 8MB and commits about 40% faster than
+    /// [`ArkMSMEngine`](crate::msm::ArkMSMEngine), on par with blst. The table isn't serialized.
+    pub fn with_fixed_base_table(mut self, copies: usize) -> Self {
+        self.fixed_base = Some(self.install(|| FixedBaseTable::new(&self.powers_of_g1, copies)));
+        self
+    }
+
+    /// Drop the table made by [`Self::with_fixed_base_table`], going back to the MSM engine
+    pub fn without_fixed_base_table(mut self) -> Self {
+        self.fixed_base = None;
+        self
+    }
+
+    /// The table made by [`Self::with_fixed_base_table`], if any
+    pub fn fixed_base_table(&self) -> Option<&FixedBaseTable<E::G1>> {
+        self.fixed_base.as_ref()
+    }
+
+    /// An msm of `scalars` against the powers of tau in G1, with the fixed base table if there is
+    /// one
+    pub(crate) fn g1_msm(&self, scalars: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        match &self.fixed_base {
+            Some(table) => table.msm(scalars.as_ref()),
+            None => M::multi_scalar_mul_g1(&self.g1_precomp, scalars),
+        }
+    }
+
+    /// Rebuild the fixed base table, if there is one, after the powers of tau in G1 change
+    fn rebuild_fixed_base(&mut self) {
+        if let Some(table) = self.fixed_base.take() {
+            self.fixed_base = Some(FixedBaseTable::with_window_bits(
+                &self.powers_of_g1,
+                table.window_bits(),
+                table.copies(),
+            ));
+        }
+    }
+
+    /// Add a lagrange basis for `domain` so that `commit_evals` over it doesn't need an IFFT
+    pub fn with_lagrange_basis(
+        mut self,
+        domain: Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Self, Error> {
+        if find_basis(&self.lagrange_bases, &domain).is_none() {
+            self.lagrange_bases
+                .push(LagrangeBasis::new(&self.powers_of_g1, domain)?);
+        }
+        Ok(self)
+    }
+
+    /// Trim the setup to the sizes [`Self::new`] makes for `max_coeffs` and `max_pts`, freeing the
+    /// rest of the powers. Lagrange bases over domains larger than the remaining powers are
+    /// dropped.
+    pub fn truncate(&mut self, max_coeffs: usize, max_pts: usize) -> Result<(), Error> {
+        let n_g2_powers = max_pts + 1;
+        let n_g1_powers = core::cmp::max(max_coeffs, n_g2_powers);
+        if n_g1_powers > self.powers_of_g1.len() {
+            return Err(Error::TooManyScalars {
+                n_coeffs: n_g1_powers,
+                expected_max: self.powers_of_g1.len(),
+            });
+        }
+        self.check_n_points(max_pts)?;
+        self.powers_of_g1.truncate(n_g1_powers);
+        self.powers_of_g1.shrink_to_fit();
+        self.powers_of_g2.truncate(n_g2_powers);
+        self.powers_of_g2.shrink_to_fit();
+        self.lagrange_bases
+            .retain(|b| b.domain().size as usize <= n_g1_powers);
+        self.g1_precomp = M::prepare_g1(self.powers_of_g1.clone());
+        self.g2_precomp = M::prepare_g2(self.powers_of_g2.clone());
+        self.rebuild_fixed_base();
+        Ok(())
+    }
+
+    /// Extend the setup with the powers in `other` past the end of this one's, along with any
+    /// lagrange bases this one doesn't have. `other` must be a setup for the same secret, so the
+    /// powers the two have in common must match. This combines a setup which is loaded in pieces,
+    /// each holding more of the powers than the last.
+    pub fn extend_from(&mut self, other: &Self) -> Result<(), Error> {
+        let n_g1 = core::cmp::min(self.powers_of_g1.len(), other.powers_of_g1.len());
+        if self.powers_of_g1[..n_g1] != other.powers_of_g1[..n_g1] {
+            return Err(Error::InconsistentSrs { group: 1 });
+        }
+        let n_g2 = core::cmp::min(self.powers_of_g2.len(), other.powers_of_g2.len());
+        if self.powers_of_g2[..n_g2] != other.powers_of_g2[..n_g2] {
+            return Err(Error::InconsistentSrs { group: 2 });
+        }
+        self.powers_of_g1
+            .extend_from_slice(&other.powers_of_g1[n_g1..]);
+        self.powers_of_g2
+            .extend_from_slice(&other.powers_of_g2[n_g2..]);
+        for basis in &other.lagrange_bases {
+            if find_basis(&self.lagrange_bases, basis.domain()).is_none() {
+                self.lagrange_bases.push(basis.clone());
+            }
+        }
+        self.g1_precomp = M::prepare_g1(self.powers_of_g1.clone());
+        self.g2_precomp = M::prepare_g2(self.powers_of_g2.clone());
+        self.rebuild_fixed_base();
+        Ok(())
+    }
+
+    /// Checks that the powers of tau are a valid setup: every power is a nonzero point in the prime
+    /// order subgroup, and both lists are successive powers of the same secret. The consistency
+    /// checks are batched with random linear combinations from `rng`, so they cost two pairing
+    /// checks no matter the size of the setup.
+    pub fn validate(&self, rng: &mut impl RngCore) -> Result<(), Error> {
+        for (index, p) in self.powers_of_g1.iter().enumerate() {
+            if p.is_zero() || p.check().is_err() {
+                return Err(Error::InvalidSrsPoint { group: 1, index });
+            }
+        }
+        for (index, p) in self.powers_of_g2.iter().enumerate() {
+            if p.is_zero() || p.check().is_err() {
+                return Err(Error::InvalidSrsPoint { group: 2, index });
+            }
+        }
+        if self.powers_of_g1.len() < 2 || self.powers_of_g2.len() < 2 {
+            return Err(Error::InvalidInputLength {
+                expected: 2,
+                got: core::cmp::min(self.powers_of_g1.len(), self.powers_of_g2.len()),
+            });
+        }
+
+        // e(sum r_i [x^i]_1, [1]_2) == e(sum r_i [x^(i-1)]_1, [x]_2)
+        let rs = (1..self.powers_of_g1.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let hi = crate::curve_msm::<E::G1>(&self.powers_of_g1[1..], &rs)?;
+        let lo = crate::curve_msm::<E::G1>(&self.powers_of_g1, &rs)?;
+        if !M::pairing_eq_check(
+            hi.into_affine(),
+            self.powers_of_g2[0],
+            lo.into_affine(),
+            self.powers_of_g2[1],
+        ) {
+            return Err(Error::InconsistentSrs { group: 1 });
+        }
+
+        // e([1]_1, sum r_i [x^i]_2) == e([x]_1, sum r_i [x^(i-1)]_2)
+        let rs = (1..self.powers_of_g2.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let hi = crate::curve_msm::<E::G2>(&self.powers_of_g2[1..], &rs)?;
+        let lo = crate::curve_msm::<E::G2>(&self.powers_of_g2, &rs)?;
+        if !M::pairing_eq_check(
+            self.powers_of_g1[0],
+            hi.into_affine(),
+            self.powers_of_g1[1],
+            lo.into_affine(),
+        ) {
+            return Err(Error::InconsistentSrs { group: 2 });
+        }
+        Ok(())
+    }
+
+    /// Make a committer which accepts the coefficients of a polynomial in chunks
+    pub fn streaming_committer(&self) -> StreamingCommitter<'_, E, M> {
+        StreamingCommitter::new(&self.powers_of_g1)
+    }
+
+    /// Open the polynomials at a single point, where `evals` has the evaluation of each
+    /// polynomial. This gives the same proof as [`PolyMultiProofNoPrecomp::open`] with one point,
+    /// but divides by $x - z$ with synthetic division instead of a general polynomial division.
+    pub fn open_single(
         &self,
-        transcript: &mut Transcript,
-        evals: &[impl AsRef<[E::ScalarField]>],
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[E::ScalarField],
         polys: &[impl AsRef<[E::ScalarField]>],
-        points: &[E::ScalarField],
-        vp: &DensePolynomial<E::ScalarField>,
+        point: E::ScalarField,
     ) -> Result<Proof<E>, Error> {
-        // Check sizes
-        check_opening_sizes(evals, polys, points.len())?;
-        // Commit the evals and the points to the transcript
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
-
-        // Read the challenge
-        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
-        // Make the gamma powers
-        let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
-        // Take a linear combo of gammas with the polynomials
+        self.check_n_points(1)?;
+        let eval_rows = evals.iter().map(|e| [*e]).collect::<Vec<_>>();
+        check_opening_sizes(&eval_rows, polys, 1)?;
+        let commits = self.transcript_commits(polys)?;
+        transcribe_points_and_evals(
+            transcript,
+            &commits,
+            &[point],
+            &eval_rows,
+            &self.transcript_encoding,
+        )?;
+
+        let gamma = self
+            .transcript_encoding
+            .challenge::<E::ScalarField>(transcript, b"open gamma");
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
         let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
             .ok_or(Error::NoPolynomialsGiven)?;
+        let q = div_by_linear(&fsum, point);
+        Ok(Proof(self.g1_msm(&q)?.into_affine()))
+    }
+
+    /// Verify a proof made with [`M1NoPrecomp::open_single`]. This needs no interpolation, and
+    /// checks a single pairing equation against $[x - z]_2$.
+    pub fn verify_single(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point: E::ScalarField,
+        evals: &[E::ScalarField],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.verifier_view()?
+            .verify_single(transcript, commits, point, evals, proof)
+    }
 
-        // Polynomial divide, the remained would contain the gamma * ri_s,
-        // The result is the correct quotient
-        let (q, _) = poly_div_q_r(
-            DensePolynomial::from_coefficients_vec(fsum).into(),
-            vp.into(),
+    /// Open the polynomials at `points` as in [`PolyMultiProofNoPrecomp::open`], and also prove
+    /// that every polynomial has fewer than `degree_bound` coefficients.
+    /// The setup needs the power $[x^{N-d}]_2$ in G2, where $N$ is the number of powers in G1, so
+    /// the supported bounds are from $N$ down to $N$ minus the maximum number of points.
+    pub fn open_with_degree_bound(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        degree_bound: usize,
+    ) -> Result<(Proof<E>, DegreeBoundProof<E>), Error> {
+        let shift = degree_bound_shift(
+            self.powers_of_g1.len(),
+            self.powers_of_g2.len(),
+            degree_bound,
         )?;
-        // Open to the resulting polynomial
-        Ok(Proof(
-            M::multi_scalar_mul_g1(&self.g1_precomp, &q)?.into_affine(),
-        ))
+        if let Some(p) = polys.iter().find(|p| p.as_ref().len() > degree_bound) {
+            return Err(Error::TooManyScalars {
+                n_coeffs: p.as_ref().len(),
+                expected_max: degree_bound,
+            });
+        }
+        let proof = self.open(transcript, evals, polys, points)?;
+
+        let gamma = degree_bound_challenge(transcript, degree_bound, &self.transcript_encoding);
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
+            .ok_or(Error::NoPolynomialsGiven)?;
+        let mut shifted = vec![E::ScalarField::zero(); shift];
+        shifted.extend(fsum);
+        let bound_proof = self.g1_msm(&shifted)?.into_affine();
+        Ok((proof, DegreeBoundProof(bound_proof)))
     }
 
-    fn verify_with_lag_ctx_g2_zeros(
+    /// Verify a proof made with [`M1NoPrecomp::open_with_degree_bound`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_degree_bound(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
-        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
-        g2_zeros: &E::G2,
+        bound_proof: &DegreeBoundProof<E>,
+        degree_bound: usize,
     ) -> Result<bool, Error> {
-        check_verify_sizes(commits, evals, points.len())?;
-
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        // Aggregate the r_is and then do a single msm of just the ri's and gammas
-        let gammas = gen_powers(gamma, evals.len());
-
-        // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
-        // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
-        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.g1_precomp, gamma_ris)?;
-
-        // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let cms_prep = M::prepare_g1(cms.clone());
-        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
-
-        let g2 = self.powers_of_g2[0];
+        self.verifier_view()?.verify_with_degree_bound(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            bound_proof,
+            degree_bound,
+        )
+    }
 
-        Ok(M::pairing_eq_check(
-            (gamma_cm_pt - gamma_ris_pt).into(),
-            g2,
-            proof.0,
-            g2_zeros.into_affine(),
-        ))
+    /// Commit to the polynomials interpolating each row of `evals` over `points`. These are the
+    /// inputs to [`M1NoPrecomp::open_with_interp_commits`] and
+    /// [`M1NoPrecomp::verify_with_interp_commits`].
+    pub fn commit_interpolations(
+        &self,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let one = [E::ScalarField::one()];
+        evals
+            .iter()
+            .map(|e| {
+                let r = lag_ctx.lagrange_interp_linear_combo(&[e], &one)?;
+                self.commit(r.coeffs)
+            })
+            .collect()
     }
-}
 
-impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1NoPrecomp<E, M> {
-    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = M::multi_scalar_mul_g1(&self.g1_precomp, poly.as_ref())?;
-        Ok(Commitment(res.into_affine()))
+    /// Open the polynomials at `points`, binding the transcript to commitments to the
+    /// polynomials interpolating their evaluations rather than the evaluations themselves.
+    /// The interpolation commitments can be made with [`M1NoPrecomp::commit_interpolations`].
+    pub fn open_with_interp_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        interp_commits: &[Commitment<E>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        if interp_commits.len() != polys.len() {
+            return Err(Error::EvalsAndPolysDifferentSizes {
+                n_eval_rows: interp_commits.len(),
+                n_polys: polys.len(),
+            });
+        }
+        self.check_n_points(points.len())?;
+        let gamma = interp_commits_challenge(
+            transcript,
+            &self.transcript_commits(polys)?,
+            points,
+            interp_commits,
+            &self.transcript_encoding,
+        )?;
+        self.open_with_gamma(polys, gamma, &vanishing_polynomial(points))
     }
-}
 
-impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProofNoPrecomp<E> for M1NoPrecomp<E, M> {
-    type Proof = Proof<E>;
+    /// Verify a proof made with [`M1NoPrecomp::open_with_interp_commits`]. A verifier which
+    /// already has the interpolation commitments skips the lagrange interpolation and the msm
+    /// against the powers of tau that [`PolyMultiProofNoPrecomp::verify`] does.
+    pub fn verify_with_interp_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        interp_commits: &[Commitment<E>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.verifier_view()?.verify_with_interp_commits(
+            transcript,
+            commits,
+            points,
+            interp_commits,
+            proof,
+        )
+    }
 
-    fn open(
+    /// Open the polynomials at `points` with a challenge `gamma` drawn by the caller, e.g. with
+    /// [`squeeze_gamma`](crate::transcript::squeeze_gamma) after absorbing the evals and points
+    /// alongside the caller's own messages, or by an interactive verifier or a randomness beacon.
+    /// No transcript is used.
+    ///
+    /// # Soundness
+    /// The proof only shows the polynomials open to the evals if `gamma` was unpredictable to the
+    /// prover until the commitments, points and evals were fixed. A prover which knows `gamma`
+    /// beforehand can pick evals which combine to the right value under it and forge a proof. So
+    /// `gamma` must be
+    /// * drawn after the prover has published, or bound in a transcript, the commitments, points
+    ///   and evals: a beacon round after they're posted, or a hash of them
+    /// * close to uniform over the field, e.g. reduced from at least 64 random bytes, rather than
+    ///   a small or biased value such as a block number
+    /// * used for this opening only, and never chosen by the prover
+    ///
+    /// [`M1NoPrecomp::verify_with_challenge`] takes `gamma` on trust, so the verifier must check
+    /// where it came from itself. Method 2 draws a second challenge after the prover's first
+    /// message, so it has no equivalent.
+    pub fn open_with_challenge(
         &self,
-        transcript: &mut Transcript,
-        evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
+        gamma: E::ScalarField,
     ) -> Result<Proof<E>, Error> {
-        let vp = vanishing_polynomial(points.as_ref());
-        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+        if points.is_empty() {
+            return Err(Error::NoPointsGiven);
+        }
+        self.check_n_points(points.len())?;
+        self.open_with_gamma(polys, gamma, &vanishing_polynomial(points))
     }
 
-    fn verify(
+    /// Verify a proof made with [`M1NoPrecomp::open_with_challenge`] against the same `gamma`,
+    /// which must meet the soundness requirements listed there
+    pub fn verify_with_challenge(
         &self,
-        transcript: &mut Transcript,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
+        gamma: E::ScalarField,
     ) -> Result<bool, Error> {
+        self.verifier_view()?
+            .verify_with_challenge(commits, points, evals, proof, gamma)
+    }
+
+    /// Open the polynomials at `points` as in [`PolyMultiProofNoPrecomp::open`], absorbing the
+    /// caller's `commits` to them with [`TranscriptVersion::V1`] rather than committing to every
+    /// polynomial again. `commits` aren't checked against `polys`, so they must be the prover's
+    /// own commitments, or the proof won't verify. With [`TranscriptVersion::Legacy`] they're
+    /// only checked for size, and the proof is the same as from
+    /// [`PolyMultiProofNoPrecomp::open`].
+    ///
+    /// [`TranscriptVersion::V1`]: crate::transcript::TranscriptVersion::V1
+    /// [`TranscriptVersion::Legacy`]: crate::transcript::TranscriptVersion::Legacy
+    pub fn open_with_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
         let vp = vanishing_polynomial(points);
-        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
-        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_g2_zeros(
-            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
-        )
+        self.open_absorbing(transcript, commits, evals, polys, points, &vp)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::M1NoPrecomp;
-    use crate::{
-        msm::{blst::BlstMSMEngine, ArkMSMEngine},
-        test_rng,
-        testing::{test_basic_no_precomp, test_size_errors},
-    };
-    use ark_bls12_381::Bls12_381;
+    pub(crate) fn open_with_vanishing_poly(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        self.check_n_points(points.len())?;
+        let commits = self.transcript_commits(polys)?;
+        self.open_absorbing(transcript, &commits, evals, polys, points, vp)
+    }
 
-    #[test]
-    fn test_basic_open_works() {
-        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
-        test_basic_no_precomp(&s);
-        test_size_errors(&s);
+    /// Opens the polynomials at `points`, absorbing `commits` if the transcript version absorbs
+    /// commitments
+    pub(crate) fn open_absorbing(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        self.check_n_points(points.len())?;
+        let timer = start_timer!(|| {
+            ark_std::format!("open {} polys at {} points", polys.len(), points.len())
+        });
+        let gamma = opening_challenge(
+            transcript,
+            commits,
+            evals,
+            polys,
+            points,
+            &self.transcript_encoding,
+        )?;
+        let res = self.open_with_gamma(polys, gamma, vp);
+        end_timer!(timer);
+        res
+    }
+
+    /// The commitments to `polys` absorbed when opening, which are only made if the transcript
+    /// version absorbs them. This is an msm per polynomial, which the `_with_commits` openings
+    /// avoid by taking the caller's commitments.
+    pub(crate) fn transcript_commits(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        self.transcript_encoding
+            .prover_commits(polys.len(), |i| self.commit(&polys[i]))
+    }
+
+    /// Checks there are enough powers of tau in G2 to verify at `n_points` points. Openings check
+    /// this up front too, since their proofs couldn't be verified.
+    pub(crate) fn check_n_points(&self, n_points: usize) -> Result<(), Error> {
+        let max_points = self.powers_of_g2.len().saturating_sub(1);
+        if n_points > max_points {
+            return Err(Error::TooManyPoints {
+                n_points,
+                max_points,
+            });
+        }
+        Ok(())
+    }
+
+    /// Opens the single polynomial `poly` at `points`, returning its evaluations along with the
+    /// proof. The remainder of the one division by the vanishing polynomial interpolates the
+    /// evaluations, so they come from it rather than from evaluating `poly`, and with one
+    /// polynomial the challenge doesn't change the quotient.
+    pub(crate) fn open_single_with_vanishing_poly(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        poly: &[E::ScalarField],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<(Vec<E::ScalarField>, Proof<E>), Error> {
+        self.check_n_points(points.len())?;
+        check_poly_sizes(&[poly], self.powers_of_g1.len())?;
+        let mut remainder = poly.to_vec();
+        let q = poly_div_q_r_in_place(&mut remainder, &vp.coeffs)?;
+        let evals = evaluate_at_points(&remainder, points);
+        // The challenge is still drawn so the transcript matches the verifier's
+        opening_challenge(
+            transcript,
+            &self.transcript_commits(&[poly])?,
+            &[&evals],
+            &[poly],
+            points,
+            &self.transcript_encoding,
+        )?;
+        let proof = self.install(|| self.g1_msm(&q))?;
+        Ok((evals, Proof(proof.into_affine())))
+    }
+
+    /// Computes the proof once the challenge `gamma` has been drawn from the transcript
+    pub(crate) fn open_with_gamma(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        gamma: E::ScalarField,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        // Make the gamma powers
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let q = compute_quotient(polys, &gammas, vp)?;
+        // Open to the resulting polynomial
+        let proof = self.install(|| self.g1_msm(&q.coeffs))?;
+        Ok(Proof(proof.into_affine()))
+    }
+
+    /// Opens each set of polynomials at the points with vanishing polynomial `vp`, with one
+    /// transcript per set. When there are fewer distinct polynomials than sets, each distinct
+    /// polynomial's quotient is committed to once and the proofs are combined from those
+    /// commitments, rather than doing a full size msm per set.
+    pub(crate) fn open_many_with_vanishing_poly<P: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        evals_sets: &[&[impl AsRef<[E::ScalarField]>]],
+        polys_sets: &[&[P]],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_open_all_sizes(transcripts, evals_sets, polys_sets.len())?;
+        self.check_n_points(points.len())?;
+        // The index of each polynomial in the list of distinct polynomials. The same polynomial
+        // is passed in each set it's opened in, so they're told apart by address.
+        let mut distinct: Vec<&[E::ScalarField]> = Vec::new();
+        let mut seen = BTreeMap::new();
+        let indices = polys_sets
+            .iter()
+            .map(|polys| {
+                check_poly_sizes(polys, self.powers_of_g1.len())?;
+                Ok(polys
+                    .iter()
+                    .map(|p| {
+                        let p = p.as_ref();
+                        *seen.entry((p.as_ptr(), p.len())).or_insert_with(|| {
+                            distinct.push(p);
+                            distinct.len() - 1
+                        })
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        // Each distinct polynomial is committed to once, for every transcript that absorbs it
+        let commits = self
+            .transcript_encoding
+            .prover_commits(distinct.len(), |i| self.commit(distinct[i]))?;
+        let gammas = transcripts
+            .iter_mut()
+            .zip(evals_sets)
+            .zip(polys_sets)
+            .zip(&indices)
+            .map(|(((transcript, evals), polys), idxs)| {
+                let set_commits = idxs
+                    .iter()
+                    .filter_map(|&i| commits.get(i).cloned())
+                    .collect::<Vec<_>>();
+                opening_challenge(
+                    transcript,
+                    &set_commits,
+                    evals,
+                    polys,
+                    points,
+                    &self.transcript_encoding,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if distinct.len() >= polys_sets.len() {
+            return polys_sets
+                .iter()
+                .zip(gammas)
+                .map(|(polys, gamma)| self.open_with_gamma(polys, gamma, vp))
+                .collect();
+        }
+        // The quotient is linear in the polynomial, so each proof is the combination of the
+        // commitments to the quotients of its polynomials
+        let one = [E::ScalarField::one()];
+        let witnesses = distinct
+            .iter()
+            .map(|p| {
+                let q = compute_quotient(&[p], &one, vp)?;
+                self.install(|| self.g1_msm(&q.coeffs))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let witnesses = E::G1::normalize_batch(&witnesses);
+        indices
+            .iter()
+            .zip(gammas)
+            .map(|(idxs, gamma)| {
+                let bases = idxs.iter().map(|&i| witnesses[i]).collect::<Vec<_>>();
+                let gammas = gen_powers(gamma, idxs.len());
+                Ok(Proof(
+                    crate::curve_msm::<E::G1>(&bases, &gammas)?.into_affine(),
+                ))
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_with_lag_ctx_g2_zeros(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        g2_zeros: &E::G2,
+    ) -> Result<bool, Error> {
+        let lhs = self.verifier_lhs_with_lag_ctx(transcript, commits, points, evals, lag_ctx)?;
+        let g2 = self.powers_of_g2[0];
+
+        Ok(M::pairing_eq_check(
+            lhs.into(),
+            g2,
+            proof.0,
+            g2_zeros.into_affine(),
+        ))
+    }
+
+    /// Verifies an opening at `points` which have the vanishing polynomial `vp`, for when the
+    /// points aren't one of the precomputed point sets
+    pub(crate) fn verify_with_vanishing_poly(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        self.check_n_points(points.len())?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let g2_zeros = crate::curve_msm::<E::G2>(&self.powers_of_g2, vp)?;
+        self.verify_with_lag_ctx_g2_zeros(
+            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
+        )
+    }
+
+    /// Computes the G1 element which is paired with the G2 generator on the left hand side of the
+    /// verification equation
+    pub(crate) fn verifier_lhs_with_lag_ctx(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+    ) -> Result<E::G1, Error> {
+        let gamma = self.verifier_gamma(transcript, commits, points, evals)?;
+        self.verifier_lhs_with_gamma(commits, evals, lag_ctx, gamma)
+    }
+
+    /// Checks the sizes of an opening to verify, commits its points and evals to the transcript
+    /// and draws the challenge used to combine the polynomials
+    pub(crate) fn verifier_gamma(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<E::ScalarField, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            points,
+            evals,
+            &self.transcript_encoding,
+        )?;
+        Ok(self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma"))
+    }
+
+    /// Computes the same as [`M1NoPrecomp::verifier_lhs_with_lag_ctx`] from the commitments
+    /// $[L_j(x)]_1$ to the lagrange polynomials of the points, so the interpolation of the
+    /// evaluations is committed to with an msm of the combined evaluations against them
+    pub(crate) fn verifier_lhs_with_lagrange_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lagrange_commits: &M::G1Prepared,
+    ) -> Result<E::G1, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            points,
+            evals,
+            &self.transcript_encoding,
+        )?;
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+        self.verifier_lhs_with_lagrange_commits_gamma(
+            commits,
+            points.len(),
+            evals,
+            lagrange_commits,
+            gamma,
+        )
+    }
+
+    /// Computes the same as [`M1NoPrecomp::verifier_lhs_with_lagrange_commits`] once the
+    /// challenge `gamma` has been drawn, the sizes must already have been checked
+    pub(crate) fn verifier_lhs_with_lagrange_commits_gamma(
+        &self,
+        commits: &[Commitment<E>],
+        n_points: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lagrange_commits: &M::G1Prepared,
+        gamma: E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        let gammas = gen_powers(gamma, evals.len());
+        let targets = linear_combination_of_evals(evals, &gammas, n_points);
+        let gamma_ris_pt = M::multi_scalar_mul_g1(lagrange_commits, targets)?;
+
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
+
+        Ok(gamma_cm_pt - gamma_ris_pt)
+    }
+
+    /// Computes the left hand side of the verification equation once the challenge `gamma` has
+    /// been drawn, the sizes must already have been checked
+    fn verifier_lhs_with_gamma(
+        &self,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        gamma: E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        // Aggregate the r_is and then do a single msm of just the ri's and gammas
+        let gammas = gen_powers(gamma, evals.len());
+
+        // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
+        // and the gamma mul in one step so we can just lagrange interp once.
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+        self.verifier_lhs_with_interp(commits, gammas, gamma_ris)
+    }
+
+    /// Computes the left hand side of the verification equation from the powers of the
+    /// challenge and the combination of the interpolations of the evals
+    pub(crate) fn verifier_lhs_with_interp(
+        &self,
+        commits: &[Commitment<E>],
+        gammas: Vec<E::ScalarField>,
+        gamma_ris: Vec<E::ScalarField>,
+    ) -> Result<E::G1, Error> {
+        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.g1_precomp, gamma_ris)?;
+        Ok(Self::aggregate_commits(commits, gammas)? - gamma_ris_pt)
+    }
+
+    /// Computes $\sum_i \gamma^i C_i$ with a single msm of the gammas and commitments
+    fn aggregate_commits(
+        commits: &[Commitment<E>],
+        gammas: Vec<E::ScalarField>,
+    ) -> Result<E::G1, Error> {
+        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
+        let cms_prep = M::prepare_g1(cms);
+        M::multi_scalar_mul_g1(&cms_prep, gammas)
+    }
+
+    /// Verify like [`PolyMultiProofNoPrecomp::verify`], but explain a failure rather than just
+    /// returning `false`. Errors from mismatched sizes become [`VerifyDiagnosis::ShapeMismatch`],
+    /// other errors are still returned.
+    ///
+    /// A failing transcript can only be told apart from failing evals or proofs given
+    /// `prover_gamma`, the challenge the prover drew. A prover can get it from the `gamma` of
+    /// [`M1NoPrecomp::verify_to_circuit_inputs`] run on its own transcript.
+    pub fn verify_explain(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        prover_gamma: Option<E::ScalarField>,
+    ) -> Result<VerifyDiagnosis<E>, Error> {
+        let inputs = self.verify_to_circuit_inputs(transcript, commits, points, evals, proof);
+        VerifyDiagnosis::diagnose::<M>(inputs, prover_gamma)
+    }
+
+    /// Does everything [`PolyMultiProofNoPrecomp::verify`] does up to the final pairing check,
+    /// returning the values it's done on, e.g. to check them inside a SNARK. The transcript is
+    /// left in the same state as after verifying.
+    pub fn verify_to_circuit_inputs(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<VerifierCircuitInputs<E>, Error> {
+        self.check_n_points(points.len())?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let gamma = self.verifier_gamma(transcript, commits, points, evals)?;
+        let gammas = gen_powers(gamma, evals.len());
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+        let interpolation_commit = M::multi_scalar_mul_g1(&self.g1_precomp, gamma_ris)?;
+        let vp = vanishing_polynomial(points);
+        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
+        Ok(VerifierCircuitInputs {
+            gamma,
+            aggregated_commit: Self::aggregate_commits(commits, gammas)?.into_affine(),
+            interpolation_commit: interpolation_commit.into_affine(),
+            g2: self.powers_of_g2[0],
+            g2_zeros: g2_zeros.into_affine(),
+            proof: proof.0,
+        })
+    }
+
+    /// Checks that $e(l_k, g_2) = e(\pi_k, z_k)$ for every `(l_k, pi_k, z_k)` in `terms` by
+    /// taking a random linear combination and doing a single multi-pairing
+    pub(crate) fn batch_pairing_check(
+        &self,
+        terms: &[(E::G1, E::G1Affine, E::G2)],
+        rng: &mut impl RngCore,
+    ) -> bool {
+        let mut lhs = E::G1::zero();
+        let mut g1s = Vec::with_capacity(terms.len() + 1);
+        let mut g2s = Vec::with_capacity(terms.len() + 1);
+        for (l, proof, zeros) in terms {
+            let r = E::ScalarField::rand(rng);
+            lhs += *l * r;
+            g1s.push(-(*proof * r));
+            g2s.push(*zeros);
+        }
+        g1s.push(lhs);
+        g2s.push(self.powers_of_g2[0].into());
+        self.install(|| {
+            M::multi_pairing_is_one(&E::G1::normalize_batch(&g1s), &E::G2::normalize_batch(&g2s))
+        })
+    }
+}
+
+/// Checks the sizes of an opening, commits the points and evals (and the commitments, from
+/// [`TranscriptEncoding::prover_commits`]) to the transcript with `encoding` and draws the
+/// challenge used to combine the polynomials
+pub(crate) fn opening_challenge<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    commits: &[Commitment<E>],
+    evals: &[impl AsRef<[E::ScalarField]>],
+    polys: &[impl AsRef<[E::ScalarField]>],
+    points: &[E::ScalarField],
+    encoding: &TranscriptEncoding,
+) -> Result<E::ScalarField, Error> {
+    // Check sizes
+    check_opening_sizes(evals, polys, points.len())?;
+    // Commit the evals and the points to the transcript
+    transcribe_points_and_evals(transcript, commits, points, evals, encoding)?;
+    // Read the challenge
+    Ok(encoding.challenge(transcript, b"open gamma"))
+}
+
+/// Derives a scalar from 64 challenge bytes, so it's close to uniform after the reduction
+fn seeded_scalar<F: PrimeField>(transcript: &mut merlin::Transcript, label: &'static [u8]) -> F {
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(label, &mut bytes);
+    F::from_le_bytes_mod_order(&bytes)
+}
+
+/// The power of tau $N - d$ the polynomials are shifted by for a degree bound $d$, with $N$ powers
+/// in G1 and `n_g2` powers in G2
+fn degree_bound_shift(n: usize, n_g2: usize, degree_bound: usize) -> Result<usize, Error> {
+    check_setup_size(2, 1, n_g2)?;
+    let min = n.saturating_sub(n_g2.saturating_sub(1)).max(1);
+    if degree_bound < min || degree_bound > n {
+        return Err(Error::DegreeBoundOutOfRange {
+            bound: degree_bound,
+            min,
+            max: n,
+        });
+    }
+    Ok(n - degree_bound)
+}
+
+/// Transcribes the degree bound and reads the challenge the polynomials are combined with, drawn
+/// with `encoding`
+fn degree_bound_challenge<F: PrimeField>(
+    transcript: &mut impl TranscriptProtocol,
+    degree_bound: usize,
+    encoding: &TranscriptEncoding,
+) -> F {
+    transcript.append_message(b"degree bound", &(degree_bound as u64).to_be_bytes());
+    encoding.challenge(transcript, b"degree bound gamma")
+}
+
+/// Transcribes the commitments (if `encoding`'s version absorbs them), the points and the
+/// interpolation commitments with `encoding`, and reads the opening challenge
+fn interp_commits_challenge<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    commits: &[Commitment<E>],
+    points: &[E::ScalarField],
+    interp_commits: &[Commitment<E>],
+    encoding: &TranscriptEncoding,
+) -> Result<E::ScalarField, Error> {
+    if points.is_empty() {
+        return Err(Error::NoPointsGiven);
+    }
+    encoding.absorb_commits(transcript, commits)?;
+    encoding.absorb_points(transcript, points)?;
+    encoding.append_commits(transcript, b"interp commits", interp_commits)?;
+    Ok(encoding.challenge(transcript, b"open gamma"))
+}
+
+/// Checks that there is a transcript and a set of evals for each of the `n_point_sets` point sets
+pub(crate) fn check_open_all_sizes<T>(
+    transcripts: &[impl TranscriptProtocol],
+    evals: &[T],
+    n_point_sets: usize,
+) -> Result<(), Error> {
+    if transcripts.len() != n_point_sets {
+        return Err(Error::InvalidInputLength {
+            expected: n_point_sets,
+            got: transcripts.len(),
+        });
+    }
+    if evals.len() != n_point_sets {
+        return Err(Error::InvalidInputLength {
+            expected: n_point_sets,
+            got: evals.len(),
+        });
+    }
+    Ok(())
+}
+
+/// The points of several point sets and their vanishing polynomial
+pub(crate) type PointSetUnion<F> = (Vec<F>, DensePolynomial<F>);
+
+/// Checks there are evaluations for each point set, then checks each set's evaluations against
+/// its number of points with `check`
+pub(crate) fn check_batched_sets_sizes<F, T: AsRef<[F]>>(
+    evals_per_set: &[impl AsRef<[T]>],
+    points_per_set: &[&[F]],
+    check: impl Fn(&[T], usize) -> Result<(), Error>,
+) -> Result<(), Error> {
+    if evals_per_set.len() != points_per_set.len() {
+        return Err(Error::InvalidInputLength {
+            expected: points_per_set.len(),
+            got: evals_per_set.len(),
+        });
+    }
+    evals_per_set
+        .iter()
+        .zip(points_per_set)
+        .try_for_each(|(evals, points)| check(evals.as_ref(), points.len()))
+}
+
+/// Draws the challenge for each point set of a batched opening from one transcript. The number
+/// of sets and the commitments are absorbed once, then each set's evaluations and points are
+/// absorbed before its challenge is drawn. With `union` one more challenge is drawn for the
+/// opening at the union of the sets, after every set has been absorbed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn batched_sets_challenges<E: Pairing, T: AsRef<[E::ScalarField]>>(
+    transcript: &mut impl TranscriptProtocol,
+    commits: &[Commitment<E>],
+    evals_per_set: &[impl AsRef<[T]>],
+    points_per_set: &[&[E::ScalarField]],
+    union: bool,
+    encoding: &TranscriptEncoding,
+) -> Result<(Vec<E::ScalarField>, Option<E::ScalarField>), Error> {
+    transcript.append_message(
+        b"pmp batched sets",
+        &(points_per_set.len() as u64).to_le_bytes(),
+    );
+    encoding.absorb_commits(transcript, commits)?;
+    let gammas = evals_per_set
+        .iter()
+        .zip(points_per_set)
+        .map(|(evals, points)| {
+            crate::transcribe_evals(transcript, evals.as_ref(), points.len(), encoding)?;
+            crate::transcribe_points(transcript, points, encoding)?;
+            Ok(encoding.challenge(transcript, b"open gamma"))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let union_gamma = union.then(|| encoding.challenge(transcript, b"union gamma"));
+    Ok((gammas, union_gamma))
+}
+
+/// The evaluations of each polynomial over every point set, concatenated in the order of the
+/// sets, which are its evaluations over the union of the sets
+pub(crate) fn concat_set_evals<F: Clone, T: AsRef<[F]>>(
+    evals_per_set: &[impl AsRef<[T]>],
+    n_rows: usize,
+) -> Vec<Vec<F>> {
+    (0..n_rows)
+        .map(|row| {
+            evals_per_set
+                .iter()
+                .flat_map(|evals| evals.as_ref()[row].as_ref().iter().cloned())
+                .collect()
+        })
+        .collect()
+}
+
+/// Concatenates the point sets at `indices` and multiplies their vanishing polynomials, so that
+/// they can be opened to with a single proof. `point_set` gets the points and vanishing
+/// polynomial of the point set at an index. Sets which share a point are rejected, since the
+/// point would be a double root of the vanishing polynomial.
+pub(crate) fn union_of_point_sets<F: FftField>(
+    indices: &[usize],
+    n_point_sets: usize,
+    point_set: impl Fn(usize) -> (Vec<F>, DensePolynomial<F>),
+) -> Result<PointSetUnion<F>, Error> {
+    if indices.is_empty() {
+        return Err(Error::NoPointsGiven);
+    }
+    let mut points = Vec::new();
+    let mut owners = Vec::new();
+    let mut vp = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+    for (i, &idx) in indices.iter().enumerate() {
+        if idx >= n_point_sets {
+            return Err(Error::PointSetIndexOutOfRange { idx, n_point_sets });
+        }
+        if indices[..i].contains(&idx) {
+            return Err(Error::DuplicatePointSetIndex(idx));
+        }
+        let (set_points, set_vp) = point_set(idx);
+        owners.extend(core::iter::repeat_n(i, set_points.len()));
+        points.extend(set_points);
+        vp = &vp * &set_vp;
+    }
+    // Any run of equal points from more than one set has two neighbours from different sets
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&i| points[i]);
+    for w in order.windows(2) {
+        if points[w[0]] == points[w[1]] && owners[w[0]] != owners[w[1]] {
+            let later = core::cmp::max(owners[w[0]], owners[w[1]]);
+            return Err(Error::OverlappingPointSets(indices[later]));
+        }
+    }
+    Ok((points, vp))
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for M1NoPrecomp<E, M> {
+    fn max_coeffs(&self) -> usize {
+        M1NoPrecomp::max_coeffs(self)
+    }
+
+    fn max_pts(&self) -> usize {
+        M1NoPrecomp::max_pts(self)
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m1-no-precomp"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        g1_compressed_size::<E>()
+    }
+
+    /// The powers of tau are held both as affine points and prepared for the engine. Opening
+    /// holds the combined polynomial and its quotient, and verifying the lagrange polynomials of
+    /// the points. There's no precomputation.
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, _: usize) -> MemoryEstimate {
+        let n_g2 = max_pts + 1;
+        let n_g1 = core::cmp::max(max_coeffs, n_g2);
+        let (g1, g2) = (size_of::<E::G1Affine>(), size_of::<E::G2Affine>());
+        MemoryEstimate {
+            srs: 2 * (n_g1 * g1 + n_g2 * g2),
+            precompute: 0,
+            per_open: (2 * max_coeffs + max_pts * (max_pts + 3)) * size_of::<E::ScalarField>(),
+        }
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1NoPrecomp<E, M> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        let poly = poly.as_ref();
+        let timer = start_timer!(|| ark_std::format!("commit to {} coeffs", poly.len()));
+        let res = self.install(|| self.g1_msm(poly));
+        end_timer!(timer);
+        Ok(Commitment(res?.into_affine()))
+    }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        match find_basis(&self.lagrange_bases, domain) {
+            Some(basis) => basis.commit_evals(evals),
+            None => {
+                crate::lagrange_basis::check_evals_len(evals.as_ref(), domain)?;
+                let timer = start_timer!(|| ark_std::format!("ifft of {} evals", domain.size));
+                let coeffs = ark_poly::EvaluationDomain::ifft(domain, evals.as_ref());
+                end_timer!(timer);
+                self.commit(coeffs)
+            }
+        }
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = self.install(|| M::sparse_multi_scalar_mul_g1(&self.powers_of_g1, poly))?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let res = self.install(|| match &self.fixed_base {
+            Some(table) => table.msm_many(polys),
+            None => M::batch_multi_scalar_mul_g1(&self.g1_precomp, polys),
+        })?;
+        Ok(E::G1::normalize_batch(&res)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProofNoPrecomp<E> for M1NoPrecomp<E, M> {
+    type Proof = Proof<E>;
+
+    fn open(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        let vp = vanishing_polynomial(points);
+        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+    }
+
+    fn open_points(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        poly: impl AsRef<[E::ScalarField]>,
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<(Vec<E::ScalarField>, Proof<E>)>, Error> {
+        check_batch_sizes(transcripts, point_sets)?;
+        transcripts
+            .iter_mut()
+            .zip(point_sets)
+            .map(|(transcript, points)| {
+                let points = points.as_ref();
+                let vp = vanishing_polynomial(points);
+                self.open_single_with_vanishing_poly(transcript, poly.as_ref(), points, &vp)
+            })
+            .collect()
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.check_n_points(points.len())?;
+        let timer = start_timer!(|| {
+            ark_std::format!(
+                "verify {} commits at {} points",
+                commits.len(),
+                points.len()
+            )
+        });
+        let res = self
+            .verifier_view()?
+            .verify(transcript, commits, points, evals, proof);
+        end_timer!(timer);
+        res
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        let (g1s, g2s) = self
+            .verifier_view()?
+            .batch_pairing_terms(transcripts, items, rng)?;
+        Ok(self.install(|| M::multi_pairing_is_one(&g1s, &g2s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_quotient, degree_bound_challenge, degree_bound_shift, interp_commits_challenge,
+        linear_combination_of_proofs, precompute::M1Precomp, DegreeBoundProof, M1NoPrecomp, Proof,
+        VerifyDiagnosis,
+    };
+    use crate::{
+        gen_powers, linear_combination, linear_combination_of_commitments,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        testing::{
+            gen_openings, test_basic_no_precomp, test_batch_no_precomp,
+            test_custom_transcript_no_precomp, test_linear_combination_no_precomp,
+            test_size_errors,
+        },
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        transcript::TranscriptEncoding,
+        vanishing_polynomial, Commitment, Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_poly::{
+        univariate::{DensePolynomial, SparsePolynomial},
+        EvaluationDomain, Radix2EvaluationDomain,
+    };
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    type ArkM1 = M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>;
+
+    #[test]
+    fn test_basic_open_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        test_basic_no_precomp(&s);
+        test_size_errors(&s);
 
         let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
         test_basic_no_precomp(&s);
         test_size_errors(&s);
     }
+
+    #[test]
+    fn test_degree_bound() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 30, &mut test_rng());
+        let points = (0..3)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        // The polys have 54 coefficients
+        let (coeffs, evals, commits) = gen_openings(&s, 4, &points);
+
+        for bound in [54, 60, 64] {
+            let (proof, bound_proof) = s
+                .open_with_degree_bound(
+                    &mut Transcript::new(b"test"),
+                    &evals,
+                    &coeffs,
+                    &points,
+                    bound,
+                )
+                .unwrap();
+            assert_eq!(
+                Ok(true),
+                s.verify_with_degree_bound(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &proof,
+                    &bound_proof,
+                    bound
+                )
+            );
+        }
+
+        // A proof for a looser bound doesn't prove a tighter one
+        let (proof, bound_proof) = s
+            .open_with_degree_bound(&mut Transcript::new(b"test"), &evals, &coeffs, &points, 54)
+            .unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_with_degree_bound(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+                &bound_proof,
+                50
+            )
+        );
+        assert!(matches!(
+            s.open_with_degree_bound(&mut Transcript::new(b"test"), &evals, &coeffs, &points, 50),
+            Err(Error::TooManyScalars {
+                n_coeffs: 54,
+                expected_max: 50
+            })
+        ));
+        assert!(matches!(
+            s.open_with_degree_bound(&mut Transcript::new(b"test"), &evals, &coeffs, &points, 20),
+            Err(Error::DegreeBoundOutOfRange {
+                bound: 20,
+                min: 34,
+                max: 64
+            })
+        ));
+
+        // A prover claiming a bound its polynomials exceed can't commit to the whole shifted sum,
+        // so dropping the coefficients past the setup doesn't give a verifying proof
+        let mut transcript = Transcript::new(b"test");
+        let proof = s.open(&mut transcript, &evals, &coeffs, &points).unwrap();
+        let gamma = degree_bound_challenge(&mut transcript, 50, &TranscriptEncoding::default());
+        let fsum = linear_combination(&coeffs, &gen_powers(gamma, coeffs.len())).unwrap();
+        let mut shifted = vec![Fr::from(0u64); 64 - 50];
+        shifted.extend(fsum);
+        shifted.truncate(64);
+        let forged = DegreeBoundProof(s.commit(&shifted).unwrap().0);
+        assert_eq!(
+            Ok(false),
+            s.verify_with_degree_bound(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+                &forged,
+                50
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verifier_key().unwrap().verify_with_degree_bound(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+                &forged,
+                50
+            )
+        );
+
+        // The challenge is drawn with the setup's encoding
+        let be = s
+            .clone()
+            .with_transcript_encoding(TranscriptEncoding::big_endian());
+        let (proof, bound_proof) = be
+            .open_with_degree_bound(&mut Transcript::new(b"test"), &evals, &coeffs, &points, 54)
+            .unwrap();
+        for (verifier, expected) in [(&be, true), (&s, false)] {
+            assert_eq!(
+                Ok(expected),
+                verifier.verify_with_degree_bound(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &proof,
+                    &bound_proof,
+                    54
+                )
+            );
+        }
+
+        // A setup without powers in G2 has no supported bounds
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 2,
+                needed: 1,
+                got: 0
+            }),
+            degree_bound_shift(64, 0, 64)
+        );
+    }
+
+    #[test]
+    fn test_interp_commits() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 4, &points);
+        let interp_commits = s.commit_interpolations(&points, &evals).unwrap();
+
+        let proof = s
+            .open_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &interp_commits,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &interp_commits,
+                &proof
+            )
+        );
+
+        // Interpolating different evals gives a different commitment
+        let mut bad_evals = evals.clone();
+        bad_evals[1][3] += Fr::from(1u64);
+        let bad_interp = s.commit_interpolations(&points, &bad_evals).unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &bad_interp,
+                &proof
+            )
+        );
+        assert!(matches!(
+            s.verify_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &interp_commits[1..],
+                &proof
+            ),
+            Err(Error::EvalsAndCommitsDifferentSizes {
+                n_evals: 3,
+                n_commits: 4
+            })
+        ));
+
+        // The challenge is drawn with the setup's encoding, so the verifier's must match
+        let be = s
+            .clone()
+            .with_transcript_encoding(TranscriptEncoding::big_endian());
+        let proof = be
+            .open_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &interp_commits,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        for (verifier, expected) in [(&be, true), (&s, false)] {
+            assert_eq!(
+                Ok(expected),
+                verifier.verify_with_interp_commits(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    &points,
+                    &interp_commits,
+                    &proof
+                )
+            );
+            assert_eq!(
+                Ok(expected),
+                verifier.verifier_key().unwrap().verify_with_interp_commits(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    &points,
+                    &interp_commits,
+                    &proof
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_interp_commits_binds_commits() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 4, &points);
+        let interp_commits = s.commit_interpolations(&points, &evals).unwrap();
+
+        // Without the commitments in the transcript, gamma is known before they're fixed, so
+        // commits[1] can be replaced by an arbitrary point and commits[0] adjusted to cancel it
+        let gamma = interp_commits_challenge(
+            &mut Transcript::new(b"test"),
+            &commits,
+            &points,
+            &interp_commits,
+            &TranscriptEncoding::default(),
+        )
+        .unwrap();
+        let other = G1Affine::rand(&mut test_rng());
+        let mut forged = commits.clone();
+        forged[1] = Commitment(other);
+        forged[0] = Commitment((commits[0].0 + (commits[1].0 - other) * gamma).into_affine());
+        let proof = s
+            .open_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &interp_commits,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &forged,
+                &points,
+                &interp_commits,
+                &proof
+            )
+        );
+
+        // V1 absorbs the commitments first, which rules both the forgery and a swap out
+        let v1 = s.clone().with_transcript_encoding(TranscriptEncoding::v1());
+        let proof = v1
+            .open_with_interp_commits(
+                &mut Transcript::new(b"test"),
+                &interp_commits,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        let mut swapped = commits.clone();
+        swapped.swap(0, 1);
+        for (checked, expected) in [(&commits, true), (&forged, false), (&swapped, false)] {
+            assert_eq!(
+                Ok(expected),
+                v1.verify_with_interp_commits(
+                    &mut Transcript::new(b"test"),
+                    checked,
+                    &points,
+                    &interp_commits,
+                    &proof
+                )
+            );
+            assert_eq!(
+                Ok(expected),
+                v1.verifier_key().unwrap().verify_with_interp_commits(
+                    &mut Transcript::new(b"test"),
+                    checked,
+                    &points,
+                    &interp_commits,
+                    &proof
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_point() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        let point = Fr::rand(&mut test_rng());
+        let (coeffs, evals, commits) = gen_openings(&s, 4, &[point]);
+        let single_evals = evals.iter().map(|e| e[0]).collect::<Vec<_>>();
+
+        let proof = s
+            .open_single(&mut Transcript::new(b"test"), &single_evals, &coeffs, point)
+            .unwrap();
+        let multi_proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, &[point])
+            .unwrap();
+        assert_eq!(multi_proof.0, proof.0);
+        assert_eq!(
+            Ok(true),
+            s.verify_single(
+                &mut Transcript::new(b"test"),
+                &commits,
+                point,
+                &single_evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &[point],
+                &evals,
+                &proof
+            )
+        );
+
+        let mut bad_evals = single_evals.clone();
+        bad_evals[2] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_single(
+                &mut Transcript::new(b"test"),
+                &commits,
+                point,
+                &bad_evals,
+                &proof
+            )
+        );
+        assert!(s
+            .verify_single(
+                &mut Transcript::new(b"test"),
+                &commits[1..],
+                point,
+                &single_evals,
+                &proof
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_new_from_secret() {
+        use ark_bls12_381::{G1Projective, G2Projective};
+        use zeroize::Zeroizing;
+
+        let x = Fr::rand(&mut test_rng());
+        let g1 = G1Projective::rand(&mut test_rng());
+        let g2 = G2Projective::rand(&mut test_rng());
+        let expected = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_scalar(x, g1, g2, 32, 8);
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_secret(
+            &Zeroizing::new(x),
+            g1,
+            g2,
+            32,
+            8,
+        );
+        assert_eq!(expected.powers_of_g1, s.powers_of_g1);
+        assert_eq!(expected.powers_of_g2, s.powers_of_g2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 16, &mut test_rng());
+        assert_eq!(Ok(()), s.validate(&mut test_rng()));
+
+        let mut bad = s.clone();
+        bad.powers_of_g1[3] = bad.powers_of_g1[4];
+        assert_eq!(
+            Err(Error::InconsistentSrs { group: 1 }),
+            bad.validate(&mut test_rng())
+        );
+
+        let mut bad = s.clone();
+        bad.powers_of_g2[5] = (bad.powers_of_g2[5] + bad.powers_of_g2[0]).into_affine();
+        assert_eq!(
+            Err(Error::InconsistentSrs { group: 2 }),
+            bad.validate(&mut test_rng())
+        );
+
+        let mut bad = s.clone();
+        bad.powers_of_g2[2] = G2Affine::zero();
+        assert_eq!(
+            Err(Error::InvalidSrsPoint { group: 2, index: 2 }),
+            bad.validate(&mut test_rng())
+        );
+
+        // A point on the curve but outside of the prime order subgroup
+        let mut bad = s;
+        bad.powers_of_g1[7] = (1u64..)
+            .find_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), false))
+            .filter(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        assert_eq!(
+            Err(Error::InvalidSrsPoint { group: 1, index: 7 }),
+            bad.validate(&mut test_rng())
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        test_batch_no_precomp(&s);
+
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        test_batch_no_precomp(&s);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_with_parallelism() {
+        use crate::{
+            m1_cycl::M1CyclPrecomp, method1::precompute::M1Precomp, parallel::ParallelismConfig,
+        };
+
+        let config = ParallelismConfig::with_max_threads(2)
+            .unwrap()
+            .with_min_chunk_len(8);
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng())
+            .with_parallelism(config);
+        assert_eq!(8, s.parallelism().min_chunk_len());
+        assert_eq!(2, s.install(rayon::current_num_threads));
+        test_basic_no_precomp(&s);
+        test_batch_no_precomp(&s);
+
+        // Schemes built on the setup use its config too
+        let cycl = M1CyclPrecomp::from_inner(s.clone(), 64, 2).unwrap();
+        assert_eq!(2, cycl.inner.install(rayon::current_num_threads));
+        let subgroup = cycl.point_sets().subgroup(0).unwrap();
+        crate::testing::test_basic_precomp(&cycl, &crate::poly_ops::ev_points(&subgroup));
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let precomp = M1Precomp::from_inner(s, vec![points.clone()]).unwrap();
+        crate::testing::test_basic_precomp(&precomp, &points);
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        test_linear_combination_no_precomp(&s);
+    }
+
+    #[test]
+    fn test_commit_sparse() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 2, &mut test_rng());
+        let terms = [0, 5, 63]
+            .into_iter()
+            .map(|i| (i, Fr::rand(&mut test_rng())))
+            .collect::<Vec<_>>();
+        let sparse = SparsePolynomial::from_coefficients_vec(terms);
+        let dense = DensePolynomial::from(sparse.clone());
+        for engine_commit in [
+            s.commit_sparse(&sparse).unwrap(),
+            ArkM1::new_from_affine(s.powers_of_g1.clone(), s.powers_of_g2.clone())
+                .commit_sparse(&sparse)
+                .unwrap(),
+        ] {
+            assert_eq!(s.commit(&dense.coeffs).unwrap().0, engine_commit.0);
+        }
+
+        let too_long = SparsePolynomial::from_coefficients_vec(vec![(64, Fr::from(1u64))]);
+        assert!(matches!(
+            s.commit_sparse(&too_long),
+            Err(Error::TooManyScalars {
+                n_coeffs: 65,
+                expected_max: 64
+            })
+        ));
+    }
+
+    #[test]
+    fn test_commit_many() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 2, &mut test_rng());
+        let polys = (0..8)
+            .map(|i| (0..64 - i).map(|_| Fr::rand(&mut test_rng())).collect())
+            .collect::<Vec<Vec<_>>>();
+        let ark = ArkM1::new_from_affine(s.powers_of_g1.clone(), s.powers_of_g2.clone());
+        for commits in [
+            s.commit_many(&polys).unwrap(),
+            ark.commit_many(&polys).unwrap(),
+        ] {
+            assert_eq!(polys.len(), commits.len());
+            for (poly, commit) in polys.iter().zip(&commits) {
+                assert_eq!(s.commit(poly).unwrap().0, commit.0);
+            }
+        }
+
+        let too_long = vec![Fr::from(1u64); 65];
+        assert!(matches!(
+            ark.commit_many(&[&polys[0], &too_long]),
+            Err(Error::TooManyScalars {
+                n_coeffs: 65,
+                expected_max: 64
+            })
+        ));
+    }
+
+    #[test]
+    fn test_fixed_base_table() {
+        let s = ArkM1::new(64, 4, &mut test_rng());
+        let table = s.clone().with_fixed_base_table(8);
+        assert_eq!(8, table.fixed_base_table().unwrap().copies());
+        let polys = (0..3)
+            .map(|_| (0..64).map(|_| Fr::rand(&mut test_rng())).collect())
+            .collect::<Vec<Vec<_>>>();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::poly_ops::evaluate_at_points(p, &points))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        for (poly, commit) in polys.iter().zip(&commits) {
+            assert_eq!(commit.0, table.commit(poly).unwrap().0);
+        }
+        assert_eq!(
+            commits.iter().map(|c| c.0).collect::<Vec<_>>(),
+            table
+                .commit_many(&polys)
+                .unwrap()
+                .iter()
+                .map(|c| c.0)
+                .collect::<Vec<_>>()
+        );
+        let proof = table
+            .open(&mut Transcript::new(b"table"), &evals, &polys, &points)
+            .unwrap();
+        assert_eq!(
+            s.open(&mut Transcript::new(b"table"), &evals, &polys, &points)
+                .unwrap()
+                .0,
+            proof.0
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"table"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+
+        // The table follows the powers when they're truncated, and isn't serialized
+        let mut truncated = table.clone();
+        truncated.truncate(32, 4).unwrap();
+        assert_eq!(32, truncated.fixed_base_table().unwrap().n_bases());
+        assert_eq!(
+            s.commit(&polys[0][..32]).unwrap().0,
+            truncated.commit(&polys[0][..32]).unwrap().0
+        );
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        let mut bytes = Vec::new();
+        table.serialize_compressed(&mut bytes).unwrap();
+        assert!(ArkM1::deserialize_compressed(&bytes[..])
+            .unwrap()
+            .fixed_base_table()
+            .is_none());
+        assert!(table
+            .without_fixed_base_table()
+            .fixed_base_table()
+            .is_none());
+    }
+
+    #[test]
+    fn test_custom_transcript_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        test_custom_transcript_no_precomp(&s);
+    }
+
+    #[test]
+    fn test_homomorphism() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let scalars = (0..3)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let proofs = (0..3)
+            .map(|i| {
+                s.open(
+                    &mut Transcript::new(b"testing"),
+                    &evals[i..i + 1],
+                    &coeffs[i..i + 1],
+                    &points,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let comb_coeffs = linear_combination(&coeffs, &scalars).unwrap();
+        let comb_evals = linear_combination(&evals, &scalars).unwrap();
+        let comb_commit = linear_combination_of_commitments(&commits, &scalars).unwrap();
+        assert_eq!(s.commit(&comb_coeffs).unwrap().0, comb_commit.0);
+        let comb_proof = linear_combination_of_proofs(&proofs, &scalars).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &[comb_commit],
+                &points,
+                &[comb_evals],
+                &comb_proof
+            )
+        );
+
+        let sum = commits[0]
+            .add(&commits[1])
+            .add(&commits[2].scale(scalars[0]));
+        let sum_proof = proofs[0].add(&proofs[1]).add(&proofs[2].scale(scalars[0]));
+        let sum_evals =
+            linear_combination(&evals, &[Fr::from(1u64), Fr::from(1u64), scalars[0]]).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &[sum],
+                &points,
+                &[sum_evals],
+                &sum_proof
+            )
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            linear_combination_of_commitments(&commits, &scalars[..2]).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            linear_combination_of_proofs(&proofs, &scalars[..2]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_insecure_for_testing() {
+        type M1 = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+        let s = M1::new_insecure_for_testing(7, 64, 8);
+        assert_eq!(
+            s.powers_of_g1,
+            M1::new_insecure_for_testing(7, 64, 8).powers_of_g1
+        );
+        assert_eq!(
+            s.powers_of_g2,
+            M1::new_insecure_for_testing(7, 64, 8).powers_of_g2
+        );
+        assert_ne!(
+            s.powers_of_g1,
+            M1::new_insecure_for_testing(8, 64, 8).powers_of_g1
+        );
+        assert_eq!(G1Affine::generator(), s.powers_of_g1[0]);
+        assert_eq!(Some(7), s.insecure_seed());
+        assert!(format!("{s:?}").contains("insecure_seed: Some(7)"));
+        assert_eq!(None, M1::new(64, 8, &mut test_rng()).insecure_seed());
+        test_basic_no_precomp(&M1::new_insecure_for_testing(1, 256, 30));
+    }
+
+    #[test]
+    fn test_new_from_seed() {
+        type M1 = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+        let s = M1::new_from_seed(b"devnet 1", 64, 8);
+        let same =
+            M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new_from_seed(b"devnet 1", 64, 8);
+        assert_eq!(s.powers_of_g1, same.powers_of_g1);
+        assert_eq!(s.powers_of_g2, same.powers_of_g2);
+        let other = M1::new_from_seed(b"devnet 2", 64, 8);
+        assert_ne!(s.powers_of_g1, other.powers_of_g1);
+        assert_ne!(s.powers_of_g2, other.powers_of_g2);
+        // The generators are derived too
+        assert_ne!(G1Affine::generator(), s.powers_of_g1[0]);
+        assert_ne!(G2Affine::generator(), s.powers_of_g2[0]);
+        assert_eq!(None, s.insecure_seed());
+        assert_eq!(Ok(()), s.validate(&mut test_rng()));
+        test_basic_no_precomp(&M1::new_from_seed(b"devnet 1", 256, 30));
+    }
+
+    #[test]
+    fn test_checked_constructor() {
+        type M1 = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+        let s = M1::new(64, 30, &mut test_rng());
+        assert_eq!(64, s.max_coeffs());
+        assert_eq!(30, s.max_pts());
+        let g1 = s.powers_of_g1.clone();
+        let g2 = s.powers_of_g2.clone();
+        let checked = M1::new_from_affine_checked(g1.clone(), g2.clone(), 64, 30).unwrap();
+        test_basic_no_precomp(&checked);
+        assert_eq!(s.powers_of_g2, checked.powers_of_g2);
+        assert_eq!(
+            Some(Error::SetupTooSmall {
+                group: 2,
+                needed: 32,
+                got: 31
+            }),
+            M1::new_from_affine_checked(g1.clone(), g2.clone(), 64, 31).err()
+        );
+        assert_eq!(
+            Some(Error::SetupTooSmall {
+                group: 1,
+                needed: 65,
+                got: 64
+            }),
+            M1::new_from_affine_checked(g1, g2, 65, 30).err()
+        );
+    }
+
+    #[test]
+    fn test_too_many_points() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 8, &mut test_rng());
+        let points = (0..9)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 2, &points);
+        let too_many = Err(Error::TooManyPoints {
+            n_points: 9,
+            max_points: 8,
+        });
+        // Opening doesn't need the G2 powers, but the proof couldn't be verified
+        assert_eq!(
+            too_many,
+            s.open(&mut Transcript::new(b"test"), &evals, &coeffs, &points)
+                .map(|_| ())
+        );
+        assert_eq!(
+            too_many,
+            s.open_with_challenge(&coeffs, &points, Fr::from(2u64))
+                .map(|_| ())
+        );
+        assert_eq!(
+            too_many,
+            s.verify(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &Proof(G1Affine::generator())
+            )
+            .map(|_| ())
+        );
+        assert_eq!(
+            too_many,
+            crate::method1::precompute::M1Precomp::from_inner(s, vec![points]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_compute_quotient() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 8, &mut test_rng());
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let gamma = Fr::rand(&mut test_rng());
+        let gammas = gen_powers(gamma, coeffs.len());
+        let q = compute_quotient(&coeffs, &gammas, &vanishing_polynomial(&points)).unwrap();
+        // The proof is the commitment to the quotient
+        let proof = s.commit(&q.coeffs).unwrap();
+        assert_eq!(
+            proof.0,
+            s.open_with_challenge(&coeffs, &points, gamma).unwrap().0
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify_with_challenge(&commits, &points, &evals, &Proof(proof.0), gamma)
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            compute_quotient(&coeffs, &gammas[..2], &vanishing_polynomial(&points))
+        );
+    }
+
+    #[test]
+    fn test_truncate_and_extend() {
+        type M1 = M1NoPrecomp<Bls12_381, BlstMSMEngine>;
+        let full = M1::new_insecure_for_testing(3, 256, 32);
+        let domain = Radix2EvaluationDomain::new(128).unwrap();
+        let full = full.with_lagrange_basis(domain).unwrap();
+
+        let mut s = full.clone();
+        s.truncate(64, 10).unwrap();
+        assert_eq!((64, 11), (s.powers_of_g1.len(), s.powers_of_g2.len()));
+        assert!(s.lagrange_bases.is_empty());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        // 60 coefficient polys still open and verify
+        let (coeffs, evals, commits) = gen_openings(&s, 2, &points);
+        assert_eq!(full.commit(&coeffs[0]).unwrap().0, commits[0].0);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 65,
+                expected_max: 64
+            }),
+            s.clone().truncate(65, 10)
+        );
+        assert_eq!(
+            Err(Error::TooManyPoints {
+                n_points: 11,
+                max_points: 10
+            }),
+            s.clone().truncate(64, 11)
+        );
+
+        s.extend_from(&full).unwrap();
+        assert_eq!(full.powers_of_g1, s.powers_of_g1);
+        assert_eq!(full.powers_of_g2, s.powers_of_g2);
+        assert_eq!(1, s.lagrange_bases.len());
+        // Extending from a shorter setup does nothing
+        s.extend_from(&M1::new_insecure_for_testing(3, 16, 4))
+            .unwrap();
+        assert_eq!(full.powers_of_g1, s.powers_of_g1);
+        assert_eq!(
+            Err(Error::InconsistentSrs { group: 1 }),
+            s.extend_from(&M1::new_insecure_for_testing(4, 512, 32))
+        );
+    }
+
+    #[test]
+    fn test_verify_to_circuit_inputs() {
+        type M = ArkMSMEngine<Bls12_381>;
+        let s = ArkM1::new(256, 30, &mut test_rng());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, &points)
+            .unwrap();
+
+        let mut transcript = Transcript::new(b"test");
+        let inputs = s
+            .verify_to_circuit_inputs(&mut transcript, &commits, &points, &evals, &proof)
+            .unwrap();
+        assert!(inputs.check::<M>());
+        let gammas = gen_powers(inputs.gamma, commits.len());
+        assert_eq!(
+            linear_combination_of_commitments(&commits, &gammas)
+                .unwrap()
+                .0,
+            inputs.aggregated_commit
+        );
+        // The transcript is left as verifying leaves it
+        let mut verified = Transcript::new(b"test");
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut verified, &commits, &points, &evals, &proof)
+        );
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        transcript.challenge_bytes(b"next", &mut a);
+        verified.challenge_bytes(b"next", &mut b);
+        assert_eq!(a, b);
+
+        let precomp = M1Precomp::from_inner(s.clone(), vec![points.clone()]).unwrap();
+        let precomp_inputs = precomp
+            .verify_to_circuit_inputs(&mut Transcript::new(b"test"), &commits, 0, &evals, &proof)
+            .unwrap();
+        assert_eq!(inputs, precomp_inputs);
+
+        let mut bad_evals = evals.clone();
+        bad_evals[0][0] += Fr::from(1u64);
+        let bad = s
+            .verify_to_circuit_inputs(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &bad_evals,
+                &proof,
+            )
+            .unwrap();
+        assert!(!bad.check::<M>());
+    }
+
+    #[test]
+    fn test_verify_explain() {
+        let s = ArkM1::new(256, 30, &mut test_rng());
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, &points)
+            .unwrap();
+        let prover_gamma = s
+            .verify_to_circuit_inputs(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+            )
+            .unwrap()
+            .gamma;
+        let explain = |label: &'static [u8], commits: &[_], evals: &[Vec<Fr>], gamma| {
+            s.verify_explain(
+                &mut Transcript::new(label),
+                commits,
+                &points,
+                evals,
+                &proof,
+                gamma,
+            )
+            .unwrap()
+        };
+
+        assert!(explain(b"test", &commits, &evals, Some(prover_gamma)).is_valid());
+        let precomp = M1Precomp::from_inner(s.clone(), vec![points.clone()]).unwrap();
+        assert!(precomp
+            .verify_explain(
+                &mut Transcript::new(b"test"),
+                &commits,
+                0,
+                &evals,
+                &proof,
+                None
+            )
+            .unwrap()
+            .is_valid());
+        assert!(matches!(
+            explain(b"other", &commits, &evals, Some(prover_gamma)),
+            VerifyDiagnosis::TranscriptMismatch { prover_gamma: g, inputs } if g == prover_gamma && inputs.gamma != g
+        ));
+        // Without the prover's challenge a transcript mismatch looks like any other failure
+        assert!(matches!(
+            explain(b"other", &commits, &evals, None),
+            VerifyDiagnosis::PairingMismatch(_)
+        ));
+        assert_eq!(
+            VerifyDiagnosis::ShapeMismatch(Error::EvalsAndCommitsDifferentSizes {
+                n_evals: 3,
+                n_commits: 2
+            }),
+            explain(b"test", &commits[..2], &evals, None)
+        );
+
+        // Wrong evals change the challenge too, but a wrong proof doesn't
+        let bad_proof = Proof(commits[0].0);
+        let diagnosis = s
+            .verify_explain(
+                &mut Transcript::new(b"test"),
+                &commits,
+                &points,
+                &evals,
+                &bad_proof,
+                Some(prover_gamma),
+            )
+            .unwrap();
+        assert!(
+            matches!(diagnosis, VerifyDiagnosis::PairingMismatch(inputs) if inputs.gamma == prover_gamma)
+        );
+    }
 }
@@ -1,17 +1,34 @@
 //! Precomputation for method 1.
-use ark_poly::univariate::DensePolynomial;
-use merlin::Transcript;
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    Radix2EvaluationDomain,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 
-use ark_ec::pairing::Pairing;
-use ark_std::vec::Vec;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_std::{end_timer, rand::RngCore, start_timer, vec::Vec};
+use core::mem::size_of;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use super::{vanishing_polynomial, Error, Proof};
-use crate::lagrange::LagrangeInterpContext;
-use crate::traits::{Committer, MSMEngine, PolyMultiProof};
-use crate::{cfg_iter, Commitment};
+use super::{
+    batched_sets_challenges, check_batched_sets_sizes, check_open_all_sizes, concat_set_evals,
+    gen_powers, opening_challenge, union_of_point_sets, vanishing_polynomial, BatchedSetsProof,
+    Error, PointSetUnion, Proof, VerifierCircuitInputs, VerifyDiagnosis,
+};
+use crate::lagrange::{linear_combination_of_evals, LagrangeInterpContext};
+use crate::point_set::{PointSet, PointSetHash};
+use crate::poly_ops::evaluate_at_points;
+use crate::progress::Progress;
+use crate::traits::{
+    check_batch_sizes, g1_compressed_size, Committer, MSMEngine, MemoryEstimate, PolyMultiProof,
+    SchemeInfo, TranscriptProtocol,
+};
+use crate::{cfg_iter, check_opening_sizes, check_verify_sizes, Commitment};
 
 /// Method 1 scheme with precomputed lagrange polynomials/vanishing polys
 #[derive(Clone)]
@@ -19,35 +36,639 @@ pub struct M1Precomp<E: Pairing, M: MSMEngine<E = E>> {
     /// The inner method 1 object without precomputation
     pub inner: super::M1NoPrecomp<E, M>,
     point_sets: Vec<Vec<E::ScalarField>>,
+    point_set_hashes: Vec<PointSetHash>,
     vanishing_polys: Vec<DensePolynomial<E::ScalarField>>,
     g2_zeros: Vec<E::G2>,
-    lagrange_ctxs: Vec<LagrangeInterpContext<E::ScalarField>>,
+    /// The commitments $[L_j(x)]_1$ to the lagrange polynomials of each point set
+    lagrange_g1: Vec<Vec<E::G1Affine>>,
+    lagrange_prepared: Vec<M::G1Prepared>,
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> M1Precomp<E, M> {
-    /// Make a precompute-optimized version of a method 1 object for the given sets of points
+    /// Make a precompute-optimized version of a method 1 object for the given sets of points.
+    /// These can be given as a [`PointSet`] or just the points.
     pub fn from_inner(
         inner: super::M1NoPrecomp<E, M>,
-        point_sets: Vec<Vec<<E as Pairing>::ScalarField>>,
+        point_sets: Vec<impl Into<PointSet<E::ScalarField>>>,
     ) -> Result<Self, Error> {
-        let vanishing_polys: Vec<_> = cfg_iter!(point_sets)
-            .map(|(_, ps)| vanishing_polynomial(ps))
-            .collect();
-        let g2_zeros = cfg_iter!(vanishing_polys)
-            .map(|(_, p)| crate::curve_msm::<E::G2>(&inner.powers_of_g2, p))
-            .collect::<Result<Vec<_>, Error>>()?;
-        let lagrange_ctxs = cfg_iter!(point_sets)
-            .map(|(_, ps)| LagrangeInterpContext::new_from_points(ps.as_ref()))
-            .collect::<Result<Vec<_>, Error>>()?;
+        Self::from_inner_with_progress(inner, point_sets, &Progress::new())
+    }
 
-        Ok(M1Precomp {
+    /// Make a precompute-optimized version as in [`M1Precomp::from_inner`], reporting each point
+    /// set as it's precomputed to `progress`. Fails with [`Error::Cancelled`] if `progress` is
+    /// cancelled first.
+    pub fn from_inner_with_progress(
+        inner: super::M1NoPrecomp<E, M>,
+        point_sets: Vec<impl Into<PointSet<E::ScalarField>>>,
+        progress: &Progress,
+    ) -> Result<Self, Error> {
+        let (point_set_hashes, point_sets): (Vec<_>, Vec<_>) = point_sets
+            .into_iter()
+            .map(|ps| {
+                let ps = ps.into();
+                (ps.hash(), ps.into_points())
+            })
+            .unzip();
+        let tracker = progress.tracker(point_sets.len());
+        let timer = start_timer!(|| ark_std::format!("precompute {} point sets", point_sets.len()));
+        let precomps = inner.install(|| {
+            cfg_iter!(point_sets)
+                .map(|(_, ps)| tracker.step(|| precompute_point_set(&inner, ps)))
+                .collect::<Result<Vec<_>, Error>>()
+        });
+        end_timer!(timer);
+        let precomps = precomps?;
+        let mut res = M1Precomp {
             inner,
             point_sets,
+            point_set_hashes,
+            vanishing_polys: Vec::with_capacity(precomps.len()),
+            g2_zeros: Vec::with_capacity(precomps.len()),
+            lagrange_g1: Vec::with_capacity(precomps.len()),
+            lagrange_prepared: Vec::with_capacity(precomps.len()),
+        };
+        for (vp, g2_zeros, lagrange_g1) in precomps {
+            res.vanishing_polys.push(vp);
+            res.g2_zeros.push(g2_zeros);
+            res.lagrange_prepared
+                .push(M::prepare_g1(lagrange_g1.clone()));
+            res.lagrange_g1.push(lagrange_g1);
+        }
+        Ok(res)
+    }
+
+    /// The point sets that can be opened at, in index order
+    pub fn point_sets(&self) -> &[Vec<E::ScalarField>] {
+        &self.point_sets
+    }
+
+    /// The hashes of the point sets, in index order
+    pub fn point_set_hashes(&self) -> &[PointSetHash] {
+        &self.point_set_hashes
+    }
+
+    /// The index of the point set with hash `hash`
+    pub fn point_set_index(&self, hash: &PointSetHash) -> Result<usize, Error> {
+        self.point_set_hashes
+            .iter()
+            .position(|h| h == hash)
+            .ok_or(Error::UnknownPointSet)
+    }
+
+    /// Add a new point set, doing the precomputation for just that set. Returns the index of the
+    /// new point set, which is after all the existing ones.
+    pub fn add_point_set(
+        &mut self,
+        points: impl Into<PointSet<E::ScalarField>>,
+    ) -> Result<usize, Error> {
+        let points = points.into();
+        let (vp, g2_zeros, lagrange_g1) = precompute_point_set(&self.inner, points.points())?;
+        self.point_set_hashes.push(points.hash());
+        self.point_sets.push(points.into_points());
+        self.vanishing_polys.push(vp);
+        self.g2_zeros.push(g2_zeros);
+        self.lagrange_prepared
+            .push(M::prepare_g1(lagrange_g1.clone()));
+        self.lagrange_g1.push(lagrange_g1);
+        Ok(self.point_sets.len() - 1)
+    }
+
+    /// Remove the point set at `index` and return its points. The indices of the point sets after
+    /// it each go down by one.
+    pub fn remove_point_set(&mut self, index: usize) -> Result<Vec<E::ScalarField>, Error> {
+        self.check_point_set_index(index)?;
+        self.point_set_hashes.remove(index);
+        self.vanishing_polys.remove(index);
+        self.g2_zeros.remove(index);
+        self.lagrange_g1.remove(index);
+        self.lagrange_prepared.remove(index);
+        Ok(self.point_sets.remove(index))
+    }
+
+    fn check_point_set_index(&self, index: usize) -> Result<(), Error> {
+        if index >= self.point_sets.len() {
+            return Err(Error::PointSetIndexOutOfRange {
+                idx: index,
+                n_point_sets: self.point_sets.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The commitment $[Z(x)]_2$ to the vanishing polynomial of the point set at `index`
+    pub fn g2_zeros(&self, index: usize) -> Result<E::G2Affine, Error> {
+        self.check_point_set_index(index)?;
+        Ok(self.g2_zeros[index].into_affine())
+    }
+
+    /// The commitments $[L_j(x)]_1$ to the lagrange polynomials of the point set at `index`
+    pub fn lagrange_g1(&self, index: usize) -> Result<&[E::G1Affine], Error> {
+        self.check_point_set_index(index)?;
+        Ok(&self.lagrange_g1[index])
+    }
+}
+
+/// The vanishing polynomial, its commitment in G2, and the commitments to the lagrange
+/// polynomials for a point set
+type PointSetPrecomp<E> = (
+    DensePolynomial<<E as Pairing>::ScalarField>,
+    <E as Pairing>::G2,
+    Vec<<E as Pairing>::G1Affine>,
+);
+
+fn precompute_point_set<E: Pairing, M: MSMEngine<E = E>>(
+    inner: &super::M1NoPrecomp<E, M>,
+    points: &[E::ScalarField],
+) -> Result<PointSetPrecomp<E>, Error> {
+    inner.check_n_points(points.len())?;
+    let vp = vanishing_polynomial(points);
+    let g2_zeros = M::multi_scalar_mul_g2(&inner.g2_precomp, &vp.coeffs)?;
+    let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+    let lagrange_g1 = lag_ctx
+        .lag_polys()
+        .iter()
+        .map(|l| M::multi_scalar_mul_g1(&inner.g1_precomp, &l.coeffs))
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok((vp, g2_zeros, E::G1::normalize_batch(&lagrange_g1)))
+}
+
+/// The precomputation is serialized along with the inner scheme, so that it can be cached and
+/// loaded without recomputing it
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalSerialize for M1Precomp<E, M> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.inner.serialize_with_mode(&mut writer, compress)?;
+        self.point_sets.serialize_with_mode(&mut writer, compress)?;
+        self.vanishing_polys
+            .serialize_with_mode(&mut writer, compress)?;
+        self.g2_zeros.serialize_with_mode(&mut writer, compress)?;
+        self.lagrange_g1.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.inner.serialized_size(compress)
+            + self.point_sets.serialized_size(compress)
+            + self.vanishing_polys.serialized_size(compress)
+            + self.g2_zeros.serialized_size(compress)
+            + self.lagrange_g1.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Valid for M1Precomp<E, M> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.inner.check()?;
+        self.g2_zeros.check()?;
+        self.lagrange_g1.check()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalDeserialize for M1Precomp<E, M> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let inner = super::M1NoPrecomp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let point_sets: Vec<Vec<E::ScalarField>> =
+            Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let vanishing_polys: Vec<_> = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g2_zeros: Vec<_> = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let lagrange_g1: Vec<Vec<E::G1Affine>> =
+            Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let n = point_sets.len();
+        if vanishing_polys.len() != n
+            || g2_zeros.len() != n
+            || lagrange_g1.len() != n
+            || point_sets
+                .iter()
+                .zip(&lagrange_g1)
+                .any(|(p, l)| p.len() != l.len())
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self {
+            inner,
+            point_set_hashes: point_sets.iter().map(|p| PointSetHash::of(p)).collect(),
+            point_sets,
             vanishing_polys,
             g2_zeros,
-            lagrange_ctxs,
+            lagrange_prepared: lagrange_g1
+                .iter()
+                .map(|l| M::prepare_g1(l.clone()))
+                .collect(),
+            lagrange_g1,
+        })
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> M1Precomp<E, M> {
+    fn union_of_point_sets(
+        &self,
+        point_set_indices: &[usize],
+    ) -> Result<PointSetUnion<E::ScalarField>, Error> {
+        union_of_point_sets(point_set_indices, self.point_sets.len(), |i| {
+            (self.point_sets[i].clone(), self.vanishing_polys[i].clone())
+        })
+    }
+
+    /// Open the polynomials at every point set, with one transcript per point set. `evals[i]`
+    /// holds the evaluations over point set `i`. The transcripts are written to in order, then
+    /// the divisions and msms for each point set are done in parallel with the `parallel`
+    /// feature.
+    pub fn open_all<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_open_all_sizes(transcripts, evals, self.point_sets.len())?;
+        let commits = self.inner.transcript_commits(polys)?;
+        self.open_all_absorbing(transcripts, &commits, evals, polys)
+    }
+
+    /// Open the polynomials at every point set as in [`M1Precomp::open_all`], absorbing the caller's
+    /// `commits` to them rather than committing to every polynomial again, see
+    /// [`M1NoPrecomp::open_with_commits`](crate::method1::M1NoPrecomp::open_with_commits)
+    pub fn open_all_with_commits<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_open_all_sizes(transcripts, evals, self.point_sets.len())?;
+        for e in evals {
+            if e.as_ref().len() != commits.len() {
+                return Err(Error::EvalsAndCommitsDifferentSizes {
+                    n_evals: e.as_ref().len(),
+                    n_commits: commits.len(),
+                });
+            }
+        }
+        self.open_all_absorbing(transcripts, commits, evals, polys)
+    }
+
+    /// Opens the polynomials at every point set, absorbing `commits` if the transcript version
+    /// absorbs commitments
+    fn open_all_absorbing<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        let gammas = transcripts
+            .iter_mut()
+            .zip(evals)
+            .zip(&self.point_sets)
+            .map(|((transcript, evals), points)| {
+                opening_challenge(
+                    transcript,
+                    commits,
+                    evals.as_ref(),
+                    polys,
+                    points,
+                    &self.inner.transcript_encoding,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.inner.install(|| {
+            cfg_iter!(gammas)
+                .map(|(i, gamma)| {
+                    self.inner
+                        .open_with_gamma(polys, *gamma, &self.vanishing_polys[i])
+                })
+                .collect()
+        })
+    }
+
+    /// Open each set of polynomials at point set `point_set_index`, with one transcript per set,
+    /// e.g. to make a proof for each consumer of the same data. Polynomials shared between sets
+    /// are only divided and committed to once when that saves msms, so opening the same
+    /// polynomials for many transcripts costs little more than a single opening.
+    pub fn open_many<P: AsRef<[E::ScalarField]>>(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        evals_sets: &[&[impl AsRef<[E::ScalarField]>]],
+        polys_sets: &[&[P]],
+        point_set_index: usize,
+    ) -> Result<Vec<Proof<E>>, Error> {
+        self.check_point_set_index(point_set_index)?;
+        self.inner.open_many_with_vanishing_poly(
+            transcripts,
+            evals_sets,
+            polys_sets,
+            &self.point_sets[point_set_index],
+            &self.vanishing_polys[point_set_index],
+        )
+    }
+
+    /// Open the polynomials at the point set with hash `point_set`, as in
+    /// [`PolyMultiProof::open`]. Fails with [`Error::UnknownPointSet`] if there's no such set,
+    /// rather than opening at whichever set has the same index for the verifier.
+    pub fn open_by_hash(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set: &PointSetHash,
+    ) -> Result<Proof<E>, Error> {
+        self.open(transcript, evals, polys, self.point_set_index(point_set)?)
+    }
+
+    /// Verify a proof at the point set with hash `point_set`, as in [`PolyMultiProof::verify`].
+    /// Fails with [`Error::UnknownPointSet`] if this setup has no such set.
+    pub fn verify_by_hash(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set: &PointSetHash,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.verify(
+            transcript,
+            commits,
+            self.point_set_index(point_set)?,
+            evals,
+            proof,
+        )
+    }
+
+    /// Verify like [`PolyMultiProof::verify`], explaining a failure as in
+    /// [`super::M1NoPrecomp::verify_explain`]
+    pub fn verify_explain(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        prover_gamma: Option<E::ScalarField>,
+    ) -> Result<VerifyDiagnosis<E>, Error> {
+        let inputs =
+            self.verify_to_circuit_inputs(transcript, commits, point_set_index, evals, proof);
+        VerifyDiagnosis::diagnose::<M>(inputs, prover_gamma)
+    }
+
+    /// Does everything [`PolyMultiProof::verify`] does up to the final pairing check, returning
+    /// the values it's done on, as in [`super::M1NoPrecomp::verify_to_circuit_inputs`]
+    pub fn verify_to_circuit_inputs(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<VerifierCircuitInputs<E>, Error> {
+        self.check_point_set_index(point_set_index)?;
+        let points = &self.point_sets[point_set_index];
+        let gamma = self
+            .inner
+            .verifier_gamma(transcript, commits, points, evals)?;
+        let gammas = gen_powers(gamma, evals.len());
+        let targets = linear_combination_of_evals(evals, &gammas, points.len());
+        let interpolation_commit =
+            M::multi_scalar_mul_g1(&self.lagrange_prepared[point_set_index], targets)?;
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let aggregated_commit = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
+        Ok(VerifierCircuitInputs {
+            gamma,
+            aggregated_commit: aggregated_commit.into_affine(),
+            interpolation_commit: interpolation_commit.into_affine(),
+            g2: self.inner.powers_of_g2[0],
+            g2_zeros: self.g2_zeros[point_set_index].into_affine(),
+            proof: proof.0,
         })
     }
+
+    /// Open the polynomials at the union of the point sets in `point_set_indices` with a single
+    /// proof. This isn't an aggregate of the proofs at each set: it's one multiproof at every
+    /// point of the sets, so it costs as much as an opening at that many points, and the inner
+    /// scheme needs enough powers of G2 for them. The rows of `evals` are the evaluations of each
+    /// polynomial over each point set in the order given, concatenated. The sets can't share any
+    /// points.
+    pub fn open_union(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_indices: &[usize],
+    ) -> Result<Proof<E>, Error> {
+        let (points, vp) = self.union_of_point_sets(point_set_indices)?;
+        self.inner
+            .open_with_vanishing_poly(transcript, evals, polys, &points, &vp)
+    }
+
+    /// Verify a proof made with [`M1Precomp::open_union`]
+    pub fn verify_union(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point_set_indices: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let (points, vp) = self.union_of_point_sets(point_set_indices)?;
+        self.inner
+            .verify_with_vanishing_poly(transcript, commits, &points, evals, proof, &vp)
+    }
+
+    /// Open the polynomials at point set `point_set_index` with a challenge `gamma` drawn by the
+    /// caller rather than from a transcript, as in [`M1NoPrecomp::open_with_challenge`]. `gamma`
+    /// must meet the soundness requirements listed there.
+    ///
+    /// [`M1NoPrecomp::open_with_challenge`]: super::M1NoPrecomp::open_with_challenge
+    pub fn open_with_challenge(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+        gamma: E::ScalarField,
+    ) -> Result<Proof<E>, Error> {
+        self.check_point_set_index(point_set_index)?;
+        self.inner
+            .open_with_gamma(polys, gamma, &self.vanishing_polys[point_set_index])
+    }
+
+    /// Verify a proof made with [`M1Precomp::open_with_challenge`] against the same `gamma`
+    pub fn verify_with_challenge(
+        &self,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma: E::ScalarField,
+    ) -> Result<bool, Error> {
+        self.check_point_set_index(point_set_index)?;
+        let n_points = self.point_sets[point_set_index].len();
+        check_verify_sizes(commits, evals, n_points)?;
+        let lhs = self.inner.verifier_lhs_with_lagrange_commits_gamma(
+            commits,
+            n_points,
+            evals,
+            &self.lagrange_prepared[point_set_index],
+            gamma,
+        )?;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            self.inner.powers_of_g2[0],
+            proof.0,
+            self.g2_zeros[point_set_index].into_affine(),
+        ))
+    }
+
+    /// Open the polynomials at each point set in `point_set_indices`, drawing the challenge for
+    /// every set from the one transcript. `evals_per_set[i]` holds the evaluations over point set
+    /// `point_set_indices[i]`. The polynomials are committed to and absorbed once rather than
+    /// once per set as with a transcript per set. With `union`, a single proof at the union
+    /// of the sets is made too, as in [`M1Precomp::open_union`], which needs enough powers
+    /// of G2 for the total number of points.
+    pub fn open_batched_sets<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals_per_set: &[impl AsRef<[T]>],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+        point_set_indices: &[usize],
+        union: bool,
+    ) -> Result<BatchedSetsProof<E>, Error> {
+        let points = self.batched_sets_points(point_set_indices)?;
+        check_batched_sets_sizes(evals_per_set, &points, |evals, n_points| {
+            check_opening_sizes(evals, polys, n_points)
+        })?;
+        let union_set = union
+            .then(|| self.union_of_point_sets(point_set_indices))
+            .transpose()?;
+        if let Some((union_points, _)) = &union_set {
+            self.inner.check_n_points(union_points.len())?;
+        }
+        let (gammas, union_gamma) = batched_sets_challenges(
+            transcript,
+            &self.inner.transcript_commits(polys)?,
+            evals_per_set,
+            &points,
+            union,
+            &self.inner.transcript_encoding,
+        )?;
+        let proofs = self.inner.install(|| {
+            cfg_iter!(gammas)
+                .map(|(i, gamma)| {
+                    let vp = &self.vanishing_polys[point_set_indices[i]];
+                    self.inner.open_with_gamma(polys, *gamma, vp)
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        })?;
+        let union = match (union_set, union_gamma) {
+            (Some((_, vp)), Some(gamma)) => Some(self.inner.open_with_gamma(polys, gamma, &vp)?),
+            _ => None,
+        };
+        Ok(BatchedSetsProof { proofs, union })
+    }
+
+    /// Verify proofs made with [`M1Precomp::open_batched_sets`], including the union proof
+    /// if there is one
+    pub fn verify_batched_sets<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals_per_set: &[impl AsRef<[T]>],
+        point_set_indices: &[usize],
+        proof: &BatchedSetsProof<E>,
+    ) -> Result<bool, Error> {
+        let points = self.batched_sets_points(point_set_indices)?;
+        check_batched_sets_sizes(evals_per_set, &points, |evals, n_points| {
+            check_verify_sizes(commits, evals, n_points)
+        })?;
+        if proof.proofs.len() != point_set_indices.len() {
+            return Err(Error::InvalidInputLength {
+                expected: point_set_indices.len(),
+                got: proof.proofs.len(),
+            });
+        }
+        let (gammas, union_gamma) = batched_sets_challenges(
+            transcript,
+            commits,
+            evals_per_set,
+            &points,
+            proof.union.is_some(),
+            &self.inner.transcript_encoding,
+        )?;
+        for (i, gamma) in gammas.into_iter().enumerate() {
+            let idx = point_set_indices[i];
+            let lhs = self.inner.verifier_lhs_with_lagrange_commits_gamma(
+                commits,
+                points[i].len(),
+                evals_per_set[i].as_ref(),
+                &self.lagrange_prepared[idx],
+                gamma,
+            )?;
+            if !M::pairing_eq_check(
+                lhs.into_affine(),
+                self.inner.powers_of_g2[0],
+                proof.proofs[i].0,
+                self.g2_zeros[idx].into_affine(),
+            ) {
+                return Ok(false);
+            }
+        }
+        match (&proof.union, union_gamma) {
+            (Some(union), Some(gamma)) => {
+                let (union_points, _) = self.union_of_point_sets(point_set_indices)?;
+                let evals = concat_set_evals(evals_per_set, commits.len());
+                self.inner
+                    .verify_with_challenge(commits, &union_points, &evals, union, gamma)
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// The points of each of the point sets at `point_set_indices`
+    fn batched_sets_points(
+        &self,
+        point_set_indices: &[usize],
+    ) -> Result<Vec<&[E::ScalarField]>, Error> {
+        point_set_indices
+            .iter()
+            .map(|i| {
+                self.check_point_set_index(*i)?;
+                Ok(&self.point_sets[*i][..])
+            })
+            .collect()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for M1Precomp<E, M> {
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
+    }
+
+    fn max_pts(&self) -> usize {
+        self.inner.max_pts()
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m1-precomp"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        g1_compressed_size::<E>()
+    }
+
+    /// Each point set holds its points, vanishing polynomial and its commitment in G2, and the
+    /// commitments to its lagrange polynomials prepared and not. Openings don't interpolate, so
+    /// they only hold the combined polynomial, its quotient and the vanishing polynomial.
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate {
+        let f = size_of::<E::ScalarField>();
+        let per_point_set =
+            (2 * max_pts + 1) * f + size_of::<E::G2>() + 2 * max_pts * size_of::<E::G1Affine>();
+        MemoryEstimate {
+            precompute: num_point_sets * per_point_set,
+            per_open: (2 * max_coeffs + max_pts + 1) * f,
+            ..super::M1NoPrecomp::<E, M>::memory_estimate(max_coeffs, max_pts, num_point_sets)
+        }
+    }
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1Precomp<E, M> {
@@ -57,6 +678,21 @@ impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1Precomp<E, M> {
     ) -> Result<Commitment<E>, Error> {
         self.inner.commit(poly)
     }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_evals(evals, domain)
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_sparse(poly)
+    }
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1Precomp<E, M> {
@@ -64,11 +700,12 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1Precomp<E, M> {
 
     fn open(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[<E as Pairing>::ScalarField]>],
         polys: &[impl AsRef<[<E as Pairing>::ScalarField]>],
         point_set_index: usize,
     ) -> Result<Self::Proof, Error> {
+        self.check_point_set_index(point_set_index)?;
         self.inner.open_with_vanishing_poly(
             transcript,
             evals,
@@ -78,23 +715,102 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1Precomp<E, M> {
         )
     }
 
+    fn open_points(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        poly: impl AsRef<[E::ScalarField]>,
+        point_set_indices: &[usize],
+    ) -> Result<Vec<(Vec<E::ScalarField>, Proof<E>)>, Error> {
+        check_batch_sizes(transcripts, point_set_indices)?;
+        transcripts
+            .iter_mut()
+            .zip(point_set_indices)
+            .map(|(transcript, i)| {
+                self.check_point_set_index(*i)?;
+                self.inner.open_single_with_vanishing_poly(
+                    transcript,
+                    poly.as_ref(),
+                    &self.point_sets[*i],
+                    &self.vanishing_polys[*i],
+                )
+            })
+            .collect()
+    }
+
     fn verify(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         point_set_index: usize,
         evals: &[impl AsRef<[<E as Pairing>::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error> {
-        self.inner.verify_with_lag_ctx_g2_zeros(
+        self.check_point_set_index(point_set_index)?;
+        let timer = start_timer!(|| {
+            ark_std::format!(
+                "verify {} commits at point set {}",
+                commits.len(),
+                point_set_index
+            )
+        });
+        let lhs = self.inner.verifier_lhs_with_lagrange_commits(
             transcript,
             commits,
             &self.point_sets[point_set_index],
             evals,
-            proof,
-            &self.lagrange_ctxs[point_set_index],
-            &self.g2_zeros[point_set_index],
-        )
+            &self.lagrange_prepared[point_set_index],
+        )?;
+        let res = M::pairing_eq_check(
+            lhs.into_affine(),
+            self.inner.powers_of_g2[0],
+            proof.0,
+            self.g2_zeros[point_set_index].into_affine(),
+        );
+        end_timer!(timer);
+        Ok(res)
+    }
+
+    fn compute_evals(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error> {
+        self.check_point_set_index(point_set_index)?;
+        let points = &self.point_sets[point_set_index];
+        Ok(polys
+            .iter()
+            .map(|p| evaluate_at_points(p.as_ref(), points))
+            .collect())
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            usize,
+            &[impl AsRef<[E::ScalarField]>],
+            &Self::Proof,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        let terms = transcripts
+            .iter_mut()
+            .zip(items)
+            .map(|(transcript, (commits, point_set_index, evals, proof))| {
+                self.check_point_set_index(*point_set_index)?;
+                let lhs = self.inner.verifier_lhs_with_lagrange_commits(
+                    transcript,
+                    commits,
+                    &self.point_sets[*point_set_index],
+                    evals,
+                    &self.lagrange_prepared[*point_set_index],
+                )?;
+                Ok((lhs, proof.0, self.g2_zeros[*point_set_index]))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(self.inner.batch_pairing_check(&terms, rng))
     }
 }
 
@@ -104,11 +820,73 @@ mod tests {
     use crate::{
         method1::M1NoPrecomp,
         msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        point_set::{PointSet, PointSetHash},
+        progress::Progress,
         test_rng,
-        testing::test_basic_precomp,
+        testing::{gen_openings, test_basic_precomp, test_batch_precomp},
+        traits::PolyMultiProof,
+        transcript::TranscriptEncoding,
+        Error,
     };
     use ark_bls12_381::{Bls12_381, Fr};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::{vec, vec::Vec, UniformRand};
+    use core::sync::atomic::AtomicBool;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_open_points() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 4, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets).unwrap();
+        let (polys, _, commits) = gen_openings(&s, 1, &[]);
+
+        let mut transcripts = [Transcript::new(b"a"), Transcript::new(b"b")];
+        let opened = s.open_points(&mut transcripts, &polys[0], &[2, 0]).unwrap();
+        for ((evals, proof), (label, i)) in opened.iter().zip([(b"a", 2), (b"b", 0)]) {
+            assert_eq!(s.compute_evals(&polys, i).unwrap()[0], *evals);
+            assert_eq!(
+                Ok(true),
+                s.verify(&mut Transcript::new(label), &commits, i, &[evals], proof)
+            );
+        }
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"a"),
+                &commits,
+                1,
+                &[&opened[0].0],
+                &opened[0].1
+            )
+        );
+
+        let (evals, proof) = s
+            .open_rows(&mut Transcript::new(b"rows"), &polys, 1)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut Transcript::new(b"rows"), &commits, 1, &evals, &proof)
+        );
+
+        assert!(matches!(
+            s.open_points(&mut [Transcript::new(b"a")], &polys[0], &[0, 1]),
+            Err(Error::InvalidInputLength {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            s.open_points(&mut [Transcript::new(b"a")], &polys[0], &[3]),
+            Err(Error::PointSetIndexOutOfRange { .. })
+        ));
+    }
 
     #[test]
     fn test_basic_open_works() {
@@ -123,4 +901,684 @@ mod tests {
         let s = M1Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
         test_basic_precomp(&s, &points);
     }
+
+    #[test]
+    fn test_batch_verify_works() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..10)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+        test_batch_precomp(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..10)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+
+        let mut bytes = Vec::new();
+        s.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), s.compressed_size());
+        let loaded = M1Precomp::<Bls12_381, BlstMSMEngine>::deserialize_compressed(&bytes[..])
+            .expect("Failed to deserialize");
+        test_basic_precomp(&loaded, &point_sets[0]);
+
+        let (coeffs, evals, commits) = gen_openings(&s, 2, &point_sets[1]);
+        let proof = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, 1)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            loaded.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                1,
+                &evals,
+                &proof
+            )
+        );
+        assert!(
+            M1Precomp::<Bls12_381, BlstMSMEngine>::deserialize_compressed(
+                &bytes[..bytes.len() - 1]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_open_all() {
+        let point_sets = (0..4)
+            .map(|_| {
+                (0..8)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+
+        let all_points = point_sets.concat();
+        let (coeffs, all_evals, commits) = gen_openings(&s, 3, &all_points);
+        let evals = (0..point_sets.len())
+            .map(|i| {
+                all_evals
+                    .iter()
+                    .map(|row| row[i * 8..(i + 1) * 8].to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut transcripts = vec![Transcript::new(b"testing"); point_sets.len()];
+        let proofs = s.open_all(&mut transcripts, &evals, &coeffs).unwrap();
+        for (i, proof) in proofs.iter().enumerate() {
+            let single = s
+                .open(&mut Transcript::new(b"testing"), &evals[i], &coeffs, i)
+                .unwrap();
+            assert_eq!(single.0, proof.0);
+            assert_eq!(
+                Ok(true),
+                s.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    i,
+                    &evals[i],
+                    proof
+                )
+            );
+        }
+
+        let mut transcripts = vec![Transcript::new(b"testing"); point_sets.len() - 1];
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 4,
+                got: 3
+            }),
+            s.open_all(&mut transcripts, &evals, &coeffs).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_open_many() {
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let (other_coeffs, other_evals, _) = gen_openings(&s, 3, &points);
+        let labels: [&'static [u8]; 4] = [b"a", b"b", b"c", b"d"];
+
+        // The same polynomials for every transcript share their quotients, and a mix of
+        // polynomials opens each set on its own
+        let shared = (vec![&evals[..]; 4], vec![&coeffs[..]; 4]);
+        let mixed = (
+            vec![&evals[..], &other_evals[..], &other_evals[1..]],
+            vec![&coeffs[..], &other_coeffs[..], &other_coeffs[1..]],
+        );
+        // With V1 the commitments to the shared polynomials are made once and absorbed into each
+        // transcript
+        let v1 = M1Precomp::from_inner(
+            s.inner
+                .clone()
+                .with_transcript_encoding(TranscriptEncoding::v1()),
+            vec![points.clone()],
+        )
+        .expect("Failed to construct");
+        for scheme in [&s, &v1] {
+            for (evals_sets, polys_sets) in [shared.clone(), mixed.clone()] {
+                let mut transcripts = labels[..polys_sets.len()]
+                    .iter()
+                    .map(|l| Transcript::new(l))
+                    .collect::<Vec<_>>();
+                let proofs = scheme
+                    .open_many(&mut transcripts, &evals_sets, &polys_sets, 0)
+                    .unwrap();
+                for (i, proof) in proofs.iter().enumerate() {
+                    let single = scheme
+                        .open(
+                            &mut Transcript::new(labels[i]),
+                            evals_sets[i],
+                            polys_sets[i],
+                            0,
+                        )
+                        .unwrap();
+                    assert_eq!(single.0, proof.0);
+                }
+            }
+        }
+        let mut transcripts = vec![Transcript::new(b"a"), Transcript::new(b"b")];
+        let proofs = s
+            .open_many(
+                &mut transcripts,
+                &[&evals[..], &evals[..]],
+                &[&coeffs[..], &coeffs[..]],
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut Transcript::new(b"b"), &commits, 0, &evals, &proofs[1])
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            s.open_many(&mut transcripts, &[&evals[..]; 3], &[&coeffs[..]; 3], 0)
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 1,
+                n_point_sets: 1
+            }),
+            s.open_many(&mut transcripts, &[&evals[..]; 2], &[&coeffs[..]; 2], 1)
+                .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let scalars = [Fr::from(2u64), Fr::from(0u64), -Fr::from(5u64)];
+        let combined_evals = (0..points.len())
+            .map(|j| evals.iter().zip(&scalars).map(|(e, c)| e[j] * c).sum())
+            .collect::<Vec<Fr>>();
+        let proof = s
+            .open_linear_combination(
+                &mut Transcript::new(b"testing"),
+                &combined_evals,
+                &coeffs,
+                &scalars,
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_linear_combination(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &scalars,
+                0,
+                &combined_evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_linear_combination(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &scalars,
+                0,
+                &evals[0],
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_union_works() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..10)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+
+        let indices = [2, 0];
+        let points = [point_sets[2].clone(), point_sets[0].clone()].concat();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open_union(&mut Transcript::new(b"testing"), &evals, &coeffs, &indices)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify_union(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &indices,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_union(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &[0, 2],
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::DuplicatePointSetIndex(2)),
+            s.open_union(&mut Transcript::new(b"testing"), &evals, &coeffs, &[2, 2])
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange {
+                idx: 3,
+                n_point_sets: 3
+            }),
+            s.open_union(&mut Transcript::new(b"testing"), &evals, &coeffs, &[3])
+                .map(|_| ())
+        );
+
+        // Sets which share a point can't be opened together, whichever order they're given in
+        let mut overlapping = point_sets.clone();
+        overlapping[1][3] = overlapping[0][7];
+        let s = M1Precomp::from_inner(s.inner, overlapping).expect("Failed to construct");
+        assert_eq!(
+            Err(Error::OverlappingPointSets(1)),
+            s.open_union(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &[2, 0, 1]
+            )
+            .map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::OverlappingPointSets(0)),
+            s.verify_union(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &[1, 0],
+                &evals,
+                &proof
+            )
+        );
+        assert!(s
+            .open_union(&mut Transcript::new(b"testing"), &evals, &coeffs, &indices)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_external_challenge() {
+        let point_sets = (0..2)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 4, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).unwrap();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &point_sets[1]);
+        let gamma = Fr::rand(&mut test_rng());
+
+        let proof = s.open_with_challenge(&coeffs, 1, gamma).unwrap();
+        assert_eq!(
+            s.inner
+                .open_with_challenge(&coeffs, &point_sets[1], gamma)
+                .unwrap()
+                .0,
+            proof.0
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify_with_challenge(&commits, 1, &evals, &proof, gamma)
+        );
+        assert_eq!(
+            Ok(true),
+            s.inner
+                .verify_with_challenge(&commits, &point_sets[1], &evals, &proof, gamma)
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_with_challenge(&commits, 1, &evals, &proof, gamma + Fr::from(1u64))
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_with_challenge(&commits, 0, &evals, &proof, gamma)
+        );
+        assert!(matches!(
+            s.verify_with_challenge(&commits, 2, &evals, &proof, gamma),
+            Err(Error::PointSetIndexOutOfRange { idx: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_open_batched_sets() {
+        let point_sets = (0..4)
+            .map(|_| {
+                (0..6)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(128, 32, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+
+        let indices = [3, 1, 2];
+        let all_points = indices.map(|i| point_sets[i].clone()).concat();
+        let (coeffs, all_evals, commits) = gen_openings(&s, 3, &all_points);
+        let evals = (0..indices.len())
+            .map(|i| {
+                all_evals
+                    .iter()
+                    .map(|row| row[i * 6..(i + 1) * 6].to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for union in [false, true] {
+            let proof = s
+                .open_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &coeffs,
+                    &indices,
+                    union,
+                )
+                .unwrap();
+            assert_eq!(indices.len(), proof.proofs.len());
+            assert_eq!(union, proof.union.is_some());
+            assert_eq!(
+                Ok(true),
+                s.verify_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &evals,
+                    &indices,
+                    &proof
+                )
+            );
+
+            // Changed evaluations at any set, or another transcript, fail
+            let mut bad_evals = evals.clone();
+            bad_evals[2][1][0] += Fr::from(1u64);
+            assert_eq!(
+                Ok(false),
+                s.verify_batched_sets(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &bad_evals,
+                    &indices,
+                    &proof
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_batched_sets(
+                    &mut Transcript::new(b"other"),
+                    &commits,
+                    &evals,
+                    &indices,
+                    &proof
+                )
+            );
+        }
+
+        // The union proof alone is checked too
+        let mut proof = s
+            .open_batched_sets(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &indices,
+                true,
+            )
+            .unwrap();
+        proof.union = Some(proof.proofs[0].clone());
+        assert_eq!(
+            Ok(false),
+            s.verify_batched_sets(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &evals,
+                &indices,
+                &proof
+            )
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            s.open_batched_sets(
+                &mut Transcript::new(b"testing"),
+                &evals[..2],
+                &coeffs,
+                &indices,
+                false
+            )
+            .map(|_| ())
+        );
+        assert!(matches!(
+            s.open_batched_sets(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &[3, 1, 4],
+                false
+            ),
+            Err(Error::PointSetIndexOutOfRange { idx: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_point_set_hashes() {
+        let point_sets = (0..3)
+            .map(|_| {
+                PointSet::new(
+                    (0..8)
+                        .map(|_| Fr::rand(&mut test_rng()))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let inner = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let prover = M1Precomp::from_inner(inner.clone(), point_sets.clone()).unwrap();
+        // The verifier has the sets in another order, so the indices disagree
+        let verifier =
+            M1Precomp::from_inner(inner, vec![point_sets[2].clone(), point_sets[0].clone()])
+                .unwrap();
+        assert_eq!(
+            point_sets.iter().map(|p| p.hash()).collect::<Vec<_>>(),
+            prover.point_set_hashes()
+        );
+        assert_eq!(Ok(1), verifier.point_set_index(&point_sets[0].hash()));
+
+        let hash = point_sets[0].hash();
+        let (coeffs, evals, commits) = gen_openings(&prover, 3, point_sets[0].points());
+        let proof = prover
+            .open_by_hash(&mut Transcript::new(b"testing"), &evals, &coeffs, &hash)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            verifier.verify_by_hash(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &hash,
+                &evals,
+                &proof
+            )
+        );
+        // The prover's index 0 is a different set for the verifier
+        assert_eq!(
+            Ok(false),
+            verifier.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                0,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::UnknownPointSet),
+            verifier.verify_by_hash(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &point_sets[1].hash(),
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::UnknownPointSet),
+            prover
+                .open_by_hash(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &coeffs,
+                    &PointSetHash([0; 32])
+                )
+                .map(|_| ())
+        );
+
+        // Hashes follow the sets when they're added, removed and reloaded
+        let mut bytes = Vec::new();
+        verifier.serialize_compressed(&mut bytes).unwrap();
+        let mut loaded =
+            M1Precomp::<Bls12_381, BlstMSMEngine>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(verifier.point_set_hashes(), loaded.point_set_hashes());
+        assert_eq!(2, loaded.add_point_set(point_sets[1].clone()).unwrap());
+        loaded.remove_point_set(0).unwrap();
+        assert_eq!(&prover.point_set_hashes()[..2], loaded.point_set_hashes());
+    }
+
+    #[test]
+    fn test_add_remove_point_set() {
+        let point_sets = (0..2)
+            .map(|_| {
+                (0..8)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let new_set = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let inner = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let mut s = M1Precomp::from_inner(inner.clone(), point_sets.clone()).unwrap();
+        assert_eq!(2, s.add_point_set(new_set.clone()).unwrap());
+        assert_eq!(3, s.point_sets().len());
+
+        // Same as constructing with all the point sets at once
+        let all_sets = [point_sets.clone(), vec![new_set.clone()]].concat();
+        let full = M1Precomp::from_inner(inner, all_sets).unwrap();
+        assert_eq!(full.g2_zeros, s.g2_zeros);
+        assert_eq!(full.vanishing_polys, s.vanishing_polys);
+        assert_eq!(full.lagrange_g1, s.lagrange_g1);
+
+        // The lagrange commitments commit to the interpolations of the unit vectors
+        let units = (0..5)
+            .map(|j| {
+                let mut unit = vec![Fr::from(0u64); 5];
+                unit[j] = Fr::from(1u64);
+                unit
+            })
+            .collect::<Vec<_>>();
+        let interps = s.inner.commit_interpolations(&new_set, &units).unwrap();
+        assert_eq!(
+            interps.iter().map(|c| c.0).collect::<Vec<_>>(),
+            s.lagrange_g1[2]
+        );
+
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &new_set);
+        let open = |s: &M1Precomp<_, _>, idx| {
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, idx)
+        };
+        let proof = open(&s, 2).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                2,
+                &evals,
+                &proof
+            )
+        );
+
+        // Removing the first set moves the new one down to index 1
+        assert_eq!(point_sets[0], s.remove_point_set(0).unwrap());
+        assert_eq!(2, s.point_sets().len());
+        assert_eq!(proof.0, open(&s, 1).unwrap().0);
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                1,
+                &evals,
+                &proof
+            )
+        );
+
+        let out_of_range = Err(Error::PointSetIndexOutOfRange {
+            idx: 2,
+            n_point_sets: 2,
+        });
+        assert_eq!(out_of_range, s.remove_point_set(2).map(|_| ()));
+        assert_eq!(out_of_range, open(&s, 2).map(|_| ()));
+        assert_eq!(
+            out_of_range,
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                2,
+                &evals,
+                &proof
+            )
+            .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_from_inner_with_progress() {
+        let point_sets = (0..5)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let inner = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 4, &mut test_rng());
+        let reports = std::sync::Mutex::new(Vec::new());
+        let callback = |done: usize, total: usize| reports.lock().unwrap().push((done, total));
+        let progress = Progress::new().with_callback(&callback);
+        let s = M1Precomp::from_inner_with_progress(inner.clone(), point_sets.clone(), &progress)
+            .unwrap();
+        assert_eq!(5, s.point_sets().len());
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort();
+        assert_eq!((0..=5).map(|i| (i, 5)).collect::<Vec<_>>(), reports);
+
+        let cancel = AtomicBool::new(true);
+        assert_eq!(
+            Err(Error::Cancelled),
+            M1Precomp::from_inner_with_progress(
+                inner,
+                point_sets,
+                &Progress::new().with_cancel(&cancel)
+            )
+            .map(|_| ())
+        );
+    }
 }
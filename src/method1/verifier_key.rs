@@ -0,0 +1,763 @@
+//! The part of a method 1 setup needed to verify proofs. This is the G1 generator and the
+//! powers of tau in G2, so a verifier doesn't have to hold the powers of tau in G1.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::Zero;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{marker::PhantomData, rand::RngCore, vec, vec::Vec, UniformRand};
+
+use super::{
+    degree_bound_challenge, degree_bound_shift, interp_commits_challenge, DegreeBoundProof,
+    M1NoPrecomp, Proof,
+};
+use crate::{
+    check_setup_size, check_verify_sizes, gen_powers,
+    lagrange::LagrangeInterpContext,
+    traits::{check_batch_sizes, MSMEngine, TranscriptProtocol},
+    transcribe_points_and_evals,
+    transcript::TranscriptEncoding,
+    vanishing_polynomial, Commitment, Error,
+};
+
+/// Verifies method 1 proofs made by a [`M1NoPrecomp`] with the same powers of tau. Rather than
+/// committing to the interpolation of the evaluations in G1, it's committed to in G2, so
+/// verifying takes one more pairing than with the full setup.
+#[derive(Clone)]
+pub struct VerifierKey<E: Pairing, M: MSMEngine<E = E>> {
+    /// The G1 generator
+    pub g1: E::G1Affine,
+    /// The powers of tau in G2, starting with the G2 generator
+    pub powers_of_g2: Vec<E::G2Affine>,
+    /// The number of powers of tau in G1 of the setup, used to check degree bounds
+    pub max_coeffs: usize,
+
+    g2_precomp: M::G2Prepared,
+    transcript_encoding: TranscriptEncoding,
+    _marker: PhantomData<M>,
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> core::fmt::Debug for VerifierKey<E, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VerifierKey")
+            .field("g1", &self.g1)
+            .field("powers_of_g2", &self.powers_of_g2)
+            .field("max_coeffs", &self.max_coeffs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> VerifierKey<E, M> {
+    /// Make a key from the G1 generator, the powers of tau in G2 and the number of powers of tau
+    /// in G1 of the setup. Fails with [`Error::SetupTooSmall`] without the G2 generator.
+    pub fn new(
+        g1: E::G1Affine,
+        powers_of_g2: Vec<E::G2Affine>,
+        max_coeffs: usize,
+    ) -> Result<Self, Error> {
+        check_setup_size(2, 1, powers_of_g2.len())?;
+        Ok(Self {
+            g1,
+            g2_precomp: M::prepare_g2(powers_of_g2.clone()),
+            powers_of_g2,
+            max_coeffs,
+            transcript_encoding: TranscriptEncoding::default(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Read opening transcripts with `encoding`, which must match the prover's. Keys made with
+    /// [`M1NoPrecomp::verifier_key`] have the setup's encoding.
+    pub fn with_transcript_encoding(mut self, encoding: TranscriptEncoding) -> Self {
+        self.transcript_encoding = encoding;
+        self
+    }
+
+    /// The G2 generator
+    pub fn g2(&self) -> E::G2Affine {
+        self.powers_of_g2[0]
+    }
+
+    /// The maximum number of points that can be verified at once
+    pub fn max_pts(&self) -> usize {
+        self.powers_of_g2.len().saturating_sub(1)
+    }
+
+    fn view(&self) -> VerifierView<'_, E, M> {
+        VerifierView {
+            g1: self.g1,
+            powers_of_g2: &self.powers_of_g2,
+            g2_precomp: &self.g2_precomp,
+            g1_precomp: None,
+            max_coeffs: self.max_coeffs,
+            transcript_encoding: &self.transcript_encoding,
+        }
+    }
+
+    /// Verify a proof made with [`PolyMultiProofNoPrecomp::open`](crate::traits::PolyMultiProofNoPrecomp::open)
+    pub fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.view()
+            .verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Verify a proof made with [`M1NoPrecomp::open_with_challenge`] against the same `gamma`
+    pub fn verify_with_challenge(
+        &self,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma: E::ScalarField,
+    ) -> Result<bool, Error> {
+        self.view()
+            .verify_with_challenge(commits, points, evals, proof, gamma)
+    }
+
+    /// Verifies many proofs at once with a single multi-pairing, see
+    /// [`PolyMultiProofNoPrecomp::verify_batch`](crate::traits::PolyMultiProofNoPrecomp::verify_batch)
+    #[allow(clippy::type_complexity)]
+    pub fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        let (g1s, g2s) = self.view().batch_pairing_terms(transcripts, items, rng)?;
+        Ok(M::multi_pairing_is_one(&g1s, &g2s))
+    }
+
+    /// Verify a proof made with [`M1NoPrecomp::open_single`]
+    pub fn verify_single(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point: E::ScalarField,
+        evals: &[E::ScalarField],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.view()
+            .verify_single(transcript, commits, point, evals, proof)
+    }
+
+    /// Verify a proof made with [`M1NoPrecomp::open_with_degree_bound`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_degree_bound(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        bound_proof: &DegreeBoundProof<E>,
+        degree_bound: usize,
+    ) -> Result<bool, Error> {
+        self.view().verify_with_degree_bound(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            bound_proof,
+            degree_bound,
+        )
+    }
+
+    /// Verify a proof made with [`M1NoPrecomp::open_with_interp_commits`]
+    pub fn verify_with_interp_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        interp_commits: &[Commitment<E>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.view()
+            .verify_with_interp_commits(transcript, commits, points, interp_commits, proof)
+    }
+}
+
+/// The parts of a setup proofs are checked against, borrowed from a [`VerifierKey`] or a
+/// [`M1NoPrecomp`] so both verify with the same code. A full setup has the powers of tau in G1
+/// too, so the interpolation of the evaluations is committed to in G1 and paired with the G2
+/// generator along with the commitments. Without them it's committed to in G2, which takes one
+/// more pairing.
+pub(crate) struct VerifierView<'a, E: Pairing, M: MSMEngine<E = E>> {
+    pub(crate) g1: E::G1Affine,
+    pub(crate) powers_of_g2: &'a [E::G2Affine],
+    pub(crate) g2_precomp: &'a M::G2Prepared,
+    pub(crate) g1_precomp: Option<&'a M::G1Prepared>,
+    pub(crate) max_coeffs: usize,
+    pub(crate) transcript_encoding: &'a TranscriptEncoding,
+}
+
+impl<'a, E: Pairing, M: MSMEngine<E = E>> VerifierView<'a, E, M> {
+    pub(crate) fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let gamma = self.gamma(transcript, commits, points, evals)?;
+        self.verify_with_challenge(commits, points, evals, proof, gamma)
+    }
+
+    pub(crate) fn verify_with_challenge(
+        &self,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma: E::ScalarField,
+    ) -> Result<bool, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+        let g2_zeros = self.g2_zeros(points)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let gammas = gen_powers(gamma, evals.len());
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+        Ok(match self.lhs(commits, gammas, gamma_ris)? {
+            (lhs, None) => M::pairing_eq_check(
+                lhs.into_affine(),
+                self.g2(),
+                proof.0,
+                g2_zeros.into_affine(),
+            ),
+            (lhs, Some(g2_interp)) => M::multi_pairing_is_one(
+                &E::G1::normalize_batch(&[lhs, -proof.0.into_group(), -self.g1.into_group()]),
+                &E::G2::normalize_batch(&[self.g2().into_group(), g2_zeros, g2_interp]),
+            ),
+        })
+    }
+
+    /// The points of the multi-pairing which checks all of `items` at once, combined with random
+    /// scalars from `rng`. Items at the same points share a lagrange context and vanishing
+    /// polynomial, so their interpolations are done together.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn batch_pairing_terms(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<(Vec<E::G1Affine>, Vec<E::G2Affine>), Error> {
+        check_batch_sizes(transcripts, items)?;
+        let gammas = transcripts
+            .iter_mut()
+            .zip(items)
+            .map(|(transcript, (commits, points, evals, _))| {
+                let gamma = self.gamma(transcript, commits, points, evals)?;
+                Ok(gen_powers(gamma, evals.len()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut point_sets: Vec<&[E::ScalarField]> = Vec::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (i, (_, points, _, _)) in items.iter().enumerate() {
+            match point_sets.iter().position(|p| p == points) {
+                Some(j) => groups[j].push(i),
+                None => {
+                    point_sets.push(points);
+                    groups.push(vec![i]);
+                }
+            }
+        }
+        // Everything paired with the G2 generator, or with the G1 generator, is summed first
+        let mut lhs = E::G1::zero();
+        let mut g2_interp = E::G2::zero();
+        let mut g1s = Vec::with_capacity(items.len() + 2);
+        let mut g2s = Vec::with_capacity(items.len() + 2);
+        for (points, group) in point_sets.into_iter().zip(groups) {
+            let g2_zeros = self.g2_zeros(points)?;
+            let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+            let evals_sets = group.iter().map(|&i| items[i].2).collect::<Vec<_>>();
+            let gammas_sets = group.iter().map(|&i| &gammas[i]).collect::<Vec<_>>();
+            let gamma_ris = lag_ctx.lagrange_interp_many(&evals_sets, &gammas_sets)?;
+            for (&i, gamma_ri) in group.iter().zip(gamma_ris) {
+                let (commits, _, _, proof) = items[i];
+                let (l, interp) = self.lhs(commits, gammas[i].clone(), gamma_ri.coeffs)?;
+                let r = E::ScalarField::rand(rng);
+                lhs += l * r;
+                if let Some(interp) = interp {
+                    g2_interp += interp * r;
+                }
+                g1s.push(-(proof.0 * r));
+                g2s.push(g2_zeros);
+            }
+        }
+        g1s.push(lhs);
+        g2s.push(self.g2().into_group());
+        if self.g1_precomp.is_none() {
+            g1s.push(-self.g1.into_group());
+            g2s.push(g2_interp);
+        }
+        Ok((E::G1::normalize_batch(&g1s), E::G2::normalize_batch(&g2s)))
+    }
+
+    pub(crate) fn verify_single(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        point: E::ScalarField,
+        evals: &[E::ScalarField],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.check_n_points(1)?;
+        let eval_rows = evals.iter().map(|e| [*e]).collect::<Vec<_>>();
+        check_verify_sizes(commits, &eval_rows, 1)?;
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            &[point],
+            &eval_rows,
+            self.transcript_encoding,
+        )?;
+
+        let gamma = self
+            .transcript_encoding
+            .challenge::<E::ScalarField>(transcript, b"open gamma");
+        let gammas = gen_powers::<E::ScalarField>(gamma, evals.len());
+        let gamma_eval = gammas
+            .iter()
+            .zip(evals)
+            .map(|(g, e)| *g * e)
+            .sum::<E::ScalarField>();
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let lhs = M::multi_scalar_mul_g1(&cms_prep, &gammas)? - self.g1 * gamma_eval;
+
+        let g2 = self.g2();
+        let x_minus_z = self.powers_of_g2[1].into_group() - g2 * point;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            g2,
+            proof.0,
+            x_minus_z.into_affine(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn verify_with_degree_bound(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        bound_proof: &DegreeBoundProof<E>,
+        degree_bound: usize,
+    ) -> Result<bool, Error> {
+        let shift = degree_bound_shift(self.max_coeffs, self.powers_of_g2.len(), degree_bound)?;
+        if !self.verify(transcript, commits, points, evals, proof)? {
+            return Ok(false);
+        }
+
+        let gamma = degree_bound_challenge::<E::ScalarField>(
+            transcript,
+            degree_bound,
+            self.transcript_encoding,
+        );
+        let gammas = gen_powers::<E::ScalarField>(gamma, commits.len());
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let csum = M::multi_scalar_mul_g1(&cms_prep, &gammas)?;
+        Ok(M::pairing_eq_check(
+            csum.into_affine(),
+            self.powers_of_g2[shift],
+            bound_proof.0,
+            self.g2(),
+        ))
+    }
+
+    pub(crate) fn verify_with_interp_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        interp_commits: &[Commitment<E>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if interp_commits.len() != commits.len() {
+            return Err(Error::EvalsAndCommitsDifferentSizes {
+                n_evals: interp_commits.len(),
+                n_commits: commits.len(),
+            });
+        }
+        if commits.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        self.check_n_points(points.len())?;
+        let gamma = interp_commits_challenge(
+            transcript,
+            commits,
+            points,
+            interp_commits,
+            self.transcript_encoding,
+        )?;
+        let gammas = gen_powers(gamma, commits.len());
+        let diffs = commits
+            .iter()
+            .zip(interp_commits)
+            .map(|(c, r)| c.0.into_group() - r.0)
+            .collect::<Vec<_>>();
+        let diffs_prep = M::prepare_g1(E::G1::normalize_batch(&diffs));
+        let lhs = M::multi_scalar_mul_g1(&diffs_prep, gammas)?;
+
+        let g2_zeros = self.g2_zeros(points)?;
+        Ok(M::pairing_eq_check(
+            lhs.into_affine(),
+            self.g2(),
+            proof.0,
+            g2_zeros.into_affine(),
+        ))
+    }
+
+    fn g2(&self) -> E::G2Affine {
+        self.powers_of_g2[0]
+    }
+
+    /// Checks the sizes of an opening to verify and that there are enough powers of tau in G2
+    /// for its points, then transcribes it and draws the challenge used to combine the
+    /// polynomials
+    fn gamma(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<E::ScalarField, Error> {
+        self.check_n_points(points.len())?;
+        check_verify_sizes(commits, evals, points.len())?;
+        transcribe_points_and_evals(transcript, commits, points, evals, self.transcript_encoding)?;
+        Ok(self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma"))
+    }
+
+    /// Checks there are enough powers of tau in G2 to verify at `n_points` points
+    fn check_n_points(&self, n_points: usize) -> Result<(), Error> {
+        let max_points = self.powers_of_g2.len().saturating_sub(1);
+        if n_points > max_points {
+            return Err(Error::TooManyPoints {
+                n_points,
+                max_points,
+            });
+        }
+        Ok(())
+    }
+
+    /// $[Z(x)]_2$ for the vanishing polynomial $Z$ of `points`
+    fn g2_zeros(&self, points: &[E::ScalarField]) -> Result<E::G2, Error> {
+        self.check_n_points(points.len())?;
+        M::multi_scalar_mul_g2(self.g2_precomp, vanishing_polynomial(points).coeffs)
+    }
+
+    /// The proof is valid when $e(\sum \gamma^i C_i - [r(x)]_1, g_2) = e(\pi, [Z(x)]_2)$, where
+    /// $r = \sum \gamma^i r_i$ interpolates the combined evaluations. Without the powers of tau
+    /// in G1 it's checked as $e(\sum \gamma^i C_i, g_2) = e(\pi, [Z(x)]_2) e(g_1, [r(x)]_2)$
+    /// instead. Returns the left hand side paired with $g_2$, and $[r(x)]_2$ if it's needed.
+    #[allow(clippy::type_complexity)]
+    fn lhs(
+        &self,
+        commits: &[Commitment<E>],
+        gammas: Vec<E::ScalarField>,
+        gamma_ris: Vec<E::ScalarField>,
+    ) -> Result<(E::G1, Option<E::G2>), Error> {
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let lhs = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
+        Ok(match self.g1_precomp {
+            Some(g1_precomp) => (lhs - M::multi_scalar_mul_g1(g1_precomp, gamma_ris)?, None),
+            None => (
+                lhs,
+                Some(M::multi_scalar_mul_g2(self.g2_precomp, gamma_ris)?),
+            ),
+        })
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
+    /// The key for verifying this setup's proofs, without the powers of tau in G1. Fails with
+    /// [`Error::SetupTooSmall`] if the setup doesn't have the generators.
+    pub fn verifier_key(&self) -> Result<VerifierKey<E, M>, Error> {
+        check_setup_size(1, 1, self.powers_of_g1.len())?;
+        Ok(VerifierKey::new(
+            self.powers_of_g1[0],
+            self.powers_of_g2.clone(),
+            self.powers_of_g1.len(),
+        )?
+        .with_transcript_encoding(self.transcript_encoding))
+    }
+
+    /// The setup's powers of tau for verifying with, including the ones in G1 so the
+    /// interpolation of the evaluations is committed to there
+    pub(crate) fn verifier_view(&self) -> Result<VerifierView<'_, E, M>, Error> {
+        check_setup_size(1, 1, self.powers_of_g1.len())?;
+        Ok(VerifierView {
+            g1: self.powers_of_g1[0],
+            powers_of_g2: &self.powers_of_g2,
+            g2_precomp: &self.g2_precomp,
+            g1_precomp: Some(&self.g1_precomp),
+            max_coeffs: self.powers_of_g1.len(),
+            transcript_encoding: &self.transcript_encoding,
+        })
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalSerialize for VerifierKey<E, M> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.g1.serialize_with_mode(&mut writer, compress)?;
+        self.powers_of_g2
+            .serialize_with_mode(&mut writer, compress)?;
+        (self.max_coeffs as u64).serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.g1.serialized_size(compress)
+            + self.powers_of_g2.serialized_size(compress)
+            + (self.max_coeffs as u64).serialized_size(compress)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Valid for VerifierKey<E, M> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g1.check()?;
+        self.powers_of_g2.check()
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> CanonicalDeserialize for VerifierKey<E, M> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let g1 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        let powers_of_g2 = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let max_coeffs = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        Self::new(g1, powers_of_g2, max_coeffs as usize)
+            .map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::VerifierKey;
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        testing::gen_openings,
+        traits::{MSMEngine, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    fn test_verifier_key<M: MSMEngine<E = Bls12_381>>() {
+        let s = M1NoPrecomp::<Bls12_381, M>::new(64, 8, &mut test_rng());
+        let vk = s.verifier_key().unwrap();
+        let mut bytes = Vec::new();
+        vk.serialize_compressed(&mut bytes).unwrap();
+        let vk = VerifierKey::<Bls12_381, M>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(64, vk.max_coeffs);
+        assert_eq!(8, vk.max_pts());
+
+        let points = (0..8u64).map(Fr::from).collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 4, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"vk"), &evals, &coeffs, &points)
+            .unwrap();
+        assert!(vk
+            .verify(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+            .unwrap());
+        let mut bad_evals = evals.clone();
+        bad_evals[1][2] += Fr::from(1u64);
+        assert!(!vk
+            .verify(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                &points,
+                &bad_evals,
+                &proof
+            )
+            .unwrap());
+        assert_eq!(
+            Err(Error::TooManyPoints {
+                n_points: 9,
+                max_points: 8
+            }),
+            vk.verify(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                &[points.clone(), vec![Fr::from(8u64)]].concat(),
+                &vec![vec![Fr::from(0u64); 9]; 4],
+                &proof
+            )
+        );
+
+        // The other kinds of proofs
+        let gamma = Fr::rand(&mut test_rng());
+        let proof = s.open_with_challenge(&coeffs, &points[..3], gamma).unwrap();
+        let short_evals = evals.iter().map(|e| &e[..3]).collect::<Vec<_>>();
+        assert!(vk
+            .verify_with_challenge(&commits, &points[..3], &short_evals, &proof, gamma)
+            .unwrap());
+
+        let single_evals = evals.iter().map(|e| e[0]).collect::<Vec<_>>();
+        let proof = s
+            .open_single(
+                &mut Transcript::new(b"vk"),
+                &single_evals,
+                &coeffs,
+                points[0],
+            )
+            .unwrap();
+        assert!(vk
+            .verify_single(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                points[0],
+                &single_evals,
+                &proof
+            )
+            .unwrap());
+
+        let (proof, bound_proof) = s
+            .open_with_degree_bound(&mut Transcript::new(b"vk"), &evals, &coeffs, &points, 64)
+            .unwrap();
+        assert!(vk
+            .verify_with_degree_bound(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+                &bound_proof,
+                64
+            )
+            .unwrap());
+
+        let interp_commits = s.commit_interpolations(&points, &evals).unwrap();
+        let proof = s
+            .open_with_interp_commits(
+                &mut Transcript::new(b"vk"),
+                &interp_commits,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        assert!(vk
+            .verify_with_interp_commits(
+                &mut Transcript::new(b"vk"),
+                &commits,
+                &points,
+                &interp_commits,
+                &proof
+            )
+            .unwrap());
+
+        // A batch of openings at different points
+        let proofs = [0..8, 2..6]
+            .into_iter()
+            .map(|range| {
+                let pts = &points[range.clone()];
+                let evals = evals
+                    .iter()
+                    .map(|e| e[range.clone()].to_vec())
+                    .collect::<Vec<_>>();
+                let proof = s
+                    .open(&mut Transcript::new(b"vk"), &evals, &coeffs, pts)
+                    .unwrap();
+                (pts, evals, proof)
+            })
+            .collect::<Vec<_>>();
+        let items = proofs
+            .iter()
+            .map(|(pts, evals, proof)| (&commits[..], *pts, &evals[..], proof))
+            .collect::<Vec<_>>();
+        let mut transcripts = vec![Transcript::new(b"vk"); 2];
+        assert!(vk
+            .verify_batch(&mut transcripts, &items, &mut test_rng())
+            .unwrap());
+        let mut items = items;
+        items[1].3 = &proofs[0].2;
+        let mut transcripts = vec![Transcript::new(b"vk"); 2];
+        assert!(!vk
+            .verify_batch(&mut transcripts, &items, &mut test_rng())
+            .unwrap());
+    }
+
+    #[test]
+    fn test_new_checks_sizes() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 2,
+                needed: 1,
+                got: 0
+            }),
+            VerifierKey::<Bls12_381, BlstMSMEngine>::new(s.powers_of_g1[0], Vec::new(), 64)
+                .map(|_| ())
+        );
+        let empty = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_affine(
+            Vec::new(),
+            s.powers_of_g2.clone(),
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 1,
+                got: 0
+            }),
+            empty.verifier_key().map(|_| ())
+        );
+
+        // A key with no G2 generator doesn't deserialize
+        let mut bytes = Vec::new();
+        s.powers_of_g1[0].serialize_compressed(&mut bytes).unwrap();
+        Vec::<ark_bls12_381::G2Affine>::new()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        64u64.serialize_compressed(&mut bytes).unwrap();
+        assert!(
+            VerifierKey::<Bls12_381, BlstMSMEngine>::deserialize_compressed(&bytes[..]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_verifier_key_ark() {
+        test_verifier_key::<ArkMSMEngine<Bls12_381>>();
+    }
+
+    #[test]
+    fn test_verifier_key_blst() {
+        test_verifier_key::<BlstMSMEngine>();
+    }
+}
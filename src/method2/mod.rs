@@ -1,52 +1,124 @@
 //! # BDFG Method 2
 use crate::{
-    check_opening_sizes, check_verify_sizes,
+    check_opening_sizes, check_poly_sizes, check_verify_sizes,
     lagrange::LagrangeInterpContext,
-    traits::{Committer, PolyMultiProofNoPrecomp},
+    lagrange_basis::{check_evals_len, find_basis, LagrangeBasis},
+    poly_ops::{div_by_linear, evaluate_at_points},
+    streaming::StreamingCommitter,
+    traits::{
+        check_batch_sizes, g1_compressed_size, Committer, MSMEngine, MemoryEstimate,
+        PolyMultiProofNoPrecomp, SchemeInfo, TranscriptProtocol,
+    },
+    transcript::TranscriptEncoding,
+};
+use ark_ff::Zero;
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    DenseUVPolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
 };
-use ark_ff::One;
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{vec, vec::Vec, UniformRand};
-use core::ops::{Div, Mul, Sub};
-use merlin::Transcript;
+use ark_std::{end_timer, marker::PhantomData, start_timer, vec::Vec, UniformRand};
+use core::{mem::size_of, ops::Mul};
 
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_std::rand::RngCore;
 
-use crate::{
-    get_challenge, get_field_size, transcribe_generic, transcribe_points_and_evals, Commitment,
-};
+use crate::{transcribe_points_and_evals, Commitment};
 
 use crate::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+    check_setup_size, gen_curve_powers, gen_powers, linear_combination, poly_div_q_r_in_place,
+    vanishing_polynomial, Error,
 };
 
 pub mod precompute;
 
 /// A method 2 proof scheme with no precomputation of lagrange polynomials
 #[derive(Clone, Debug)]
-pub struct M2NoPrecomp<E: Pairing> {
+pub struct M2NoPrecomp<E: Pairing, M: MSMEngine<E = E>> {
     /// The given powers tau in G1
     pub powers_of_g1: Vec<E::G1Affine>,
     /// The G2 generator
     pub g2: E::G2Affine,
     /// The G2 generator multiplied by tau
     pub g2x: E::G2Affine,
+
+    // The precomputed powers of the G1 generator
+    // When using arkworks, these just duplicate the affine points above
+    pub(crate) g1_precomp: M::G1Prepared,
+
+    /// Lagrange bases used for committing to polynomials in evaluation form
+    pub lagrange_bases: Vec<LagrangeBasis<E, M>>,
+
+    /// How points and evaluations are written into the opening transcript
+    transcript_encoding: TranscriptEncoding,
+
+    _marker: PhantomData<M>,
 }
 
-impl<E: Pairing> M2NoPrecomp<E> {
-    /// Make a new scheme from the given powers of tau and generators in affine form
+impl<E: Pairing, M: MSMEngine<E = E>> M2NoPrecomp<E, M> {
+    /// Make a new scheme from the given powers of tau and generators in affine form. The sizes
+    /// aren't checked, see [`Self::new_from_affine_checked`]
     pub fn new_from_affine(
         powers_of_g1: Vec<E::G1Affine>,
         g2: E::G2Affine,
         g2x: E::G2Affine,
     ) -> Self {
         Self {
+            g1_precomp: M::prepare_g1(powers_of_g1.clone()),
             powers_of_g1,
             g2,
             g2x,
+            lagrange_bases: Vec::new(),
+            transcript_encoding: TranscriptEncoding::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Make a new scheme from the given powers of tau and generators in affine form, checking
+    /// that there are enough powers for polynomials with up to `max_coeffs` coefficients. A setup
+    /// that's too small fails here with [`Error::SetupTooSmall`], rather than when it's used.
+    pub fn new_from_affine_checked(
+        powers_of_g1: Vec<E::G1Affine>,
+        g2: E::G2Affine,
+        g2x: E::G2Affine,
+        max_coeffs: usize,
+    ) -> Result<Self, Error> {
+        check_setup_size(1, max_coeffs, powers_of_g1.len())?;
+        Ok(Self::new_from_affine(powers_of_g1, g2, g2x))
+    }
+
+    /// The most coefficients a committed polynomial can have
+    pub fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
+    }
+
+    /// Add a lagrange basis for `domain` so that `commit_evals` over it doesn't need an IFFT
+    pub fn with_lagrange_basis(
+        mut self,
+        domain: Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Self, Error> {
+        if find_basis(&self.lagrange_bases, &domain).is_none() {
+            self.lagrange_bases
+                .push(LagrangeBasis::new(&self.powers_of_g1, domain)?);
         }
+        Ok(self)
+    }
+
+    /// Write field elements and points into the opening transcript, and read its challenges, with
+    /// `encoding`. Schemes built on this setup use it too.
+    pub fn with_transcript_encoding(mut self, encoding: TranscriptEncoding) -> Self {
+        self.transcript_encoding = encoding;
+        self
+    }
+
+    /// The encoding of the opening transcript
+    pub fn transcript_encoding(&self) -> &TranscriptEncoding {
+        &self.transcript_encoding
+    }
+
+    /// Make a committer which accepts the coefficients of a polynomial in chunks
+    pub fn streaming_committer(&self) -> StreamingCommitter<'_, E, M> {
+        StreamingCommitter::new(&self.powers_of_g1)
     }
 
     /// Make a new scheme from the given powers of tau and generators in projective form
@@ -60,16 +132,50 @@ impl<E: Pairing> M2NoPrecomp<E> {
 
     /// Generate a new scheme with random generators and powers of tau
     pub fn new(max_coeffs: usize, rng: &mut impl RngCore) -> Self {
-        let x = E::ScalarField::rand(rng);
+        #[allow(unused_mut)]
+        let mut x = E::ScalarField::rand(rng);
         let g1 = E::G1::rand(rng);
         let g2 = E::G2::rand(rng);
-        Self::new_from_scalar(x, g1, g2, max_coeffs)
+        let res = Self::new_from_scalar_ref(&x, g1, g2, max_coeffs);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x);
+        res
+    }
+
+    /// Generate a new scheme from a secret scalar without copying it. The powers of the secret
+    /// are wiped from memory once the setup is generated, and the secret itself when `x` is
+    /// dropped.
+    #[cfg(feature = "zeroize")]
+    pub fn new_from_secret(
+        x: &zeroize::Zeroizing<E::ScalarField>,
+        g1: E::G1,
+        g2: E::G2,
+        max_coeffs: usize,
+    ) -> Self {
+        Self::new_from_scalar_ref(x, g1, g2, max_coeffs)
+    }
+
+    /// Generate a new scheme from a known secret scalar. With the `zeroize` feature, the scalar
+    /// passed in is wiped once the setup is generated, but the caller's copy isn't, see
+    /// `new_from_secret`.
+    pub fn new_from_scalar(
+        #[allow(unused_mut)] mut x: E::ScalarField,
+        g1: E::G1,
+        g2: E::G2,
+        max_coeffs: usize,
+    ) -> Self {
+        let res = Self::new_from_scalar_ref(&x, g1, g2, max_coeffs);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x);
+        res
     }
 
-    /// Generate a new scheme from a known secret scalar
-    pub fn new_from_scalar(x: E::ScalarField, g1: E::G1, g2: E::G2, max_coeffs: usize) -> Self {
-        let x_powers = gen_powers(x, max_coeffs);
+    fn new_from_scalar_ref(x: &E::ScalarField, g1: E::G1, g2: E::G2, max_coeffs: usize) -> Self {
+        #[allow(unused_mut)]
+        let mut x_powers = gen_powers(*x, max_coeffs);
         let powers_of_g1 = gen_curve_powers::<E::G1>(x_powers.as_ref(), g1);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x_powers);
         let g2x = (g2 * x).into_affine();
 
         Self::new_from_affine(powers_of_g1, g2.into_affine(), g2x)
@@ -80,52 +186,99 @@ impl<E: Pairing> M2NoPrecomp<E> {
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<E: Pairing>(pub E::G1Affine, pub E::G1Affine);
 
-impl<E: Pairing> M2NoPrecomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> M2NoPrecomp<E, M> {
+    /// Open the polynomials at `points` as in [`PolyMultiProofNoPrecomp::open`], absorbing the
+    /// caller's `commits` to them rather than committing to every polynomial again, see
+    /// [`M1NoPrecomp::open_with_commits`](crate::method1::M1NoPrecomp::open_with_commits)
+    pub fn open_with_commits(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+        let vp = vanishing_polynomial(points);
+        self.open_absorbing(transcript, commits, evals, polys, points, &vp)
+    }
+
     fn open_with_vanishing_poly(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<Proof<E>, Error> {
         check_opening_sizes(evals, polys, points.len())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        let commits = self
+            .transcript_encoding
+            .prover_commits(polys.len(), |i| self.commit(&polys[i]))?;
+        self.open_absorbing(transcript, &commits, evals, polys, points, vp)
+    }
 
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
-
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-
-        let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
-        let gamma_fis = linear_combination::<E::ScalarField>(polys, &gammas)
+    /// Opens the polynomials at `points`, absorbing `commits` if the transcript version absorbs
+    /// commitments
+    fn open_absorbing(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        check_opening_sizes(evals, polys, points.len())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            points,
+            evals,
+            &self.transcript_encoding,
+        )?;
+
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let mut l = linear_combination::<E::ScalarField>(polys, &gammas)
             .ok_or(Error::NoPolynomialsGiven)?;
-        let gamma_fis_poly = DensePolynomial::from_coefficients_vec(gamma_fis);
-
-        let (h, gamma_ris_over_zs) = poly_div_q_r((&gamma_fis_poly).into(), (vp).into())?;
 
-        let w_1 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &h)?.into_affine();
+        // The division leaves the remainder in place, and l needs the full combination
+        let mut gamma_ris_over_zs = l.clone();
+        let h = poly_div_q_r_in_place(&mut gamma_ris_over_zs, &vp.coeffs)?;
 
-        transcribe_generic(transcript, b"open W1", &w_1)?;
-        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+        let w_1 = M::multi_scalar_mul_g1(&self.g1_precomp, &h)?.into_affine();
 
-        let gamma_ri_z = DensePolynomial::from_coefficients_vec(gamma_ris_over_zs)
-            .mul(vp)
-            .evaluate(&chal_z);
+        let encoding = &self.transcript_encoding;
+        encoding.append_point(transcript, b"open W1", &w_1)?;
+        let chal_z = encoding.challenge(transcript, b"open z");
 
-        let f_z = gamma_fis_poly.sub(&DensePolynomial::from_coefficients_vec(vec![gamma_ri_z])); // XXX
-        let l = f_z.sub(&DensePolynomial::from_coefficients_vec(h).mul(vp.evaluate(&chal_z)));
+        let vp_z = vp.evaluate(&chal_z);
+        let gamma_ri_z = evaluate_at_points(&gamma_ris_over_zs, &[chal_z])[0] * vp_z;
 
-        let x_minus_z =
-            DensePolynomial::from_coefficients_vec(vec![-chal_z, E::ScalarField::one()]);
-        let l_quotient = l.div(&x_minus_z);
+        // l = f - gamma_ri_z - Z(z) h, computed in place
+        if l.is_empty() {
+            l.push(E::ScalarField::zero());
+        }
+        l[0] -= gamma_ri_z;
+        for (l_i, h_i) in l.iter_mut().zip(&h) {
+            *l_i -= vp_z * h_i;
+        }
+        let l_quotient = div_by_linear(&l, chal_z);
 
-        let w_2 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &l_quotient)?.into_affine();
+        let w_2 = M::multi_scalar_mul_g1(&self.g1_precomp, &l_quotient)?.into_affine();
         Ok(Proof(w_1, w_2))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn verify_with_lag_ctx_vanishing_poly(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
@@ -133,14 +286,46 @@ impl<E: Pairing> M2NoPrecomp<E> {
         lag_ctx: &LagrangeInterpContext<E::ScalarField>,
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<bool, Error> {
-        check_verify_sizes(commits, evals, points.len())?;
+        let (f, chal_z) = self
+            .verifier_terms_with_lag_ctx(transcript, commits, points, evals, proof, lag_ctx, vp)?;
+        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
+        Ok(M::pairing_eq_check(
+            f.into_affine(),
+            self.g2,
+            proof.1,
+            x_minus_z.into_affine(),
+        ))
+    }
 
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
+    /// Computes the G1 element `f` paired with the G2 generator in the verification equation
+    /// along with the challenge point `z`
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn verifier_terms_with_lag_ctx(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<(E::G1, E::ScalarField), Error> {
+        check_verify_sizes(commits, evals, points.len())?;
 
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        transcribe_generic(transcript, b"open W1", &proof.0)?;
-        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            points,
+            evals,
+            &self.transcript_encoding,
+        )?;
+
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+        let encoding = &self.transcript_encoding;
+        encoding.append_point(transcript, b"open W1", &proof.0)?;
+        let chal_z = encoding.challenge(transcript, b"open z");
 
         let zeros_z = vp.evaluate(&chal_z);
 
@@ -153,50 +338,171 @@ impl<E: Pairing> M2NoPrecomp<E> {
         let gamma_ris_z_pt = self.powers_of_g1[0].mul(gamma_ris_z);
 
         // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let gamma_cm_pt = crate::curve_msm::<E::G1>(&cms, gammas.as_ref())?;
+        let cms_prep = M::prepare_g1(commits.iter().map(|i| i.0).collect());
+        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, &gammas)?;
 
         let f = gamma_cm_pt - gamma_ris_z_pt - proof.0.mul(zeros_z);
+        Ok((f, chal_z))
+    }
 
-        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
-        Ok(E::pairing(f, self.g2) == E::pairing(proof.1, x_minus_z))
+    /// Checks that $e(f_k, g_2) = e(W_{2, k}, [x - z_k]_2)$ for every `(f_k, W_2k, z_k)` in
+    /// `terms` by taking a random linear combination, which leaves a single pair of pairings
+    pub(crate) fn batch_pairing_check(
+        &self,
+        terms: &[(E::G1, E::G1Affine, E::ScalarField)],
+        rng: &mut impl RngCore,
+    ) -> bool {
+        // e(sum r_k (f_k + z_k W_2k), g2) == e(sum r_k W_2k, g2x)
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        for (f, w2, z) in terms {
+            let r = E::ScalarField::rand(rng);
+            lhs += (*f + w2.mul(*z)) * r;
+            rhs += w2.mul(r);
+        }
+        M::multi_pairing_is_one(&E::G1::normalize_batch(&[lhs, -rhs]), &[self.g2, self.g2x])
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for M2NoPrecomp<E, M> {
+    fn max_coeffs(&self) -> usize {
+        M2NoPrecomp::max_coeffs(self)
+    }
+
+    fn max_pts(&self) -> usize {
+        // The vanishing polynomial is evaluated at a challenge rather than committed to, so the
+        // setup doesn't limit the number of points
+        usize::MAX
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m2-no-precomp"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        2 * g1_compressed_size::<E>()
+    }
+
+    /// The powers of tau in G1 are held both as affine points and prepared for the engine.
+    /// Opening holds the combined polynomial and two quotients, and interpolating the
+    /// evaluations holds the lagrange polynomials of the points. There's no precomputation.
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, _: usize) -> MemoryEstimate {
+        MemoryEstimate {
+            srs: 2 * max_coeffs * size_of::<E::G1Affine>() + 2 * size_of::<E::G2Affine>(),
+            precompute: 0,
+            per_open: (3 * max_coeffs + max_pts * (max_pts + 3)) * size_of::<E::ScalarField>(),
+        }
     }
 }
 
-impl<E: Pairing> Committer<E> for M2NoPrecomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M2NoPrecomp<E, M> {
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())?;
+        let res = M::multi_scalar_mul_g1(&self.g1_precomp, poly.as_ref())?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        match find_basis(&self.lagrange_bases, domain) {
+            Some(basis) => basis.commit_evals(evals),
+            None => {
+                check_evals_len(evals.as_ref(), domain)?;
+                self.commit(domain.ifft(evals.as_ref()))
+            }
+        }
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = M::sparse_multi_scalar_mul_g1(&self.powers_of_g1, poly)?;
         Ok(Commitment(res.into_affine()))
     }
+
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let res = M::batch_multi_scalar_mul_g1(&self.g1_precomp, polys)?;
+        Ok(E::G1::normalize_batch(&res)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
 }
 
-impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E, M> {
     type Proof = Proof<E>;
 
     fn open(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
     ) -> Result<Proof<E>, Error> {
-        let vp = vanishing_polynomial(points.as_ref());
-        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+        let timer = start_timer!(|| {
+            ark_std::format!("open {} polys at {} points", polys.len(), points.len())
+        });
+        let vp = vanishing_polynomial(points);
+        let res = self.open_with_vanishing_poly(transcript, evals, polys, points, &vp);
+        end_timer!(timer);
+        res
     }
 
     fn verify(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
+        let timer = start_timer!(|| {
+            ark_std::format!(
+                "verify {} commits at {} points",
+                commits.len(),
+                points.len()
+            )
+        });
         let vp = vanishing_polynomial(points);
         let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_vanishing_poly(
+        let res = self.verify_with_lag_ctx_vanishing_poly(
             transcript, commits, points, evals, proof, &lag_ctx, &vp,
-        )
+        );
+        end_timer!(timer);
+        res
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        let terms = transcripts
+            .iter_mut()
+            .zip(items)
+            .map(|(transcript, (commits, points, evals, proof))| {
+                let vp = vanishing_polynomial(points);
+                let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+                let (f, chal_z) = self.verifier_terms_with_lag_ctx(
+                    transcript, commits, points, evals, proof, &lag_ctx, &vp,
+                )?;
+                Ok((f, proof.1, chal_z))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(self.batch_pairing_check(&terms, rng))
     }
 }
 
@@ -204,15 +510,69 @@ impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
 mod tests {
     use super::M2NoPrecomp;
     use crate::{
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
         test_rng,
-        testing::{test_basic_no_precomp, test_size_errors},
+        testing::{
+            test_basic_no_precomp, test_batch_no_precomp, test_custom_transcript_no_precomp,
+            test_linear_combination_no_precomp, test_size_errors,
+        },
+        Error,
     };
     use ark_bls12_381::Bls12_381;
 
     #[test]
     fn test_basic_open_works() {
-        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let s = M2NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, &mut test_rng());
+        test_basic_no_precomp(&s);
+        test_size_errors(&s);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
         test_basic_no_precomp(&s);
         test_size_errors(&s);
+
+        assert_eq!(256, s.max_coeffs());
+        let checked = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_affine_checked(
+            s.powers_of_g1.clone(),
+            s.g2,
+            s.g2x,
+            256,
+        )
+        .unwrap();
+        test_basic_no_precomp(&checked);
+        assert_eq!(
+            Some(Error::SetupTooSmall {
+                group: 1,
+                needed: 257,
+                got: 256
+            }),
+            M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_affine_checked(
+                s.powers_of_g1.clone(),
+                s.g2,
+                s.g2x,
+                257
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_works() {
+        let s = M2NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, &mut test_rng());
+        test_batch_no_precomp(&s);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        test_batch_no_precomp(&s);
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        test_linear_combination_no_precomp(&s);
+    }
+
+    #[test]
+    fn test_custom_transcript_works() {
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        test_custom_transcript_no_precomp(&s);
     }
 }
@@ -1,31 +1,38 @@
 //! # BDFG Method 2 with precomputation
 use ark_ec::pairing::Pairing;
-use ark_poly::univariate::DensePolynomial;
-use ark_std::vec::Vec;
-use merlin::Transcript;
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    Radix2EvaluationDomain,
+};
+use ark_std::{rand::RngCore, vec::Vec};
+use core::mem::size_of;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 use super::{vanishing_polynomial, Error, Proof};
 use crate::lagrange::LagrangeInterpContext;
-use crate::traits::{Committer, PolyMultiProof};
+use crate::poly_ops::evaluate_at_points;
+use crate::traits::{
+    check_batch_sizes, g1_compressed_size, Committer, MSMEngine, MemoryEstimate, PolyMultiProof,
+    SchemeInfo, TranscriptProtocol,
+};
 use crate::{cfg_iter, Commitment};
 
 /// Method 2 with precomputation
-#[derive(Clone, Debug)]
-pub struct M2Precomp<E: Pairing> {
+#[derive(Clone)]
+pub struct M2Precomp<E: Pairing, M: MSMEngine<E = E>> {
     /// The inner method 2 object without precomputation
-    pub inner: super::M2NoPrecomp<E>,
+    pub inner: super::M2NoPrecomp<E, M>,
     point_sets: Vec<Vec<E::ScalarField>>,
     vanishing_polys: Vec<DensePolynomial<E::ScalarField>>,
     lagrange_ctxs: Vec<LagrangeInterpContext<E::ScalarField>>,
 }
 
-impl<E: Pairing> M2Precomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> M2Precomp<E, M> {
     /// Make a precompute-optimized version of a method 2 object for the given sets of points
     pub fn from_inner(
-        inner: super::M2NoPrecomp<E>,
+        inner: super::M2NoPrecomp<E, M>,
         point_sets: Vec<Vec<E::ScalarField>>,
     ) -> Result<Self, Error> {
         let vanishing_polys = cfg_iter!(point_sets)
@@ -42,27 +49,82 @@ impl<E: Pairing> M2Precomp<E> {
             lagrange_ctxs,
         })
     }
+
+    fn check_point_set_index(&self, index: usize) -> Result<(), Error> {
+        if index >= self.point_sets.len() {
+            return Err(Error::PointSetIndexOutOfRange {
+                idx: index,
+                n_point_sets: self.point_sets.len(),
+            });
+        }
+        Ok(())
+    }
 }
 
-impl<E: Pairing> Committer<E> for M2Precomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> SchemeInfo for M2Precomp<E, M> {
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
+    }
+
+    fn max_pts(&self) -> usize {
+        usize::MAX
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m2-precomp"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        2 * g1_compressed_size::<E>()
+    }
+
+    /// Each point set holds its points, vanishing polynomial and lagrange polynomials, so
+    /// openings only hold the combined polynomial and two quotients
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate {
+        let f = size_of::<E::ScalarField>();
+        MemoryEstimate {
+            precompute: num_point_sets * (2 * max_pts + 1 + max_pts * max_pts) * f,
+            per_open: 3 * max_coeffs * f,
+            ..super::M2NoPrecomp::<E, M>::memory_estimate(max_coeffs, max_pts, num_point_sets)
+        }
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M2Precomp<E, M> {
     fn commit(
         &self,
         poly: impl AsRef<[<E as Pairing>::ScalarField]>,
     ) -> Result<Commitment<E>, Error> {
         self.inner.commit(poly)
     }
+
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_evals(evals, domain)
+    }
+
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.inner.commit_sparse(poly)
+    }
 }
 
-impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
+impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M2Precomp<E, M> {
     type Proof = Proof<E>;
 
     fn open(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         point_set_index: usize,
     ) -> Result<Proof<E>, Error> {
+        self.check_point_set_index(point_set_index)?;
         self.inner.open_with_vanishing_poly(
             transcript,
             evals,
@@ -74,12 +136,13 @@ impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
 
     fn verify(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         point_set_index: usize,
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
+        self.check_point_set_index(point_set_index)?;
         self.inner.verify_with_lag_ctx_vanishing_poly(
             transcript,
             commits,
@@ -90,23 +153,134 @@ impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
             &self.vanishing_polys[point_set_index],
         )
     }
+
+    fn compute_evals(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error> {
+        self.check_point_set_index(point_set_index)?;
+        let points = &self.point_sets[point_set_index];
+        Ok(polys
+            .iter()
+            .map(|p| evaluate_at_points(p.as_ref(), points))
+            .collect())
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            usize,
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        let terms = transcripts
+            .iter_mut()
+            .zip(items)
+            .map(|(transcript, (commits, point_set_index, evals, proof))| {
+                self.check_point_set_index(*point_set_index)?;
+                let (f, chal_z) = self.inner.verifier_terms_with_lag_ctx(
+                    transcript,
+                    commits,
+                    &self.point_sets[*point_set_index],
+                    evals,
+                    proof,
+                    &self.lagrange_ctxs[*point_set_index],
+                    &self.vanishing_polys[*point_set_index],
+                )?;
+                Ok((f, proof.1, chal_z))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(self.inner.batch_pairing_check(&terms, rng))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::M2Precomp;
-    use crate::{method2::M2NoPrecomp, test_rng, testing::test_basic_precomp};
+    use crate::{
+        method2::M2NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        testing::{gen_openings, test_basic_precomp, test_batch_precomp},
+        traits::PolyMultiProof,
+        Error,
+    };
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
 
     #[test]
     fn test_basic_open_works() {
         let points = (0..30)
             .map(|_| Fr::rand(&mut test_rng()))
             .collect::<Vec<_>>();
-        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
-        let s = M2Precomp::<Bls12_381>::from_inner(s, vec![points.clone()])
-            .expect("Failed to construct");
-        test_basic_precomp(&s, &points)
+        let s = M2NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, &mut test_rng());
+        let s = M2Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
+        test_basic_precomp(&s, &points);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        let s = M2Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
+        test_basic_precomp(&s, &points);
+    }
+
+    #[test]
+    fn test_batch_verify_works() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..10)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        let s = M2Precomp::from_inner(s, point_sets.clone()).expect("Failed to construct");
+        test_batch_precomp(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_point_set_index_errors() {
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        let s = M2Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        let proof = s
+            .open(&mut Transcript::new(b"test"), &evals, &coeffs, 0)
+            .unwrap();
+        let out_of_range = Err(Error::PointSetIndexOutOfRange {
+            idx: 1,
+            n_point_sets: 1,
+        });
+
+        assert_eq!(
+            out_of_range,
+            s.open(&mut Transcript::new(b"test"), &evals, &coeffs, 1)
+                .map(|_| ())
+        );
+        assert_eq!(
+            out_of_range,
+            s.verify(&mut Transcript::new(b"test"), &commits, 1, &evals, &proof)
+                .map(|_| ())
+        );
+        assert_eq!(out_of_range, s.compute_evals(&coeffs, 1).map(|_| ()));
+        assert_eq!(
+            out_of_range,
+            s.verify_batch(
+                &mut [Transcript::new(b"test"), Transcript::new(b"test")],
+                &[
+                    (&commits[..], 0, &evals[..], &proof),
+                    (&commits[..], 1, &evals[..], &proof)
+                ],
+                &mut test_rng()
+            )
+            .map(|_| ())
+        );
     }
 }
@@ -1,9 +1,19 @@
 //! Multi-scalar multiplication engines
 
-use ark_ec::pairing::{Pairing, PairingOutput};
-use ark_std::{marker::PhantomData, vec::Vec};
-
-use crate::{traits::MSMEngine, Error};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_std::{end_timer, marker::PhantomData, start_timer, vec, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    cfg_iter,
+    traits::{MSMCapabilities, MSMEngine},
+    Error,
+};
 
 /// The MSM engine for the Arkworks library
 #[derive(Clone, Copy)]
@@ -27,16 +37,36 @@ impl<E: Pairing> MSMEngine for ArkMSMEngine<E> {
         g: &Self::G1Prepared,
         s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
     ) -> Result<<Self::E as Pairing>::G1, Error> {
-        crate::curve_msm(g, s.as_ref())
+        let timer = start_timer!(|| ark_std::format!("ark msm g1 of {} scalars", s.as_ref().len()));
+        let res = crate::curve_msm(g, s.as_ref());
+        end_timer!(timer);
+        res
     }
 
     fn multi_scalar_mul_g2(
         g: &Self::G2Prepared,
         s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
     ) -> Result<<Self::E as Pairing>::G2, Error> {
+        let timer = start_timer!(|| ark_std::format!("ark msm g2 of {} scalars", s.as_ref().len()));
+        let res = crate::curve_msm(g, s.as_ref());
+        end_timer!(timer);
+        res
+    }
+
+    fn multi_scalar_mul_g1_unprepared(
+        g: &[<Self::E as Pairing>::G1Affine],
+        s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
+    ) -> Result<<Self::E as Pairing>::G1, Error> {
         crate::curve_msm(g, s.as_ref())
     }
 
+    fn batch_multi_scalar_mul_g1(
+        g: &Self::G1Prepared,
+        s: &[impl AsRef<[<Self::E as Pairing>::ScalarField]> + Sync],
+    ) -> Result<Vec<<Self::E as Pairing>::G1>, Error> {
+        batch_msm(g, s)
+    }
+
     fn pairing_eq_check(
         a: <Self::E as Pairing>::G1Affine,
         b: <Self::E as Pairing>::G2Affine,
@@ -52,14 +82,493 @@ impl<E: Pairing> MSMEngine for ArkMSMEngine<E> {
     ) -> PairingOutput<Self::E> {
         Self::E::pairing(p1, q1)
     }
+
+    fn name() -> &'static str {
+        "arkworks"
+    }
+}
+
+/// Picks the fastest available engine for the curve `E` at runtime, so code that is generic over
+/// `E: Pairing` gets the blst backend on BLS12-381 without specializing on the curve. Every
+/// other curve, and BLS12-381 without the `blst` feature, uses [`ArkMSMEngine`]. Sets of fewer
+/// than [`AnyMSMEngine::MIN_BLST_BASES`] points are left to arkworks, since converting them
+/// isn't worth it. There's no GPU backend yet.
+#[derive(Clone, Copy)]
+pub struct AnyMSMEngine<E: Pairing>(PhantomData<E>);
+
+/// G1 points prepared by [`AnyMSMEngine`] for the engine it picked
+#[derive(Clone)]
+pub enum AnyPreparedG1<E: Pairing> {
+    /// Prepared for [`ArkMSMEngine`]
+    Ark(Vec<E::G1Affine>),
+    /// Prepared for [`blst::BlstMSMEngine`]
+    #[cfg(feature = "blst")]
+    Blst(blst::PreparedG1s),
+}
+
+/// G2 points prepared by [`AnyMSMEngine`] for the engine it picked
+#[derive(Clone)]
+pub enum AnyPreparedG2<E: Pairing> {
+    /// Prepared for [`ArkMSMEngine`]
+    Ark(Vec<E::G2Affine>),
+    /// Prepared for [`blst::BlstMSMEngine`]
+    #[cfg(feature = "blst")]
+    Blst(ark_std::boxed::Box<blst::PreparedG2s>),
+}
+
+impl<E: Pairing> AnyMSMEngine<E> {
+    /// The smallest number of points which are prepared for blst
+    pub const MIN_BLST_BASES: usize = 2;
+
+    /// Whether blst is used for `E`
+    pub fn uses_blst() -> bool {
+        #[cfg(feature = "blst")]
+        {
+            core::any::TypeId::of::<E>() == core::any::TypeId::of::<ark_bls12_381::Bls12_381>()
+        }
+        #[cfg(not(feature = "blst"))]
+        {
+            false
+        }
+    }
+}
+
+/// Converts between two types which were checked to be the same
+#[cfg(feature = "blst")]
+fn cast<A: 'static, B: 'static>(a: A) -> B {
+    let mut a = Some(a);
+    (&mut a as &mut dyn core::any::Any)
+        .downcast_mut::<Option<B>>()
+        .and_then(Option::take)
+        .expect("Types are checked before casting")
+}
+
+/// Converts between slices of two types which were checked to be the same
+#[cfg(feature = "blst")]
+fn cast_slice<A: 'static, B: 'static>(a: &[A]) -> &[B] {
+    assert_eq!(core::any::TypeId::of::<A>(), core::any::TypeId::of::<B>());
+    // SAFETY: A and B are the same type, so the layouts match
+    unsafe { core::slice::from_raw_parts(a.as_ptr() as *const B, a.len()) }
+}
+
+impl<E: Pairing> MSMEngine for AnyMSMEngine<E> {
+    type E = E;
+
+    type G1Prepared = AnyPreparedG1<E>;
+    type G2Prepared = AnyPreparedG2<E>;
+
+    fn prepare_g1(g: Vec<<Self::E as Pairing>::G1Affine>) -> Self::G1Prepared {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() && g.len() >= Self::MIN_BLST_BASES {
+            return AnyPreparedG1::Blst(blst::BlstMSMEngine::prepare_g1(cast(g)));
+        }
+        AnyPreparedG1::Ark(g)
+    }
+
+    fn prepare_g2(g: Vec<<Self::E as Pairing>::G2Affine>) -> Self::G2Prepared {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() && g.len() >= Self::MIN_BLST_BASES {
+            return AnyPreparedG2::Blst(blst::BlstMSMEngine::prepare_g2(cast(g)).into());
+        }
+        AnyPreparedG2::Ark(g)
+    }
+
+    fn multi_scalar_mul_g1(
+        g: &Self::G1Prepared,
+        s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
+    ) -> Result<<Self::E as Pairing>::G1, Error> {
+        match g {
+            AnyPreparedG1::Ark(g) => ArkMSMEngine::<E>::multi_scalar_mul_g1(g, s),
+            #[cfg(feature = "blst")]
+            AnyPreparedG1::Blst(g) => {
+                blst::BlstMSMEngine::multi_scalar_mul_g1(g, cast_slice(s.as_ref())).map(cast)
+            }
+        }
+    }
+
+    fn multi_scalar_mul_g1_unprepared(
+        g: &[<Self::E as Pairing>::G1Affine],
+        s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
+    ) -> Result<<Self::E as Pairing>::G1, Error> {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() && g.len() >= Self::MIN_BLST_BASES {
+            return Self::multi_scalar_mul_g1(&Self::prepare_g1(g.to_vec()), s);
+        }
+        ArkMSMEngine::<E>::multi_scalar_mul_g1_unprepared(g, s)
+    }
+
+    fn multi_scalar_mul_g2(
+        g: &Self::G2Prepared,
+        s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
+    ) -> Result<<Self::E as Pairing>::G2, Error> {
+        match g {
+            AnyPreparedG2::Ark(g) => ArkMSMEngine::<E>::multi_scalar_mul_g2(g, s),
+            #[cfg(feature = "blst")]
+            AnyPreparedG2::Blst(g) => {
+                blst::BlstMSMEngine::multi_scalar_mul_g2(g, cast_slice(s.as_ref())).map(cast)
+            }
+        }
+    }
+
+    fn batch_multi_scalar_mul_g1(
+        g: &Self::G1Prepared,
+        s: &[impl AsRef<[<Self::E as Pairing>::ScalarField]> + Sync],
+    ) -> Result<Vec<<Self::E as Pairing>::G1>, Error> {
+        match g {
+            AnyPreparedG1::Ark(g) => ArkMSMEngine::<E>::batch_multi_scalar_mul_g1(g, s),
+            #[cfg(feature = "blst")]
+            AnyPreparedG1::Blst(g) => s
+                .iter()
+                .map(|row| {
+                    blst::BlstMSMEngine::multi_scalar_mul_g1(g, cast_slice(row.as_ref())).map(cast)
+                })
+                .collect(),
+        }
+    }
+
+    fn pairing_eq_check(
+        p1: <Self::E as Pairing>::G1Affine,
+        q1: <Self::E as Pairing>::G2Affine,
+        p2: <Self::E as Pairing>::G1Affine,
+        q2: <Self::E as Pairing>::G2Affine,
+    ) -> bool {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() {
+            return blst::BlstMSMEngine::pairing_eq_check(cast(p1), cast(q1), cast(p2), cast(q2));
+        }
+        ArkMSMEngine::<E>::pairing_eq_check(p1, q1, p2, q2)
+    }
+
+    fn pairing(
+        p1: <Self::E as Pairing>::G1Affine,
+        q1: <Self::E as Pairing>::G2Affine,
+    ) -> PairingOutput<Self::E> {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() {
+            return cast(blst::BlstMSMEngine::pairing(cast(p1), cast(q1)));
+        }
+        ArkMSMEngine::<E>::pairing(p1, q1)
+    }
+
+    fn multi_pairing_is_one(
+        ps: &[<Self::E as Pairing>::G1Affine],
+        qs: &[<Self::E as Pairing>::G2Affine],
+    ) -> bool {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() {
+            return blst::BlstMSMEngine::multi_pairing_is_one(cast_slice(ps), cast_slice(qs));
+        }
+        ArkMSMEngine::<E>::multi_pairing_is_one(ps, qs)
+    }
+
+    fn name() -> &'static str {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() {
+            return blst::BlstMSMEngine::name();
+        }
+        ArkMSMEngine::<E>::name()
+    }
+
+    fn capabilities() -> MSMCapabilities {
+        #[cfg(feature = "blst")]
+        if Self::uses_blst() {
+            return blst::BlstMSMEngine::capabilities();
+        }
+        ArkMSMEngine::<E>::capabilities()
+    }
+}
+
+/// Batches of fewer rows than this are always done one msm at a time by [`batch_msm`]. Building
+/// the shared table takes about 255 doublings of each base, which no fewer rows make up for.
+pub const MIN_BATCH_ROWS: usize = 16;
+
+/// The most memory the table built by [`batch_msm`] can take, in bytes. Bigger batches hold
+/// fewer copies of the bases, and are done one msm at a time once those don't pay for
+/// themselves.
+pub const MAX_BATCH_TABLE_BYTES: usize = 1 << 28;
+
+/// Computes an msm of each row of `scalars` against the same `bases`, sharing the work that only
+/// depends on the bases between the rows.
+///
+/// When there are enough rows, a [`FixedBaseTable`] of the bases in the longest row is built and
+/// every row is done against it, which saves most of the bucket sums and doublings of each msm.
+/// The window size and number of copies are picked by estimating the cost of building the table
+/// and of each row against it, keeping the table under [`MAX_BATCH_TABLE_BYTES`]. If that's no
+/// cheaper than an msm of each row on its own, which it isn't for fewer than about 15 rows of 1024
+/// scalars or 20 rows of 4096, each row is done on its own. The table is freed on return, hold
+/// one with [`FixedBaseTable::new`] to reuse it between calls.
+///
+/// This is how [`ArkMSMEngine`] does batched msms. blst's msm of a single row is faster still, so
+/// `blst::BlstMSMEngine` does one per row.
+pub fn batch_msm<G: CurveGroup>(
+    bases: &[G::Affine],
+    scalars: &[impl AsRef<[G::ScalarField]> + Sync],
+) -> Result<Vec<G>, Error> {
+    let mut n = 0;
+    for row in scalars {
+        n = core::cmp::max(n, check_n_scalars(row.as_ref().len(), bases.len())?);
+    }
+    let max_copies =
+        MAX_BATCH_TABLE_BYTES / core::cmp::max(1, n * core::mem::size_of::<G::Affine>());
+    let plan = match scalars.len() < MIN_BATCH_ROWS || n == 0 {
+        true => None,
+        false => batch_plan(n, scalars.len(), scalar_bits::<G>(), max_copies),
+    };
+    let Some((c, copies)) = plan else {
+        return scalars
+            .iter()
+            .map(|row| crate::curve_msm::<G>(bases, row.as_ref()))
+            .collect();
+    };
+    let timer = start_timer!(|| {
+        ark_std::format!("batch msm of {} rows of {} scalars", scalars.len(), n)
+    });
+    let table = FixedBaseTable::with_window_bits(&bases[..n], c, copies);
+    let res = table.msm_many(scalars);
+    end_timer!(timer);
+    res
+}
+
+/// The bases of an msm multiplied by $2^{cw}$ for the $c$-bit windows $w$ of a scalar, so msms
+/// against them need fewer doublings and bucket sums. A setup which is committed against
+/// repeatedly can hold one, see [`M1NoPrecomp::with_fixed_base_table`].
+///
+/// The table holds `copies` copies of the bases, the $j$th multiplied by $2^{cjs}$ for a stride
+/// of $s = \lceil W / \text{copies} \rceil$ windows out of $W = \lceil b/c \rceil$. An msm makes
+/// $s$ passes over the scalars with a set of $2^c$ buckets each, adding one point per scalar for
+/// each copy, and doubles its sum $c$ times between passes. With one copy this is Pippenger's
+/// algorithm, and with a copy for every window it's a single pass with no doublings, so more
+/// copies trade memory for speed.
+///
+/// [`M1NoPrecomp::with_fixed_base_table`]: crate::method1::M1NoPrecomp::with_fixed_base_table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBaseTable<G: CurveGroup> {
+    window_bits: usize,
+    stride: usize,
+    copies: usize,
+    n_bases: usize,
+    table: Vec<G::Affine>,
+}
+
+impl<G: CurveGroup> FixedBaseTable<G> {
+    /// Make a table with up to `copies` copies of `bases`, with the window size which makes a
+    /// single msm fastest for that many copies. There's never a use for more than one copy per
+    /// window, see [`Self::max_copies`].
+    pub fn new(bases: &[G::Affine], copies: usize) -> Self {
+        let bits = scalar_bits::<G>();
+        let c = (2..=16)
+            .min_by_key(|c| {
+                let windows = bits.div_ceil(*c);
+                let stride = windows.div_ceil(copies.clamp(1, windows));
+                bases.len() * windows + stride * ((2 << c) + c)
+            })
+            .expect("Range is nonempty");
+        Self::with_window_bits(bases, c, copies)
+    }
+
+    /// Make a table with `window_bits`-bit windows and up to `copies` copies of `bases`
+    pub fn with_window_bits(bases: &[G::Affine], window_bits: usize, copies: usize) -> Self {
+        let c = window_bits.clamp(1, 16);
+        let windows = scalar_bits::<G>().div_ceil(c);
+        let stride = windows.div_ceil(copies.clamp(1, windows));
+        let copies = windows.div_ceil(stride);
+        let timer = start_timer!(|| {
+            ark_std::format!("table of {} copies of {} bases", copies, bases.len())
+        });
+        let mut table = Vec::with_capacity(copies * bases.len());
+        table.extend_from_slice(bases);
+        let mut shifted = match copies {
+            1 => Vec::new(),
+            _ => bases.iter().map(|b| (*b).into()).collect::<Vec<G>>(),
+        };
+        for _ in 1..copies {
+            for b in shifted.iter_mut() {
+                for _ in 0..c * stride {
+                    b.double_in_place();
+                }
+            }
+            table.extend(G::normalize_batch(&shifted));
+        }
+        end_timer!(timer);
+        Self {
+            window_bits: c,
+            stride,
+            copies,
+            n_bases: bases.len(),
+            table,
+        }
+    }
+
+    /// The most copies a table with `window_bits`-bit windows can use, one for each window
+    pub fn max_copies(window_bits: usize) -> usize {
+        scalar_bits::<G>().div_ceil(window_bits.clamp(1, 16))
+    }
+
+    /// The number of bits in each window
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// The number of copies of the bases held
+    pub fn copies(&self) -> usize {
+        self.copies
+    }
+
+    /// The number of bases, which is the most scalars an msm can have
+    pub fn n_bases(&self) -> usize {
+        self.n_bases
+    }
+
+    /// The size of the points held, in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.table.len() * core::mem::size_of::<G::Affine>()
+    }
+
+    /// Computes $\sum_i s_i g_i$ for the bases $g_i$, split between threads with `parallel`
+    pub fn msm(&self, scalars: &[G::ScalarField]) -> Result<G, Error> {
+        check_n_scalars(scalars.len(), self.n_bases)?;
+        let timer = start_timer!(|| ark_std::format!("table msm of {} scalars", scalars.len()));
+        let scalars = scalars.iter().map(|s| s.into_bigint()).collect::<Vec<_>>();
+        #[cfg(feature = "parallel")]
+        let chunk_size = scalars.len().div_ceil(rayon::current_num_threads()).max(1);
+        #[cfg(not(feature = "parallel"))]
+        let chunk_size = scalars.len().max(1);
+        let res = ark_std::cfg_chunks!(scalars, chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| self.partial_msm(i * chunk_size, chunk))
+            .sum();
+        end_timer!(timer);
+        Ok(res)
+    }
+
+    /// Computes an msm of each row of `scalars`, with the rows split between threads with
+    /// `parallel`
+    pub fn msm_many(
+        &self,
+        scalars: &[impl AsRef<[G::ScalarField]> + Sync],
+    ) -> Result<Vec<G>, Error> {
+        for row in scalars {
+            check_n_scalars(row.as_ref().len(), self.n_bases)?;
+        }
+        Ok(cfg_iter!(scalars)
+            .map(|(_, row)| {
+                let row = row
+                    .as_ref()
+                    .iter()
+                    .map(|s| s.into_bigint())
+                    .collect::<Vec<_>>();
+                self.partial_msm(0, &row)
+            })
+            .collect())
+    }
+
+    /// The msm of `scalars` against the bases starting at `start`
+    fn partial_msm(&self, start: usize, scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G {
+        let c = self.window_bits;
+        let windows = scalar_bits::<G>().div_ceil(c);
+        let mut buckets = vec![G::zero(); (1 << c) - 1];
+        let mut acc = G::zero();
+        for r in (0..self.stride).rev() {
+            for _ in 0..c {
+                acc.double_in_place();
+            }
+            buckets.fill(G::zero());
+            for (i, s) in scalars.iter().enumerate() {
+                for (j, w) in (r..windows).step_by(self.stride).enumerate() {
+                    let digit = window_digit(s.as_ref(), w * c, c);
+                    if digit != 0 {
+                        buckets[digit - 1] += self.table[j * self.n_bases + start + i];
+                    }
+                }
+            }
+            // sum_d d * B_d, as the sum of the running sums from the top bucket down
+            let mut running = G::zero();
+            for b in buckets.iter().rev() {
+                running += b;
+                acc += running;
+            }
+        }
+        acc
+    }
+}
+
+/// The number of bits in a scalar of `G`
+fn scalar_bits<G: CurveGroup>() -> usize {
+    G::ScalarField::MODULUS_BIT_SIZE as usize
+}
+
+/// Checks `n_scalars` scalars fit against `n_bases` bases, returning `n_scalars`
+fn check_n_scalars(n_scalars: usize, n_bases: usize) -> Result<usize, Error> {
+    if n_scalars > n_bases {
+        return Err(Error::TooManyScalars {
+            n_coeffs: n_scalars,
+            expected_max: n_bases,
+        });
+    }
+    Ok(n_scalars)
+}
+
+// Rough costs of curve operations relative to each other, measured on BLS12-381 G1, for
+// estimating the cost of an msm
+const DOUBLE_COST: u64 = 7;
+const MIXED_ADD_COST: u64 = 10;
+const ADD_COST: u64 = 16;
+const NORMALIZE_COST: u64 = 10;
+
+/// The estimated cost of an msm of `n` scalars of `bits` bits on its own, with signed windows
+/// as arkworks does it
+fn msm_cost(n: usize, bits: usize) -> u64 {
+    (2..=16)
+        .map(|c| {
+            let windows = bits.div_ceil(c) as u64;
+            windows * (n as u64 * MIXED_ADD_COST + (1 << c) * ADD_COST) + bits as u64 * DOUBLE_COST
+        })
+        .min()
+        .expect("Range is nonempty")
+}
+
+/// The window size and number of copies, up to `max_copies`, of the [`FixedBaseTable`] which
+/// makes `rows` msms of `n` scalars of `bits` bits cheapest, counting the cost of building it.
+/// Returns `None` if an msm of each row on its own is cheaper.
+fn batch_plan(n: usize, rows: usize, bits: usize, max_copies: usize) -> Option<(usize, usize)> {
+    let (n, rows) = (n as u64, rows as u64);
+    let mut best = (rows * msm_cost(n as usize, bits), None);
+    for c in 2..=16 {
+        let windows = bits.div_ceil(c);
+        for copies in 2..=core::cmp::min(windows, max_copies) {
+            let stride = windows.div_ceil(copies);
+            let copies = windows.div_ceil(stride);
+            // Each copy after the first is the one before doubled `c * stride` times, normalized
+            let shift = (c * stride) as u64 * DOUBLE_COST + NORMALIZE_COST;
+            let build = (copies - 1) as u64 * n * shift;
+            let buckets = (2 << c) * ADD_COST + c as u64 * DOUBLE_COST;
+            let row = n * windows as u64 * MIXED_ADD_COST + stride as u64 * buckets;
+            let cost = build + rows * row;
+            if cost < best.0 {
+                best = (cost, Some((c, copies)));
+            }
+        }
+    }
+    best.1
+}
+
+/// The `c` bits of the little endian `limbs` starting at bit `start`
+fn window_digit(limbs: &[u64], start: usize, c: usize) -> usize {
+    let (limb, shift) = (start / 64, start % 64);
+    let mut digit = limbs[limb] >> shift;
+    if shift + c > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - shift);
+    }
+    (digit & ((1 << c) - 1)) as usize
 }
 
 #[cfg(feature = "blst")]
 /// The MSM engine for the blst library
 pub mod blst {
     use ::blst::{
-        blst_final_exp, blst_fp, blst_fp12, blst_fp12_finalverify, blst_fp2, blst_miller_loop,
-        blst_p1, blst_p1_affine, blst_p1_mult, blst_p2, blst_p2_affine, blst_p2_mult, MultiPoint,
+        blst_final_exp, blst_fp, blst_fp12, blst_fp12_finalverify, blst_fp12_one, blst_fp2,
+        blst_miller_loop, blst_miller_loop_n, blst_p1, blst_p1_affine, blst_p1_mult, blst_p2,
+        blst_p2_affine, blst_p2_mult, MultiPoint,
     };
     use ark_ec::{pairing::PairingOutput, AffineRepr};
     use ark_ff::{BigInt, Zero};
@@ -89,14 +598,22 @@ pub mod blst {
             g: &Self::G1Prepared,
             s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
         ) -> Result<<Self::E as Pairing>::G1, Error> {
-            g.msm(s.as_ref())
+            let timer =
+                start_timer!(|| ark_std::format!("blst msm g1 of {} scalars", s.as_ref().len()));
+            let res = g.msm(s.as_ref());
+            end_timer!(timer);
+            res
         }
 
         fn multi_scalar_mul_g2(
             g: &Self::G2Prepared,
             s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
         ) -> Result<<Self::E as Pairing>::G2, Error> {
-            g.msm(s.as_ref())
+            let timer =
+                start_timer!(|| ark_std::format!("blst msm g2 of {} scalars", s.as_ref().len()));
+            let res = g.msm(s.as_ref());
+            end_timer!(timer);
+            res
         }
 
         fn pairing_eq_check(
@@ -114,6 +631,43 @@ pub mod blst {
         ) -> PairingOutput<Self::E> {
             PairingOutput(pairing(p1, q1))
         }
+
+        fn multi_pairing_is_one(
+            ps: &[<Self::E as Pairing>::G1Affine],
+            qs: &[<Self::E as Pairing>::G2Affine],
+        ) -> bool {
+            let pairs = ps
+                .iter()
+                .copied()
+                .zip(qs.iter().copied())
+                .collect::<Vec<_>>();
+            check_pairings_equal_many(&pairs, &[])
+        }
+
+        fn name() -> &'static str {
+            "blst"
+        }
+
+        fn capabilities() -> MSMCapabilities {
+            MSMCapabilities {
+                fast_msm: true,
+                fast_pairing_check: true,
+                gpu: false,
+                adx: has_adx(),
+            }
+        }
+    }
+
+    /// Whether blst can use the ADX instructions, which it detects at runtime on x86_64
+    fn has_adx() -> bool {
+        #[cfg(all(feature = "std", target_arch = "x86_64"))]
+        {
+            std::is_x86_feature_detected!("adx")
+        }
+        #[cfg(not(all(feature = "std", target_arch = "x86_64")))]
+        {
+            false
+        }
     }
 
     /// Check that two pairings are equal by doing two miller loops and a single final exponentiation
@@ -149,6 +703,54 @@ pub mod blst {
         }
     }
 
+    /// Check that the product of the pairings $e(p_i, q_i)$ of `lhs` equals that of `rhs`. The
+    /// Miller loops of each side are done together, sharing their squarings, and both sides share
+    /// a single final exponentiation.
+    pub fn check_pairings_equal_many(
+        lhs: &[(ark_bls12_381::G1Affine, ark_bls12_381::G2Affine)],
+        rhs: &[(ark_bls12_381::G1Affine, ark_bls12_381::G2Affine)],
+    ) -> bool {
+        let ret1 = miller_loop_many(lhs);
+        let ret2 = miller_loop_many(rhs);
+        unsafe {
+            blst_fp12_finalverify(
+                &ret1 as *const blst::blst_fp12,
+                &ret2 as *const blst::blst_fp12,
+            )
+        }
+    }
+
+    /// The product of the Miller loops of `pairs`. Pairs with a point at infinity pair to one, so
+    /// they're skipped rather than handed to blst.
+    fn miller_loop_many(pairs: &[(ark_bls12_381::G1Affine, ark_bls12_381::G2Affine)]) -> blst_fp12 {
+        let (ps, qs): (Vec<_>, Vec<_>) = pairs
+            .iter()
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .map(|(p, q)| (convert_g1_affine(*p), convert_g2_affine(*q)))
+            .unzip();
+        if ps.is_empty() {
+            return unsafe { *blst_fp12_one() };
+        }
+        let p_ptrs = ps
+            .iter()
+            .map(|p| p as *const blst_p1_affine)
+            .collect::<Vec<_>>();
+        let q_ptrs = qs
+            .iter()
+            .map(|q| q as *const blst_p2_affine)
+            .collect::<Vec<_>>();
+        let mut ret = blst_fp12::default();
+        unsafe {
+            blst_miller_loop_n(
+                &mut ret as *mut blst_fp12,
+                q_ptrs.as_ptr(),
+                p_ptrs.as_ptr(),
+                ps.len(),
+            )
+        };
+        ret
+    }
+
     /// Compute a pairing
     pub fn pairing(p: ark_bls12_381::G1Affine, q: ark_bls12_381::G2Affine) -> ark_bls12_381::Fq12 {
         let bp = convert_g1_affine(p);
@@ -211,7 +813,7 @@ pub mod blst {
         /// Construct from a set of affine points
         fn from_affines(value: Vec<ark_bls12_381::G1Affine>) -> Self {
             let len = value.len();
-            let first = value.get(0).map(|p1| convert_g1(p1.into_group()));
+            let first = value.first().map(|p1| convert_g1(p1.into_group()));
             let all: Vec<_> = value.into_iter().map(convert_g1_affine).collect();
             Self { first, all, len }
         }
@@ -223,7 +825,7 @@ pub mod blst {
         ) -> Result<ark_bls12_381::G1Projective, Error> {
             scalars = trim_zeros(scalars);
             check_scalars(scalars, self.len)?;
-            if scalars.len() == 0 || self.len == 0 {
+            if scalars.is_empty() || self.len == 0 {
                 return Ok(Zero::zero());
             }
             let scalars_le = prep_scalars(scalars);
@@ -261,7 +863,7 @@ pub mod blst {
     impl PreparedG2s {
         fn from_affines(value: Vec<ark_bls12_381::G2Affine>) -> Self {
             let len = value.len();
-            let first = value.get(0).map(|p2| convert_g2(p2.into_group()));
+            let first = value.first().map(|p2| convert_g2(p2.into_group()));
             let all: Vec<_> = value.into_iter().map(convert_g2_affine).collect();
             Self { first, all, len }
         }
@@ -272,7 +874,7 @@ pub mod blst {
         ) -> Result<ark_bls12_381::G2Projective, Error> {
             scalars = trim_zeros(scalars);
             check_scalars(scalars, self.len)?;
-            if scalars.len() == 0 || self.len == 0 {
+            if scalars.is_empty() || self.len == 0 {
                 return Ok(Zero::zero());
             }
             let scalars_le = prep_scalars(scalars);
@@ -377,7 +979,7 @@ pub mod blst {
     mod tests {
         use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
         use ark_ec::{pairing::Pairing, CurveGroup};
-        use ark_ff::UniformRand;
+        use ark_ff::{Field, UniformRand};
         use ark_std::vec::Vec;
         use rand::thread_rng;
 
@@ -397,7 +999,7 @@ pub mod blst {
 
             fn run(err: bool, g1s: &[ark_bls12_381::G1Affine], scalars: &[ark_bls12_381::Fr]) {
                 let pg1 = PreparedG1s::from_affines(g1s.to_vec());
-                let res = pg1.msm(&scalars);
+                let res = pg1.msm(scalars);
                 assert_eq!(res.is_err(), err);
             }
 
@@ -484,6 +1086,47 @@ pub mod blst {
             assert_eq!(Ok(G2Projective::zero()), res2);
         }
 
+        #[test]
+        fn test_any_engine() {
+            use crate::{
+                method1::M1NoPrecomp,
+                msm::{AnyMSMEngine, AnyPreparedG1},
+                test_rng,
+                testing::test_basic_no_precomp,
+                traits::MSMCapabilities,
+            };
+            type Any = AnyMSMEngine<ark_bls12_381::Bls12_381>;
+            type AnyBn = AnyMSMEngine<ark_bn254::Bn254>;
+
+            assert_eq!("blst", Any::name());
+            assert!(Any::capabilities().fast_pairing_check);
+            assert_eq!("arkworks", AnyBn::name());
+            assert_eq!(MSMCapabilities::default(), AnyBn::capabilities());
+
+            let g1s = (0..10)
+                .map(|_| G1Projective::rand(&mut thread_rng()).into_affine())
+                .collect::<Vec<_>>();
+            let scalars = (0..10)
+                .map(|_| Fr::rand(&mut thread_rng()))
+                .collect::<Vec<_>>();
+            let pg1 = Any::prepare_g1(g1s.clone());
+            assert!(matches!(pg1, AnyPreparedG1::Blst(_)));
+            assert_eq!(
+                curve_msm::<G1Projective>(&g1s, &scalars),
+                Any::multi_scalar_mul_g1(&pg1, &scalars)
+            );
+            // A single point is left to arkworks
+            let pg1 = Any::prepare_g1(g1s[..1].to_vec());
+            assert!(matches!(pg1, AnyPreparedG1::Ark(_)));
+            assert_eq!(
+                Ok(g1s[0] * scalars[0]),
+                Any::multi_scalar_mul_g1(&pg1, &scalars[..1])
+            );
+
+            test_basic_no_precomp(&M1NoPrecomp::<_, Any>::new(64, 32, &mut test_rng()));
+            test_basic_no_precomp(&M1NoPrecomp::<_, AnyBn>::new(64, 32, &mut test_rng()));
+        }
+
         #[test]
         fn test_pairings() {
             use ark_ff::One;
@@ -503,5 +1146,173 @@ pub mod blst {
             assert_eq!(ref1, ref2);
             assert!(check_pairings_equal(p1, q1, p2, q2));
         }
+
+        #[test]
+        fn test_pairings_many() {
+            let rand_pairs = |n: usize| {
+                (0..n)
+                    .map(|_| {
+                        (
+                            G1Affine::rand(&mut thread_rng()),
+                            G2Affine::rand(&mut thread_rng()),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let lhs = rand_pairs(11);
+            // The same product of pairings, with scalars moved between the groups
+            let mut rhs = lhs
+                .iter()
+                .map(|(p, q)| {
+                    let z = Fr::rand(&mut thread_rng());
+                    (
+                        (*p * z).into_affine(),
+                        (*q * z.inverse().unwrap()).into_affine(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            rhs.push((G1Affine::zero(), G2Affine::rand(&mut thread_rng())));
+            assert!(check_pairings_equal_many(&lhs, &rhs));
+            assert!(!check_pairings_equal_many(&lhs, &rhs[1..]));
+            assert!(check_pairings_equal_many(&[], &[]));
+            assert!(check_pairings_equal_many(
+                &[(G1Affine::zero(), G2Affine::zero())],
+                &[]
+            ));
+
+            // Agrees with arkworks on whether the product is one
+            let (ps, qs): (Vec<_>, Vec<_>) = lhs
+                .iter()
+                .copied()
+                .chain(
+                    rhs.iter()
+                        .map(|(p, q)| ((-p.into_group()).into_affine(), *q)),
+                )
+                .unzip();
+            assert!(BlstMSMEngine::multi_pairing_is_one(&ps, &qs));
+            assert!(ArkMSMEngine::<ark_bls12_381::Bls12_381>::multi_pairing_is_one(&ps, &qs));
+            assert!(!BlstMSMEngine::multi_pairing_is_one(&ps[1..], &qs[1..]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_std::{vec::Vec, UniformRand, Zero};
+
+    use super::{
+        batch_msm, batch_plan, window_digit, FixedBaseTable, MAX_BATCH_TABLE_BYTES, MIN_BATCH_ROWS,
+    };
+    use crate::{curve_msm, test_rng, Error};
+
+    #[test]
+    fn test_batch_msm() {
+        let bases = G1Projective::normalize_batch(
+            &(0..40)
+                .map(|_| G1Projective::rand(&mut test_rng()))
+                .collect::<Vec<_>>(),
+        );
+        // Rows of different lengths, including an empty row and one of zeros and minus ones
+        let mut rows = (0..6)
+            .map(|i| (0..40 - 7 * i).map(|_| Fr::rand(&mut test_rng())).collect())
+            .collect::<Vec<Vec<_>>>();
+        rows.push(Vec::new());
+        rows.push(ark_std::vec![Fr::zero(), -Fr::from(1u64), Fr::zero()]);
+        // Enough rows for a table to pay off
+        let rows = rows.iter().cycle().take(32).cloned().collect::<Vec<_>>();
+        assert!(batch_plan(40, rows.len(), 255, usize::MAX).is_some());
+        for n_rows in [1, MIN_BATCH_ROWS - 1, rows.len()] {
+            let res = batch_msm::<G1Projective>(&bases, &rows[..n_rows]).unwrap();
+            for (row, r) in rows.iter().zip(res) {
+                assert_eq!(curve_msm::<G1Projective>(&bases, row).unwrap(), r);
+            }
+        }
+        assert_eq!(
+            -G1Projective::from(bases[1]),
+            batch_msm::<G1Projective>(&bases, &rows[2..]).unwrap()[5]
+        );
+        assert!(batch_msm::<G1Projective>(&bases, &[] as &[Vec<Fr>])
+            .unwrap()
+            .is_empty());
+
+        let too_long = ark_std::vec![Fr::from(1u64); 41];
+        let mut with_long = rows.clone();
+        with_long.push(too_long);
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 41,
+                expected_max: 40
+            }),
+            batch_msm::<G1Projective>(&bases, &with_long)
+        );
+    }
+
+    #[test]
+    fn test_fixed_base_table() {
+        let bases = G1Projective::normalize_batch(
+            &(0..40)
+                .map(|_| G1Projective::rand(&mut test_rng()))
+                .collect::<Vec<_>>(),
+        );
+        let scalars = (0..40)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let expected = curve_msm::<G1Projective>(&bases, &scalars).unwrap();
+        let max = FixedBaseTable::<G1Projective>::max_copies(5);
+        assert_eq!(51, max);
+        for copies in [0, 1, 2, 7, max, usize::MAX] {
+            let table = FixedBaseTable::<G1Projective>::with_window_bits(&bases, 5, copies);
+            assert_eq!(copies.clamp(1, max), table.copies());
+            assert_eq!(expected, table.msm(&scalars).unwrap());
+            assert_eq!(
+                curve_msm::<G1Projective>(&bases, &scalars[..9]).unwrap(),
+                table.msm(&scalars[..9]).unwrap()
+            );
+            assert_eq!(
+                ark_std::vec![expected, G1Projective::zero()],
+                table.msm_many(&[&scalars[..], &[]]).unwrap()
+            );
+        }
+        // More copies take more memory
+        let small = FixedBaseTable::<G1Projective>::new(&bases, 2);
+        let big = FixedBaseTable::<G1Projective>::new(&bases, usize::MAX);
+        assert!(small.size_in_bytes() < big.size_in_bytes());
+        assert_eq!(expected, small.msm(&scalars).unwrap());
+        assert_eq!(expected, big.msm(&scalars).unwrap());
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 40,
+                expected_max: 39
+            }),
+            FixedBaseTable::<G1Projective>::new(&bases[..39], 4).msm(&scalars)
+        );
+    }
+
+    #[test]
+    fn test_window_digit() {
+        let limbs = [0xf000_0000_0000_0001u64, 0x5];
+        assert_eq!(1, window_digit(&limbs, 0, 4));
+        // A window straddling two limbs
+        assert_eq!(0x5f, window_digit(&limbs, 60, 8));
+        // The top window runs past the last limb
+        assert_eq!(0x5, window_digit(&limbs, 64, 16));
+    }
+
+    #[test]
+    fn test_batch_plan() {
+        // A table doesn't pay for itself over a few rows, and does over many
+        assert_eq!(None, batch_plan(4096, 8, 255, usize::MAX));
+        let (small_c, _) = batch_plan(256, 64, 255, usize::MAX).unwrap();
+        let (big_c, copies) = batch_plan(4096, 256, 255, usize::MAX).unwrap();
+        assert!(copies > 1);
+        // Longer rows get bigger windows
+        assert!(big_c > small_c);
+        // The copies are capped, and a table of 2^20 bases doesn't pay off in the cap
+        let max_copies = MAX_BATCH_TABLE_BYTES / (65536 * 104);
+        let (_, copies) = batch_plan(65536, 256, 255, max_copies).unwrap();
+        assert!(copies <= max_copies);
+        assert_eq!(None, batch_plan(1 << 20, 256, 255, 2));
     }
 }
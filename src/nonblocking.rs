@@ -0,0 +1,321 @@
+//! Non-blocking versions of committing, opening and verifying for async code. The MSM heavy work
+//! is handed to a [`BlockingExecutor`] and the result comes back through a [`BlockingTask`]
+//! future, so an async runtime isn't blocked while it runs. There's no dependency on a runtime,
+//! e.g. with tokio the executor is
+//!
+//! ```ignore
+//! struct Tokio;
+//!
+//! impl BlockingExecutor for Tokio {
+//!     fn spawn_blocking(&self, work: Box<dyn FnOnce() + Send>) {
+//!         tokio::task::spawn_blocking(work);
+//!     }
+//! }
+//! ```
+//!
+//! The work has to own everything it uses, so the setup is shared with an [`Arc`] and the
+//! transcript is moved in and handed back along with the result.
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::sync::{Arc, Mutex};
+
+use ark_ec::pairing::Pairing;
+use ark_std::{boxed::Box, vec::Vec};
+
+use crate::{
+    traits::{Committer, PolyMultiProofNoPrecomp, TranscriptProtocol},
+    Commitment, Error,
+};
+
+/// Runs blocking work away from the async runtime
+pub trait BlockingExecutor {
+    /// Run `work` to completion somewhere it's fine to block
+    fn spawn_blocking(&self, work: Box<dyn FnOnce() + Send>);
+}
+
+/// Runs each piece of work on a new thread
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadExecutor;
+
+impl BlockingExecutor for ThreadExecutor {
+    fn spawn_blocking(&self, work: Box<dyn FnOnce() + Send>) {
+        std::thread::spawn(work);
+    }
+}
+
+/// Runs work on the thread pool of the config
+#[cfg(feature = "parallel")]
+impl BlockingExecutor for crate::parallel::ParallelismConfig {
+    fn spawn_blocking(&self, work: Box<dyn FnOnce() + Send>) {
+        self.spawn(work)
+    }
+}
+
+struct TaskState<T> {
+    result: Option<Result<T, Error>>,
+    waker: Option<Waker>,
+}
+
+/// The result of work running on a [`BlockingExecutor`]. Resolves to
+/// [`Error::BlockingTaskFailed`] if the work panics or the executor drops it.
+pub struct BlockingTask<T> {
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+/// Sends the result of the work to its task, or a failure if it's dropped before then
+struct TaskSender<T> {
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+impl<T> TaskSender<T> {
+    fn send(self, result: Result<T, Error>) {
+        self.complete(result)
+    }
+
+    fn complete(&self, result: Result<T, Error>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.result.is_none() {
+            state.result = Some(result);
+        }
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for TaskSender<T> {
+    fn drop(&mut self) {
+        // A no-op after a send, since the result is already set
+        self.complete(Err(Error::BlockingTaskFailed));
+    }
+}
+
+impl<T> Future for BlockingTask<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Run `work` on `executor`, returning a future of its result
+pub fn run_blocking<T: Send + 'static>(
+    executor: &impl BlockingExecutor,
+    work: impl FnOnce() -> Result<T, Error> + Send + 'static,
+) -> BlockingTask<T> {
+    let state = Arc::new(Mutex::new(TaskState {
+        result: None,
+        waker: None,
+    }));
+    let sender = TaskSender {
+        state: state.clone(),
+    };
+    executor.spawn_blocking(Box::new(move || sender.send(work())));
+    BlockingTask { state }
+}
+
+/// [`Committer::commit`] on `executor`
+pub fn commit_async<E: Pairing, S: Committer<E> + Send + Sync + 'static>(
+    executor: &impl BlockingExecutor,
+    srs: Arc<S>,
+    poly: Vec<E::ScalarField>,
+) -> BlockingTask<Commitment<E>> {
+    run_blocking(executor, move || srs.commit(poly))
+}
+
+/// [`PolyMultiProofNoPrecomp::open`] on `executor`. Resolves to the transcript after opening
+/// along with the proof.
+pub fn open_async<E, S, T>(
+    executor: &impl BlockingExecutor,
+    srs: Arc<S>,
+    mut transcript: T,
+    evals: Vec<Vec<E::ScalarField>>,
+    polys: Vec<Vec<E::ScalarField>>,
+    points: Vec<E::ScalarField>,
+) -> BlockingTask<(T, S::Proof)>
+where
+    E: Pairing,
+    S: PolyMultiProofNoPrecomp<E> + Send + Sync + 'static,
+    S::Proof: Send,
+    T: TranscriptProtocol + Send + 'static,
+{
+    run_blocking(executor, move || {
+        let proof = srs.open(&mut transcript, &evals, &polys, &points)?;
+        Ok((transcript, proof))
+    })
+}
+
+/// [`PolyMultiProofNoPrecomp::verify`] on `executor`. Resolves to the transcript after verifying
+/// along with whether the proof is valid.
+pub fn verify_async<E, S, T>(
+    executor: &impl BlockingExecutor,
+    srs: Arc<S>,
+    mut transcript: T,
+    commits: Vec<Commitment<E>>,
+    points: Vec<E::ScalarField>,
+    evals: Vec<Vec<E::ScalarField>>,
+    proof: S::Proof,
+) -> BlockingTask<(T, bool)>
+where
+    E: Pairing,
+    S: PolyMultiProofNoPrecomp<E> + Send + Sync + 'static,
+    S::Proof: Send + 'static,
+    T: TranscriptProtocol + Send + 'static,
+{
+    run_blocking(executor, move || {
+        let valid = srs.verify(&mut transcript, &commits, &points, &evals, &proof)?;
+        Ok((transcript, valid))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll},
+    };
+    use std::{
+        sync::Arc,
+        task::Wake,
+        thread::{self, Thread},
+    };
+
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{boxed::Box, vec};
+    use merlin::Transcript;
+
+    use super::{
+        commit_async, open_async, run_blocking, verify_async, BlockingExecutor, ThreadExecutor,
+    };
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::blst::BlstMSMEngine,
+        test_rng,
+        testing::gen_openings,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(out) => return out,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    /// Drops the work without running it
+    struct DroppingExecutor;
+
+    impl BlockingExecutor for DroppingExecutor {
+        fn spawn_blocking(&self, _work: Box<dyn FnOnce() + Send>) {}
+    }
+
+    #[test]
+    fn test_async() {
+        let s = Arc::new(M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(
+            64,
+            8,
+            &mut test_rng(),
+        ));
+        let points = (0..8u64).map(Fr::from).collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(s.as_ref(), 3, &points);
+
+        let commit = block_on(commit_async(&ThreadExecutor, s.clone(), coeffs[0].clone()));
+        assert_eq!(commits[0].0, commit.unwrap().0);
+
+        let (_, proof) = block_on(open_async(
+            &ThreadExecutor,
+            s.clone(),
+            Transcript::new(b"async"),
+            evals.clone(),
+            coeffs.clone(),
+            points.clone(),
+        ))
+        .unwrap();
+        let expected = s
+            .open(&mut Transcript::new(b"async"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(expected.0, proof.0);
+        let (_, valid) = block_on(verify_async(
+            &ThreadExecutor,
+            s.clone(),
+            Transcript::new(b"async"),
+            commits,
+            points,
+            evals,
+            proof,
+        ))
+        .unwrap();
+        assert!(valid);
+
+        // Errors and failed work come back through the future
+        assert_eq!(
+            Err(Error::NoPolynomialsGiven),
+            block_on(run_blocking(&ThreadExecutor, || Err::<(), _>(
+                Error::NoPolynomialsGiven
+            )))
+        );
+        assert_eq!(
+            Err(Error::BlockingTaskFailed),
+            block_on(run_blocking(&ThreadExecutor, || -> Result<(), Error> {
+                panic!("work failed")
+            }))
+        );
+        assert_eq!(
+            Err(Error::BlockingTaskFailed),
+            block_on(run_blocking(&DroppingExecutor, || Ok(vec![1u8])))
+        );
+        assert_eq!(
+            Ok(64),
+            block_on(run_blocking(&ThreadExecutor, move || Ok(s
+                .powers_of_g1
+                .len())))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallelism_config_executor() {
+        use crate::parallel::ParallelismConfig;
+
+        let config = ParallelismConfig::with_max_threads(2).unwrap();
+        assert_eq!(
+            Ok(2),
+            block_on(run_blocking(&config, || Ok(rayon::current_num_threads())))
+        );
+        let s = Arc::new(M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(
+            32,
+            4,
+            &mut test_rng(),
+        ));
+        let poly = vec![Fr::from(1u64); 32];
+        assert_eq!(
+            s.commit(&poly).unwrap().0,
+            block_on(commit_async(&config, s, poly)).unwrap().0
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Controlling the threads used by the `parallel` feature
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_std::{sync::Arc, vec::Vec};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+use crate::{traits::MSMEngine, Error};
+
+/// The default fewest items each chunk of split work gets, below this the chunks aren't worth it
+pub const DEFAULT_MIN_CHUNK_LEN: usize = 64;
+
+/// Which thread pool parallel operations run on, and how finely they're split. By default this is
+/// rayon's global pool, which uses every core. Applications which already keep the global pool
+/// busy, or verifiers embedded in async runtimes, can cap the crate's thread usage by running on a
+/// dedicated pool instead. A config can be attached to a setup with
+/// [`crate::method1::M1NoPrecomp::with_parallelism`], and is then used for its precomputation,
+/// msms and batched verification.
+#[derive(Clone, Debug)]
+pub struct ParallelismConfig {
+    pool: Option<Arc<ThreadPool>>,
+    min_chunk_len: usize,
+}
+
+impl Default for ParallelismConfig {
+    fn default() -> Self {
+        Self {
+            pool: None,
+            min_chunk_len: DEFAULT_MIN_CHUNK_LEN,
+        }
+    }
+}
+
+impl ParallelismConfig {
+    /// Run on rayon's global thread pool
+    pub fn global() -> Self {
+        Self::default()
+    }
+
+    /// Run on a dedicated pool of `num_threads` threads
+    pub fn with_max_threads(num_threads: usize) -> Result<Self, Error> {
+        if num_threads == 0 {
+            return Err(Error::InvalidThreadCount);
+        }
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|_| Error::InvalidThreadCount)?;
+        Ok(Self {
+            pool: Some(Arc::new(pool)),
+            ..Self::default()
+        })
+    }
+
+    /// Split work into chunks of at least `min_chunk_len` items, so small inputs aren't spread
+    /// across more threads than they're worth. This is at least one.
+    pub fn with_min_chunk_len(mut self, min_chunk_len: usize) -> Self {
+        self.min_chunk_len = min_chunk_len.max(1);
+        self
+    }
+
+    /// The fewest items each chunk of split work gets
+    pub fn min_chunk_len(&self) -> usize {
+        self.min_chunk_len
+    }
+
+    /// The number of threads operations will be split across
+    pub fn num_threads(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Run `f` on this config's thread pool
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+
+    /// Run `f` on this config's thread pool in the background
+    pub fn spawn(&self, f: impl FnOnce() + Send + 'static) {
+        match &self.pool {
+            Some(pool) => pool.spawn(f),
+            None => rayon::spawn(f),
+        }
+    }
+}
+
+/// Computes $\sum_i s_i r_i$ for rows $r_i$ of the same length, parallelized over the columns
+/// in chunks of at least `min_chunk_len`
+pub(crate) fn linear_combination<F: Field>(
+    rows: &[&[F]],
+    scalars: &[F],
+    min_chunk_len: usize,
+) -> Option<Vec<F>> {
+    let n_cols = rows.first()?.len();
+    Some(
+        (0..n_cols)
+            .into_par_iter()
+            .with_min_len(min_chunk_len.max(1))
+            .map(|j| rows.iter().zip(scalars).map(|(r, s)| r[j] * s).sum())
+            .collect(),
+    )
+}
+
+/// Does an msm of `scalars` against `bases` by splitting them into a chunk per thread of the
+/// current pool, each of at least `min_chunk_len` bases, and summing the msms of each chunk
+pub(crate) fn split_msm_g1<M: MSMEngine>(
+    bases: &[<M::E as Pairing>::G1Affine],
+    scalars: &[<M::E as Pairing>::ScalarField],
+    min_chunk_len: usize,
+) -> Result<<M::E as Pairing>::G1, Error> {
+    let n = bases.len().min(scalars.len());
+    let chunk_size = n
+        .div_ceil(rayon::current_num_threads())
+        .max(min_chunk_len.max(1));
+    bases[..n]
+        .par_chunks(chunk_size)
+        .zip(scalars[..n].par_chunks(chunk_size))
+        .map(|(b, s)| M::multi_scalar_mul_g1(&M::prepare_g1(b.to_vec()), s))
+        .try_reduce(Default::default, |a, b| Ok(a + b))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use ark_std::vec::Vec;
+
+    use super::{linear_combination, split_msm_g1, ParallelismConfig};
+    use crate::{
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng, Error,
+    };
+
+    #[test]
+    fn test_split_msm() {
+        let bases = (0..1000)
+            .map(|_| G1Projective::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let bases = G1Projective::normalize_batch(&bases);
+        let scalars = (0..1000)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let expected = crate::curve_msm::<G1Projective>(&bases, &scalars).unwrap();
+        for threads in [1, 3, 8] {
+            let config = ParallelismConfig::with_max_threads(threads).unwrap();
+            assert_eq!(threads, config.num_threads());
+            for min_chunk_len in [1, 64, 600] {
+                let got = config
+                    .install(|| split_msm_g1::<BlstMSMEngine>(&bases, &scalars, min_chunk_len));
+                assert_eq!(expected, got.unwrap());
+            }
+            let got =
+                config.install(|| split_msm_g1::<ArkMSMEngine<Bls12_381>>(&bases, &scalars, 64));
+            assert_eq!(expected, got.unwrap());
+        }
+        let empty: [G1Affine; 0] = [];
+        assert_eq!(
+            G1Projective::default(),
+            split_msm_g1::<BlstMSMEngine>(&empty, &[], 64).unwrap()
+        );
+        assert_eq!(64, ParallelismConfig::global().min_chunk_len());
+        assert_eq!(
+            1,
+            ParallelismConfig::global()
+                .with_min_chunk_len(0)
+                .min_chunk_len()
+        );
+        assert!(matches!(
+            ParallelismConfig::with_max_threads(0),
+            Err(Error::InvalidThreadCount)
+        ));
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let rows = (0..5)
+            .map(|_| {
+                (0..16)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let scalars = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let row_refs = rows.iter().map(|r| r.as_slice()).collect::<Vec<_>>();
+        assert_eq!(
+            crate::linear_combination(&rows, &scalars),
+            linear_combination(&row_refs, &scalars, 1)
+        );
+        assert_eq!(
+            crate::linear_combination(&rows, &scalars),
+            linear_combination(&row_refs, &scalars, 100)
+        );
+        assert_eq!(None, linear_combination::<Fr>(&[], &[], 1));
+    }
+}
@@ -0,0 +1,189 @@
+//! Point sets identified by a hash of their points. A prover and verifier which refer to point
+//! sets by hash rather than by index find out when they disagree about the points, instead of
+//! the proof just failing to verify.
+//!
+//! Protocols which sample points from public data, like a block hash, can derive them with
+//! [`derive_from_bytes`] so that every implementation samples the same points.
+use ark_ff::PrimeField;
+use ark_serialize::Compress;
+use ark_std::{collections::BTreeSet, vec::Vec};
+use merlin::Transcript;
+
+/// The hash of the points of a point set, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointSetHash(pub [u8; 32]);
+
+impl core::fmt::Display for PointSetHash {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+impl PointSetHash {
+    /// Hash `points`. This is a merlin transcript over the number of points and each point
+    /// compressed in order, so it's the same across platforms and versions.
+    pub fn of<F: PrimeField>(points: &[F]) -> Self {
+        let mut transcript = Transcript::new(b"pmp point set");
+        transcript.append_message(b"n points", &(points.len() as u64).to_le_bytes());
+        let mut bytes = Vec::with_capacity(points.len() * F::zero().serialized_size(Compress::Yes));
+        for p in points {
+            p.serialize_compressed(&mut bytes)
+                .expect("Serializing to a vec failed");
+        }
+        transcript.append_message(b"points", &bytes);
+        let mut hash = [0u8; 32];
+        transcript.challenge_bytes(b"point set hash", &mut hash);
+        Self(hash)
+    }
+}
+
+/// An ordered set of points along with its hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointSet<F> {
+    points: Vec<F>,
+    hash: PointSetHash,
+}
+
+impl<F: PrimeField> PointSet<F> {
+    /// Make a point set from `points`, in the order they're opened at
+    pub fn new(points: Vec<F>) -> Self {
+        Self {
+            hash: PointSetHash::of(&points),
+            points,
+        }
+    }
+
+    /// The points, in order
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// The hash of the points
+    pub fn hash(&self) -> PointSetHash {
+        self.hash
+    }
+
+    /// The number of points
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether there are no points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Take the points out of the set
+    pub fn into_points(self) -> Vec<F> {
+        self.points
+    }
+}
+
+impl<F: PrimeField> From<Vec<F>> for PointSet<F> {
+    fn from(points: Vec<F>) -> Self {
+        Self::new(points)
+    }
+}
+
+impl<F: PrimeField> AsRef<[F]> for PointSet<F> {
+    fn as_ref(&self) -> &[F] {
+        &self.points
+    }
+}
+
+/// Derive `n` distinct points from `seed`, such as a block hash, as in
+/// [`derive_from_bytes_with_domain`] with an empty domain
+pub fn derive_from_bytes<F: PrimeField>(seed: &[u8], n: usize) -> Vec<F> {
+    derive_from_bytes_with_domain(b"", seed, n)
+}
+
+/// Derive `n` distinct points from `seed`, separated by `domain` from the points other protocols
+/// derive from the same seed. This is a merlin transcript over the domain, the seed and `n`,
+/// and each point reduces 64 challenge bytes little endian so it's close to uniform. A point
+/// equal to an earlier one is skipped for the next challenge, so a point set from this can always
+/// be opened at.
+pub fn derive_from_bytes_with_domain<F: PrimeField>(
+    domain: &[u8],
+    seed: &[u8],
+    n: usize,
+) -> Vec<F> {
+    let mut transcript = Transcript::new(b"pmp derive points");
+    transcript.append_message(b"domain", domain);
+    transcript.append_message(b"seed", seed);
+    transcript.append_message(b"n points", &(n as u64).to_le_bytes());
+    let mut seen = BTreeSet::new();
+    let mut points = Vec::with_capacity(n);
+    while points.len() < n {
+        let mut bytes = [0u8; 64];
+        transcript.challenge_bytes(b"point", &mut bytes);
+        let point = F::from_le_bytes_mod_order(&bytes);
+        if seen.insert(point) {
+            points.push(point);
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_std::{string::ToString, vec, vec::Vec};
+
+    use super::{derive_from_bytes, derive_from_bytes_with_domain, PointSet, PointSetHash};
+
+    #[test]
+    fn test_point_set_hash() {
+        let points = (0..4u64).map(Fr::from).collect::<Vec<_>>();
+        let set = PointSet::new(points.clone());
+        assert_eq!(PointSetHash::of(&points), set.hash());
+        assert_eq!(set, PointSet::from(points.clone()));
+
+        // The order and number of points matter
+        let mut reordered = points.clone();
+        reordered.swap(0, 1);
+        assert_ne!(set.hash(), PointSetHash::of(&reordered));
+        assert_ne!(set.hash(), PointSetHash::of(&points[..3]));
+        assert_ne!(
+            PointSetHash::of::<Fr>(&[]),
+            PointSetHash::of(&[Fr::from(0u64)])
+        );
+
+        // Pinned so that changes to the hash are noticed
+        assert_eq!(
+            "9042f1fba985e9d41a1869fc44896c04b8805691dd5106ddcd84a68366426f1a",
+            PointSet::new(vec![Fr::from(1u64), Fr::from(2u64)])
+                .hash()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_derive_from_bytes() {
+        let points = derive_from_bytes::<Fr>(b"block hash", 16);
+        assert_eq!(16, points.len());
+        assert_eq!(points, derive_from_bytes(b"block hash", 16));
+        assert_eq!(
+            points,
+            derive_from_bytes_with_domain::<Fr>(b"", b"block hash", 16)
+        );
+        assert!(points
+            .iter()
+            .enumerate()
+            .all(|(i, p)| !points[..i].contains(p)));
+
+        // The seed, domain and number of points all change the points
+        assert_ne!(points, derive_from_bytes(b"other hash", 16));
+        assert_ne!(
+            points,
+            derive_from_bytes_with_domain(b"sampling", b"block hash", 16)
+        );
+        assert_ne!(points[..8], derive_from_bytes::<Fr>(b"block hash", 8)[..]);
+        assert!(derive_from_bytes::<Fr>(b"block hash", 0).is_empty());
+
+        // Pinned so that other implementations can check against it
+        assert_eq!(
+            "dae7c4d797b1878fde5407c1259ac9870eafeddb70945ad25b7799ba93493b8d",
+            PointSetHash::of(&derive_from_bytes::<Fr>(b"pmp", 4)).to_string()
+        );
+    }
+}
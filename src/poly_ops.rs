@@ -1,16 +1,13 @@
 //! Polynomial operations and utilities
 //! Don't use these unless you're sure you have to
-use crate::{utils::smallest_power_of_2_greater_than, Error};
+use crate::{grid::radix2_domain, utils::smallest_power_of_2_greater_than, Error};
 use ark_ff::{FftField, Field};
 use ark_poly::{
-    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
-    Radix2EvaluationDomain,
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    MixedRadixEvaluationDomain, Polynomial, Radix2EvaluationDomain,
 };
-use core::{
-    iter::StepBy,
-    ops::{Mul, Range},
-};
-use ark_std::{vec::Vec, result::Result, vec};
+use ark_std::{result::Result, vec, vec::Vec};
+use core::ops::Mul;
 
 fn poly<F: Field>(p: Vec<F>) -> DensePolynomial<F> {
     DensePolynomial::from_coefficients_vec(p)
@@ -20,7 +17,7 @@ fn poly<F: Field>(p: Vec<F>) -> DensePolynomial<F> {
 fn inv_modl<F: FftField>(u: &DensePolynomial<F>, n: usize) -> DensePolynomial<F> {
     debug_assert!(!u[0].is_zero());
 
-    let u0 = u.coeffs[0].clone();
+    let u0 = u.coeffs[0];
     let mut v: Vec<F> = vec![F::one() / u0];
     for _i in 0..=smallest_power_of_2_greater_than(n) {
         let vpoly: DensePolynomial<F> = poly(v.clone());
@@ -47,6 +44,33 @@ pub(crate) fn rev_poly<F: Field>(mut p: DensePolynomial<F>) -> DensePolynomial<F
     poly(p.coeffs)
 }
 
+/// Divides `poly` by $x - z$ with synthetic division and returns the quotient. The remainder,
+/// which is `poly(z)`, is dropped.
+pub fn div_by_linear<F: Field>(poly: &[F], z: F) -> Vec<F> {
+    let mut q = vec![F::zero(); poly.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for i in (1..poly.len()).rev() {
+        acc = acc * z + poly[i];
+        q[i - 1] = acc;
+    }
+    q
+}
+
+/// Divides `poly` by the vanishing polynomial $x^m - h^m$ of `domain`, which may be a coset,
+/// and returns the quotient. Unlike `DensePolynomial::divide_by_vanishing_poly`, this is correct
+/// for cosets when the degree of `poly` is at least twice the size of the domain.
+pub fn div_by_vanishing_poly<F: FftField>(poly: &[F], domain: &impl EvaluationDomain<F>) -> Vec<F> {
+    let m = domain.size();
+    let c = domain.coset_offset_pow_size();
+    let mut q = vec![F::zero(); poly.len().saturating_sub(m)];
+    // p_i = q_{i-m} - c q_i, so q_{i-m} = p_i + c q_i
+    for i in (m..poly.len()).rev() {
+        let higher = q.get(i).map(|qi| c * qi).unwrap_or_default();
+        q[i - m] = poly[i] + higher;
+    }
+    q
+}
+
 /// Context for performing polynomial division with a fixed denominator in near-linear time
 pub struct FastDivisionContext<F: Field> {
     denom_rev_inv: DensePolynomial<F>,
@@ -68,10 +92,13 @@ impl<F: FftField> FastDivisionContext<F> {
     }
 
     /// Performs a fast division. This has roughly the runtime of polynomial multiplication.
-    pub fn fast_div(&self, num_poly: DensePolynomial<F>) -> Result<DensePolynomial<F>, ()> {
+    pub fn fast_div(&self, num_poly: DensePolynomial<F>) -> Result<DensePolynomial<F>, Error> {
         //TODO: Figure out what degrees are ok to use and error otherwise
         if num_poly.degree() > self.max_num_poly_deg {
-            return Err(());
+            return Err(Error::TooManyScalars {
+                n_coeffs: num_poly.degree() + 1,
+                expected_max: self.max_num_poly_deg + 1,
+            });
         }
         let l = num_poly.degree() - self.denom_degree + 1;
         let num_rev = rev_poly(num_poly);
@@ -81,54 +108,229 @@ impl<F: FftField> FastDivisionContext<F> {
     }
 }
 
-/// A conveniece wrapper for getting cyclic subgroups of a base evaluation domain
+/// The size of the largest radix 2 evaluation domain over `F`, which is $2^s$ where $s$ is the
+/// two-adicity of the field. This is $2^{28}$ for BN254 and $2^{32}$ for BLS12-381.
+pub fn max_domain_size<F: FftField>() -> usize {
+    1usize.checked_shl(F::TWO_ADICITY).unwrap_or(usize::MAX)
+}
+
+/// Checks that a radix 2 domain of `size` elements exists over `F`
+pub(crate) fn check_domain_size<F: FftField>(size: usize) -> Result<(), Error> {
+    let max = max_domain_size::<F>();
+    if size > max {
+        return Err(Error::DomainTooLarge { size, max });
+    }
+    Ok(())
+}
+
+/// Make the domain of exactly `size` elements over the coset with `offset`. This is a radix 2
+/// domain when `size` is a power of two, and otherwise a mixed radix domain, which exists for
+/// sizes $q^a 2^b$ when `F` has a small subgroup base $q$, such as $3 \cdot 2^k$ over BLS12-381.
+/// Unlike `GeneralEvaluationDomain::new`, the size is never rounded up.
+pub fn exact_domain<F: FftField>(size: usize, offset: F) -> Option<GeneralEvaluationDomain<F>> {
+    if size.is_power_of_two() {
+        Radix2EvaluationDomain::new_coset(size, offset).map(GeneralEvaluationDomain::Radix2)
+    } else if F::SMALL_SUBGROUP_BASE.is_none() {
+        // The mixed radix domain panics without a small subgroup
+        None
+    } else {
+        MixedRadixEvaluationDomain::new_coset(size, offset)
+            .filter(|d| d.size() == size)
+            .map(GeneralEvaluationDomain::MixedRadix)
+    }
+}
+
+/// A conveniece wrapper for getting cyclic subgroups of a base evaluation domain. The base domain
+/// can also be a coset $cH$ of a subgroup, in which case the splits are cosets of the same
+/// subgroups shifted by $c$.
+///
+/// In bit-reversed order (see [`SplitEvalDomain::into_brp`]) the items over the base domain are
+/// indexed by [`to_brp`] of its elements. Each subgroup is then a contiguous chunk of the items,
+/// as in the cells of most data availability specs. See [`SplitStrategy`] for the other ways
+/// the items can be split.
+///
+/// The base size doesn't need to be a power of two, see [`exact_domain`] for the sizes that are
+/// supported.
 #[derive(Clone, Debug)]
 pub struct SplitEvalDomain<F: FftField> {
-    base_domain: Radix2EvaluationDomain<F>,
+    base_domain: GeneralEvaluationDomain<F>,
     base_size: usize,
     n_splits: usize,
+    strategy: SplitStrategy,
+}
+
+/// How the items over the base domain of a [`SplitEvalDomain`] are split into groups. Data
+/// availability specs partition the cells of a row into sample groups differently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// The items are in natural order, and split `idx` is every `n_splits`th item starting at
+    /// `idx`. Each split is a coset of the subgroup of order `base_size / n_splits`.
+    #[default]
+    Strided,
+    /// The items are in bit-reversed order, and each split is a contiguous block of them. Each
+    /// split is again a coset of a subgroup, and the base size and number of splits must be
+    /// powers of two.
+    BitReversedBlocks,
+    /// The items are in natural order, and each split is a contiguous block of them. These
+    /// splits aren't cosets, so [`SplitEvalDomain::subgroup`] is `None` for them. They can still
+    /// be opened as general point sets with [`SplitEvalDomain::split_points`].
+    Contiguous,
 }
 
 impl<F: FftField> SplitEvalDomain<F> {
     /// Make a new split evaluation domain
     pub fn new(base_size: usize, n_splits: usize) -> Option<Self> {
-        let base = Radix2EvaluationDomain::new(base_size)?;
-        if base_size % n_splits != 0 {
+        Self::new_coset(base_size, n_splits, F::one())
+    }
+
+    /// Make a new split evaluation domain over the coset of the base domain with `offset`
+    pub fn new_coset(base_size: usize, n_splits: usize, offset: F) -> Option<Self> {
+        if offset.is_zero() {
+            return None;
+        }
+        let base = exact_domain(base_size, offset)?;
+        if n_splits == 0 || !base_size.is_multiple_of(n_splits) {
             return None;
         }
         Some(Self {
             base_domain: base,
             base_size,
             n_splits,
+            strategy: SplitStrategy::Strided,
         })
     }
 
+    /// Split the items with `strategy`. Returns `None` for [`SplitStrategy::BitReversedBlocks`]
+    /// unless the base size and the number of splits are powers of two.
+    pub fn with_strategy(mut self, strategy: SplitStrategy) -> Option<Self> {
+        if strategy == SplitStrategy::BitReversedBlocks
+            && (!self.base_size.is_power_of_two() || !self.n_splits.is_power_of_two())
+        {
+            return None;
+        }
+        self.strategy = strategy;
+        Some(self)
+    }
+
+    /// How the items are split
+    pub fn strategy(&self) -> SplitStrategy {
+        self.strategy
+    }
+
+    /// Index the items over the base domain in bit-reversed order, so subgroup `idx` is the
+    /// `idx`th chunk of `base_size / n_splits` items. Returns `None` unless the base size and the
+    /// number of splits are powers of two.
+    pub fn into_brp(self) -> Option<Self> {
+        self.with_strategy(SplitStrategy::BitReversedBlocks)
+    }
+
+    /// Whether items over the base domain are in bit-reversed order
+    pub fn is_brp(&self) -> bool {
+        self.strategy == SplitStrategy::BitReversedBlocks
+    }
+
     /// Get the base field
-    pub fn base(&self) -> &Radix2EvaluationDomain<F> {
+    pub fn base(&self) -> &GeneralEvaluationDomain<F> {
         &self.base_domain
     }
 
-    /// Get the subgroup with index `idx`
-    pub fn subgroup(&self, idx: usize) -> Option<Radix2EvaluationDomain<F>> {
-        if idx >= self.n_splits {
-            return None;
+    /// Get the number of subgroups the base domain is split into
+    pub fn n_splits(&self) -> usize {
+        self.n_splits
+    }
+
+    /// Get the subgroup with index `idx`, or `None` with [`SplitStrategy::Contiguous`]
+    pub fn subgroup(&self, idx: usize) -> Option<GeneralEvaluationDomain<F>> {
+        if idx >= self.n_splits || self.strategy == SplitStrategy::Contiguous {
+            None
         } else {
-            let gen = self.base_domain.group_gen().pow([idx.try_into().unwrap()]);
-            return Radix2EvaluationDomain::new_coset(self.base_size / self.n_splits, gen);
+            let gen = self
+                .base_domain
+                .group_gen()
+                .pow([self.natural_index(idx) as u64]);
+            let offset = self.base_domain.coset_offset() * gen;
+            exact_domain(self.base_size / self.n_splits, offset)
         }
     }
 
-    /// Gets the subgroups of the base cyclic group
-    pub fn subgroups(&self) -> Vec<Radix2EvaluationDomain<F>> {
+    /// Gets the subgroups of the base cyclic group. This is empty with
+    /// [`SplitStrategy::Contiguous`], whose splits aren't subgroups.
+    pub fn subgroups(&self) -> Vec<GeneralEvaluationDomain<F>> {
         (0..self.n_splits)
-            .into_iter()
-            .map(|idx| self.subgroup(idx).expect("idx < nsplits"))
+            .filter_map(|idx| self.subgroup(idx))
             .collect()
     }
 
-    /// Get indices of subgroup `idx` elements in the base domain
-    pub fn subgroup_indices(&self, idx: usize) -> StepBy<Range<usize>> {
-        (idx..self.base_size).step_by(self.n_splits)
+    /// Get indices of subgroup `idx` elements in the base domain, in the order of the subgroup's
+    /// elements
+    pub fn subgroup_indices(&self, idx: usize) -> impl ExactSizeIterator<Item = usize> + Clone {
+        let (n_splits, strategy) = (self.n_splits, self.strategy);
+        let set_size = self.base_size / n_splits;
+        (0..set_size).map(move |k| match strategy {
+            SplitStrategy::Strided => idx + k * n_splits,
+            // In bit-reversed order the kth element of subgroup idx is at brp(k) in chunk idx
+            SplitStrategy::BitReversedBlocks => idx * set_size + reverse_index_bits(k, set_size),
+            SplitStrategy::Contiguous => idx * set_size + k,
+        })
+    }
+
+    /// The split containing the item at `index`, and its position in that split, the inverse of
+    /// [`SplitEvalDomain::item_index`]
+    pub fn split_position(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.base_size {
+            return None;
+        }
+        let set_size = self.base_size / self.n_splits;
+        Some(match self.strategy {
+            SplitStrategy::Strided => (index % self.n_splits, index / self.n_splits),
+            SplitStrategy::BitReversedBlocks => (
+                index / set_size,
+                reverse_index_bits(index % set_size, set_size),
+            ),
+            SplitStrategy::Contiguous => (index / set_size, index % set_size),
+        })
+    }
+
+    /// The index of the item at `position` in split `idx`, the same as the `position`th element
+    /// of [`SplitEvalDomain::subgroup_indices`]
+    pub fn item_index(&self, idx: usize, position: usize) -> Option<usize> {
+        let set_size = self.base_size / self.n_splits;
+        if idx >= self.n_splits || position >= set_size {
+            return None;
+        }
+        self.subgroup_indices(idx).nth(position)
+    }
+
+    /// The points of the base domain in split `idx`, in the same order as
+    /// [`SplitEvalDomain::subgroup_indices`]. These can be opened with any strategy, e.g. as the
+    /// point sets of an [`M1Precomp`](crate::method1::precompute::M1Precomp).
+    pub fn split_points(&self, idx: usize) -> Option<Vec<F>> {
+        if idx >= self.n_splits {
+            return None;
+        }
+        Some(
+            self.subgroup_indices(idx)
+                .map(|i| self.base_domain.element(self.natural_item_index(i)))
+                .collect(),
+        )
+    }
+
+    /// The index in the base domain of the item at `index`
+    fn natural_item_index(&self, index: usize) -> usize {
+        if self.is_brp() {
+            reverse_index_bits(index, self.base_size)
+        } else {
+            index
+        }
+    }
+
+    /// The index of subgroup `idx` when the items are in natural order
+    fn natural_index(&self, idx: usize) -> usize {
+        if self.is_brp() {
+            reverse_index_bits(idx, self.n_splits)
+        } else {
+            idx
+        }
     }
 
     /// Get indices of subgroup `idx` elements in the base domain
@@ -138,18 +340,54 @@ impl<F: FftField> SplitEvalDomain<F> {
         items: T,
     ) -> Result<Vec<K>, Error> {
         if idx >= self.n_splits {
-            return Err(Error::InvalidSubgroupIndex{idx, n_splits: self.n_splits});
+            return Err(Error::InvalidSubgroupIndex {
+                idx,
+                n_splits: self.n_splits,
+            });
         }
         if items.as_ref().len() != self.base_size {
-            return Err(Error::InvalidInputLength{expected: self.base_size, got: items.as_ref().len()});
+            return Err(Error::InvalidInputLength {
+                expected: self.base_size,
+                got: items.as_ref().len(),
+            });
         }
         let items = items.as_ref();
-        Ok(
-            self.subgroup_indices(idx)
-                .map(|i| items[i].clone())
-                .collect(),
-        )
+        Ok(self
+            .subgroup_indices(idx)
+            .map(|i| items[i].clone())
+            .collect())
+    }
+}
+
+/// Reverses the low $\log_2 n$ bits of `i`
+fn reverse_index_bits(i: usize, n: usize) -> usize {
+    if n <= 1 {
+        return i;
+    }
+    i.reverse_bits() >> (usize::BITS - n.trailing_zeros())
+}
+
+/// The bit-reversal permutation of `0..n`, where `n` must be a power of two
+pub fn brp_indices(n: usize) -> Result<Vec<usize>, Error> {
+    if !n.is_power_of_two() {
+        return Err(Error::NotPowerOfTwo(n));
     }
+    Ok((0..n).map(|i| reverse_index_bits(i, n)).collect())
+}
+
+/// Reorder `items` from natural to bit-reversed order, so element `i` of the output is element
+/// `brp(i)` of the input. The number of items must be a power of two.
+pub fn to_brp<T: Clone>(items: &[T]) -> Result<Vec<T>, Error> {
+    Ok(brp_indices(items.len())?
+        .into_iter()
+        .map(|i| items[i].clone())
+        .collect())
+}
+
+/// Reorder `items` from bit-reversed to natural order, the inverse of [`to_brp`]
+pub fn from_brp<T: Clone>(items: &[T]) -> Result<Vec<T>, Error> {
+    // The permutation is its own inverse
+    to_brp(items)
 }
 
 /// Convenience method to get a vec of points from an evaluation domain
@@ -157,16 +395,133 @@ pub fn ev_points<F: FftField>(ev: &impl EvaluationDomain<F>) -> Vec<F> {
     ev.elements().collect()
 }
 
+/// Evaluates the polynomial with coefficients `poly` at each of `points` with Horner's method
+pub fn evaluate_at_points<F: Field>(poly: &[F], points: &[F]) -> Vec<F> {
+    points
+        .iter()
+        .map(|x| poly.iter().rev().fold(F::zero(), |acc, c| acc * x + c))
+        .collect()
+}
+
+/// Evaluates the polynomial with coefficients `poly` over a (coset) domain with an FFT, in the
+/// order of the domain's elements. Polynomials longer than the domain are first reduced mod
+/// $x^n - c^n$ for the coset offset $c$.
+pub fn evaluate_over_coset<F: FftField>(poly: &[F], domain: &impl EvaluationDomain<F>) -> Vec<F> {
+    let n = domain.size();
+    let offset_pow_size = domain.coset_offset_pow_size();
+    let mut reduced = vec![F::zero(); n];
+    let mut chunk_scale = F::one();
+    for chunk in poly.chunks(n) {
+        for (r, c) in reduced.iter_mut().zip(chunk) {
+            *r += chunk_scale * c;
+        }
+        chunk_scale *= offset_pow_size;
+    }
+    domain.fft_in_place(&mut reduced);
+    reduced
+}
+
+/// Erasure extend `evals`, the evaluations of a polynomial over the radix 2 domain of their size,
+/// to the domain `extension_factor` times larger. The extension is systematic, `evals[i]` is
+/// element `i * extension_factor` of the result, as in [`Grid::extend_rows`](crate::grid::Grid::extend_rows).
+pub fn extend<F: FftField>(evals: &[F], extension_factor: usize) -> Result<Vec<F>, Error> {
+    if evals.is_empty() {
+        return Err(Error::EmptyEvals);
+    }
+    if !extension_factor.is_power_of_two() {
+        return Err(Error::NotPowerOfTwo(extension_factor));
+    }
+    let domain = radix2_domain::<F>(evals.len())?;
+    let ext_domain = radix2_domain::<F>(evals.len().saturating_mul(extension_factor))?;
+    Ok(ext_domain.fft(&domain.ifft(evals)))
+}
+
+/// Recover all the evaluations of an extension made with [`extend`] from some of them, given as
+/// `(index, eval)` pairs. `extended_size` is the length of the extension and `extension_factor`
+/// the factor it was extended by, so at least `extended_size / extension_factor` evaluations are
+/// needed.
+///
+/// This uses the zero polynomial $Z(x)$ of the missing indices: $(E Z)(x)$, where $E$ is the
+/// evaluations with zeros at the missing indices, is the extended polynomial $D$ times $Z$ over
+/// the whole domain, so it's interpolated and divided by $Z$ over a coset, where $Z$ has no
+/// zeros.
+pub fn recover<F: FftField>(
+    partial_evals_with_indices: &[(usize, F)],
+    extended_size: usize,
+    extension_factor: usize,
+) -> Result<Vec<F>, Error> {
+    if !extension_factor.is_power_of_two() {
+        return Err(Error::NotPowerOfTwo(extension_factor));
+    }
+    let domain = radix2_domain::<F>(extended_size)?;
+    let needed = extended_size / extension_factor;
+    let mut known = vec![None; extended_size];
+    for (idx, eval) in partial_evals_with_indices {
+        match known.get_mut(*idx) {
+            None => {
+                return Err(Error::PositionOutOfRange {
+                    idx: *idx,
+                    len: extended_size,
+                })
+            }
+            Some(Some(_)) => return Err(Error::DuplicatePosition(*idx)),
+            Some(k) => *k = Some(*eval),
+        }
+    }
+    if partial_evals_with_indices.len() < needed.max(1) {
+        return Err(Error::TooFewEvals {
+            got: partial_evals_with_indices.len(),
+            needed: needed.max(1),
+        });
+    }
+
+    let missing = domain
+        .elements()
+        .zip(&known)
+        .filter(|(_, k)| k.is_none())
+        .map(|(x, _)| x)
+        .collect::<Vec<_>>();
+    let zero_poly = crate::vanishing_polynomial(missing);
+    let mut ez = domain.fft(&zero_poly);
+    for (e, k) in ez.iter_mut().zip(&known) {
+        *e *= k.unwrap_or_default();
+    }
+    domain.ifft_in_place(&mut ez);
+
+    let coset = domain
+        .get_coset(F::GENERATOR)
+        .ok_or(Error::DomainConstructionFailed(extended_size))?;
+    let mut z_coset = coset.fft(&zero_poly);
+    ark_ff::batch_inversion(&mut z_coset);
+    let mut d = coset.fft(&ez);
+    for (d, z_inv) in d.iter_mut().zip(z_coset) {
+        *d *= z_inv;
+    }
+    coset.ifft_in_place(&mut d);
+
+    if d[needed..].iter().any(|c| !c.is_zero()) {
+        return Err(Error::InconsistentEvals);
+    }
+    domain.fft_in_place(&mut d);
+    if d.iter()
+        .zip(&known)
+        .any(|(e, k)| k.is_some_and(|k| k != *e))
+    {
+        return Err(Error::InconsistentEvals);
+    }
+    Ok(d)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::poly_div_q_r;
     use ark_bls12_381::Fr;
-    use ark_ff::{One, Zero};
+    use ark_ff::{One, UniformRand, Zero};
     use rand::thread_rng;
     use rayon::prelude::*;
 
-    fn tostr(p: &Vec<Fr>) -> String {
+    fn tostr(p: &[Fr]) -> String {
         let a = p
             .iter()
             .map(|a| {
@@ -223,6 +578,31 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_div_by_linear() {
+        for size in [0, 1, 2, 50] {
+            let num = DensePolynomial::<Fr>::rand(size, &mut thread_rng());
+            let z = Fr::rand(&mut thread_rng());
+            let denom = DensePolynomial::from_coefficients_vec(vec![-z, Fr::one()]);
+            let (naive_q, _) = poly_div_q_r(num.clone().into(), denom.into()).unwrap();
+            assert_eq!(naive_q, div_by_linear(&num.coeffs, z));
+        }
+    }
+
+    #[test]
+    fn test_div_by_vanishing_poly() {
+        let split = SplitEvalDomain::<Fr>::new(64, 4).unwrap();
+        for subgroup in split.subgroups() {
+            for size in [0, 10, 16, 40, 100] {
+                let num = DensePolynomial::<Fr>::rand(size, &mut thread_rng());
+                let (naive_q, _) =
+                    poly_div_q_r(num.clone().into(), subgroup.vanishing_polynomial().into())
+                        .unwrap();
+                assert_eq!(naive_q, div_by_vanishing_poly(&num.coeffs, &subgroup));
+            }
+        }
+    }
+
     #[test]
     fn test_ev_points() {
         let evd = Radix2EvaluationDomain::<Fr>::new(256).unwrap();
@@ -247,7 +627,7 @@ mod tests {
         let mut inds = Vec::new();
         for i in 0..16 {
             let i_inds = split_evd.subgroup_indices(i);
-            dbg!(i_inds.clone().into_iter().collect::<Vec<_>>());
+            dbg!(i_inds.clone().collect::<Vec<_>>());
             let i_pts = ev_points(&split_evd.subgroup(i).unwrap());
             assert_eq!(i_inds.len(), i_pts.len());
             for (ind, pt) in i_inds.zip(i_pts) {
@@ -258,4 +638,134 @@ mod tests {
         inds.sort();
         assert_eq!(inds, (0..256).collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_brp() {
+        assert_eq!(Ok(vec![0, 4, 2, 6, 1, 5, 3, 7]), brp_indices(8));
+        assert_eq!(Ok(vec![0]), brp_indices(1));
+        assert_eq!(Err(Error::NotPowerOfTwo(6)), brp_indices(6));
+        let items = (0..64).collect::<Vec<_>>();
+        assert_eq!(items, from_brp(&to_brp(&items).unwrap()).unwrap());
+
+        let split = SplitEvalDomain::<Fr>::new(256, 16).unwrap();
+        assert!(!split.is_brp());
+        let brp = split.clone().into_brp().unwrap();
+        assert!(brp.is_brp());
+        assert!(SplitEvalDomain::<Fr>::new(96, 3)
+            .unwrap()
+            .into_brp()
+            .is_none());
+        assert!(SplitEvalDomain::<Fr>::new(96, 2)
+            .unwrap()
+            .into_brp()
+            .is_none());
+        let brp_pts = to_brp(&ev_points(brp.base())).unwrap();
+        for i in 0..16 {
+            // Each subgroup is a contiguous chunk
+            let mut inds = brp.subgroup_indices(i).collect::<Vec<_>>();
+            let pts = brp.take_subgroup_indices(i, &brp_pts).unwrap();
+            assert_eq!(ev_points(&brp.subgroup(i).unwrap()), pts);
+            inds.sort();
+            assert_eq!((i * 16..(i + 1) * 16).collect::<Vec<_>>(), inds);
+        }
+        // The subgroups are the same, just reordered
+        let mut natural = split.subgroups().iter().map(ev_points).collect::<Vec<_>>();
+        let mut reordered = brp.subgroups().iter().map(ev_points).collect::<Vec<_>>();
+        assert_ne!(natural, reordered);
+        natural.sort();
+        reordered.sort();
+        assert_eq!(natural, reordered);
+    }
+
+    #[test]
+    fn test_split_strategies() {
+        let split = SplitEvalDomain::<Fr>::new(64, 8).unwrap();
+        assert_eq!(SplitStrategy::Strided, split.strategy());
+        let all_pts = ev_points(split.base());
+        let brp_pts = to_brp(&all_pts).unwrap();
+        for strategy in [
+            SplitStrategy::Strided,
+            SplitStrategy::BitReversedBlocks,
+            SplitStrategy::Contiguous,
+        ] {
+            let split = split.clone().with_strategy(strategy).unwrap();
+            let items = if split.is_brp() { &brp_pts } else { &all_pts };
+            let mut seen = Vec::new();
+            for idx in 0..8 {
+                let points = split.split_points(idx).unwrap();
+                assert_eq!(points, split.take_subgroup_indices(idx, items).unwrap());
+                if let Some(subgroup) = split.subgroup(idx) {
+                    assert_eq!(ev_points(&subgroup), points);
+                }
+                for (pos, i) in split.subgroup_indices(idx).enumerate() {
+                    assert_eq!(Some((idx, pos)), split.split_position(i));
+                    assert_eq!(Some(i), split.item_index(idx, pos));
+                    seen.push(i);
+                }
+            }
+            seen.sort();
+            assert_eq!((0..64).collect::<Vec<_>>(), seen);
+            assert_eq!(None, split.split_position(64));
+            assert_eq!(None, split.item_index(0, 8));
+            assert_eq!(None, split.split_points(8));
+        }
+
+        let contiguous = split
+            .clone()
+            .with_strategy(SplitStrategy::Contiguous)
+            .unwrap();
+        assert_eq!(
+            (8..16).collect::<Vec<_>>(),
+            contiguous.subgroup_indices(1).collect::<Vec<_>>()
+        );
+        assert!(contiguous.subgroup(0).is_none());
+        assert!(contiguous.subgroups().is_empty());
+        assert!(SplitEvalDomain::<Fr>::new(96, 3)
+            .unwrap()
+            .with_strategy(SplitStrategy::Contiguous)
+            .is_some());
+    }
+
+    #[test]
+    fn test_extend_recover() {
+        let evals = (0..8)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let ext = extend(&evals, 4).unwrap();
+        assert_eq!(32, ext.len());
+        for (i, e) in evals.iter().enumerate() {
+            assert_eq!(*e, ext[4 * i]);
+        }
+        assert_eq!(Err(Error::NotPowerOfTwo(3)), extend(&evals, 3));
+        assert_eq!(Err(Error::NotPowerOfTwo(6)), extend(&evals[..6], 2));
+        assert_eq!(Err(Error::EmptyEvals), extend::<Fr>(&[], 2));
+
+        // Any 8 of the 32 evaluations recover the rest
+        let partial = ext
+            .iter()
+            .enumerate()
+            .skip(5)
+            .step_by(3)
+            .take(8)
+            .map(|(i, e)| (i, *e))
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(ext.clone()), recover(&partial, 32, 4));
+        let all = ext.iter().copied().enumerate().collect::<Vec<_>>();
+        assert_eq!(Ok(ext.clone()), recover(&all, 32, 4));
+
+        assert_eq!(
+            Err(Error::TooFewEvals { got: 7, needed: 8 }),
+            recover(&partial[..7], 32, 4)
+        );
+        let mut bad = partial.clone();
+        bad.push((0, ext[0] + Fr::one()));
+        assert_eq!(Err(Error::InconsistentEvals), recover(&bad, 32, 4));
+        bad[8] = (40, ext[0]);
+        assert_eq!(
+            Err(Error::PositionOutOfRange { idx: 40, len: 32 }),
+            recover(&bad, 32, 4)
+        );
+        bad[8] = partial[0];
+        assert_eq!(Err(Error::DuplicatePosition(5)), recover(&bad, 32, 4));
+    }
 }
@@ -0,0 +1,105 @@
+//! Progress reporting and cancellation for precomputations that can take minutes, like
+//! [`M1Precomp::from_inner_with_progress`](crate::method1::precompute::M1Precomp::from_inner_with_progress)
+//! and
+//! [`M1CyclPrecomp::from_inner_coset_with_progress`](crate::m1_cycl::M1CyclPrecomp::from_inner_coset_with_progress).
+//!
+//! The callback is given the number of steps done and the total, so it can drive a progress bar,
+//! e.g. an `indicatif::ProgressBar` with `|done, total| { bar.set_length(total as u64);
+//! bar.set_position(done as u64) }`. Steps may finish on several threads at once with the
+//! `parallel` feature, so the callback must be `Sync`.
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::Error;
+
+/// How to report progress of a precomputation, and when to stop it
+#[derive(Clone, Copy, Default)]
+pub struct Progress<'a> {
+    callback: Option<&'a (dyn Fn(usize, usize) + Sync)>,
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a> Progress<'a> {
+    /// Report nothing and never stop, which is what the constructors without progress use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` with the steps done and the total steps after each step finishes
+    pub fn with_callback(mut self, callback: &'a (dyn Fn(usize, usize) + Sync)) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    /// Stop with [`Error::Cancelled`] before the next step once `cancel` is set, e.g. on shutdown
+    pub fn with_cancel(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Start counting `total` steps
+    pub(crate) fn tracker(&self, total: usize) -> ProgressTracker<'a> {
+        if let Some(callback) = self.callback {
+            callback(0, total);
+        }
+        ProgressTracker {
+            progress: *self,
+            done: AtomicUsize::new(0),
+            total,
+        }
+    }
+}
+
+/// Counts the steps of one precomputation
+pub(crate) struct ProgressTracker<'a> {
+    progress: Progress<'a>,
+    done: AtomicUsize,
+    total: usize,
+}
+
+impl<'a> ProgressTracker<'a> {
+    /// Fails if the precomputation was cancelled, to be called before each step
+    pub fn check(&self) -> Result<(), Error> {
+        match self.progress.cancel {
+            Some(cancel) if cancel.load(Ordering::Relaxed) => Err(Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs a step, skipping it if cancelled, and reports it once it's done
+    pub fn step<T>(&self, f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+        self.check()?;
+        let res = f()?;
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(callback) = self.progress.callback {
+            callback(done, self.total);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use super::Progress;
+    use crate::Error;
+
+    #[test]
+    fn test_progress_tracker() {
+        let calls = AtomicUsize::new(0);
+        let callback = |done: usize, total: usize| {
+            assert_eq!(3, total);
+            assert_eq!(calls.fetch_add(1, Ordering::Relaxed), done);
+        };
+        let cancel = AtomicBool::new(false);
+        let progress = Progress::new()
+            .with_callback(&callback)
+            .with_cancel(&cancel);
+        let tracker = progress.tracker(3);
+        assert_eq!(Ok(1), tracker.step(|| Ok(1)));
+        assert_eq!(Ok(2), tracker.step(|| Ok(2)));
+        cancel.store(true, Ordering::Relaxed);
+        assert_eq!(Err(Error::Cancelled), tracker.step(|| Ok(3)));
+        assert_eq!(3, calls.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,263 @@
+//! Python bindings for [`M1CyclPrecomp`] over BLS12-381, enabled with the `python` feature.
+//!
+//! Scalars and points are passed as bytes in the arkworks compressed serialization, the same as
+//! the C API. Anything supporting the buffer protocol works in place of `bytes`, so a numpy
+//! `uint8` array of concatenated scalars can be passed directly. To build the module, run
+//! ```bash
+//! maturin develop --release
+//! ```
+// The wrappers generated by pyo3 convert every `PyResult` into itself
+#![allow(clippy::useless_conversion)]
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use merlin::Transcript;
+use pyo3::{buffer::PyBuffer, exceptions::PyValueError, prelude::*, types::PyBytes};
+
+use crate::{
+    m1_cycl::M1CyclPrecomp,
+    method1::{M1NoPrecomp, Proof},
+    msm::blst::BlstMSMEngine,
+    poly_ops::evaluate_over_coset,
+    traits::{Committer, PolyMultiProof},
+    Commitment, Error,
+};
+
+const SCALAR_BYTES: usize = 32;
+
+impl From<Error> for PyErr {
+    fn from(e: Error) -> Self {
+        PyValueError::new_err(e.to_string())
+    }
+}
+
+/// Method 1 with cyclic point sets over BLS12-381
+#[pyclass(name = "M1CyclPrecomp", module = "poly_multiproof")]
+pub struct PyM1CyclPrecomp(M1CyclPrecomp<Bls12_381, BlstMSMEngine>);
+
+#[pymethods]
+impl PyM1CyclPrecomp {
+    /// Make a new setup with a secret derived from `seed`, the same as
+    /// `M1NoPrecomp::new_insecure_for_testing`. This is insecure, and only for testing and
+    /// prototyping.
+    #[staticmethod]
+    fn insecure_from_seed(
+        seed: u64,
+        max_coeffs: usize,
+        base_size: usize,
+        num_point_sets: usize,
+    ) -> PyResult<Self> {
+        let inner =
+            M1NoPrecomp::new_insecure_for_testing(seed, max_coeffs, base_size / num_point_sets);
+        Ok(Self(M1CyclPrecomp::from_inner(
+            inner,
+            base_size,
+            num_point_sets,
+        )?))
+    }
+
+    /// Load a setup from its compressed serialization
+    #[staticmethod]
+    fn from_bytes(data: PyBuffer<u8>, py: Python<'_>) -> PyResult<Self> {
+        let data = data.to_vec(py)?;
+        let inner = M1CyclPrecomp::deserialize_compressed(data.as_slice())
+            .map_err(|_| PyValueError::new_err("invalid setup"))?;
+        Ok(Self(inner))
+    }
+
+    /// The compressed serialization of the setup
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut out = Vec::new();
+        self.0.serialize_compressed(&mut out).map_err(Error::from)?;
+        Ok(PyBytes::new_bound(py, &out))
+    }
+
+    /// The number of point sets
+    fn num_point_sets(&self) -> usize {
+        self.0.point_sets().n_splits()
+    }
+
+    /// The indices into the base domain of the points in point set `point_set`
+    fn point_set_indices(&self, point_set: usize) -> PyResult<Vec<usize>> {
+        if point_set >= self.num_point_sets() {
+            return Err(Error::PointSetIndexOutOfRange {
+                idx: point_set,
+                n_point_sets: self.num_point_sets(),
+            }
+            .into());
+        }
+        Ok(self.0.point_sets().subgroup_indices(point_set).collect())
+    }
+
+    /// Evaluate a polynomial over the whole base domain
+    fn evaluate<'py>(
+        &self,
+        py: Python<'py>,
+        coeffs: PyBuffer<u8>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let coeffs = scalars(py, &coeffs)?;
+        to_bytes(
+            py,
+            &evaluate_over_coset(&coeffs, self.0.point_sets().base()),
+        )
+    }
+
+    /// The evaluations of a polynomial in point set `point_set`, given its evaluations over the
+    /// whole base domain
+    fn point_set_evals<'py>(
+        &self,
+        py: Python<'py>,
+        evals: PyBuffer<u8>,
+        point_set: usize,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let evals = scalars(py, &evals)?;
+        let set_evals = self
+            .0
+            .point_sets()
+            .take_subgroup_indices(point_set, evals)?;
+        to_bytes(py, &set_evals)
+    }
+
+    /// Commit to a polynomial from its coefficients
+    fn commit<'py>(&self, py: Python<'py>, coeffs: PyBuffer<u8>) -> PyResult<Bound<'py, PyBytes>> {
+        let coeffs = scalars(py, &coeffs)?;
+        let commit = py.allow_threads(|| self.0.commit(coeffs))?;
+        to_bytes(py, &[commit.0])
+    }
+
+    /// Open polynomials at point set `point_set`, given the coefficients of each polynomial and
+    /// its evaluations in the point set. The transcript is seeded with `domain`.
+    fn open<'py>(
+        &self,
+        py: Python<'py>,
+        domain: &[u8],
+        evals: Vec<PyBuffer<u8>>,
+        polys: Vec<PyBuffer<u8>>,
+        point_set: usize,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let evals = scalar_rows(py, &evals)?;
+        let polys = scalar_rows(py, &polys)?;
+        let proof = py.allow_threads(|| {
+            self.0
+                .open(&mut transcript(domain), &evals, &polys, point_set)
+        })?;
+        to_bytes(py, &[proof.0])
+    }
+
+    /// Verify an opening made with `open`
+    fn verify(
+        &self,
+        py: Python<'_>,
+        domain: &[u8],
+        commits: Vec<PyBuffer<u8>>,
+        point_set: usize,
+        evals: Vec<PyBuffer<u8>>,
+        proof: PyBuffer<u8>,
+    ) -> PyResult<bool> {
+        let commits = commits
+            .iter()
+            .map(|c| g1(py, c).map(Commitment))
+            .collect::<PyResult<Vec<_>>>()?;
+        let evals = scalar_rows(py, &evals)?;
+        let proof = Proof(g1(py, &proof)?);
+        Ok(py.allow_threads(|| {
+            self.0
+                .verify(&mut transcript(domain), &commits, point_set, &evals, &proof)
+        })?)
+    }
+}
+
+fn transcript(domain: &[u8]) -> Transcript {
+    let mut t = Transcript::new(b"poly-multiproof python");
+    t.append_message(b"domain", domain);
+    t
+}
+
+fn scalars(py: Python<'_>, data: &PyBuffer<u8>) -> PyResult<Vec<Fr>> {
+    let data = data.to_vec(py)?;
+    if data.len() % SCALAR_BYTES != 0 {
+        return Err(PyValueError::new_err(format!(
+            "length {} is not a multiple of {SCALAR_BYTES}",
+            data.len()
+        )));
+    }
+    data.chunks(SCALAR_BYTES)
+        .map(|b| Fr::deserialize_compressed(b).map_err(|_| PyValueError::new_err("invalid scalar")))
+        .collect()
+}
+
+fn scalar_rows(py: Python<'_>, rows: &[PyBuffer<u8>]) -> PyResult<Vec<Vec<Fr>>> {
+    rows.iter().map(|r| scalars(py, r)).collect()
+}
+
+fn g1(py: Python<'_>, data: &PyBuffer<u8>) -> PyResult<G1Affine> {
+    G1Affine::deserialize_compressed(data.to_vec(py)?.as_slice())
+        .map_err(|_| PyValueError::new_err("invalid G1 point"))
+}
+
+fn to_bytes<'py>(
+    py: Python<'py>,
+    items: &[impl CanonicalSerialize],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut out = Vec::new();
+    for i in items {
+        i.serialize_compressed(&mut out).map_err(Error::from)?;
+    }
+    Ok(PyBytes::new_bound(py, &out))
+}
+
+/// The `poly_multiproof` python module
+#[pymodule]
+fn poly_multiproof(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("SCALAR_BYTES", SCALAR_BYTES)?;
+    m.add("G1_BYTES", 48)?;
+    m.add_class::<PyM1CyclPrecomp>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::poly_multiproof;
+    use pyo3::{prelude::*, types::PyDict};
+
+    #[test]
+    fn test_python_roundtrip() {
+        pyo3::append_to_inittab!(poly_multiproof);
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let locals = PyDict::new_bound(py);
+            py.run_bound(
+                r#"
+import poly_multiproof as pmp
+s = pmp.M1CyclPrecomp.insecure_from_seed(1, 64, 64, 4)
+s = pmp.M1CyclPrecomp.from_bytes(s.to_bytes())
+polys = [
+    b"".join(i.to_bytes(32, "little") for i in range(1, 41)),
+    b"".join(i.to_bytes(32, "little") for i in range(41, 81)),
+]
+set_evals = [s.point_set_evals(s.evaluate(p), 2) for p in polys]
+assert len(set_evals[0]) == 16 * pmp.SCALAR_BYTES
+assert s.point_set_indices(2)[:2] == [2, 6]
+commits = [s.commit(bytearray(p)) for p in polys]
+proof = s.open(b"test", set_evals, polys, 2)
+valid = s.verify(b"test", commits, 2, set_evals, proof)
+# With more than one polynomial the domain changes the challenge
+other_domain = s.verify(b"other", commits, 2, set_evals, proof)
+other_set = s.verify(b"test", commits, 1, set_evals, proof)
+try:
+    s.point_set_indices(4)
+    raised = False
+except ValueError:
+    raised = True
+"#,
+                Some(&locals),
+                None,
+            )
+            .unwrap();
+            let get = |k: &str| -> bool { locals.get_item(k).unwrap().unwrap().extract().unwrap() };
+            assert!(get("valid"));
+            assert!(!get("other_domain"));
+            assert!(!get("other_set"));
+            assert!(get("raised"));
+        });
+    }
+}
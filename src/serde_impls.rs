@@ -0,0 +1,197 @@
+//! `serde` support for the public types, enabled with the `serde` feature. Everything is encoded as
+//! its arkworks compressed serialization, as a hex string for human readable formats like JSON and
+//! as raw bytes otherwise. Deserialization checks that points are on the curve and in the right
+//! subgroup.
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{fmt, string::String, vec::Vec};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    ceremony, hiding, lagrange_basis::LagrangeBasis, m1_cycl::M1CyclPrecomp, method1, method2,
+    srs::Srs, traits::MSMEngine, Commitment,
+};
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Lower case hex, without a prefix
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|b| [HEX_CHARS[(b >> 4) as usize], HEX_CHARS[(b & 0xf) as usize]])
+        .map(char::from)
+        .collect()
+}
+
+/// Reads hex of either case, with or without a `0x` prefix
+pub(crate) fn hex_decode(hex: &str) -> Result<Vec<u8>, &'static str> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex).as_bytes();
+    if !hex.len().is_multiple_of(2) {
+        return Err("odd number of hex digits");
+    }
+    hex.chunks(2)
+        .map(|c| match (hex_value(c[0]), hex_value(c[1])) {
+            (Some(hi), Some(lo)) => Ok((hi << 4) | lo),
+            _ => Err("invalid hex digit"),
+        })
+        .collect()
+}
+
+fn serialize_canonical<S: Serializer>(
+    item: &impl CanonicalSerialize,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = Vec::with_capacity(item.compressed_size());
+    item.serialize_compressed(&mut bytes)
+        .map_err(serde::ser::Error::custom)?;
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex_encode(&bytes))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+fn deserialize_canonical<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)?
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)?
+    };
+    T::deserialize_compressed(bytes.as_slice()).map_err(de::Error::custom)
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Reads bytes from a hex string, a byte string, or a sequence of bytes
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        hex_decode(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+macro_rules! impl_serde_canonical {
+    ($ty:ty, $($generics:tt)*) => {
+        impl<$($generics)*> Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_canonical(self, serializer)
+            }
+        }
+
+        impl<'de, $($generics)*> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_canonical(deserializer)
+            }
+        }
+    };
+}
+
+impl_serde_canonical!(Commitment<E>, E: Pairing);
+impl_serde_canonical!(method1::Proof<E>, E: Pairing);
+impl_serde_canonical!(method1::DegreeBoundProof<E>, E: Pairing);
+impl_serde_canonical!(method1::BatchedSetsProof<E>, E: Pairing);
+impl_serde_canonical!(method2::Proof<E>, E: Pairing);
+impl_serde_canonical!(hiding::HidingProof<E>, E: Pairing);
+impl_serde_canonical!(ceremony::ContributionProof<E>, E: Pairing);
+impl_serde_canonical!(Srs<E>, E: Pairing);
+impl_serde_canonical!(method1::M1NoPrecomp<E, M>, E: Pairing, M: MSMEngine<E = E>);
+impl_serde_canonical!(method1::precompute::M1Precomp<E, M>, E: Pairing, M: MSMEngine<E = E>);
+impl_serde_canonical!(method1::verifier_key::VerifierKey<E, M>, E: Pairing, M: MSMEngine<E = E>);
+impl_serde_canonical!(M1CyclPrecomp<E, M>, E: Pairing, M: MSMEngine<E = E>);
+impl_serde_canonical!(LagrangeBasis<E, M>, E: Pairing, M: MSMEngine<E = E>);
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::{vec, vec::Vec};
+    use merlin::Transcript;
+
+    use crate::{
+        m1_cycl::M1CyclPrecomp,
+        method1::{M1NoPrecomp, Proof},
+        msm::blst::BlstMSMEngine,
+        test_rng,
+        testing::gen_openings,
+        traits::PolyMultiProofNoPrecomp,
+        Commitment,
+    };
+
+    fn compressed(item: &impl CanonicalSerialize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        item.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = vec![Fr::from(1u64), Fr::from(2u64)];
+        let (coeffs, evals, commits) = gen_openings(&s, 1, &points);
+        let commit = commits[0].clone();
+        let proof = s
+            .open(&mut Transcript::new(b"serde"), &evals, &coeffs, &points)
+            .unwrap();
+
+        let json = serde_json::to_string(&commit).unwrap();
+        assert_eq!(96 + 2, json.len());
+        let de: Commitment<Bls12_381> = serde_json::from_str(&json).unwrap();
+        assert_eq!(commit.0, de.0);
+        let bin = bincode::serialize(&proof).unwrap();
+        let de: Proof<Bls12_381> = bincode::deserialize(&bin).unwrap();
+        assert_eq!(proof.0, de.0);
+
+        let json = serde_json::to_vec(&s).unwrap();
+        let de: M1NoPrecomp<Bls12_381, BlstMSMEngine> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(compressed(&s), compressed(&de));
+        let s = M1CyclPrecomp::from_inner(s, 8, 2).unwrap();
+        let bin = bincode::serialize(&s).unwrap();
+        let de: M1CyclPrecomp<Bls12_381, BlstMSMEngine> = bincode::deserialize(&bin).unwrap();
+        assert_eq!(compressed(&s), compressed(&de));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(serde_json::from_str::<Commitment<Bls12_381>>("\"abc\"").is_err());
+        assert!(serde_json::from_str::<Commitment<Bls12_381>>("\"zz\"").is_err());
+        // The right length, but not a point
+        let zeros = format!("\"{}\"", "00".repeat(48));
+        assert!(serde_json::from_str::<Commitment<Bls12_381>>(&zeros).is_err());
+        assert!(bincode::deserialize::<Commitment<Bls12_381>>(&[0u8; 8]).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Powers of tau as a ceremony publishes them, before they're made into a scheme. Ceremonies
+//! don't follow the sizes [`M1NoPrecomp::new_from_scalar`] makes: they often have far more powers
+//! in G1 than in G2, or more powers in G2 than a scheme opens at, and may not use the standard
+//! generators. An [`Srs`] holds any number of powers in each group, and each scheme takes just
+//! the powers it needs with [`Srs::to_method1`] or [`Srs::to_method2`]. The precomputed schemes
+//! are built from these with their `from_inner` constructors as usual.
+//!
+//! The generators of a setup are its zeroth powers, which needn't be the standard generators of
+//! the curve. Check the powers with [`M1NoPrecomp::validate`] on the scheme made from them.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+use crate::{
+    check_setup_size, gen_curve_powers, gen_powers, method1::M1NoPrecomp, method2::M2NoPrecomp,
+    traits::MSMEngine, Error,
+};
+
+/// Powers of tau in G1 and G2, with any number of each
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Srs<E: Pairing> {
+    /// The powers of tau in G1, starting from the G1 generator
+    pub powers_of_g1: Vec<E::G1Affine>,
+    /// The powers of tau in G2, starting from the G2 generator
+    pub powers_of_g2: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> Srs<E> {
+    /// Make a setup from the given powers. Neither the sizes nor the powers are checked.
+    pub fn new(powers_of_g1: Vec<E::G1Affine>, powers_of_g2: Vec<E::G2Affine>) -> Self {
+        Self {
+            powers_of_g1,
+            powers_of_g2,
+        }
+    }
+
+    /// Make a setup with `n_g1` powers of the secret `x` in G1 and `n_g2` in G2
+    pub fn from_scalar(x: E::ScalarField, g1: E::G1, g2: E::G2, n_g1: usize, n_g2: usize) -> Self {
+        #[allow(unused_mut)]
+        let mut x_powers = gen_powers(x, core::cmp::max(n_g1, n_g2));
+        let powers_of_g1 = gen_curve_powers::<E::G1>(&x_powers[..n_g1], g1);
+        let powers_of_g2 = gen_curve_powers::<E::G2>(&x_powers[..n_g2], g2);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut x_powers);
+        Self::new(powers_of_g1, powers_of_g2)
+    }
+
+    /// The G1 generator, the zeroth power of tau in G1
+    pub fn g1(&self) -> Result<E::G1Affine, Error> {
+        check_setup_size(1, 1, self.powers_of_g1.len())?;
+        Ok(self.powers_of_g1[0])
+    }
+
+    /// The G2 generator, the zeroth power of tau in G2
+    pub fn g2(&self) -> Result<E::G2Affine, Error> {
+        check_setup_size(2, 1, self.powers_of_g2.len())?;
+        Ok(self.powers_of_g2[0])
+    }
+
+    /// The G2 generator multiplied by tau
+    pub fn g2x(&self) -> Result<E::G2Affine, Error> {
+        check_setup_size(2, 2, self.powers_of_g2.len())?;
+        Ok(self.powers_of_g2[1])
+    }
+
+    /// Whether the generators are the standard generators of the curve, which some verifiers,
+    /// such as precompiles, assume
+    pub fn has_standard_generators(&self) -> bool {
+        self.powers_of_g1.first() == Some(&E::G1Affine::generator())
+            && self.powers_of_g2.first() == Some(&E::G2Affine::generator())
+    }
+
+    /// Scale the generators by `g1` and `g2`, moving a setup made with one convention for the
+    /// generators to another. The secret is unchanged, so this is still a setup for it.
+    pub fn with_generators_scaled(&self, g1: E::ScalarField, g2: E::ScalarField) -> Self {
+        Self::new(
+            E::G1::normalize_batch(
+                &self
+                    .powers_of_g1
+                    .iter()
+                    .map(|p| *p * g1)
+                    .collect::<Vec<_>>(),
+            ),
+            E::G2::normalize_batch(
+                &self
+                    .powers_of_g2
+                    .iter()
+                    .map(|p| *p * g2)
+                    .collect::<Vec<_>>(),
+            ),
+        )
+    }
+
+    /// The most coefficients a polynomial committed with this setup can have
+    pub fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
+    }
+
+    /// The most points a method 1 proof with this setup can be verified at
+    pub fn max_pts(&self) -> usize {
+        self.powers_of_g2.len().saturating_sub(1)
+    }
+
+    /// Make a method 1 scheme for polynomials with up to `max_coeffs` coefficients opened at up to
+    /// `max_pts` points, as [`M1NoPrecomp::new`] makes, from the first powers of this setup.
+    /// Fails with [`Error::SetupTooSmall`] if there aren't enough of them.
+    pub fn to_method1<M: MSMEngine<E = E>>(
+        &self,
+        max_coeffs: usize,
+        max_pts: usize,
+    ) -> Result<M1NoPrecomp<E, M>, Error> {
+        let n_g2 = max_pts + 1;
+        let n_g1 = core::cmp::max(max_coeffs, n_g2);
+        check_setup_size(1, n_g1, self.powers_of_g1.len())?;
+        check_setup_size(2, n_g2, self.powers_of_g2.len())?;
+        Ok(M1NoPrecomp::new_from_affine(
+            self.powers_of_g1[..n_g1].to_vec(),
+            self.powers_of_g2[..n_g2].to_vec(),
+        ))
+    }
+
+    /// Make a method 1 scheme from every power in this setup, which needs at least as many powers
+    /// in G1 as in G2
+    pub fn to_method1_full<M: MSMEngine<E = E>>(&self) -> Result<M1NoPrecomp<E, M>, Error> {
+        self.to_method1(self.max_coeffs(), self.max_pts())
+    }
+
+    /// Make a method 2 scheme for polynomials with up to `max_coeffs` coefficients from the first
+    /// powers of this setup. Method 2 only needs the first two powers in G2.
+    pub fn to_method2<M: MSMEngine<E = E>>(
+        &self,
+        max_coeffs: usize,
+    ) -> Result<M2NoPrecomp<E, M>, Error> {
+        check_setup_size(1, max_coeffs, self.powers_of_g1.len())?;
+        Ok(M2NoPrecomp::new_from_affine(
+            self.powers_of_g1[..max_coeffs].to_vec(),
+            self.g2()?,
+            self.g2x()?,
+        ))
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> From<&M1NoPrecomp<E, M>> for Srs<E> {
+    fn from(s: &M1NoPrecomp<E, M>) -> Self {
+        Self::new(s.powers_of_g1.clone(), s.powers_of_g2.clone())
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> From<&M2NoPrecomp<E, M>> for Srs<E> {
+    fn from(s: &M2NoPrecomp<E, M>) -> Self {
+        Self::new(s.powers_of_g1.clone(), Vec::from([s.g2, s.g2x]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::Field;
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    use super::Srs;
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::ArkMSMEngine,
+        poly_ops::evaluate_at_points,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    type Ark = ArkMSMEngine<Bls12_381>;
+
+    #[test]
+    fn test_ragged_srs() {
+        let x = Fr::rand(&mut test_rng());
+        // More powers in G2 than the scheme needs, and far fewer than in G1
+        let srs = Srs::<Bls12_381>::from_scalar(
+            x,
+            G1Projective::generator(),
+            G2Projective::generator(),
+            64,
+            20,
+        );
+        assert!(srs.has_standard_generators());
+        assert_eq!((64, 19), (srs.max_coeffs(), srs.max_pts()));
+
+        let m1 = srs.to_method1::<Ark>(32, 8).unwrap();
+        assert_eq!((32, 8), (m1.max_coeffs(), m1.max_pts()));
+        let reference = M1NoPrecomp::<Bls12_381, Ark>::new_from_scalar(
+            x,
+            G1Projective::generator(),
+            G2Projective::generator(),
+            32,
+            8,
+        );
+        assert_eq!(reference.powers_of_g1, m1.powers_of_g1);
+        assert_eq!(reference.powers_of_g2, m1.powers_of_g2);
+        assert_eq!(srs.to_method1_full::<Ark>().unwrap().max_pts(), 19);
+
+        let m2 = srs.to_method2::<Ark>(64).unwrap();
+        assert_eq!(srs.g2x().unwrap(), m2.g2x);
+        assert_eq!(Srs::from(&m2).powers_of_g2, srs.powers_of_g2[..2]);
+        assert_eq!(Srs::from(&m1), srs_prefix(&srs, 32, 9));
+
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 2,
+                needed: 21,
+                got: 20
+            }),
+            srs.to_method1::<Ark>(32, 20).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 65,
+                got: 64
+            }),
+            srs.to_method2::<Ark>(65).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 2,
+                needed: 2,
+                got: 0
+            }),
+            Srs::<Bls12_381>::new(srs.powers_of_g1.clone(), Vec::new())
+                .g2x()
+                .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_scaled_generators() {
+        let x = Fr::rand(&mut test_rng());
+        let (a, b) = (Fr::rand(&mut test_rng()), Fr::rand(&mut test_rng()));
+        let srs = Srs::<Bls12_381>::from_scalar(
+            x,
+            G1Projective::generator(),
+            G2Projective::generator(),
+            32,
+            5,
+        );
+        let scaled = srs.with_generators_scaled(a, b);
+        assert!(!scaled.has_standard_generators());
+        assert_eq!(
+            Srs::from_scalar(
+                x,
+                G1Projective::generator() * a,
+                G2Projective::generator() * b,
+                32,
+                5
+            ),
+            scaled
+        );
+        assert_eq!(G1Projective::generator() * a, scaled.g1().unwrap());
+        assert_eq!(
+            srs,
+            scaled.with_generators_scaled(a.inverse().unwrap(), b.inverse().unwrap())
+        );
+
+        // Schemes from setups with other generators still open and verify
+        let s = scaled.to_method1::<Ark>(32, 4).unwrap();
+        s.validate(&mut test_rng()).unwrap();
+        let poly = (0..32)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = [evaluate_at_points(&poly, &points)];
+        let proof = s
+            .open(&mut Transcript::new(b"srs"), &evals, &[&poly], &points)
+            .unwrap();
+        let commit = s.commit(&poly).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"srs"),
+                &[commit],
+                &points,
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    fn srs_prefix(srs: &Srs<Bls12_381>, n_g1: usize, n_g2: usize) -> Srs<Bls12_381> {
+        Srs::new(
+            srs.powers_of_g1[..n_g1].to_vec(),
+            srs.powers_of_g2[..n_g2].to_vec(),
+        )
+    }
+}
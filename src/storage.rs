@@ -0,0 +1,516 @@
+//! Backing stores for the powers of tau in G1. For large setups these are hundreds of MB, so
+//! [`StoredM1`] is a method 1 setup which reads them from an [`SrsStorage`] in chunks rather
+//! than holding them all in memory. With the `mmap` feature on unix, `MmapStorage` maps a file
+//! written by [`write_srs_file`], and the OS pages points in as the msms read them.
+//!
+//! Only commitments and openings read the full setup. Verifying needs just the first few powers,
+//! which [`StoredM1`] keeps in memory in a regular [`M1NoPrecomp`].
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::{Compress, Write};
+use ark_std::{borrow::Cow, ops::Range, rand::RngCore, vec::Vec};
+
+use crate::{
+    check_poly_sizes, gen_powers,
+    method1::{compute_quotient, opening_challenge, verifier_key::VerifierKey, M1NoPrecomp, Proof},
+    traits::{
+        g1_compressed_size, Committer, MSMEngine, MemoryEstimate, PolyMultiProofNoPrecomp,
+        SchemeInfo, TranscriptProtocol,
+    },
+    vanishing_polynomial, Commitment, Error,
+};
+
+/// The number of points read from storage for each partial msm
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 16;
+
+/// Marks the start of a file written by [`write_srs_file`], followed by the number of points and
+/// the size of each point as little endian u64s
+const SRS_FILE_MAGIC: &[u8; 8] = b"PMPSRS01";
+
+/// A source of curve points which can be read by range
+pub trait SrsStorage<G: Clone>: Send + Sync {
+    /// The number of points stored
+    fn len(&self) -> usize;
+
+    /// Whether there are no points stored
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the points in `range`, which must be in bounds
+    fn read(&self, range: Range<usize>) -> Result<Cow<'_, [G]>, Error>;
+}
+
+impl<G: Clone + Send + Sync> SrsStorage<G> for Vec<G> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn read(&self, range: Range<usize>) -> Result<Cow<'_, [G]>, Error> {
+        let len = self.as_slice().len();
+        self.get(range.clone())
+            .map(Cow::Borrowed)
+            .ok_or(Error::InvalidInputLength {
+                expected: len,
+                got: range.end,
+            })
+    }
+}
+
+/// Computes the msm of `scalars` against the first points of `storage`, reading `chunk_size`
+/// points at a time
+pub fn storage_msm<M: MSMEngine>(
+    storage: &impl SrsStorage<<M::E as Pairing>::G1Affine>,
+    scalars: &[<M::E as Pairing>::ScalarField],
+    chunk_size: usize,
+) -> Result<<M::E as Pairing>::G1, Error> {
+    if scalars.len() > storage.len() {
+        return Err(Error::TooManyScalars {
+            n_coeffs: scalars.len(),
+            expected_max: storage.len(),
+        });
+    }
+    let mut res = <M::E as Pairing>::G1::default();
+    for (i, chunk) in scalars.chunks(chunk_size.max(1)).enumerate() {
+        let start = i * chunk_size.max(1);
+        // Points in memory are used where they are, points read from a file are already a copy
+        res += match storage.read(start..start + chunk.len())? {
+            Cow::Borrowed(bases) => M::multi_scalar_mul_g1_unprepared(bases, chunk)?,
+            Cow::Owned(bases) => M::multi_scalar_mul_g1(&M::prepare_g1(bases), chunk)?,
+        };
+    }
+    Ok(res)
+}
+
+/// Write `points` in the format read by `MmapStorage`. Points are stored uncompressed so they
+/// can be read without decompressing.
+pub fn write_srs_file<G: AffineRepr>(mut writer: impl Write, points: &[G]) -> Result<(), Error> {
+    let point_size = G::zero().serialized_size(Compress::No);
+    writer
+        .write_all(SRS_FILE_MAGIC)
+        .map_err(|_| Error::SerializationError)?;
+    writer
+        .write_all(&(points.len() as u64).to_le_bytes())
+        .map_err(|_| Error::SerializationError)?;
+    writer
+        .write_all(&(point_size as u64).to_le_bytes())
+        .map_err(|_| Error::SerializationError)?;
+    for p in points {
+        p.serialize_uncompressed(&mut writer)
+            .map_err(|_| Error::SerializationError)?;
+    }
+    Ok(())
+}
+
+/// A method 1 setup with the powers of tau in G1 read from a [`SrsStorage`]. Proofs and
+/// commitments are the same as those of the [`M1NoPrecomp`] with the same powers.
+#[derive(Clone)]
+pub struct StoredM1<E: Pairing, M: MSMEngine<E = E>, S> {
+    /// The powers of tau in G1
+    pub storage: S,
+    /// The setup used for verifying, which holds the G1 powers up to the number of G2 powers
+    pub head: M1NoPrecomp<E, M>,
+    /// The number of points read for each partial msm
+    pub chunk_size: usize,
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>, S: SrsStorage<E::G1Affine>> StoredM1<E, M, S> {
+    /// Make a setup from the powers of tau in G1 in `storage`, and the powers in G2 for the same
+    /// tau
+    pub fn new(storage: S, powers_of_g2: Vec<E::G2Affine>) -> Result<Self, Error> {
+        let n_head = core::cmp::min(storage.len(), powers_of_g2.len());
+        let head_g1 = storage.read(0..n_head)?.into_owned();
+        Ok(Self {
+            head: M1NoPrecomp::new_from_affine(head_g1, powers_of_g2),
+            storage,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        })
+    }
+
+    /// Read `chunk_size` points at a time in msms
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// The maximum number of coefficients of a committed polynomial
+    pub fn max_coeffs(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// The key for verifying this setup's proofs. Fails with [`Error::SetupTooSmall`] if the
+    /// storage or the powers in G2 are empty.
+    pub fn verifier_key(&self) -> Result<VerifierKey<E, M>, Error> {
+        let mut vk = self.head.verifier_key()?;
+        vk.max_coeffs = self.max_coeffs();
+        Ok(vk)
+    }
+
+    fn msm(&self, scalars: &[E::ScalarField]) -> Result<E::G1, Error> {
+        storage_msm::<M>(&self.storage, scalars, self.chunk_size)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>, S: SrsStorage<E::G1Affine>> SchemeInfo for StoredM1<E, M, S> {
+    fn max_coeffs(&self) -> usize {
+        StoredM1::max_coeffs(self)
+    }
+
+    fn max_pts(&self) -> usize {
+        self.head.max_pts()
+    }
+
+    fn scheme_name(&self) -> &'static str {
+        "m1-stored"
+    }
+
+    fn proof_size_bytes(&self) -> usize {
+        g1_compressed_size::<E>()
+    }
+
+    /// Only the head of the setup, up to the number of G2 powers, is held in memory. The chunks
+    /// read from storage during an msm aren't counted.
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate {
+        let head = M1NoPrecomp::<E, M>::memory_estimate(max_pts + 1, max_pts, num_point_sets);
+        MemoryEstimate {
+            per_open: M1NoPrecomp::<E, M>::memory_estimate(max_coeffs, max_pts, num_point_sets)
+                .per_open,
+            ..head
+        }
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>, S: SrsStorage<E::G1Affine>> Committer<E>
+    for StoredM1<E, M, S>
+{
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        Ok(Commitment(self.msm(poly.as_ref())?.into_affine()))
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>, S: SrsStorage<E::G1Affine>> PolyMultiProofNoPrecomp<E>
+    for StoredM1<E, M, S>
+{
+    type Proof = Proof<E>;
+
+    fn open(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        self.head.check_n_points(points.len())?;
+        let gamma = opening_challenge(
+            transcript,
+            &self
+                .head
+                .transcript_encoding
+                .prover_commits(polys.len(), |i| self.commit(&polys[i]))?,
+            evals,
+            polys,
+            points,
+            &self.head.transcript_encoding,
+        )?;
+        check_poly_sizes(polys, self.storage.len())?;
+        let gammas = gen_powers(gamma, polys.len());
+        let vp: DensePolynomial<E::ScalarField> = vanishing_polynomial(points);
+        let q = compute_quotient(polys, &gammas, &vp)?;
+        Ok(Proof(self.msm(&q.coeffs)?.into_affine()))
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.head.verify(transcript, commits, points, evals, proof)
+    }
+
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Proof<E>,
+        )],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        self.head.verify_batch(transcripts, items, rng)
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+pub use mmap::MmapStorage;
+
+#[cfg(all(feature = "mmap", unix))]
+#[allow(unsafe_code)]
+mod mmap {
+    use std::{fs::File, marker::PhantomData, ops::Range, os::unix::io::AsRawFd, path::Path};
+
+    use ark_ec::AffineRepr;
+    use ark_serialize::{Compress, Validate};
+    use ark_std::{borrow::Cow, vec::Vec};
+
+    use super::{SrsStorage, SRS_FILE_MAGIC};
+    use crate::Error;
+
+    /// The magic bytes, the number of points and the size of each point
+    const SRS_FILE_HEADER_SIZE: usize = 24;
+
+    /// A read only mapping of a whole file
+    struct Mapping {
+        ptr: *mut libc::c_void,
+        len: usize,
+    }
+
+    // The mapping is read only and never aliased mutably
+    unsafe impl Send for Mapping {}
+    unsafe impl Sync for Mapping {}
+
+    impl Mapping {
+        fn new(file: &File) -> Result<Self, Error> {
+            let len = file.metadata().map_err(|_| Error::SrsFileUnreadable)?.len() as usize;
+            if len < SRS_FILE_HEADER_SIZE {
+                return Err(Error::InvalidSrsFile);
+            }
+            // SAFETY: the file is mapped privately and read only, and the length is the file's
+            let ptr = unsafe {
+                libc::mmap(
+                    core::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ,
+                    libc::MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(Error::SrsFileUnreadable);
+            }
+            Ok(Self { ptr, len })
+        }
+
+        fn bytes(&self) -> &[u8] {
+            // SAFETY: the mapping is valid for `len` bytes until it's dropped
+            unsafe { core::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: the pointer and length came from a successful mmap
+            unsafe {
+                libc::munmap(self.ptr, self.len);
+            }
+        }
+    }
+
+    /// Curve points read from a memory mapped file written by [`super::write_srs_file`]. Nothing
+    /// is read from disk until the points are used. The file must not be modified while it's
+    /// mapped.
+    pub struct MmapStorage<G> {
+        mapping: Mapping,
+        len: usize,
+        point_size: usize,
+        validate: Validate,
+        _marker: PhantomData<G>,
+    }
+
+    impl<G: AffineRepr> MmapStorage<G> {
+        /// Map the file at `path`. With [`Validate::Yes`] each point is checked to be on the curve
+        /// and in the subgroup as it's read, otherwise the file must be trusted.
+        pub fn open(path: impl AsRef<Path>, validate: Validate) -> Result<Self, Error> {
+            let file = File::open(path).map_err(|_| Error::SrsFileUnreadable)?;
+            let mapping = Mapping::new(&file)?;
+            let header = &mapping.bytes()[..SRS_FILE_HEADER_SIZE];
+            let len = u64::from_le_bytes(header[8..16].try_into().unwrap()) as usize;
+            let point_size = u64::from_le_bytes(header[16..24].try_into().unwrap()) as usize;
+            if &header[..8] != SRS_FILE_MAGIC
+                || point_size != G::zero().serialized_size(Compress::No)
+                || len
+                    .checked_mul(point_size)
+                    .and_then(|n| n.checked_add(SRS_FILE_HEADER_SIZE))
+                    != Some(mapping.len)
+            {
+                return Err(Error::InvalidSrsFile);
+            }
+            Ok(Self {
+                mapping,
+                len,
+                point_size,
+                validate,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    impl<G: AffineRepr> SrsStorage<G> for MmapStorage<G> {
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn read(&self, range: Range<usize>) -> Result<Cow<'_, [G]>, Error> {
+            if range.start > range.end || range.end > self.len {
+                return Err(Error::InvalidInputLength {
+                    expected: self.len,
+                    got: range.end,
+                });
+            }
+            let start = SRS_FILE_HEADER_SIZE + range.start * self.point_size;
+            let end = SRS_FILE_HEADER_SIZE + range.end * self.point_size;
+            self.mapping.bytes()[start..end]
+                .chunks(self.point_size)
+                .map(|b| {
+                    G::deserialize_with_mode(b, Compress::No, self.validate)
+                        .map_err(|_| Error::InvalidSrsFile)
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Cow::Owned)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::CurveGroup;
+    use ark_std::{vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::{storage_msm, write_srs_file, SrsStorage, StoredM1};
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        testing::{gen_openings, test_basic_no_precomp},
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    #[test]
+    fn test_stored_m1() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 32, &mut test_rng());
+        let stored =
+            StoredM1::<_, BlstMSMEngine, _>::new(s.powers_of_g1.clone(), s.powers_of_g2.clone())
+                .unwrap()
+                .with_chunk_size(50);
+        assert_eq!(256, stored.max_coeffs());
+        assert_eq!(33, stored.head.powers_of_g1.len());
+        test_basic_no_precomp(&stored);
+
+        // Proofs and commitments match the in memory setup
+        let points = (0..8u64).map(Fr::from).collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+        assert_eq!(commits[1].0, stored.commit(&coeffs[1]).unwrap().0);
+        let proof = stored
+            .open(&mut Transcript::new(b"stored"), &evals, &coeffs, &points)
+            .unwrap();
+        let expected = s
+            .open(&mut Transcript::new(b"stored"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(expected.0, proof.0);
+
+        let too_big = (0..257)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 257,
+                expected_max: 256
+            }),
+            storage_msm::<BlstMSMEngine>(&s.powers_of_g1, &too_big, 64)
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 256,
+                got: 300
+            }),
+            SrsStorage::read(&s.powers_of_g1, 200..300).map(|p| p.len())
+        );
+        assert_eq!(
+            s.commit(&too_big[..200]).unwrap().0,
+            storage_msm::<ArkMSMEngine<Bls12_381>>(&s.powers_of_g1, &too_big[..200], 64)
+                .unwrap()
+                .into_affine()
+        );
+
+        let vk = stored.verifier_key().unwrap();
+        assert_eq!(256, vk.max_coeffs);
+        assert_eq!(
+            Ok(true),
+            vk.verify(
+                &mut Transcript::new(b"stored"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        let empty =
+            StoredM1::<_, BlstMSMEngine, _>::new(Vec::new(), s.powers_of_g2.clone()).unwrap();
+        assert_eq!(
+            Err(Error::SetupTooSmall {
+                group: 1,
+                needed: 1,
+                got: 0
+            }),
+            empty.verifier_key().map(|_| ())
+        );
+
+        let mut file = Vec::new();
+        write_srs_file(&mut file, &s.powers_of_g1[..2]).unwrap();
+        assert_eq!(24 + 2 * 96, file.len());
+        assert_eq!(b"PMPSRS01", &file[..8]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "mmap", unix))]
+    fn test_mmap_storage() {
+        use ark_bls12_381::G1Affine;
+        use ark_serialize::Validate;
+
+        use super::MmapStorage;
+
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(128, 32, &mut test_rng());
+        let path = std::env::temp_dir().join(format!("pmp-srs-{}", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write_srs_file(&mut file, &s.powers_of_g1).unwrap();
+        drop(file);
+
+        let storage = MmapStorage::<G1Affine>::open(&path, Validate::Yes).unwrap();
+        assert_eq!(128, storage.len());
+        assert_eq!(&s.powers_of_g1[10..20], &storage.read(10..20).unwrap()[..]);
+        let stored = StoredM1::<_, BlstMSMEngine, _>::new(storage, s.powers_of_g2.clone())
+            .unwrap()
+            .with_chunk_size(40);
+        test_basic_no_precomp(&stored);
+        let poly = (0..128)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(s.commit(&poly).unwrap().0, stored.commit(&poly).unwrap().0);
+
+        // Truncated and mismatched files are rejected
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(
+            Some(Error::InvalidSrsFile),
+            MmapStorage::<G1Affine>::open(&path, Validate::Yes).err()
+        );
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            Some(Error::InvalidSrsFile),
+            MmapStorage::<ark_bls12_381::G2Affine>::open(&path, Validate::Yes).err()
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Some(Error::SrsFileUnreadable),
+            MmapStorage::<G1Affine>::open(&path, Validate::Yes).err()
+        );
+    }
+}
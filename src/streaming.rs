@@ -0,0 +1,122 @@
+//! Committing to polynomials whose coefficients arrive in chunks
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::Zero;
+use ark_std::marker::PhantomData;
+
+use crate::{traits::MSMEngine, Commitment, Error};
+
+/// Accumulates a commitment from chunks of coefficients, in order from lowest to highest degree.
+/// Each chunk is committed with a partial msm against the matching slice of the powers of tau, so
+/// the full polynomial never needs to be held in memory.
+#[derive(Clone, Debug)]
+pub struct StreamingCommitter<'a, E: Pairing, M: MSMEngine<E = E>> {
+    powers_of_g1: &'a [E::G1Affine],
+    offset: usize,
+    accum: E::G1,
+    _marker: PhantomData<M>,
+}
+
+impl<'a, E: Pairing, M: MSMEngine<E = E>> StreamingCommitter<'a, E, M> {
+    /// Make a new streaming committer over the given powers of tau in G1
+    pub fn new(powers_of_g1: &'a [E::G1Affine]) -> Self {
+        Self {
+            powers_of_g1,
+            offset: 0,
+            accum: E::G1::zero(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of coefficients committed to so far
+    pub fn len(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether no coefficients have been committed to yet
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Add the next chunk of coefficients to the commitment
+    pub fn update(&mut self, chunk: impl AsRef<[E::ScalarField]>) -> Result<(), Error> {
+        let chunk = chunk.as_ref();
+        let end = self.offset + chunk.len();
+        if end > self.powers_of_g1.len() {
+            return Err(Error::TooManyScalars {
+                n_coeffs: end,
+                expected_max: self.powers_of_g1.len(),
+            });
+        }
+        let bases = M::prepare_g1(self.powers_of_g1[self.offset..end].to_vec());
+        self.accum += M::multi_scalar_mul_g1(&bases, chunk)?;
+        self.offset = end;
+        Ok(())
+    }
+
+    /// Finish the commitment
+    pub fn finalize(self) -> Commitment<E> {
+        Commitment(self.accum.into_affine())
+    }
+
+    /// Commit to all the chunks yielded by `chunks`
+    pub fn commit_chunks(
+        mut self,
+        chunks: impl IntoIterator<Item = impl AsRef<[E::ScalarField]>>,
+    ) -> Result<Commitment<E>, Error> {
+        for chunk in chunks {
+            self.update(chunk)?;
+        }
+        Ok(self.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_std::vec::Vec;
+
+    use crate::{
+        method1::M1NoPrecomp,
+        method2::M2NoPrecomp,
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        test_rng,
+        traits::Committer,
+        Error,
+    };
+
+    #[test]
+    fn test_streaming_matches_commit() {
+        let poly = (0..200)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 4, &mut test_rng());
+        let expected = s.commit(&poly).unwrap();
+        let got = s
+            .streaming_committer()
+            .commit_chunks(poly.chunks(64))
+            .unwrap();
+        assert_eq!(expected.0, got.0);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, &mut test_rng());
+        let expected = s.commit(&poly).unwrap();
+        let mut sc = s.streaming_committer();
+        assert!(sc.is_empty());
+        for chunk in poly.chunks(33) {
+            sc.update(chunk).unwrap();
+        }
+        assert_eq!(poly.len(), sc.len());
+        assert_eq!(expected.0, sc.finalize().0);
+
+        let mut sc = s.streaming_committer();
+        sc.update(&poly).unwrap();
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 400,
+                expected_max: 256
+            }),
+            sc.update(&poly)
+        );
+    }
+}
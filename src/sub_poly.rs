@@ -0,0 +1,269 @@
+//! Proofs that a contiguous range of coefficients of a committed polynomial is a second, smaller
+//! committed polynomial, e.g. for a storage provider to show it holds a chunk of a committed blob.
+//!
+//! For the range $[a, b)$ of $f$, the prover splits
+//! $f(X) = l(X) + X^a g(X) + X^b h(X)$ where $g$ holds the range, and commits to $l$ and $h$.
+//! This only pins $g$ down if $l$ has fewer than $a$ coefficients and $g$ fewer than $b - a$,
+//! which is shown with the reversed polynomials $\tilde{l}(X) = X^{a-1} l(1/X)$ and
+//! $\tilde{g}(X) = X^{b-a-1} g(1/X)$. These are only polynomials when the degree bounds hold, so
+//! the prover can't commit to them otherwise.
+//!
+//! All the commitments go into the transcript to derive a point $z$, and the polynomials are
+//! opened at $z$ and $1/z$ with a single multiproof. The verifier checks the split at $z$ and
+//! each reversal against its polynomial at $1/z$, none of which needs powers of tau in G2 past
+//! what a two point opening does.
+use core::ops::Range;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{vec, vec::Vec};
+
+use crate::{
+    get_challenge, get_field_size,
+    method1::Proof,
+    poly_ops::evaluate_at_points,
+    traits::{Committer, PolyMultiProofNoPrecomp, TranscriptProtocol},
+    transcribe_generic, Commitment, Error,
+};
+
+/// A proof that a range of coefficients of a committed polynomial is another committed polynomial
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SubPolyProof<E: Pairing> {
+    /// The coefficients before the range, $[l(x)]_1$
+    pub low: Commitment<E>,
+    /// The coefficients after the range, $[h(x)]_1$
+    pub high: Commitment<E>,
+    /// The reversed low coefficients, $[\tilde{l}(x)]_1$
+    pub low_rev: Commitment<E>,
+    /// The reversed range, $[\tilde{g}(x)]_1$
+    pub sub_rev: Commitment<E>,
+    /// The evaluations of $f, g, l, h, \tilde{l}, \tilde{g}$ at $z$ and $1/z$
+    pub evals: Vec<Vec<E::ScalarField>>,
+    /// The multiproof of the evaluations
+    pub proof: Proof<E>,
+}
+
+/// Commit to the coefficients of `poly` in `range` and prove they're the range of `poly`.
+/// Coefficients past the end of `poly` are zero. Returns the commitment to the range along with
+/// the proof.
+pub fn prove_sub_poly<
+    E: Pairing,
+    S: PolyMultiProofNoPrecomp<E, Proof = Proof<E>> + Committer<E>,
+>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    poly: &[E::ScalarField],
+    range: Range<usize>,
+) -> Result<(Commitment<E>, SubPolyProof<E>), Error> {
+    check_range(&range)?;
+    let coeffs = |r: Range<usize>| -> Vec<E::ScalarField> {
+        let mut c = vec![E::ScalarField::ZERO; r.len()];
+        let end = r.end.min(poly.len());
+        if r.start < end {
+            c[..end - r.start].copy_from_slice(&poly[r.start..end]);
+        }
+        c
+    };
+    let low = coeffs(0..range.start);
+    let sub = coeffs(range.clone());
+    let high = coeffs(range.end..poly.len().max(range.end));
+    let low_rev = low.iter().rev().copied().collect::<Vec<_>>();
+    let sub_rev = sub.iter().rev().copied().collect::<Vec<_>>();
+
+    let commit = srs.commit(poly)?;
+    let sub_commit = srs.commit(&sub)?;
+    let low_commit = srs.commit(&low)?;
+    let high_commit = srs.commit(&high)?;
+    let low_rev_commit = srs.commit(&low_rev)?;
+    let sub_rev_commit = srs.commit(&sub_rev)?;
+    let points = sub_poly_points(
+        transcript,
+        &range,
+        [
+            &commit,
+            &sub_commit,
+            &low_commit,
+            &high_commit,
+            &low_rev_commit,
+            &sub_rev_commit,
+        ],
+    )?;
+
+    let polys = [poly, &sub, &low, &high, &low_rev, &sub_rev];
+    let evals = polys
+        .iter()
+        .map(|p| evaluate_at_points(p, &points))
+        .collect::<Vec<_>>();
+    let proof = srs.open(transcript, &evals, &polys, &points)?;
+    Ok((
+        sub_commit,
+        SubPolyProof {
+            low: low_commit,
+            high: high_commit,
+            low_rev: low_rev_commit,
+            sub_rev: sub_rev_commit,
+            evals,
+            proof,
+        },
+    ))
+}
+
+/// Verify that `sub_commit` commits to the coefficients in `range` of the polynomial under
+/// `commit`
+pub fn verify_sub_poly<E: Pairing, S: PolyMultiProofNoPrecomp<E, Proof = Proof<E>>>(
+    srs: &S,
+    transcript: &mut impl TranscriptProtocol,
+    commit: &Commitment<E>,
+    sub_commit: &Commitment<E>,
+    range: Range<usize>,
+    proof: &SubPolyProof<E>,
+) -> Result<bool, Error> {
+    check_range(&range)?;
+    let points = sub_poly_points(
+        transcript,
+        &range,
+        [
+            commit,
+            sub_commit,
+            &proof.low,
+            &proof.high,
+            &proof.low_rev,
+            &proof.sub_rev,
+        ],
+    )?;
+    if proof.evals.len() != 6 || proof.evals.iter().any(|e| e.len() != 2) {
+        return Ok(false);
+    }
+    let (z, z_inv) = (points[0], points[1]);
+    let at = |i: usize, j: usize| proof.evals[i][j];
+    // f(z) = l(z) + z^a g(z) + z^b h(z)
+    let split =
+        at(2, 0) + z.pow([range.start as u64]) * at(1, 0) + z.pow([range.end as u64]) * at(3, 0);
+    // The reversals are z^{d-1} times their polynomial at 1/z, for d = a and d = b - a
+    let low_rev = z.pow([range.start as u64]) * z_inv * at(2, 1);
+    let sub_rev = z.pow([range.len() as u64]) * z_inv * at(1, 1);
+    if at(0, 0) != split || at(4, 0) != low_rev || at(5, 0) != sub_rev {
+        return Ok(false);
+    }
+    let commits = [
+        commit.clone(),
+        sub_commit.clone(),
+        proof.low.clone(),
+        proof.high.clone(),
+        proof.low_rev.clone(),
+        proof.sub_rev.clone(),
+    ];
+    srs.verify(transcript, &commits, &points, &proof.evals, &proof.proof)
+}
+
+fn check_range(range: &Range<usize>) -> Result<(), Error> {
+    if range.is_empty() {
+        return Err(Error::PositionOutOfRange {
+            idx: range.start,
+            len: range.end,
+        });
+    }
+    Ok(())
+}
+
+/// Absorbs the range and commitments, and derives the points $z$ and $1/z$
+fn sub_poly_points<E: Pairing>(
+    transcript: &mut impl TranscriptProtocol,
+    range: &Range<usize>,
+    commits: [&Commitment<E>; 6],
+) -> Result<Vec<E::ScalarField>, Error> {
+    transcript.append_message(b"sub poly start", &(range.start as u64).to_be_bytes());
+    transcript.append_message(b"sub poly end", &(range.end as u64).to_be_bytes());
+    for c in commits {
+        transcribe_generic(transcript, b"sub poly commit", &c.0)?;
+    }
+    let z: E::ScalarField = get_challenge(
+        transcript,
+        b"sub poly point",
+        get_field_size::<E::ScalarField>(),
+    );
+    let z_inv = z.inverse().ok_or(Error::DivisorIsZero)?;
+    Ok(vec![z, z_inv])
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    use super::{prove_sub_poly, verify_sub_poly};
+    use crate::{method1::M1NoPrecomp, msm::ArkMSMEngine, test_rng, traits::Committer, Error};
+
+    #[test]
+    fn test_sub_poly() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 4, &mut test_rng());
+        let poly = (0..40)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let commit = s.commit(&poly).unwrap();
+        for range in [0..8, 8..16, 30..40, 32..48, 0..40] {
+            let (sub_commit, proof) =
+                prove_sub_poly(&s, &mut Transcript::new(b"sub"), &poly, range.clone()).unwrap();
+            let sub = poly[range.start..range.end.min(poly.len())].to_vec();
+            assert_eq!(s.commit(&sub).unwrap().0, sub_commit.0);
+            assert_eq!(
+                Ok(true),
+                verify_sub_poly(
+                    &s,
+                    &mut Transcript::new(b"sub"),
+                    &commit,
+                    &sub_commit,
+                    range.clone(),
+                    &proof
+                )
+            );
+            // The same commitment isn't the range shifted by one
+            let shifted = range.start + 1..range.end + 1;
+            assert_eq!(
+                Ok(false),
+                verify_sub_poly(
+                    &s,
+                    &mut Transcript::new(b"sub"),
+                    &commit,
+                    &sub_commit,
+                    shifted,
+                    &proof
+                )
+            );
+        }
+
+        // A commitment to other coefficients fails even with a consistent split, since the low
+        // part then has too many coefficients to reverse
+        let (sub_commit, proof) =
+            prove_sub_poly(&s, &mut Transcript::new(b"sub"), &poly, 8..16).unwrap();
+        let other = s.commit(&poly[9..17]).unwrap();
+        assert_eq!(
+            Ok(false),
+            verify_sub_poly(
+                &s,
+                &mut Transcript::new(b"sub"),
+                &commit,
+                &other,
+                8..16,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            verify_sub_poly(
+                &s,
+                &mut Transcript::new(b"other"),
+                &commit,
+                &sub_commit,
+                8..16,
+                &proof
+            )
+        );
+        assert_eq!(
+            Err(Error::PositionOutOfRange { idx: 4, len: 4 }),
+            prove_sub_poly(&s, &mut Transcript::new(b"sub"), &poly, 4..4).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! A pairing with its groups swapped, so the schemes commit and prove in the other group.
+//!
+//! Every scheme is generic over the [`Pairing`], and commits to polynomials and makes proofs in
+//! its G1 while verifiers use the powers of tau in G2. [`Swapped<E>`] is `E` with G1 and G2
+//! exchanged, so `M1NoPrecomp<Swapped<E>, ArkMSMEngine<Swapped<E>>>` has commitments and proofs in
+//! `E`'s G2, and verifiers only need the powers of tau in `E`'s G1. This puts the smaller group on
+//! whichever side a protocol sends more of, such as a verifier key which is shipped to many
+//! clients, or points which are cheaper to check in a particular group.
+//!
+//! The blst MSM engine is specific to BLS12-381's groups, so swapped schemes use
+//! [`ArkMSMEngine`](crate::msm::ArkMSMEngine).
+use ark_ec::pairing::{MillerLoopOutput, Pairing, PairingOutput};
+use ark_std::marker::PhantomData;
+
+/// The pairing `E` with G1 and G2 exchanged, so $e_{swapped}(a, b) = e(b, a)$
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Swapped<E: Pairing>(PhantomData<E>);
+
+impl<E: Pairing> Pairing for Swapped<E> {
+    type BaseField = E::BaseField;
+    type ScalarField = E::ScalarField;
+    type G1 = E::G2;
+    type G1Affine = E::G2Affine;
+    type G1Prepared = E::G2Prepared;
+    type G2 = E::G1;
+    type G2Affine = E::G1Affine;
+    type G2Prepared = E::G1Prepared;
+    type TargetField = E::TargetField;
+
+    fn multi_miller_loop(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> MillerLoopOutput<Self> {
+        let a = a.into_iter().map(|p| -> E::G2Prepared { p.into() });
+        let b = b.into_iter().map(|p| -> E::G1Prepared { p.into() });
+        MillerLoopOutput(E::multi_miller_loop(b, a).0)
+    }
+
+    fn final_exponentiation(mlo: MillerLoopOutput<Self>) -> Option<PairingOutput<Self>> {
+        E::final_exponentiation(MillerLoopOutput(mlo.0)).map(|p| PairingOutput(p.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ec::pairing::Pairing;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+
+    use super::Swapped;
+    use crate::{
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        method2::M2NoPrecomp,
+        msm::ArkMSMEngine,
+        test_rng,
+        testing::{test_basic_no_precomp, test_basic_precomp, test_batch_precomp},
+        traits::Committer,
+    };
+
+    type Sw = Swapped<Bls12_381>;
+
+    #[test]
+    fn test_swapped_pairing() {
+        let a = G1Projective::rand(&mut test_rng());
+        let b = G2Projective::rand(&mut test_rng());
+        assert_eq!(Bls12_381::pairing(a, b).0, Sw::pairing(b, a).0);
+        assert_eq!(
+            Bls12_381::multi_pairing([a, -a], [b, b]).0,
+            Sw::multi_pairing([b, b], [a, -a]).0
+        );
+    }
+
+    #[test]
+    fn test_swapped_schemes() {
+        let m1 = M1NoPrecomp::<Sw, ArkMSMEngine<Sw>>::new(256, 30, &mut test_rng());
+        test_basic_no_precomp(&m1);
+        // Commitments are in BLS12-381's G2
+        let commit = m1.commit([1u64.into(), 2u64.into()]).unwrap();
+        assert_eq!(96, commit.compressed_size());
+
+        let point_sets = (0..2)
+            .map(|i| (0..4).map(|j| (10 * i + j + 1).into()).collect())
+            .collect::<Vec<Vec<_>>>();
+        let precomp = M1Precomp::from_inner(m1, point_sets.clone()).unwrap();
+        test_basic_precomp(&precomp, &point_sets[0]);
+        test_batch_precomp(&precomp, &point_sets);
+
+        let m2 = M2NoPrecomp::<Sw, ArkMSMEngine<Sw>>::new(256, &mut test_rng());
+        test_basic_no_precomp(&m2);
+    }
+}
@@ -0,0 +1,336 @@
+//! Method 1 openings at a tensor grid of points $(x_i, y_j)$, with $x_i = \omega^i$ ranging over a
+//! radix-2 subgroup $H$ of size $n$ and $y_j$ over $m$ shifts. Each pair is the point
+//! $y_j \omega^i$, so the grid is the union of the cosets $y_j H$, which is how 2d sampling schemes
+//! lay out the cells they open.
+//!
+//! The vanishing polynomial of the grid factors as
+//! $Z(X) = \prod_j (X^n - y_j^n) = Z_Y(X^n)$, where $Z_Y$ vanishes at the $m$ values $u_j = y_j^n$.
+//! Openings split the combined polynomial into the $n$ strided polynomials $g_k$ with
+//! $f(X) = \sum_k X^k g_k(X^n)$ and divide each by the degree $m$ polynomial $Z_Y$, rather than
+//! dividing by the degree $nm$ polynomial $Z$. Verifiers interpolate the evaluations with a coset
+//! ifft per shift and an interpolation over the $u_j$, rather than over all $nm$ points.
+//!
+//! Proofs are the same as [`PolyMultiProofNoPrecomp::open`](crate::traits::PolyMultiProofNoPrecomp::open)
+//! at [`TensorGrid::points`], so either side can use the generic methods.
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::FftField;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
+};
+use ark_std::{vec, vec::Vec};
+
+use crate::{
+    check_poly_sizes, check_verify_sizes, gen_powers,
+    grid::radix2_domain,
+    lagrange::{linear_combination_of_evals, LagrangeInterpContext},
+    method1::{combine_polys, opening_challenge, M1NoPrecomp, Proof},
+    poly_div_q_r_in_place,
+    traits::{MSMEngine, TranscriptProtocol},
+    transcribe_points_and_evals, vanishing_polynomial, Commitment, Error,
+};
+
+/// A grid of $nm$ points $y_j \omega^i$, ordered by shift and then by power of $\omega$, so the
+/// point at index $jn + i$ is $y_j \omega^i$. Evaluations opened at the grid are in this order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TensorGrid<F: FftField> {
+    domain: Radix2EvaluationDomain<F>,
+    shifts: Vec<F>,
+    /// The coefficients of $Z_Y$, the vanishing polynomial of the $y_j^n$
+    shift_zeros: Vec<F>,
+}
+
+impl<F: FftField> TensorGrid<F> {
+    /// Make a grid over the subgroup of size `n`, which must be a power of two, and `shifts`. The
+    /// cosets of the shifts must be disjoint, i.e. the $y_j^n$ must be nonzero and distinct.
+    pub fn new(n: usize, shifts: Vec<F>) -> Result<Self, Error> {
+        let domain = radix2_domain::<F>(n)?;
+        if shifts.is_empty() {
+            return Err(Error::NoPointsGiven);
+        }
+        let shift_pows = shifts.iter().map(|y| y.pow([n as u64])).collect::<Vec<_>>();
+        for (j, u) in shift_pows.iter().enumerate() {
+            if u.is_zero() || shift_pows[..j].contains(u) {
+                return Err(Error::OverlappingCosets(j));
+            }
+        }
+        let shift_zeros = vanishing_polynomial(&shift_pows).coeffs;
+        Ok(Self {
+            domain,
+            shifts,
+            shift_zeros,
+        })
+    }
+
+    /// The size $n$ of the subgroup
+    pub fn domain_size(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// The shifts $y_j$
+    pub fn shifts(&self) -> &[F] {
+        &self.shifts
+    }
+
+    /// The number of points in the grid
+    pub fn len(&self) -> usize {
+        self.domain_size() * self.shifts.len()
+    }
+
+    /// A grid always has at least one point
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The points of the grid, in order
+    pub fn points(&self) -> Vec<F> {
+        let elements = self.domain.elements().collect::<Vec<_>>();
+        self.shifts
+            .iter()
+            .flat_map(|y| elements.iter().map(move |x| *y * x))
+            .collect()
+    }
+
+    /// The vanishing polynomial $Z(X) = Z_Y(X^n)$ of the grid
+    pub fn vanishing_polynomial(&self) -> DensePolynomial<F> {
+        let n = self.domain_size();
+        let mut coeffs = vec![F::zero(); (self.shift_zeros.len() - 1) * n + 1];
+        for (t, c) in self.shift_zeros.iter().enumerate() {
+            coeffs[t * n] = *c;
+        }
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// Divides `num` by $Z_Y(X^n)$, returning the quotient, by dividing each strided polynomial
+    /// $g_k$ by $Z_Y$. The quotient of $g_k$ holds the coefficients of the quotient at $k$ mod $n$.
+    fn divide(&self, num: &[F]) -> Result<Vec<F>, Error> {
+        let n = self.domain_size();
+        let mut q = vec![F::zero(); num.len()];
+        for k in 0..n.min(num.len()) {
+            let mut g_k = num[k..].iter().step_by(n).copied().collect::<Vec<_>>();
+            let q_k = poly_div_q_r_in_place(&mut g_k, &self.shift_zeros)?;
+            for (t, c) in q_k.into_iter().enumerate() {
+                q[t * n + k] = c;
+            }
+        }
+        Ok(q)
+    }
+
+    /// Interpolates the evaluations `targets` at the grid to the polynomial $r$ with fewer than
+    /// $nm$ coefficients. Writing $r(X) = \sum_k X^k r_k(X^n)$, a coset ifft of the evaluations at
+    /// $y_j H$ gives the $r_k(y_j^n)$, and each $r_k$ is interpolated from those.
+    fn interpolate(&self, targets: &[F]) -> Result<Vec<F>, Error> {
+        let n = self.domain_size();
+        let shift_pows = self
+            .shifts
+            .iter()
+            .map(|y| y.pow([n as u64]))
+            .collect::<Vec<_>>();
+        let lag_ctx = LagrangeInterpContext::new_from_points(&shift_pows)?;
+        let mut r = vec![F::zero(); self.len()];
+        for ((y, evals), l_j) in self
+            .shifts
+            .iter()
+            .zip(targets.chunks(n))
+            .zip(lag_ctx.lag_polys())
+        {
+            let coset = self
+                .domain
+                .get_coset(*y)
+                .ok_or(Error::DomainConstructionFailed(n))?;
+            // The kth coefficient is r_k(y_j^n)
+            for (k, r_k_u) in coset.ifft(evals).into_iter().enumerate() {
+                for (t, l) in l_j.coeffs.iter().enumerate() {
+                    r[t * n + k] += r_k_u * l;
+                }
+            }
+        }
+        Ok(r)
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
+    /// Open the polynomials at the points of `grid`, with `evals` in the grid's order. The
+    /// transcript and proof are the same as opening at [`TensorGrid::points`].
+    pub fn open_tensor_grid(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        grid: &TensorGrid<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        self.check_n_points(grid.len())?;
+        let gamma = opening_challenge(
+            transcript,
+            &self.transcript_commits(polys)?,
+            evals,
+            polys,
+            &grid.points(),
+            &self.transcript_encoding,
+        )?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let q = grid.divide(&combine_polys(polys, &gammas)?)?;
+        let proof = self.install(|| self.g1_msm(&q))?;
+        Ok(Proof(proof.into_affine()))
+    }
+
+    /// Verify an opening at the points of `grid`, made with [`M1NoPrecomp::open_tensor_grid`]
+    /// or by opening at [`TensorGrid::points`]
+    pub fn verify_tensor_grid(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        grid: &TensorGrid<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        self.check_n_points(grid.len())?;
+        check_verify_sizes(commits, evals, grid.len())?;
+        transcribe_points_and_evals(
+            transcript,
+            commits,
+            &grid.points(),
+            evals,
+            &self.transcript_encoding,
+        )?;
+        let gamma = self
+            .transcript_encoding
+            .challenge(transcript, b"open gamma");
+        let gammas = gen_powers(gamma, evals.len());
+        let targets = linear_combination_of_evals(evals, &gammas, grid.len());
+        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.g1_precomp, grid.interpolate(&targets)?)?;
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
+
+        // Only every nth power of tau is needed for Z_Y(x^n)
+        let bases = self
+            .powers_of_g2
+            .iter()
+            .step_by(grid.domain_size())
+            .take(grid.shift_zeros.len())
+            .copied()
+            .collect::<Vec<_>>();
+        let g2_zeros = crate::curve_msm::<E::G2>(&bases, &grid.shift_zeros)?;
+        Ok(M::pairing_eq_check(
+            (gamma_cm_pt - gamma_ris_pt).into_affine(),
+            self.powers_of_g2[0],
+            proof.0,
+            g2_zeros.into_affine(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{FftField, Field, One};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    use super::TensorGrid;
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::ArkMSMEngine,
+        poly_ops::evaluate_at_points,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        vanishing_polynomial, Error,
+    };
+
+    #[test]
+    fn test_tensor_grid() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 30, &mut test_rng());
+        let shifts = (0..3).map(|j| Fr::GENERATOR.pow([j])).collect();
+        let grid = TensorGrid::new(8, shifts).unwrap();
+        let points = grid.points();
+        assert_eq!(24, grid.len());
+        assert_eq!(vanishing_polynomial(&points), grid.vanishing_polynomial());
+
+        let polys = (0..3)
+            .map(|_| {
+                (0..50)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| evaluate_at_points(p, &points))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+
+        let proof = s
+            .open_tensor_grid(&mut Transcript::new(b"grid"), &evals, &polys, &grid)
+            .unwrap();
+        let generic = s
+            .open(&mut Transcript::new(b"grid"), &evals, &polys, &points)
+            .unwrap();
+        assert_eq!(generic.0, proof.0);
+
+        assert!(s
+            .verify_tensor_grid(
+                &mut Transcript::new(b"grid"),
+                &commits,
+                &evals,
+                &proof,
+                &grid
+            )
+            .unwrap());
+        assert!(s
+            .verify(
+                &mut Transcript::new(b"grid"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+            .unwrap());
+
+        let mut bad_evals = evals.clone();
+        bad_evals[1][9] += Fr::one();
+        assert!(!s
+            .verify_tensor_grid(
+                &mut Transcript::new(b"grid"),
+                &commits,
+                &bad_evals,
+                &proof,
+                &grid
+            )
+            .unwrap());
+        assert!(!s
+            .verify_tensor_grid(
+                &mut Transcript::new(b"other"),
+                &commits,
+                &evals,
+                &proof,
+                &grid
+            )
+            .unwrap());
+
+        // 30 points are supported, a 4 by 8 grid has 32
+        let big = TensorGrid::new(8, (1..5).map(|j| Fr::from(j as u64)).collect()).unwrap();
+        assert!(matches!(
+            s.open_tensor_grid(&mut Transcript::new(b"grid"), &evals, &polys, &big),
+            Err(Error::TooManyPoints { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tensor_grid_overlap() {
+        let omega = Radix2EvaluationDomain::<Fr>::new(4).unwrap().group_gen();
+        let y = Fr::from(5u64);
+        assert_eq!(
+            Err(Error::OverlappingCosets(1)),
+            TensorGrid::new(4, vec![y, y * omega])
+        );
+        assert_eq!(
+            Err(Error::OverlappingCosets(0)),
+            TensorGrid::new(4, vec![Fr::from(0u64), y])
+        );
+        assert_eq!(Err(Error::NotPowerOfTwo(3)), TensorGrid::new(3, vec![y]));
+    }
+}
@@ -0,0 +1,322 @@
+//! A harness checking that several schemes made from the same setup are interchangeable: for the
+//! same polynomials, points and transcript they give byte-identical commitments and proofs, and
+//! each accepts the others' proofs. Method 1 with and without precomputation, including the
+//! cyclic precomputation at its own point sets, all prove the same way, so a new scheme or MSM
+//! engine can be checked against [`M1NoPrecomp`](crate::method1::M1NoPrecomp) by wrapping both
+//! with [`AtPoints`] or [`AtPointSet`] and calling [`assert_equivalent`].
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::RngCore, vec::Vec};
+use merlin::Transcript;
+
+use crate::{
+    poly_ops::evaluate_at_points,
+    traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+    Commitment, Error,
+};
+
+/// The label of the transcripts the harness opens and verifies with
+pub const TRANSCRIPT_LABEL: &[u8] = b"scheme equivalence";
+
+/// A scheme opening at one fixed set of points, with its proofs in their compressed
+/// serialization so schemes with different proof types can be compared
+pub trait EquivalenceCase<E: Pairing> {
+    /// The points opened at
+    fn points(&self) -> &[E::ScalarField];
+
+    /// Commit to `poly`
+    fn commit(&self, poly: &[E::ScalarField]) -> Result<Commitment<E>, Error>;
+
+    /// Open `polys` with evaluations `evals` at the points, returning the compressed proof
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[Vec<E::ScalarField>],
+        polys: &[Vec<E::ScalarField>],
+    ) -> Result<Vec<u8>, Error>;
+
+    /// Verify a compressed proof of `evals` at the points
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[Vec<E::ScalarField>],
+        proof: &[u8],
+    ) -> Result<bool, Error>;
+}
+
+/// A scheme without precomputation opening at `points`
+pub struct AtPoints<'a, E: Pairing, S> {
+    /// The scheme
+    pub scheme: &'a S,
+    /// The points opened at
+    pub points: Vec<E::ScalarField>,
+}
+
+/// A scheme with precomputation opening at the point set `point_set_index`, which is `points`
+pub struct AtPointSet<'a, E: Pairing, S> {
+    /// The scheme
+    pub scheme: &'a S,
+    /// The index of the point set opened at
+    pub point_set_index: usize,
+    /// The points of the point set, in the scheme's order
+    pub points: Vec<E::ScalarField>,
+}
+
+fn serialize_proof(proof: &impl CanonicalSerialize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+impl<'a, E, S> EquivalenceCase<E> for AtPoints<'a, E, S>
+where
+    E: Pairing,
+    S: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    S::Proof: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn points(&self) -> &[E::ScalarField] {
+        &self.points
+    }
+
+    fn commit(&self, poly: &[E::ScalarField]) -> Result<Commitment<E>, Error> {
+        self.scheme.commit(poly)
+    }
+
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[Vec<E::ScalarField>],
+        polys: &[Vec<E::ScalarField>],
+    ) -> Result<Vec<u8>, Error> {
+        serialize_proof(&self.scheme.open(transcript, evals, polys, &self.points)?)
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[Vec<E::ScalarField>],
+        proof: &[u8],
+    ) -> Result<bool, Error> {
+        let proof = S::Proof::deserialize_compressed(proof)?;
+        self.scheme
+            .verify(transcript, commits, &self.points, evals, &proof)
+    }
+}
+
+impl<'a, E, S> EquivalenceCase<E> for AtPointSet<'a, E, S>
+where
+    E: Pairing,
+    S: PolyMultiProof<E> + Committer<E>,
+    S::Proof: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn points(&self) -> &[E::ScalarField] {
+        &self.points
+    }
+
+    fn commit(&self, poly: &[E::ScalarField]) -> Result<Commitment<E>, Error> {
+        self.scheme.commit(poly)
+    }
+
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[Vec<E::ScalarField>],
+        polys: &[Vec<E::ScalarField>],
+    ) -> Result<Vec<u8>, Error> {
+        serialize_proof(
+            &self
+                .scheme
+                .open(transcript, evals, polys, self.point_set_index)?,
+        )
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[Vec<E::ScalarField>],
+        proof: &[u8],
+    ) -> Result<bool, Error> {
+        let proof = S::Proof::deserialize_compressed(proof)?;
+        self.scheme
+            .verify(transcript, commits, self.point_set_index, evals, &proof)
+    }
+}
+
+/// Asserts that the schemes in `cases` are interchangeable over `n_trials` random openings of up
+/// to `max_polys` polynomials with `poly_len` coefficients. Every case must open at the same
+/// points. Each trial checks that
+/// * every case commits to each polynomial the same way
+/// * every case gives the same proof bytes
+/// * every case accepts every other case's proof, and rejects it for changed evaluations
+///
+/// Panics describing the first difference found.
+pub fn assert_equivalent<E: Pairing>(
+    cases: &[&dyn EquivalenceCase<E>],
+    rng: &mut impl RngCore,
+    n_trials: usize,
+    max_polys: usize,
+    poly_len: usize,
+) {
+    let (first, rest) = cases.split_first().expect("No cases given");
+    let points = first.points();
+    for (i, case) in rest.iter().enumerate() {
+        assert_eq!(
+            points,
+            case.points(),
+            "Case {} opens at other points",
+            i + 1
+        );
+    }
+
+    for trial in 0..n_trials {
+        let n_polys = 1 + (rng.next_u32() as usize) % max_polys.max(1);
+        let polys = (0..n_polys)
+            .map(|_| {
+                (0..poly_len)
+                    .map(|_| E::ScalarField::rand(rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| evaluate_at_points(p, points))
+            .collect::<Vec<_>>();
+
+        let commits = polys
+            .iter()
+            .map(|p| first.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let proof = first
+            .open(&mut Transcript::new(TRANSCRIPT_LABEL), &evals, &polys)
+            .expect("Open failed");
+        for (i, case) in rest.iter().enumerate() {
+            for (p, c) in polys.iter().zip(&commits) {
+                assert_eq!(
+                    c.0,
+                    case.commit(p).expect("Commit failed").0,
+                    "Case {} commits differently in trial {}",
+                    i + 1,
+                    trial
+                );
+            }
+            let other = case
+                .open(&mut Transcript::new(TRANSCRIPT_LABEL), &evals, &polys)
+                .expect("Open failed");
+            assert_eq!(
+                proof,
+                other,
+                "Case {} proves differently in trial {}",
+                i + 1,
+                trial
+            );
+        }
+
+        let mut bad_evals = evals.clone();
+        bad_evals[0][0] += E::ScalarField::one();
+        for (i, case) in cases.iter().enumerate() {
+            assert_eq!(
+                Ok(true),
+                case.verify(
+                    &mut Transcript::new(TRANSCRIPT_LABEL),
+                    &commits,
+                    &evals,
+                    &proof
+                ),
+                "Case {} rejects the proof in trial {}",
+                i,
+                trial
+            );
+            assert_eq!(
+                Ok(false),
+                case.verify(
+                    &mut Transcript::new(TRANSCRIPT_LABEL),
+                    &commits,
+                    &bad_evals,
+                    &proof
+                ),
+                "Case {} accepts changed evaluations in trial {}",
+                i,
+                trial
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::UniformRand;
+
+    use super::{assert_equivalent, AtPointSet, AtPoints};
+    use crate::{
+        m1_cycl::M1CyclPrecomp,
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        msm::{blst::BlstMSMEngine, ArkMSMEngine},
+        poly_ops::ev_points,
+        test_rng,
+    };
+
+    #[test]
+    fn test_method1_schemes_equivalent() {
+        let ark =
+            M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new_insecure_for_testing(5, 64, 16);
+        let blst = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_insecure_for_testing(5, 64, 16);
+        let cycl = M1CyclPrecomp::from_inner(ark.clone(), 64, 4).unwrap();
+        let cycl_points = ev_points(cycl.point_set_group(2).unwrap());
+        let rand_points = (0..6)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let precomp =
+            M1Precomp::from_inner(blst.clone(), vec![rand_points.clone(), cycl_points.clone()])
+                .unwrap();
+
+        // At arbitrary points
+        let ark_case = AtPoints {
+            scheme: &ark,
+            points: rand_points.clone(),
+        };
+        let blst_case = AtPoints {
+            scheme: &blst,
+            points: rand_points.clone(),
+        };
+        let precomp_case = AtPointSet {
+            scheme: &precomp,
+            point_set_index: 0,
+            points: rand_points,
+        };
+        assert_equivalent(
+            &[&ark_case, &blst_case, &precomp_case],
+            &mut test_rng(),
+            4,
+            5,
+            40,
+        );
+
+        // At a coset the cyclic precomputation can open at
+        let ark_case = AtPoints {
+            scheme: &ark,
+            points: cycl_points.clone(),
+        };
+        let precomp_case = AtPointSet {
+            scheme: &precomp,
+            point_set_index: 1,
+            points: cycl_points.clone(),
+        };
+        let cycl_case = AtPointSet {
+            scheme: &cycl,
+            point_set_index: 2,
+            points: cycl_points,
+        };
+        assert_equivalent(
+            &[&ark_case, &precomp_case, &cycl_case],
+            &mut test_rng(),
+            4,
+            5,
+            40,
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Shared helpers for testing the proof schemes
+use crate::{msm::blst::BlstMSMEngine, test_rng, traits::KZGProof, vec, Commitment, Error, Vec};
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use merlin::Transcript;
+
+use crate::traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp, TranscriptProtocol};
+
+/// Opens and verifies random polynomials at random points
+pub fn test_basic_no_precomp<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..30)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..20)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let mut transcript = Transcript::new(b"testing");
+    let open = s
+        .open(&mut transcript, &evals, &coeffs, &points)
+        .expect("Open failed");
+    let mut transcript = Transcript::new(b"testing");
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut transcript, &commits, &points, &evals, &open)
+    );
+
+    // Only some of the polynomials
+    let indices = [11, 3, 7];
+    let partial_evals = indices.map(|i| evals[i].clone());
+    let open = s
+        .open_partial(
+            &mut Transcript::new(b"testing"),
+            &partial_evals,
+            &coeffs,
+            &indices,
+            &points,
+        )
+        .expect("Open failed");
+    let verify = |indices: &[usize]| {
+        s.verify_partial(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            indices,
+            &points,
+            &partial_evals,
+            &open,
+        )
+    };
+    assert_eq!(Ok(true), verify(&indices));
+    assert_eq!(Ok(false), verify(&[3, 11, 7]));
+    assert_eq!(
+        Err(Error::CommitmentIndexOutOfRange {
+            idx: 20,
+            n_commits: 20
+        }),
+        verify(&[11, 20, 7])
+    );
+}
+
+/// Basic test for a precomp. Assumes `points` are the zero-th pointset.
+pub fn test_basic_precomp<E: Pairing, P: PolyMultiProof<E> + Committer<E>>(
+    s: &P,
+    points: &[E::ScalarField],
+) {
+    let polys = (0..20)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let mut transcript = Transcript::new(b"testing");
+    let open = s
+        .open(&mut transcript, &evals, &coeffs, 0)
+        .expect("Open failed");
+    let mut transcript = Transcript::new(b"testing");
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut transcript, &commits, 0, &evals, &open)
+    );
+
+    let (computed, open_computed) = s
+        .open_with_poly_evals(&mut Transcript::new(b"testing"), &coeffs, 0)
+        .expect("Open failed");
+    assert_eq!(evals, computed);
+    let mut transcript = Transcript::new(b"testing");
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut transcript, &commits, 0, &computed, &open_computed)
+    );
+
+    // Only some of the polynomials
+    let indices = [11, 3, 7];
+    let partial_evals = indices.map(|i| evals[i].clone());
+    let open = s
+        .open_partial(
+            &mut Transcript::new(b"testing"),
+            &partial_evals,
+            &coeffs,
+            &indices,
+            0,
+        )
+        .expect("Open failed");
+    let verify = |indices: &[usize]| {
+        s.verify_partial(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            indices,
+            0,
+            &partial_evals,
+            &open,
+        )
+    };
+    assert_eq!(Ok(true), verify(&indices));
+    assert_eq!(Ok(false), verify(&[3, 11, 7]));
+    assert_eq!(Err(Error::DuplicateCommitmentIndex(3)), verify(&[3, 3, 7]));
+    assert_eq!(
+        Err(Error::CommitmentIndexOutOfRange {
+            idx: 20,
+            n_commits: 20
+        }),
+        verify(&[11, 20, 7])
+    );
+}
+
+/// Checks that mismatched input sizes return the correct errors
+pub fn test_size_errors<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..20)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..20)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    // Eval row commit mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPolysDifferentSizes {
+            n_eval_rows: 19,
+            n_polys: 20,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals[..19],
+            &coeffs,
+            &points
+        )
+        .map(|_| ())
+    );
+    // Eval poly mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPolysDifferentSizes {
+            n_eval_rows: 20,
+            n_polys: 19,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &coeffs[..19],
+            &points
+        )
+        .map(|_| ())
+    );
+    // Evals point mismatch
+    assert_eq!(
+        Err(Error::EvalRowAndPointsDifferentSizes {
+            row: 0,
+            n_evals: 19,
+            n_points: 20,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals.iter().map(|s| &s[..19]).collect::<Vec<_>>(),
+            &coeffs,
+            &points,
+        )
+        .map(|_| ())
+    );
+
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    // Eval row commit mismatch
+    assert_eq!(
+        Err(Error::EvalsAndCommitsDifferentSizes {
+            n_evals: 20,
+            n_commits: 19,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits[..19],
+            &points,
+            &evals,
+            &open
+        )
+    );
+    // Eval point mismatch
+    assert_eq!(
+        Err(Error::EvalRowAndPointsDifferentSizes {
+            row: 0,
+            n_evals: 19,
+            n_points: 20,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals.iter().map(|a| &a[..19]).collect::<Vec<_>>(),
+            &open
+        )
+    );
+    // The error points at the row that's the wrong size
+    let mut short_row = evals.clone();
+    short_row[5].pop();
+    assert_eq!(
+        Err(Error::EvalRowAndPointsDifferentSizes {
+            row: 5,
+            n_evals: 19,
+            n_points: 20,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &short_row,
+            &open
+        )
+    );
+    // A polynomial longer than the setup
+    let mut long_poly = coeffs.clone();
+    long_poly[2].resize(10_000, E::ScalarField::one());
+    assert!(matches!(
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &long_poly,
+            &points
+        ),
+        Err(Error::PolynomialTooLarge {
+            index: 2,
+            n_coeffs: 10_000,
+            ..
+        })
+    ));
+}
+
+/// Checks single point KZG proofs for a handful of polynomials
+pub fn test_kzg(srs: &(impl KZGProof<Bls12_381> + Committer<Bls12_381>)) {
+    use ark_bls12_381::Fr;
+
+    fn run(srs: &(impl KZGProof<Bls12_381> + Committer<Bls12_381>), coeffs: Vec<Fr>) {
+        let pt = Fr::rand(&mut test_rng());
+        let value = DensePolynomial::from_coefficients_vec(coeffs.clone()).evaluate(&pt);
+
+        let commit = srs.commit(&coeffs).unwrap();
+        let witness = srs.compute_witness_polynomial(coeffs, pt).unwrap();
+        let proof = srs.open(witness).unwrap();
+
+        let veri = srs
+            .verify::<BlstMSMEngine>(&commit, pt, value, &proof)
+            .unwrap();
+        assert!(veri);
+    }
+
+    // Test random poly works
+    run(srs, DensePolynomial::<Fr>::rand(50, &mut test_rng()).coeffs);
+    // Test zero poly works
+    run(srs, vec![Fr::zero(); 50]);
+    // Test constant poly works
+    run(srs, vec![Fr::one(); 50]);
+    // Test unit poly works
+    let mut unit = vec![Fr::zero(); 50];
+    unit[0] = Fr::one();
+    run(srs, unit);
+}
+
+/// Random polynomials, their commitments and evaluations at the given points
+#[allow(clippy::type_complexity)]
+pub fn gen_openings<E: Pairing, P: Committer<E>>(
+    s: &P,
+    n_polys: usize,
+    points: &[E::ScalarField],
+) -> (
+    Vec<Vec<E::ScalarField>>,
+    Vec<Vec<E::ScalarField>>,
+    Vec<Commitment<E>>,
+) {
+    // Keep the degree above the number of points so the proofs aren't trivial
+    let polys = (0..n_polys)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(points.len() + 50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    (coeffs, evals, commits)
+}
+
+/// Checks that batch verification accepts valid proofs and rejects swapped ones, including
+/// openings at the same points
+pub fn test_batch_no_precomp<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let mut point_sets = [5, 10, 20]
+        .iter()
+        .map(|&n| {
+            (0..n)
+                .map(|_| E::ScalarField::rand(&mut test_rng()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    point_sets.push(point_sets[1].clone());
+    let openings = point_sets
+        .iter()
+        .enumerate()
+        .map(|(i, points)| {
+            let (coeffs, evals, commits) = gen_openings(s, i + 1, points);
+            let proof = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, points)
+                .expect("Open failed");
+            (commits, evals, proof)
+        })
+        .collect::<Vec<_>>();
+    let mut items = openings
+        .iter()
+        .zip(point_sets.iter())
+        .map(|((commits, evals, proof), points)| {
+            (
+                commits.as_slice(),
+                points.as_slice(),
+                evals.as_slice(),
+                proof,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len()];
+    assert_eq!(
+        Ok(true),
+        s.verify_batch(&mut transcripts, &items, &mut test_rng())
+    );
+
+    items[3].3 = &openings[1].2;
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len()];
+    assert_eq!(
+        Ok(false),
+        s.verify_batch(&mut transcripts, &items, &mut test_rng())
+    );
+    items[3].3 = &openings[3].2;
+
+    items[0].3 = &openings[1].2;
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len()];
+    assert_eq!(
+        Ok(false),
+        s.verify_batch(&mut transcripts, &items, &mut test_rng())
+    );
+
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len() - 1];
+    assert!(s
+        .verify_batch(&mut transcripts, &items, &mut test_rng())
+        .is_err());
+}
+
+/// Checks that batch verification accepts valid proofs and rejects swapped ones. Assumes
+/// `point_sets[i]` is the `i`th point set of `s`.
+pub fn test_batch_precomp<E: Pairing, P: PolyMultiProof<E> + Committer<E>>(
+    s: &P,
+    point_sets: &[Vec<E::ScalarField>],
+) {
+    assert!(point_sets.len() >= 2);
+    let openings = point_sets
+        .iter()
+        .enumerate()
+        .map(|(i, points)| {
+            let (coeffs, evals, commits) = gen_openings(s, i + 1, points);
+            let proof = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, i)
+                .expect("Open failed");
+            (commits, evals, proof)
+        })
+        .collect::<Vec<_>>();
+    let mut items = openings
+        .iter()
+        .enumerate()
+        .map(|(i, (commits, evals, proof))| (commits.as_slice(), i, evals.as_slice(), proof))
+        .collect::<Vec<_>>();
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len()];
+    assert_eq!(
+        Ok(true),
+        s.verify_batch(&mut transcripts, &items, &mut test_rng())
+    );
+
+    items[0].3 = &openings[1].2;
+    let mut transcripts = vec![Transcript::new(b"testing"); items.len()];
+    assert_eq!(
+        Ok(false),
+        s.verify_batch(&mut transcripts, &items, &mut test_rng())
+    );
+}
+
+/// A transcript which isn't merlin, and keeps the labels it was given. Challenges are a running
+/// byte sum of everything absorbed, which is fine for checking the plumbing but not secure.
+#[derive(Default)]
+pub struct LoggingTranscript {
+    state: u8,
+    /// The labels of each absorb and challenge, in order
+    pub labels: Vec<&'static [u8]>,
+}
+
+impl TranscriptProtocol for LoggingTranscript {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.labels.push(label);
+        self.state = message.iter().fold(self.state, |a, b| a.wrapping_add(*b));
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        self.labels.push(label);
+        for (i, d) in dest.iter_mut().enumerate() {
+            *d = self.state.wrapping_add(i as u8);
+        }
+        self.state = self.state.wrapping_add(1);
+    }
+}
+
+/// Checks that opening and verifying works with a transcript other than merlin's
+pub fn test_custom_transcript_no_precomp<
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+>(
+    s: &P,
+) {
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let (coeffs, evals, commits) = gen_openings(s, 3, &points);
+    let mut transcript = LoggingTranscript::default();
+    let open = s
+        .open(&mut transcript, &evals, &coeffs, &points)
+        .expect("Open failed");
+    let mut verifier_transcript = LoggingTranscript::default();
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut verifier_transcript, &commits, &points, &evals, &open)
+    );
+    assert!(!transcript.labels.is_empty());
+    assert_eq!(transcript.labels, verifier_transcript.labels);
+}
+
+/// Opens a linear combination of polynomials and verifies it against their commitments
+pub fn test_linear_combination_no_precomp<
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+>(
+    s: &P,
+) {
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let (coeffs, evals, commits) = gen_openings(s, 4, &points);
+    let scalars = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let combined_evals = (0..points.len())
+        .map(|j| evals.iter().zip(&scalars).map(|(e, c)| e[j] * c).sum())
+        .collect::<Vec<E::ScalarField>>();
+    let proof = s
+        .open_linear_combination(
+            &mut Transcript::new(b"testing"),
+            &combined_evals,
+            &coeffs,
+            &scalars,
+            &points,
+        )
+        .expect("Failed to open");
+    let verify = |scalars: &[E::ScalarField], evals: &[E::ScalarField]| {
+        s.verify_linear_combination(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            scalars,
+            &points,
+            evals,
+            &proof,
+        )
+    };
+    assert_eq!(Ok(true), verify(&scalars, &combined_evals));
+    let mut other_scalars = scalars.clone();
+    other_scalars[2] += E::ScalarField::one();
+    assert_eq!(Ok(false), verify(&other_scalars, &combined_evals));
+    assert_eq!(Ok(false), verify(&scalars, &evals[0]));
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 4,
+            got: 3
+        }),
+        verify(&scalars[..3], &combined_evals)
+    );
+    assert_eq!(
+        Some(Error::InvalidInputLength {
+            expected: 4,
+            got: 3
+        }),
+        s.open_linear_combination(
+            &mut Transcript::new(b"testing"),
+            &combined_evals,
+            &coeffs,
+            &scalars[..3],
+            &points,
+        )
+        .err()
+    );
+}
@@ -0,0 +1,12 @@
+//! Helpers for testing the proof schemes. The shared test helpers are only built for the crate's
+//! own tests, [`vectors`] is built with the `test-vectors` feature, and [`equivalence`] with the
+//! `test-harness` feature.
+#[cfg(any(test, feature = "test-harness"))]
+pub mod equivalence;
+#[cfg(test)]
+mod helpers;
+#[cfg(feature = "test-vectors")]
+pub mod vectors;
+
+#[cfg(test)]
+pub use helpers::*;
@@ -0,0 +1,578 @@
+//! Deterministic test vectors for implementations of the schemes in other languages. Each
+//! [`TestVector`] holds a setup seed, the polynomials and points opened, their evaluations,
+//! commitments and proof, and every step taken on the opening transcript, for BLS12-381.
+//! [`to_json`] writes them as JSON and [`load`] reads them back, and [`TestVector::check`]
+//! regenerates a vector from its seed and checks it matches and verifies.
+//!
+//! ### Derivation
+//! Everything is derived from the seed with a merlin transcript labelled [`SEED_LABEL`], which
+//! absorbs the seed as 8 little endian bytes with the label `seed`. The secret $\tau$ is the
+//! challenge `tau`, then each coefficient of each polynomial is the challenge `coeff`, then each
+//! point of two point sets is the challenge `point`, all read as in [`crate::transcript`]. The
+//! setup uses the standard generators of G1 and G2. The precomputed schemes open at the second
+//! point set, and the cyclic scheme opens at the second of two cosets of a domain twice the size
+//! of a point set rather than at the derived points.
+//!
+//! The opening is made with a merlin transcript labelled [`TRANSCRIPT_LABEL`]. Field elements,
+//! commitments and proofs are written as their hex encoded compressed serializations.
+//!
+//! The vectors for the default [`VectorParams`] are checked in as `src/testing/vectors.json`,
+//! and the tests fail if what's generated changes.
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_ec::Group;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{string::String, vec::Vec};
+use merlin::Transcript;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    get_challenge, get_field_size,
+    m1_cycl::M1CyclPrecomp,
+    method1::{precompute::M1Precomp, M1NoPrecomp},
+    method2::{precompute::M2Precomp, M2NoPrecomp},
+    msm::ArkMSMEngine,
+    poly_ops::{ev_points, evaluate_at_points},
+    serde_impls::{hex_decode, hex_encode},
+    traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+    transcript::{RecordingTranscript, TranscriptEvent, TranscriptTrace},
+    Commitment, Error,
+};
+
+/// The label of the transcript everything in a vector is derived from
+pub const SEED_LABEL: &[u8] = b"poly-multiproof test vectors";
+/// The label of the transcript the opening is made with
+pub const TRANSCRIPT_LABEL: &[u8] = b"poly-multiproof test vector opening";
+
+type Engine = ArkMSMEngine<Bls12_381>;
+
+/// The schemes test vectors are made for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VectorScheme {
+    /// [`M1NoPrecomp`]
+    M1NoPrecomp,
+    /// [`M1Precomp`]
+    M1Precomp,
+    /// [`M1CyclPrecomp`]
+    M1CyclPrecomp,
+    /// [`M2NoPrecomp`]
+    M2NoPrecomp,
+    /// [`M2Precomp`]
+    M2Precomp,
+}
+
+impl VectorScheme {
+    /// Every scheme
+    pub const ALL: [Self; 5] = [
+        Self::M1NoPrecomp,
+        Self::M1Precomp,
+        Self::M1CyclPrecomp,
+        Self::M2NoPrecomp,
+        Self::M2Precomp,
+    ];
+
+    /// The name of the scheme, as given by [`crate::traits::SchemeInfo::scheme_name`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::M1NoPrecomp => "m1-no-precomp",
+            Self::M1Precomp => "m1-precomp",
+            Self::M1CyclPrecomp => "m1-cycl-precomp",
+            Self::M2NoPrecomp => "m2-no-precomp",
+            Self::M2Precomp => "m2-precomp",
+        }
+    }
+
+    /// The scheme with the given name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+/// The sizes of a test vector and the seed it's derived from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VectorParams {
+    /// The seed everything is derived from
+    pub seed: u64,
+    /// The number of coefficients of each polynomial, and of the setup
+    pub max_coeffs: usize,
+    /// The number of polynomials opened
+    pub n_polys: usize,
+    /// The number of points opened at, which must be a power of two for the cyclic scheme
+    pub n_points: usize,
+}
+
+impl Default for VectorParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_coeffs: 16,
+            n_polys: 3,
+            n_points: 4,
+        }
+    }
+}
+
+/// An opening made by one of the schemes, with everything needed to reproduce it
+#[derive(Clone, Debug)]
+pub struct TestVector {
+    /// The scheme which made the opening
+    pub scheme: VectorScheme,
+    /// The seed and sizes
+    pub params: VectorParams,
+    /// The index of the point set opened at, zero for the schemes without precomputation
+    pub point_set_index: usize,
+    /// The points opened at
+    pub points: Vec<Fr>,
+    /// The coefficients of each polynomial
+    pub polys: Vec<Vec<Fr>>,
+    /// The evaluations of each polynomial at the points
+    pub evals: Vec<Vec<Fr>>,
+    /// The commitment to each polynomial
+    pub commits: Vec<Commitment<Bls12_381>>,
+    /// The compressed serialization of the proof
+    pub proof: Vec<u8>,
+    /// Every step taken on the opening transcript
+    pub transcript: TranscriptTrace,
+}
+
+struct Inputs {
+    tau: Fr,
+    polys: Vec<Vec<Fr>>,
+    point_sets: Vec<Vec<Fr>>,
+}
+
+fn derive_inputs(params: &VectorParams) -> Inputs {
+    let mut transcript = Transcript::new(SEED_LABEL);
+    transcript.append_message(b"seed", &params.seed.to_le_bytes());
+    let field_size = get_field_size::<Fr>();
+    let tau = get_challenge(&mut transcript, b"tau", field_size);
+    let polys = (0..params.n_polys)
+        .map(|_| {
+            (0..params.max_coeffs)
+                .map(|_| get_challenge(&mut transcript, b"coeff", field_size))
+                .collect()
+        })
+        .collect();
+    let point_sets = (0..2)
+        .map(|_| {
+            (0..params.n_points)
+                .map(|_| get_challenge(&mut transcript, b"point", field_size))
+                .collect()
+        })
+        .collect();
+    Inputs {
+        tau,
+        polys,
+        point_sets,
+    }
+}
+
+/// The schemes built from the derived setup
+struct Schemes {
+    m1: M1NoPrecomp<Bls12_381, Engine>,
+    m2: M2NoPrecomp<Bls12_381, Engine>,
+}
+
+impl Schemes {
+    fn new(params: &VectorParams, tau: Fr) -> Self {
+        let (g1, g2) = (G1Projective::generator(), G2Projective::generator());
+        Self {
+            m1: M1NoPrecomp::new_from_scalar(tau, g1, g2, params.max_coeffs, params.n_points),
+            m2: M2NoPrecomp::new_from_scalar(tau, g1, g2, params.max_coeffs),
+        }
+    }
+
+    fn cycl(&self, params: &VectorParams) -> Result<M1CyclPrecomp<Bls12_381, Engine>, Error> {
+        M1CyclPrecomp::from_inner(self.m1.clone(), 2 * params.n_points, 2)
+    }
+}
+
+/// The opening made by a scheme, before it's put into a [`TestVector`]
+struct Opening {
+    point_set_index: usize,
+    points: Vec<Fr>,
+    evals: Vec<Vec<Fr>>,
+    commits: Vec<Commitment<Bls12_381>>,
+    proof: Vec<u8>,
+    transcript: TranscriptTrace,
+}
+
+fn open_no_precomp<S>(s: &S, polys: &[Vec<Fr>], points: &[Fr]) -> Result<Opening, Error>
+where
+    S: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>,
+    S::Proof: CanonicalSerialize,
+{
+    let commits = commit_all(s, polys)?;
+    let mut transcript = RecordingTranscript::new(Transcript::new(TRANSCRIPT_LABEL));
+    let (evals, proof) = s.open_rows(&mut transcript, polys, points)?;
+    Ok(Opening {
+        point_set_index: 0,
+        points: points.to_vec(),
+        evals,
+        commits,
+        proof: serialize(&proof)?,
+        transcript: transcript.into_parts().1,
+    })
+}
+
+fn open_precomp<S>(
+    s: &S,
+    polys: &[Vec<Fr>],
+    points: Vec<Fr>,
+    point_set_index: usize,
+) -> Result<Opening, Error>
+where
+    S: PolyMultiProof<Bls12_381> + Committer<Bls12_381>,
+    S::Proof: CanonicalSerialize,
+{
+    let commits = commit_all(s, polys)?;
+    let mut transcript = RecordingTranscript::new(Transcript::new(TRANSCRIPT_LABEL));
+    let (evals, proof) = s.open_rows(&mut transcript, polys, point_set_index)?;
+    Ok(Opening {
+        point_set_index,
+        points,
+        evals,
+        commits,
+        proof: serialize(&proof)?,
+        transcript: transcript.into_parts().1,
+    })
+}
+
+fn verify_no_precomp<S>(s: &S, v: &TestVector) -> Result<bool, Error>
+where
+    S: PolyMultiProofNoPrecomp<Bls12_381>,
+    S::Proof: CanonicalDeserialize,
+{
+    let proof = deserialize(&v.proof)?;
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    s.verify(&mut transcript, &v.commits, &v.points, &v.evals, &proof)
+}
+
+fn verify_precomp<S>(s: &S, v: &TestVector) -> Result<bool, Error>
+where
+    S: PolyMultiProof<Bls12_381>,
+    S::Proof: CanonicalDeserialize,
+{
+    let proof = deserialize(&v.proof)?;
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    s.verify(
+        &mut transcript,
+        &v.commits,
+        v.point_set_index,
+        &v.evals,
+        &proof,
+    )
+}
+
+fn commit_all(
+    s: &impl Committer<Bls12_381>,
+    polys: &[Vec<Fr>],
+) -> Result<Vec<Commitment<Bls12_381>>, Error> {
+    polys.iter().map(|p| s.commit(p)).collect()
+}
+
+fn serialize(item: &impl CanonicalSerialize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    item.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    Ok(T::deserialize_compressed(bytes)?)
+}
+
+impl TestVector {
+    /// Derive the inputs from `params` and open them with `scheme`
+    pub fn generate(scheme: VectorScheme, params: VectorParams) -> Result<Self, Error> {
+        let Inputs {
+            tau,
+            polys,
+            point_sets,
+        } = derive_inputs(&params);
+        let schemes = Schemes::new(&params, tau);
+        let opening = match scheme {
+            VectorScheme::M1NoPrecomp => open_no_precomp(&schemes.m1, &polys, &point_sets[0])?,
+            VectorScheme::M2NoPrecomp => open_no_precomp(&schemes.m2, &polys, &point_sets[0])?,
+            VectorScheme::M1Precomp => {
+                let s = M1Precomp::from_inner(schemes.m1, point_sets)?;
+                open_precomp(&s, &polys, s.point_sets()[1].clone(), 1)?
+            }
+            VectorScheme::M2Precomp => {
+                let points = point_sets[1].clone();
+                let s = M2Precomp::from_inner(schemes.m2, point_sets)?;
+                open_precomp(&s, &polys, points, 1)?
+            }
+            VectorScheme::M1CyclPrecomp => {
+                let s = schemes.cycl(&params)?;
+                let points = ev_points(s.point_set_group(1)?);
+                open_precomp(&s, &polys, points, 1)?
+            }
+        };
+        Ok(Self {
+            scheme,
+            params,
+            point_set_index: opening.point_set_index,
+            points: opening.points,
+            polys,
+            evals: opening.evals,
+            commits: opening.commits,
+            proof: opening.proof,
+            transcript: opening.transcript,
+        })
+    }
+
+    /// Regenerate the vector from its seed and check it matches, and that its proof verifies
+    /// against its commitments and evaluations
+    pub fn check(&self) -> Result<bool, Error> {
+        let expected = Self::generate(self.scheme, self.params)?;
+        let matches = self.point_set_index == expected.point_set_index
+            && self.points == expected.points
+            && self.polys == expected.polys
+            && self.evals == expected.evals
+            && self.commits.len() == expected.commits.len()
+            && self
+                .commits
+                .iter()
+                .zip(&expected.commits)
+                .all(|(a, b)| a.0 == b.0)
+            && self.proof == expected.proof
+            && self.transcript == expected.transcript;
+        if !matches {
+            return Ok(false);
+        }
+        let Inputs {
+            tau, point_sets, ..
+        } = derive_inputs(&self.params);
+        let schemes = Schemes::new(&self.params, tau);
+        let evals_match = self
+            .polys
+            .iter()
+            .zip(&self.evals)
+            .all(|(p, e)| evaluate_at_points(p, &self.points) == *e);
+        let verified = match self.scheme {
+            VectorScheme::M1NoPrecomp => verify_no_precomp(&schemes.m1, self)?,
+            VectorScheme::M2NoPrecomp => verify_no_precomp(&schemes.m2, self)?,
+            VectorScheme::M1Precomp => {
+                verify_precomp(&M1Precomp::from_inner(schemes.m1, point_sets)?, self)?
+            }
+            VectorScheme::M2Precomp => {
+                verify_precomp(&M2Precomp::from_inner(schemes.m2, point_sets)?, self)?
+            }
+            VectorScheme::M1CyclPrecomp => verify_precomp(&schemes.cycl(&self.params)?, self)?,
+        };
+        Ok(evals_match && verified)
+    }
+
+    /// The vector as a JSON object
+    pub fn to_json(&self) -> Result<Value, Error> {
+        let commits = self
+            .commits
+            .iter()
+            .map(|c| serialize(c).map(|b| hex_encode(&b)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let transcript = self
+            .transcript
+            .events
+            .iter()
+            .map(|e| {
+                let (kind, label, bytes) = match e {
+                    TranscriptEvent::Absorb { label, message } => ("absorb", label, message),
+                    TranscriptEvent::Challenge { label, bytes } => ("challenge", label, bytes),
+                };
+                json!({
+                    "kind": kind,
+                    "label": String::from_utf8_lossy(label),
+                    "bytes": hex_encode(bytes),
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({
+            "scheme": self.scheme.name(),
+            "curve": "bls12-381",
+            "seed": self.params.seed,
+            "max_coeffs": self.params.max_coeffs,
+            "n_polys": self.params.n_polys,
+            "n_points": self.params.n_points,
+            "point_set_index": self.point_set_index,
+            "points": field_hexes(&self.points)?,
+            "polys": self.polys.iter().map(|p| field_hexes(p)).collect::<Result<Vec<_>, _>>()?,
+            "evals": self.evals.iter().map(|e| field_hexes(e)).collect::<Result<Vec<_>, _>>()?,
+            "commitments": commits,
+            "proof": hex_encode(&self.proof),
+            "transcript": transcript,
+        }))
+    }
+
+    /// Read a vector from a JSON object made by [`Self::to_json`]. Fails with
+    /// [`Error::SerializationError`] when a field is missing or malformed.
+    pub fn from_json(value: &Value) -> Result<Self, Error> {
+        let obj = value.as_object().ok_or(Error::SerializationError)?;
+        let scheme =
+            VectorScheme::from_name(get_str(obj, "scheme")?).ok_or(Error::SerializationError)?;
+        let params = VectorParams {
+            seed: get(obj, "seed")?
+                .as_u64()
+                .ok_or(Error::SerializationError)?,
+            max_coeffs: get_usize(obj, "max_coeffs")?,
+            n_polys: get_usize(obj, "n_polys")?,
+            n_points: get_usize(obj, "n_points")?,
+        };
+        let transcript = get_array(obj, "transcript")?
+            .iter()
+            .map(|e| {
+                let e = e.as_object().ok_or(Error::SerializationError)?;
+                let label = get_str(e, "label")?.as_bytes().to_vec();
+                let bytes = get_bytes(e, "bytes")?;
+                match get_str(e, "kind")? {
+                    "absorb" => Ok(TranscriptEvent::Absorb {
+                        label,
+                        message: bytes,
+                    }),
+                    "challenge" => Ok(TranscriptEvent::Challenge { label, bytes }),
+                    _ => Err(Error::SerializationError),
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self {
+            scheme,
+            params,
+            point_set_index: get_usize(obj, "point_set_index")?,
+            points: parse_fields(get(obj, "points")?)?,
+            polys: parse_field_rows(get(obj, "polys")?)?,
+            evals: parse_field_rows(get(obj, "evals")?)?,
+            commits: get_array(obj, "commitments")?
+                .iter()
+                .map(|c| deserialize(&parse_hex(c)?))
+                .collect::<Result<Vec<_>, Error>>()?,
+            proof: get_bytes(obj, "proof")?,
+            transcript: TranscriptTrace { events: transcript },
+        })
+    }
+}
+
+fn field_hexes(fields: &[Fr]) -> Result<Vec<String>, Error> {
+    fields
+        .iter()
+        .map(|f| serialize(f).map(|b| hex_encode(&b)))
+        .collect()
+}
+
+fn get<'a>(obj: &'a Map<String, Value>, key: &str) -> Result<&'a Value, Error> {
+    obj.get(key).ok_or(Error::SerializationError)
+}
+
+fn get_str<'a>(obj: &'a Map<String, Value>, key: &str) -> Result<&'a str, Error> {
+    get(obj, key)?.as_str().ok_or(Error::SerializationError)
+}
+
+fn get_usize(obj: &Map<String, Value>, key: &str) -> Result<usize, Error> {
+    get(obj, key)?
+        .as_u64()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or(Error::SerializationError)
+}
+
+fn get_array<'a>(obj: &'a Map<String, Value>, key: &str) -> Result<&'a Vec<Value>, Error> {
+    get(obj, key)?.as_array().ok_or(Error::SerializationError)
+}
+
+fn get_bytes(obj: &Map<String, Value>, key: &str) -> Result<Vec<u8>, Error> {
+    parse_hex(get(obj, key)?)
+}
+
+fn parse_hex(value: &Value) -> Result<Vec<u8>, Error> {
+    let hex = value.as_str().ok_or(Error::SerializationError)?;
+    hex_decode(hex).map_err(|_| Error::SerializationError)
+}
+
+fn parse_fields(value: &Value) -> Result<Vec<Fr>, Error> {
+    value
+        .as_array()
+        .ok_or(Error::SerializationError)?
+        .iter()
+        .map(|f| deserialize(&parse_hex(f)?))
+        .collect()
+}
+
+fn parse_field_rows(value: &Value) -> Result<Vec<Vec<Fr>>, Error> {
+    value
+        .as_array()
+        .ok_or(Error::SerializationError)?
+        .iter()
+        .map(parse_fields)
+        .collect()
+}
+
+/// Make a vector for every scheme from the same `params`
+pub fn generate_all(params: VectorParams) -> Result<Vec<TestVector>, Error> {
+    VectorScheme::ALL
+        .into_iter()
+        .map(|s| TestVector::generate(s, params))
+        .collect()
+}
+
+/// Write `vectors` as a pretty printed JSON array
+pub fn to_json(vectors: &[TestVector]) -> Result<String, Error> {
+    let values = vectors
+        .iter()
+        .map(TestVector::to_json)
+        .collect::<Result<Vec<_>, Error>>()?;
+    serde_json::to_string_pretty(&values).map_err(|_| Error::SerializationError)
+}
+
+/// Read the vectors written by [`to_json`]
+pub fn load(json: &str) -> Result<Vec<TestVector>, Error> {
+    let value: Value = serde_json::from_str(json).map_err(|_| Error::SerializationError)?;
+    value
+        .as_array()
+        .ok_or(Error::SerializationError)?
+        .iter()
+        .map(TestVector::from_json)
+        .collect()
+}
+
+/// Read the vectors in `json` and [`check`](TestVector::check) every one
+pub fn load_and_check(json: &str) -> Result<bool, Error> {
+    for vector in load(json)? {
+        if !vector.check()? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_all, load, load_and_check, to_json, VectorParams, VectorScheme};
+
+    #[test]
+    fn test_vectors() {
+        let params = VectorParams::default();
+        let vectors = generate_all(params).unwrap();
+        let json = to_json(&vectors).unwrap();
+        // Generation is deterministic, and matches the vectors checked in for other
+        // implementations to test against
+        assert_eq!(json, to_json(&generate_all(params).unwrap()).unwrap());
+        assert_eq!(include_str!("vectors.json").trim_end(), json);
+        assert_eq!(Ok(true), load_and_check(include_str!("vectors.json")));
+        assert_ne!(
+            json,
+            to_json(&generate_all(VectorParams { seed: 1, ..params }).unwrap()).unwrap()
+        );
+
+        let loaded = load(&json).unwrap();
+        assert_eq!(VectorScheme::ALL.len(), loaded.len());
+        for (v, scheme) in loaded.iter().zip(VectorScheme::ALL) {
+            assert_eq!(scheme, v.scheme);
+            assert_eq!(Ok(true), v.check());
+        }
+        assert_eq!(Ok(true), load_and_check(&json));
+
+        // Changing a stored evaluation is caught
+        let mut bad = loaded[2].clone();
+        bad.evals[0][1] += ark_bls12_381::Fr::from(1u64);
+        assert_eq!(Ok(false), bad.check());
+        let tampered = json.replacen("\"m1-precomp\"", "\"m2-precomp\"", 1);
+        assert_eq!(Ok(false), load_and_check(&tampered));
+        assert!(load("{}").is_err());
+    }
+}
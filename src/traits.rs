@@ -1,15 +1,162 @@
 //! Traits used in the BDFG21 and KZG Schemes
 use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::Zero;
+use ark_poly::{
+    univariate::{DensePolynomial, SparsePolynomial},
+    Radix2EvaluationDomain,
+};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::vec::Vec;
+use ark_std::{rand::RngCore, vec::Vec};
 use merlin::Transcript;
 
-use crate::{Commitment, Error};
+use crate::{
+    linear_combination_of_commitments, method1::combine_polys, poly_ops::evaluate_at_points,
+    select_indices, Commitment, Error,
+};
+
+/// A Fiat-Shamir transcript which the proof schemes absorb their inputs into and draw challenges
+/// from. This is implemented for [`merlin::Transcript`], implement it for another transcript to
+/// use a different hash, e.g. to match a verifier in a SNARK circuit or on the EVM.
+pub trait TranscriptProtocol {
+    /// Absorb `message` into the transcript under `label`
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
+    /// Fill `dest` with challenge bytes derived from everything absorbed so far
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]);
+}
+
+impl TranscriptProtocol for Transcript {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        Transcript::append_message(self, label, message)
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        Transcript::challenge_bytes(self, label, dest)
+    }
+}
+
+/// So a borrowed transcript can be wrapped, e.g. in a
+/// [`RecordingTranscript`](crate::transcript::RecordingTranscript)
+impl<T: TranscriptProtocol + ?Sized> TranscriptProtocol for &mut T {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        (**self).append_message(label, message)
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        (**self).challenge_bytes(label, dest)
+    }
+}
 
 /// A curve-agnostic trait for a KZG commitment scheme
 pub trait Committer<E: Pairing> {
     /// Commit to the given polynomial
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error>;
+
+    /// Commit to the polynomial with the given evaluations over `domain`. The default
+    /// implementation interpolates with an IFFT and commits to the coefficients, setups with a
+    /// lagrange basis for the domain commit to the evaluations directly.
+    fn commit_evals(
+        &self,
+        evals: impl AsRef<[E::ScalarField]>,
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        use ark_poly::EvaluationDomain;
+        crate::lagrange_basis::check_evals_len(evals.as_ref(), domain)?;
+        self.commit(domain.ifft(evals.as_ref()))
+    }
+
+    /// Commit to a sparse polynomial. The default implementation makes it dense and commits to
+    /// that, setups which hold their powers of tau override it to only use the powers at the
+    /// nonzero coefficients.
+    fn commit_sparse(
+        &self,
+        poly: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.commit(DensePolynomial::from(poly.clone()).coeffs)
+    }
+
+    /// Commit to each of `polys`. The default implementation commits to them one at a time,
+    /// setups which hold their powers of tau override it to commit to all of them with
+    /// [`MSMEngine::batch_multi_scalar_mul_g1`].
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        polys.iter().map(|p| self.commit(p)).collect()
+    }
+}
+
+/// The limits and sizes of a setup, so configuration can be checked before it's used, rather
+/// than failing deep inside an opening
+pub trait SchemeInfo {
+    /// The most coefficients a committed polynomial can have
+    fn max_coeffs(&self) -> usize;
+
+    /// The most points a proof can open at
+    fn max_pts(&self) -> usize;
+
+    /// A short name for the scheme, such as `"m1-precomp"`
+    fn scheme_name(&self) -> &'static str;
+
+    /// The size of a compressed proof in bytes
+    fn proof_size_bytes(&self) -> usize;
+
+    /// The expected heap usage of the scheme with a setup for `max_coeffs` coefficients and
+    /// `max_pts` points, precomputed for `num_point_sets` point sets of `max_pts` points, so
+    /// machines can be sized before building a large setup
+    fn memory_estimate(max_coeffs: usize, max_pts: usize, num_point_sets: usize) -> MemoryEstimate
+    where
+        Self: Sized;
+
+    /// Checks that the setup can open polynomials with `n_coeffs` coefficients at `n_pts` points
+    fn check_capacity(&self, n_coeffs: usize, n_pts: usize) -> Result<(), Error> {
+        if n_coeffs > self.max_coeffs() {
+            return Err(Error::TooManyScalars {
+                n_coeffs,
+                expected_max: self.max_coeffs(),
+            });
+        }
+        if n_pts > self.max_pts() {
+            return Err(Error::TooManyPoints {
+                n_points: n_pts,
+                max_points: self.max_pts(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The heap usage of a scheme in bytes, from [`SchemeInfo::memory_estimate`]. Only the buffers
+/// which grow with the setup are counted, not allocator overhead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// The powers of tau, along with the copies prepared for the MSM engine
+    pub srs: usize,
+    /// The tables precomputed for the point sets
+    pub precompute: usize,
+    /// The temporaries of a single opening or verification
+    pub per_open: usize,
+}
+
+impl MemoryEstimate {
+    /// The total of the setup, the precomputation and one opening
+    pub fn total(&self) -> usize {
+        self.srs + self.precompute + self.per_open
+    }
+
+    /// The larger of each part of the two estimates
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            srs: self.srs.max(other.srs),
+            precompute: self.precompute.max(other.precompute),
+            per_open: self.per_open.max(other.per_open),
+        }
+    }
+}
+
+/// The size of a compressed G1 point of `E`
+pub(crate) fn g1_compressed_size<E: Pairing>() -> usize {
+    use ark_ec::AffineRepr;
+    E::G1Affine::zero().compressed_size()
 }
 
 /// A curve-agnostic trait for making KZG opening proofs
@@ -47,7 +194,7 @@ pub trait PolyMultiProof<E: Pairing>: Sized {
     /// Creates a of the given polynomials at the given point set index
     fn open(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         point_set_index: usize,
@@ -56,12 +203,163 @@ pub trait PolyMultiProof<E: Pairing>: Sized {
     /// Verifies a proof against the given set of commitments and points
     fn verify(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         point_set_index: usize,
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Evaluates each polynomial at the points of point set `point_set_index`, in the order
+    /// [`Self::open`] and [`Self::verify`] expect them
+    fn compute_evals(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error>;
+
+    /// Opens the polynomials at point set `point_set_index`, computing their evaluations with
+    /// [`Self::compute_evals`]. Returns the evaluations along with the proof.
+    #[allow(clippy::type_complexity)]
+    fn open_with_poly_evals(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Self::Proof), Error> {
+        let evals = self.compute_evals(polys, point_set_index)?;
+        let proof = self.open(transcript, &evals, polys, point_set_index)?;
+        Ok((evals, proof))
+    }
+
+    /// Opens many polynomials at the same point set, as when sampling a row of a grid. This is
+    /// the shape [`Self::open`] is built for, so it's [`Self::open_with_poly_evals`].
+    #[allow(clippy::type_complexity)]
+    fn open_rows(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Self::Proof), Error> {
+        self.open_with_poly_evals(transcript, polys, point_set_index)
+    }
+
+    /// Opens a single polynomial at each point set in `point_set_indices`, returning the
+    /// evaluations and proof for each. Each proof is made against the transcript at the same
+    /// index in `transcripts`, and is verified with [`Self::verify`] and the commitment to
+    /// `poly`. The default implementation opens at each point set separately, schemes override
+    /// it to skip combining the polynomials, since there's only one.
+    #[allow(clippy::type_complexity)]
+    fn open_points(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        poly: impl AsRef<[E::ScalarField]>,
+        point_set_indices: &[usize],
+    ) -> Result<Vec<(Vec<E::ScalarField>, Self::Proof)>, Error> {
+        check_batch_sizes(transcripts, point_set_indices)?;
+        let polys = [poly.as_ref()];
+        transcripts
+            .iter_mut()
+            .zip(point_set_indices)
+            .map(|(transcript, i)| {
+                let (mut evals, proof) = self.open_with_poly_evals(transcript, &polys, *i)?;
+                Ok((evals.pop().unwrap_or_default(), proof))
+            })
+            .collect()
+    }
+
+    /// Opens only the polynomials at `indices`, with `evals[i]` the evaluations of polynomial
+    /// `indices[i]`. The proof is verified with [`Self::verify_partial`] and the same indices.
+    fn open_partial(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        indices: &[usize],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        let polys = select_indices(polys, indices)?
+            .into_iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>();
+        self.open(transcript, evals, &polys, point_set_index)
+    }
+
+    /// Verifies a proof of only the commitments at `indices`, with `evals[i]` the evaluations of
+    /// the polynomial committed to by `commits[indices[i]]`. This lets a verifier which only has
+    /// some of the evaluations check them against the full list of commitments.
+    fn verify_partial(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        indices: &[usize],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commits = select_indices(commits, indices)?
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        self.verify(transcript, &commits, point_set_index, evals, proof)
+    }
+
+    /// Opens $\sum_i c_i f_i$ for the polynomials $f_i$ in `polys` and the public coefficients
+    /// $c_i$ in `coeffs`, where `evals` are the evaluations of the combination. The proof is
+    /// verified with [`Self::verify_linear_combination`] against the commitments to each $f_i$.
+    fn open_linear_combination(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: impl AsRef<[E::ScalarField]>,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        coeffs: &[E::ScalarField],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        let combined = combine_polys(polys, coeffs)?;
+        self.open(transcript, &[evals], &[combined], point_set_index)
+    }
+
+    /// Verifies a proof made with [`Self::open_linear_combination`], combining `commits` with
+    /// `coeffs` into the commitment to $\sum_i c_i f_i$
+    fn verify_linear_combination(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        coeffs: &[E::ScalarField],
+        point_set_index: usize,
+        evals: impl AsRef<[E::ScalarField]>,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commit = linear_combination_of_commitments(commits, coeffs)?;
+        self.verify(transcript, &[commit], point_set_index, &[evals], proof)
+    }
+
+    /// Verifies many independent proofs at once. Each item is a tuple of
+    /// `(commits, point_set_index, evals, proof)` and is checked against the transcript at the same
+    /// index in `transcripts`. The default implementation verifies each proof separately, schemes
+    /// override it to combine the checks with a random linear combination drawn from `rng`.
+    #[allow(clippy::type_complexity)]
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            usize,
+            &[impl AsRef<[E::ScalarField]>],
+            &Self::Proof,
+        )],
+        _rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        for (transcript, (commits, point_set_index, evals, proof)) in
+            transcripts.iter_mut().zip(items)
+        {
+            if !self.verify(transcript, commits, *point_set_index, evals, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
 
 /// A curve-agnostic trait for a BDFG commitment scheme *without precomputation*
@@ -72,7 +370,7 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
     /// Creates a proof of the given polynomials and evals at the given points
     fn open(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
@@ -81,22 +379,167 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
     /// Verifies a proof against the given set of commitments and points
     fn verify(
         &self,
-        transcript: &mut Transcript,
+        transcript: &mut impl TranscriptProtocol,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Opens many polynomials at the same points, as when sampling a row of a grid, computing
+    /// their evaluations. Returns the evaluations along with the proof.
+    #[allow(clippy::type_complexity)]
+    fn open_rows(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Self::Proof), Error> {
+        let evals = polys
+            .iter()
+            .map(|p| evaluate_at_points(p.as_ref(), points))
+            .collect::<Vec<_>>();
+        let proof = self.open(transcript, &evals, polys, points)?;
+        Ok((evals, proof))
+    }
+
+    /// Opens a single polynomial at each of `point_sets`, returning the evaluations and proof for
+    /// each. Each proof is made against the transcript at the same index in `transcripts`, and is
+    /// verified with [`Self::verify`] and the commitment to `poly`. The default implementation
+    /// opens at each point set separately, schemes override it to skip combining the
+    /// polynomials, since there's only one.
+    #[allow(clippy::type_complexity)]
+    fn open_points(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        poly: impl AsRef<[E::ScalarField]>,
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<(Vec<E::ScalarField>, Self::Proof)>, Error> {
+        check_batch_sizes(transcripts, point_sets)?;
+        let polys = [poly.as_ref()];
+        transcripts
+            .iter_mut()
+            .zip(point_sets)
+            .map(|(transcript, points)| {
+                let (mut evals, proof) = self.open_rows(transcript, &polys, points.as_ref())?;
+                Ok((evals.pop().unwrap_or_default(), proof))
+            })
+            .collect()
+    }
+
+    /// Opens only the polynomials at `indices`, with `evals[i]` the evaluations of polynomial
+    /// `indices[i]`. The proof is verified with [`Self::verify_partial`] and the same indices.
+    fn open_partial(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        indices: &[usize],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let polys = select_indices(polys, indices)?
+            .into_iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>();
+        self.open(transcript, evals, &polys, points)
+    }
+
+    /// Verifies a proof of only the commitments at `indices`, with `evals[i]` the evaluations of
+    /// the polynomial committed to by `commits[indices[i]]`. This lets a verifier which only has
+    /// some of the evaluations check them against the full list of commitments.
+    fn verify_partial(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        indices: &[usize],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commits = select_indices(commits, indices)?
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        self.verify(transcript, &commits, points, evals, proof)
+    }
+
+    /// Opens $\sum_i c_i f_i$ for the polynomials $f_i$ in `polys` and the public coefficients
+    /// $c_i$ in `coeffs`, where `evals` are the evaluations of the combination. The proof is
+    /// verified with [`Self::verify_linear_combination`] against the commitments to each $f_i$.
+    fn open_linear_combination(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: impl AsRef<[E::ScalarField]>,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        coeffs: &[E::ScalarField],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let combined = combine_polys(polys, coeffs)?;
+        self.open(transcript, &[evals], &[combined], points)
+    }
+
+    /// Verifies a proof made with [`Self::open_linear_combination`], combining `commits` with
+    /// `coeffs` into the commitment to $\sum_i c_i f_i$
+    fn verify_linear_combination(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        coeffs: &[E::ScalarField],
+        points: &[E::ScalarField],
+        evals: impl AsRef<[E::ScalarField]>,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commit = linear_combination_of_commitments(commits, coeffs)?;
+        self.verify(transcript, &[commit], points, &[evals], proof)
+    }
+
+    /// Verifies many independent proofs at once. Each item is a tuple of
+    /// `(commits, points, evals, proof)` and is checked against the transcript at the same index
+    /// in `transcripts`. The default implementation verifies each proof separately, schemes
+    /// override it to combine the checks with a random linear combination drawn from `rng`.
+    #[allow(clippy::type_complexity)]
+    fn verify_batch(
+        &self,
+        transcripts: &mut [impl TranscriptProtocol],
+        items: &[(
+            &[Commitment<E>],
+            &[E::ScalarField],
+            &[impl AsRef<[E::ScalarField]>],
+            &Self::Proof,
+        )],
+        _rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        check_batch_sizes(transcripts, items)?;
+        for (transcript, (commits, points, evals, proof)) in transcripts.iter_mut().zip(items) {
+            if !self.verify(transcript, commits, points, evals, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+pub(crate) fn check_batch_sizes<T>(
+    transcripts: &[impl TranscriptProtocol],
+    items: &[T],
+) -> Result<(), Error> {
+    if transcripts.len() != items.len() {
+        return Err(Error::InvalidInputLength {
+            expected: items.len(),
+            got: transcripts.len(),
+        });
+    }
+    Ok(())
 }
 
 /// A curve-agnostic trait for fast multi-scalar multiplication
-pub trait MSMEngine: Clone + Copy {
+pub trait MSMEngine: Clone + Copy + Send + Sync {
     /// The curve type implemented
     type E: Pairing;
     /// The prepared G1 Scalars
-    type G1Prepared: Clone;
+    type G1Prepared: Clone + Send + Sync;
     /// The prepared G2 Scalars
-    type G2Prepared: Clone;
+    type G2Prepared: Clone + Send + Sync;
 
     /// Prepare the given points for multi-scalar multiplication
     fn prepare_g1(g: Vec<<Self::E as Pairing>::G1Affine>) -> Self::G1Prepared;
@@ -116,6 +559,48 @@ pub trait MSMEngine: Clone + Copy {
         s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
     ) -> Result<<Self::E as Pairing>::G2, Error>;
 
+    /// Computes an msm of `s` against points which are only used once, so preparing them isn't
+    /// worth keeping. The default implementation prepares a copy of them, engines which use the
+    /// affine points as they are override it to skip the copy.
+    fn multi_scalar_mul_g1_unprepared(
+        g: &[<Self::E as Pairing>::G1Affine],
+        s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
+    ) -> Result<<Self::E as Pairing>::G1, Error> {
+        Self::multi_scalar_mul_g1(&Self::prepare_g1(g.to_vec()), s)
+    }
+
+    /// Computes $\sum_j s_j g_{i_j}$ for each `(i_j, s_j)` in `terms`, for when most of the scalars
+    /// for `bases` would be zero. The default implementation prepares just the points at the
+    /// indices and does an msm of those.
+    fn sparse_multi_scalar_mul_g1(
+        bases: &[<Self::E as Pairing>::G1Affine],
+        terms: &[(usize, <Self::E as Pairing>::ScalarField)],
+    ) -> Result<<Self::E as Pairing>::G1, Error> {
+        let (points, scalars) = terms
+            .iter()
+            .map(|(i, s)| {
+                let point = bases.get(*i).ok_or(Error::TooManyScalars {
+                    n_coeffs: i + 1,
+                    expected_max: bases.len(),
+                })?;
+                Ok((*point, *s))
+            })
+            .collect::<Result<(Vec<_>, Vec<_>), Error>>()?;
+        Self::multi_scalar_mul_g1(&Self::prepare_g1(points), scalars)
+    }
+
+    /// Computes an msm of each row of `s` against the given G1 elements. The default
+    /// implementation does one msm per row, engines which can share work between the rows
+    /// override it.
+    fn batch_multi_scalar_mul_g1(
+        g: &Self::G1Prepared,
+        s: &[impl AsRef<[<Self::E as Pairing>::ScalarField]> + Sync],
+    ) -> Result<Vec<<Self::E as Pairing>::G1>, Error> {
+        s.iter()
+            .map(|row| Self::multi_scalar_mul_g1(g, row.as_ref()))
+            .collect()
+    }
+
     /// Checks that e(p1, q1) == e(p2, q2)
     fn pairing_eq_check(
         p1: <Self::E as Pairing>::G1Affine,
@@ -129,6 +614,37 @@ pub trait MSMEngine: Clone + Copy {
         p1: <Self::E as Pairing>::G1Affine,
         q1: <Self::E as Pairing>::G2Affine,
     ) -> PairingOutput<Self::E>;
+
+    /// Checks that the product of e(p_i, q_i) is the identity
+    fn multi_pairing_is_one(
+        ps: &[<Self::E as Pairing>::G1Affine],
+        qs: &[<Self::E as Pairing>::G2Affine],
+    ) -> bool {
+        Self::E::multi_pairing(ps.iter().copied(), qs.iter().copied()).is_zero()
+    }
+
+    /// A short name for the backend doing the work, such as `"blst"` or `"arkworks"`
+    fn name() -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// What the backend accelerates
+    fn capabilities() -> MSMCapabilities {
+        MSMCapabilities::default()
+    }
+}
+
+/// What an [`MSMEngine`] accelerates over the generic arkworks implementation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MSMCapabilities {
+    /// Multi-scalar multiplications use an optimized backend
+    pub fast_msm: bool,
+    /// Pairing equality checks share a single final exponentiation
+    pub fast_pairing_check: bool,
+    /// The backend runs on a GPU
+    pub gpu: bool,
+    /// The CPU has the ADX instructions, which the backend uses for faster field arithmetic
+    pub adx: bool,
 }
 
 /// Utility trait for serialization and deserialization
@@ -142,30 +658,22 @@ pub trait AsBytes<const N: usize>: Sized {
 #[cfg(feature = "ark-bls12-381")]
 impl AsBytes<48> for Commitment<ark_bls12_381::Bls12_381> {
     fn to_bytes(&self) -> Result<[u8; 48], Error> {
-        let mut out = [0u8; 48];
-        self.0.serialize_compressed(&mut out[..])?;
-        Ok(out)
+        Ok(self.to_bytes_compressed())
     }
 
     fn from_bytes(bytes: &[u8; 48]) -> Result<Self, Error> {
-        Ok(Self(ark_bls12_381::G1Affine::deserialize_compressed(
-            &bytes[..],
-        )?))
+        Self::from_bytes_compressed(bytes)
     }
 }
 
 #[cfg(feature = "ark-bls12-381")]
 impl AsBytes<48> for crate::method1::Proof<ark_bls12_381::Bls12_381> {
     fn to_bytes(&self) -> Result<[u8; 48], Error> {
-        let mut out = [0u8; 48];
-        self.0.serialize_compressed(&mut out[..])?;
-        Ok(out)
+        Ok(self.to_bytes_compressed())
     }
 
     fn from_bytes(bytes: &[u8; 48]) -> Result<Self, Error> {
-        Ok(Self(ark_bls12_381::G1Affine::deserialize_compressed(
-            &bytes[..],
-        )?))
+        Self::from_bytes_compressed(bytes)
     }
 }
 
@@ -0,0 +1,1082 @@
+//! Application level domain separation for transcripts. Proofs are only bound to what's absorbed
+//! into the transcript, so two protocols which open the same commitments at the same points with
+//! the same transcript can replay each other's proofs. Applying a different [`DomainSeparator`] to
+//! the transcripts of each protocol before opening and verifying prevents this.
+//!
+//! The opening challenge can also be derived step by step with [`absorb_evals`],
+//! [`absorb_points`] and [`squeeze_gamma`], for protocols which interleave their own messages
+//! with the opening in the same transcript. The challenge is then passed to
+//! [`M1NoPrecomp::open_with_challenge`] and [`M1NoPrecomp::verify_with_challenge`]. Absorbing the
+//! evals and then the points with nothing in between gives the same challenge as
+//! [`PolyMultiProofNoPrecomp::open`], after [`TranscriptEncoding::absorb_commits`] for
+//! [`TranscriptVersion::V1`].
+//!
+//! ### Byte layout
+//! Opening and verifying absorb two messages, with field elements in their compressed (little
+//! endian) serialization:
+//! * `open evals`: the evaluations of each polynomial at every point, polynomial by polynomial
+//! * `open points`: the points
+//!
+//! and then squeeze the 32 byte (for BLS12-381) challenge `open gamma`, which is read as a big
+//! endian integer mod the field order. A [`RecordingTranscript`] records each of these steps as a
+//! [`TranscriptTrace`], and [`replay_open`] re-runs an opening with recorded challenges instead of
+//! merlin, for checking an implementation in another language step by step.
+//!
+//! A scheme can be configured with a [`TranscriptEncoding`] to absorb big endian field elements
+//! or uncompressed points, or read its challenges little endian, for verifiers using other
+//! conventions.
+//!
+//! With [`TranscriptVersion::V1`], the version and the commitments are absorbed before the
+//! evals, so the challenge is bound to the commitments without the caller absorbing them:
+//! * `pmp transcript version`: the single byte `1`
+//! * `open commits`: each commitment's serialization, compressed unless the encoding says not to
+//!
+//! [`PolyMultiProofNoPrecomp::open`] and the other openings which only take the polynomials
+//! commit to them again to absorb the commitments, an msm of the polynomial's size per
+//! polynomial. That's as much work again as committing, and usually more than the opening
+//! itself, which is one msm however many polynomials there are. A prover which already has the
+//! commitments should pass them to [`M1NoPrecomp::open_with_commits`],
+//! [`M2NoPrecomp::open_with_commits`] or the `open_all_with_commits` of the precomputed schemes
+//! instead, which absorb them as given. The default is still [`TranscriptVersion::Legacy`], so
+//! existing proofs and deployments keep verifying, and it costs nothing extra.
+//!
+//! When the commitments opened are a subset of a list both sides agree on, such as a block's
+//! blob commitments, a [`CommitmentOrder`] absorbs their positions in it:
+//! * `pmp commit order`: the number of positions then each position, as 8 byte little endian
+//!   integers
+//!
+//! so a proof can't be checked against the commitments at other positions, even if they're
+//! received out of order or reused across positions.
+//!
+//! [`M1NoPrecomp::open_with_challenge`]: crate::method1::M1NoPrecomp::open_with_challenge
+//! [`M1NoPrecomp::verify_with_challenge`]: crate::method1::M1NoPrecomp::verify_with_challenge
+//! [`M1NoPrecomp::open_with_commits`]: crate::method1::M1NoPrecomp::open_with_commits
+//! [`M2NoPrecomp::open_with_commits`]: crate::method2::M2NoPrecomp::open_with_commits
+//! [`PolyMultiProofNoPrecomp::open`]: crate::traits::PolyMultiProofNoPrecomp::open
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, Compress};
+use ark_std::{collections::VecDeque, fmt, vec, vec::Vec};
+use merlin::Transcript;
+
+use crate::{
+    get_field_size,
+    traits::{PolyMultiProofNoPrecomp, TranscriptProtocol},
+    transcribe_evals, transcribe_points, Commitment, Error,
+};
+
+/// Absorb the evaluations of each polynomial at `n_points` points
+pub fn absorb_evals<F: PrimeField>(
+    transcript: &mut impl TranscriptProtocol,
+    evals: &[impl AsRef<[F]>],
+    n_points: usize,
+) -> Result<(), Error> {
+    TranscriptEncoding::default().absorb_evals(transcript, evals, n_points)
+}
+
+/// Absorb the points being opened at
+pub fn absorb_points<F: PrimeField>(
+    transcript: &mut impl TranscriptProtocol,
+    points: &[F],
+) -> Result<(), Error> {
+    TranscriptEncoding::default().absorb_points(transcript, points)
+}
+
+/// Squeeze the challenge $\gamma$ used to combine the polynomials being opened
+pub fn squeeze_gamma<F: PrimeField>(transcript: &mut impl TranscriptProtocol) -> F {
+    TranscriptEncoding::default().squeeze_gamma(transcript)
+}
+
+/// A byte order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Least significant byte first
+    #[default]
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
+/// Which messages an opening absorbs, see the [module docs](self)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TranscriptVersion {
+    /// Only the evals and the points are absorbed. The challenge isn't bound to the commitments
+    /// unless the caller absorbs them before opening and verifying.
+    #[default]
+    Legacy,
+    /// The version and the commitments are absorbed before the evals and points. Openings which
+    /// don't take the commitments make them again, an msm per polynomial.
+    V1,
+}
+
+/// How a scheme writes field elements and curve points into its opening transcript, and reads
+/// its challenges. The default is the layout described in the [module docs](self). Changing it
+/// lets proofs be Fiat-Shamir compatible with verifiers using other conventions, like an EVM
+/// verifier absorbing 32 byte big endian words. A scheme is configured with it by
+/// [`M1NoPrecomp::with_transcript_encoding`] or
+/// [`M2NoPrecomp::with_transcript_encoding`](crate::method2::M2NoPrecomp::with_transcript_encoding),
+/// and the provers and verifiers of a proof must use the same encoding.
+///
+/// [`M1NoPrecomp::with_transcript_encoding`]: crate::method1::M1NoPrecomp::with_transcript_encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TranscriptEncoding {
+    /// The byte order of the points and evaluations absorbed
+    pub field_endianness: Endianness,
+    /// The byte order challenge bytes are read in, before they're reduced mod the field order
+    pub challenge_endianness: Endianness,
+    /// Whether curve points absorbed, such as the commitments or the first element of a method
+    /// 2 proof, are compressed
+    pub compress_points: bool,
+    /// Which messages are absorbed
+    pub version: TranscriptVersion,
+}
+
+impl Default for TranscriptEncoding {
+    fn default() -> Self {
+        Self {
+            field_endianness: Endianness::Little,
+            challenge_endianness: Endianness::Big,
+            compress_points: true,
+            version: TranscriptVersion::Legacy,
+        }
+    }
+}
+
+impl TranscriptEncoding {
+    /// Field elements are absorbed and challenges read big endian, and points compressed
+    pub fn big_endian() -> Self {
+        Self {
+            field_endianness: Endianness::Big,
+            ..Self::default()
+        }
+    }
+
+    /// The default encoding with [`TranscriptVersion::V1`], which absorbs the commitments
+    pub fn v1() -> Self {
+        Self {
+            version: TranscriptVersion::V1,
+            ..Self::default()
+        }
+    }
+
+    /// Absorb the version and the commitments being opened, if this encoding's version absorbs
+    /// them. This comes before [`TranscriptEncoding::absorb_evals`].
+    pub fn absorb_commits<E: Pairing>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+    ) -> Result<(), Error> {
+        if self.version == TranscriptVersion::Legacy {
+            return Ok(());
+        }
+        transcript.append_message(b"pmp transcript version", &[1]);
+        self.append_commits(transcript, b"open commits", commits)
+    }
+
+    /// Absorbs `commits` as one message under `label`, with the points written one after another
+    pub(crate) fn append_commits<E: Pairing>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        label: &'static [u8],
+        commits: &[Commitment<E>],
+    ) -> Result<(), Error> {
+        let compress = self.compress();
+        let mut bytes = Vec::with_capacity(commits.serialized_size(compress));
+        for c in commits {
+            c.0.serialize_with_mode(&mut bytes, compress)?;
+        }
+        transcript.append_message(label, &bytes);
+        Ok(())
+    }
+
+    /// The commitments to the `n_polys` polynomials a prover absorbs, with `commit` making the
+    /// commitment to each index, or none if this encoding's version doesn't absorb them
+    pub(crate) fn prover_commits<E: Pairing>(
+        &self,
+        n_polys: usize,
+        commit: impl Fn(usize) -> Result<Commitment<E>, Error>,
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        if self.version == TranscriptVersion::Legacy {
+            return Ok(Vec::new());
+        }
+        (0..n_polys).map(commit).collect()
+    }
+
+    /// Absorb the evaluations of each polynomial at `n_points` points with this encoding, as in
+    /// [`absorb_evals`]
+    pub fn absorb_evals<F: PrimeField>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[F]>],
+        n_points: usize,
+    ) -> Result<(), Error> {
+        transcribe_evals(transcript, evals, n_points, self)
+    }
+
+    /// Absorb the points being opened at with this encoding, as in [`absorb_points`]
+    pub fn absorb_points<F: PrimeField>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        points: &[F],
+    ) -> Result<(), Error> {
+        transcribe_points(transcript, points, self)
+    }
+
+    /// Squeeze the challenge $\gamma$ with this encoding, as in [`squeeze_gamma`]
+    pub fn squeeze_gamma<F: PrimeField>(&self, transcript: &mut impl TranscriptProtocol) -> F {
+        self.challenge(transcript, b"open gamma")
+    }
+
+    /// Writes `f` to `out`, which is the field's compressed size
+    pub(crate) fn write_field<F: CanonicalSerialize>(
+        &self,
+        f: &F,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        f.serialize_compressed(&mut *out)?;
+        if self.field_endianness == Endianness::Big {
+            out.reverse();
+        }
+        Ok(())
+    }
+
+    /// Squeezes a challenge the size of the field and reduces it mod the field order
+    pub(crate) fn challenge<F: PrimeField>(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        label: &'static [u8],
+    ) -> F {
+        let mut bytes = vec![0u8; get_field_size::<F>()];
+        transcript.challenge_bytes(label, &mut bytes);
+        match self.challenge_endianness {
+            Endianness::Little => F::from_le_bytes_mod_order(&bytes),
+            Endianness::Big => F::from_be_bytes_mod_order(&bytes),
+        }
+    }
+
+    /// Absorbs the curve point `p`
+    pub(crate) fn append_point(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        label: &'static [u8],
+        p: &impl CanonicalSerialize,
+    ) -> Result<(), Error> {
+        let compress = self.compress();
+        let mut bytes = Vec::with_capacity(p.serialized_size(compress));
+        p.serialize_with_mode(&mut bytes, compress)?;
+        transcript.append_message(label, &bytes);
+        Ok(())
+    }
+
+    fn compress(&self) -> Compress {
+        if self.compress_points {
+            Compress::Yes
+        } else {
+            Compress::No
+        }
+    }
+}
+
+/// Identifies the application a proof belongs to by its protocol name, version, and optionally the
+/// chain id it's used on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainSeparator {
+    protocol: Vec<u8>,
+    version: u64,
+    chain_id: Option<u64>,
+}
+
+impl DomainSeparator {
+    /// A separator for version 0 of `protocol` with no chain id
+    pub fn new(protocol: impl Into<Vec<u8>>) -> Self {
+        Self {
+            protocol: protocol.into(),
+            version: 0,
+            chain_id: None,
+        }
+    }
+
+    /// Set the protocol version
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Set the chain id
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// The protocol name
+    pub fn protocol(&self) -> &[u8] {
+        &self.protocol
+    }
+
+    /// The protocol version
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The chain id, if any
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain_id
+    }
+
+    /// Absorb the separator into `transcript`. This must be done to both the prover's and the
+    /// verifier's transcripts before opening and verifying.
+    pub fn apply(&self, transcript: &mut impl TranscriptProtocol) {
+        transcript.append_message(b"pmp protocol", &self.protocol);
+        transcript.append_message(b"pmp version", &self.version.to_le_bytes());
+        // Without a chain id nothing is absorbed, so it can't collide with any chain id
+        let chain_id = self.chain_id.map(u64::to_le_bytes);
+        transcript.append_message(b"pmp chain id", chain_id.as_ref().map_or(&[], |c| &c[..]));
+    }
+
+    /// A new merlin transcript with the separator applied
+    pub fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(b"poly-multiproof");
+        self.apply(&mut transcript);
+        transcript
+    }
+}
+
+/// The positions of the commitments being opened in a list both sides agree on. Absorbing it
+/// binds a proof to those positions, so a verifier can't be tricked into checking it against a
+/// reordering of the commitments. Positions can't repeat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentOrder {
+    positions: Vec<usize>,
+}
+
+impl CommitmentOrder {
+    /// The order where the `i`th commitment opened is at `positions[i]`
+    pub fn new(positions: Vec<usize>) -> Result<Self, Error> {
+        let mut sorted = positions.clone();
+        sorted.sort_unstable();
+        if let Some(w) = sorted.windows(2).find(|w| w[0] == w[1]) {
+            return Err(Error::DuplicatePosition(w[0]));
+        }
+        Ok(Self { positions })
+    }
+
+    /// The position of each commitment opened
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// Absorb the positions into `transcript`, which must be done to both the prover's and the
+    /// verifier's transcripts. Fails if there isn't a position for each of the `n_commits`
+    /// commitments.
+    pub fn apply(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        n_commits: usize,
+    ) -> Result<(), Error> {
+        if self.positions.len() != n_commits {
+            return Err(Error::InvalidInputLength {
+                expected: n_commits,
+                got: self.positions.len(),
+            });
+        }
+        let mut bytes = Vec::with_capacity(8 * (self.positions.len() + 1));
+        bytes.extend_from_slice(&(self.positions.len() as u64).to_le_bytes());
+        for p in &self.positions {
+            bytes.extend_from_slice(&(*p as u64).to_le_bytes());
+        }
+        transcript.append_message(b"pmp commit order", &bytes);
+        Ok(())
+    }
+
+    /// Open `polys`, the polynomials at each position, bound to this order as in
+    /// [`PolyMultiProofNoPrecomp::open`]
+    pub fn open<E: Pairing, S: PolyMultiProofNoPrecomp<E>>(
+        &self,
+        srs: &S,
+        transcript: &mut impl TranscriptProtocol,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<S::Proof, Error> {
+        self.apply(transcript, polys.len())?;
+        srs.open(transcript, evals, polys, points)
+    }
+
+    /// Verify a proof made with [`CommitmentOrder::open`], where `commits` are the commitments at
+    /// each position
+    pub fn verify<E: Pairing, S: PolyMultiProofNoPrecomp<E>>(
+        &self,
+        srs: &S,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &S::Proof,
+    ) -> Result<bool, Error> {
+        self.apply(transcript, commits.len())?;
+        srs.verify(transcript, commits, points, evals, proof)
+    }
+}
+
+/// A single step taken on a transcript
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEvent {
+    /// A message was absorbed
+    Absorb {
+        /// The label of the message
+        label: Vec<u8>,
+        /// The bytes absorbed
+        message: Vec<u8>,
+    },
+    /// Challenge bytes were squeezed
+    Challenge {
+        /// The label of the challenge
+        label: Vec<u8>,
+        /// The bytes squeezed
+        bytes: Vec<u8>,
+    },
+}
+
+/// Every step taken on a transcript, in order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TranscriptTrace {
+    /// The steps
+    pub events: Vec<TranscriptEvent>,
+}
+
+impl TranscriptTrace {
+    /// The bytes of each challenge squeezed, in order
+    pub fn challenges(&self) -> Vec<Vec<u8>> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                TranscriptEvent::Challenge { bytes, .. } => Some(bytes.clone()),
+                TranscriptEvent::Absorb { .. } => None,
+            })
+            .collect()
+    }
+}
+
+/// One line per step, as `absorb <label> <hex>` or `challenge <label> <hex>`
+impl fmt::Display for TranscriptTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            let (kind, label, bytes) = match event {
+                TranscriptEvent::Absorb { label, message } => ("absorb", label, message),
+                TranscriptEvent::Challenge { label, bytes } => ("challenge", label, bytes),
+            };
+            write!(
+                f,
+                "{} {} ",
+                kind,
+                ark_std::string::String::from_utf8_lossy(label)
+            )?;
+            for b in bytes {
+                write!(f, "{:02x}", b)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Records every step taken on the transcript it wraps
+#[derive(Clone, Debug)]
+pub struct RecordingTranscript<T> {
+    inner: T,
+    trace: TranscriptTrace,
+}
+
+impl<T: TranscriptProtocol> RecordingTranscript<T> {
+    /// Record the steps taken on `inner`
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            trace: TranscriptTrace::default(),
+        }
+    }
+
+    /// The steps taken so far
+    pub fn trace(&self) -> &TranscriptTrace {
+        &self.trace
+    }
+
+    /// The wrapped transcript along with the steps taken on it
+    pub fn into_parts(self) -> (T, TranscriptTrace) {
+        (self.inner, self.trace)
+    }
+}
+
+impl<T: TranscriptProtocol> TranscriptProtocol for RecordingTranscript<T> {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.inner.append_message(label, message);
+        self.trace.events.push(TranscriptEvent::Absorb {
+            label: label.to_vec(),
+            message: message.to_vec(),
+        });
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        self.inner.challenge_bytes(label, dest);
+        self.trace.events.push(TranscriptEvent::Challenge {
+            label: label.to_vec(),
+            bytes: dest.to_vec(),
+        });
+    }
+}
+
+/// A transcript which hands out recorded challenges in order rather than deriving them, and
+/// records what's absorbed. A challenge of the wrong length, or one past the end of the recording,
+/// is filled with zeros and makes [`Self::finish`] fail.
+#[derive(Clone, Debug)]
+pub struct ReplayTranscript {
+    challenges: VecDeque<Vec<u8>>,
+    trace: TranscriptTrace,
+    mismatch: bool,
+}
+
+impl ReplayTranscript {
+    /// Replay the given challenges in order
+    pub fn new(challenges: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self {
+            challenges: challenges.into_iter().collect(),
+            trace: TranscriptTrace::default(),
+            mismatch: false,
+        }
+    }
+
+    /// The steps taken, failing if the challenges asked for didn't match the recording
+    pub fn finish(self) -> Result<TranscriptTrace, Error> {
+        if self.mismatch || !self.challenges.is_empty() {
+            return Err(Error::TranscriptReplayMismatch);
+        }
+        Ok(self.trace)
+    }
+}
+
+impl TranscriptProtocol for ReplayTranscript {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.trace.events.push(TranscriptEvent::Absorb {
+            label: label.to_vec(),
+            message: message.to_vec(),
+        });
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        match self.challenges.pop_front() {
+            Some(c) if c.len() == dest.len() => dest.copy_from_slice(&c),
+            _ => {
+                self.mismatch = true;
+                dest.fill(0);
+            }
+        }
+        self.trace.events.push(TranscriptEvent::Challenge {
+            label: label.to_vec(),
+            bytes: dest.to_vec(),
+        });
+    }
+}
+
+/// Re-run [`PolyMultiProofNoPrecomp::open`] with `challenges` in place of the challenges the
+/// transcript would derive. Returns the proof along with the steps taken, which fails if the
+/// opening asked for different challenges than were given.
+pub fn replay_open<E: Pairing, S: PolyMultiProofNoPrecomp<E>>(
+    srs: &S,
+    challenges: impl IntoIterator<Item = Vec<u8>>,
+    evals: &[impl AsRef<[E::ScalarField]>],
+    polys: &[impl AsRef<[E::ScalarField]>],
+    points: &[E::ScalarField],
+) -> Result<(S::Proof, TranscriptTrace), Error> {
+    let mut transcript = ReplayTranscript::new(challenges);
+    let proof = srs.open(&mut transcript, evals, polys, points)?;
+    Ok((proof, transcript.finish()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{vec::Vec, UniformRand};
+
+    use merlin::Transcript;
+
+    use super::{
+        absorb_evals, absorb_points, replay_open, squeeze_gamma, CommitmentOrder, DomainSeparator,
+        RecordingTranscript, TranscriptEncoding, TranscriptEvent,
+    };
+    use crate::{
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        method2::M2NoPrecomp,
+        msm::blst::BlstMSMEngine,
+        test_rng,
+        testing::gen_openings,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    #[test]
+    fn test_domain_separation() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        // The challenge only matters with more than one polynomial
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+
+        let sep = DomainSeparator::new("rollup")
+            .with_version(2)
+            .with_chain_id(1);
+        assert_eq!(b"rollup", sep.protocol());
+        assert_eq!((2, Some(1)), (sep.version(), sep.chain_id()));
+        let proof = s
+            .open(&mut sep.transcript(), &evals, &coeffs, &points)
+            .unwrap();
+        let verify = |sep: DomainSeparator| {
+            s.verify(&mut sep.transcript(), &commits, &points, &evals, &proof)
+                .unwrap()
+        };
+        assert!(verify(sep.clone()));
+        assert!(!verify(sep.clone().with_chain_id(2)));
+        assert!(!verify(sep.clone().with_version(3)));
+        assert!(!verify(DomainSeparator::new("rollup").with_version(2)));
+        assert!(!verify(
+            DomainSeparator::new("other")
+                .with_version(2)
+                .with_chain_id(1)
+        ));
+    }
+
+    #[test]
+    fn test_commitment_order() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 2, &points);
+        let order = CommitmentOrder::new(vec![7, 3]).unwrap();
+        assert_eq!(&[7, 3], order.positions());
+        let proof = order
+            .open(&s, &mut Transcript::new(b"order"), &evals, &coeffs, &points)
+            .unwrap();
+        let verify = |order: &CommitmentOrder| {
+            order.verify(
+                &s,
+                &mut Transcript::new(b"order"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+            )
+        };
+        assert_eq!(Ok(true), verify(&order));
+        // The same commitments and evals claimed at other positions
+        assert_eq!(
+            Ok(false),
+            verify(&CommitmentOrder::new(vec![3, 7]).unwrap())
+        );
+        assert_eq!(
+            Ok(false),
+            verify(&CommitmentOrder::new(vec![7, 4]).unwrap())
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 2,
+                got: 3
+            }),
+            verify(&CommitmentOrder::new(vec![7, 3, 1]).unwrap())
+        );
+        assert_eq!(
+            Err(Error::DuplicatePosition(3)),
+            CommitmentOrder::new(vec![3, 1, 3])
+        );
+    }
+
+    #[test]
+    fn test_incremental_transcript() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = gen_openings(&s, 3, &points);
+
+        // Absorbing the evals then the points matches the one shot opening
+        let mut transcript = Transcript::new(b"testing");
+        absorb_evals(&mut transcript, &evals, points.len()).unwrap();
+        absorb_points(&mut transcript, &points).unwrap();
+        let gamma = squeeze_gamma::<Fr>(&mut transcript);
+        let proof = s.open_with_challenge(&coeffs, &points, gamma).unwrap();
+        let expected = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(expected.0, proof.0);
+
+        // Other messages can be interleaved, as long as the verifier does the same
+        let interleaved = |other: &[u8]| {
+            let mut transcript = Transcript::new(b"testing");
+            absorb_points(&mut transcript, &points).unwrap();
+            transcript.append_message(b"other", other);
+            absorb_evals(&mut transcript, &evals, points.len()).unwrap();
+            squeeze_gamma::<Fr>(&mut transcript)
+        };
+        let gamma = interleaved(b"message");
+        let proof = s.open_with_challenge(&coeffs, &points, gamma).unwrap();
+        assert!(s
+            .verify_with_challenge(&commits, &points, &evals, &proof, gamma)
+            .unwrap());
+        assert!(!s
+            .verify_with_challenge(&commits, &points, &evals, &proof, interleaved(b"other"))
+            .unwrap());
+
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n_evals: 4,
+                expected: 3
+            }),
+            absorb_evals(&mut Transcript::new(b"testing"), &evals, 3)
+        );
+    }
+
+    #[test]
+    fn test_transcript_vectors() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let polys = [vec![Fr::from(3u64)], vec![Fr::from(1u64), Fr::from(1u64)]];
+        let evals = [[Fr::from(3u64); 2], [Fr::from(2u64), Fr::from(3u64)]];
+
+        let mut transcript = RecordingTranscript::new(Transcript::new(b"vectors"));
+        let proof = s.open(&mut transcript, &evals, &polys, &points).unwrap();
+        let (_, trace) = transcript.into_parts();
+        let le = |n: u8| {
+            let mut b = vec![0u8; 32];
+            b[0] = n;
+            b
+        };
+        assert_eq!(
+            TranscriptEvent::Absorb {
+                label: b"open evals".to_vec(),
+                message: [le(3), le(3), le(2), le(3)].concat(),
+            },
+            trace.events[0]
+        );
+        assert_eq!(
+            TranscriptEvent::Absorb {
+                label: b"open points".to_vec(),
+                message: [le(1), le(2)].concat(),
+            },
+            trace.events[1]
+        );
+        assert_eq!(
+            "challenge open gamma 22bb131aa76f7fa67464889ed377947f3a2b25846859c7f7106eb90f87501b03",
+            trace.to_string().lines().nth(2).unwrap()
+        );
+
+        // Replaying the recorded challenges gives the same proof and trace
+        let (replayed, replay_trace) =
+            replay_open(&s, trace.challenges(), &evals, &polys, &points).unwrap();
+        assert_eq!(proof.0, replayed.0);
+        assert_eq!(trace, replay_trace);
+
+        // Any challenge can be replayed, it's read as a big endian integer
+        let mut challenge = vec![0u8; 32];
+        challenge[31] = 5;
+        let (replayed, _) = replay_open(&s, [challenge], &evals, &polys, &points).unwrap();
+        let expected = s
+            .open_with_challenge(&polys, &points, Fr::from(5u64))
+            .unwrap();
+        assert_eq!(expected.0, replayed.0);
+
+        for challenges in [vec![], vec![vec![0u8; 31]], vec![vec![0u8; 32]; 2]] {
+            assert_eq!(
+                Some(Error::TranscriptReplayMismatch),
+                replay_open(&s, challenges, &evals, &polys, &points).err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_transcript_encoding() {
+        let encoding = TranscriptEncoding::big_endian();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let be = s.clone().with_transcript_encoding(encoding);
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let (polys, evals, commits) = gen_openings(&s, 2, &points);
+
+        let mut transcript = RecordingTranscript::new(Transcript::new(b"encoding"));
+        let proof = be.open(&mut transcript, &evals, &polys, &points).unwrap();
+        let mut be_one = [0u8; 32];
+        be_one[31] = 1;
+        assert!(matches!(
+            &transcript.trace().events[1],
+            TranscriptEvent::Absorb { message, .. } if message[..32] == be_one[..]
+        ));
+        assert_eq!(
+            Ok(true),
+            be.verify(
+                &mut Transcript::new(b"encoding"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(true),
+            be.verifier_key().unwrap().verify(
+                &mut Transcript::new(b"encoding"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        // A verifier with the default encoding draws a different challenge
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"encoding"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+
+        // The step by step challenge matches the scheme's
+        let mut transcript = Transcript::new(b"encoding");
+        encoding.absorb_evals(&mut transcript, &evals, 2).unwrap();
+        encoding.absorb_points(&mut transcript, &points).unwrap();
+        let gamma = encoding.squeeze_gamma(&mut transcript);
+        assert_eq!(
+            proof.0,
+            be.open_with_challenge(&polys, &points, gamma).unwrap().0
+        );
+
+        // Method 2 absorbs a curve point, which can be uncompressed
+        let m2 = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, &mut test_rng());
+        let uncompressed = m2.clone().with_transcript_encoding(TranscriptEncoding {
+            compress_points: false,
+            ..Default::default()
+        });
+        let mut transcript = RecordingTranscript::new(Transcript::new(b"encoding"));
+        let proof = uncompressed
+            .open(&mut transcript, &evals, &polys, &points)
+            .unwrap();
+        assert!(matches!(
+            &transcript.trace().events[3],
+            TranscriptEvent::Absorb { message, .. } if message.len() == 96
+        ));
+        let commits = polys
+            .iter()
+            .map(|p| m2.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            uncompressed.verify(
+                &mut Transcript::new(b"encoding"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            m2.verify(
+                &mut Transcript::new(b"encoding"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_transcript_version() {
+        let v1 = TranscriptEncoding::v1();
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let hardened = s.clone().with_transcript_encoding(v1);
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let (polys, evals, commits) = gen_openings(&s, 2, &points);
+
+        let mut transcript = RecordingTranscript::new(Transcript::new(b"version"));
+        let proof = hardened
+            .open(&mut transcript, &evals, &polys, &points)
+            .unwrap();
+        let labels = transcript
+            .trace()
+            .events
+            .iter()
+            .map(|e| match e {
+                TranscriptEvent::Absorb { label, .. } => label.clone(),
+                TranscriptEvent::Challenge { label, .. } => label.clone(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                b"pmp transcript version".to_vec(),
+                b"open commits".to_vec(),
+                b"open evals".to_vec(),
+                b"open points".to_vec(),
+                b"open gamma".to_vec(),
+            ],
+            labels
+        );
+        assert_eq!(
+            Ok(true),
+            hardened.verify(
+                &mut Transcript::new(b"version"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(true),
+            hardened.verifier_key().unwrap().verify(
+                &mut Transcript::new(b"version"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        // A legacy verifier draws a different challenge
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"version"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        // The challenge depends on the commitments, so it's drawn step by step with them
+        let mut transcript = Transcript::new(b"version");
+        v1.absorb_commits(&mut transcript, &commits).unwrap();
+        v1.absorb_evals(&mut transcript, &evals, 2).unwrap();
+        v1.absorb_points(&mut transcript, &points).unwrap();
+        let gamma = v1.squeeze_gamma(&mut transcript);
+        assert_eq!(
+            proof.0,
+            hardened
+                .open_with_challenge(&polys, &points, gamma)
+                .unwrap()
+                .0
+        );
+        // Legacy absorbs nothing for the commitments
+        let mut transcript = RecordingTranscript::new(Transcript::new(b"version"));
+        TranscriptEncoding::default()
+            .absorb_commits(&mut transcript, &commits)
+            .unwrap();
+        assert!(transcript.trace().events.is_empty());
+
+        let m2 = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, &mut test_rng())
+            .with_transcript_encoding(v1);
+        let proof = m2
+            .open(&mut Transcript::new(b"version"), &evals, &polys, &points)
+            .unwrap();
+        let commits = polys
+            .iter()
+            .map(|p| m2.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            m2.verify(
+                &mut Transcript::new(b"version"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_with_commits() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng())
+            .with_transcript_encoding(TranscriptEncoding::v1());
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let (polys, evals, commits) = gen_openings(&s, 3, &points);
+
+        // The caller's commitments give the same proof as committing again
+        let proof = s
+            .open(&mut Transcript::new(b"commits"), &evals, &polys, &points)
+            .unwrap();
+        let given = s
+            .open_with_commits(
+                &mut Transcript::new(b"commits"),
+                &commits,
+                &evals,
+                &polys,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(proof.0, given.0);
+        assert_eq!(
+            Err(Error::EvalsAndCommitsDifferentSizes {
+                n_evals: 3,
+                n_commits: 2
+            }),
+            s.open_with_commits(
+                &mut Transcript::new(b"commits"),
+                &commits[..2],
+                &evals,
+                &polys,
+                &points,
+            )
+            .map(|_| ())
+        );
+
+        let other_points = [Fr::from(3u64), Fr::from(4u64)];
+        let other_evals = polys
+            .iter()
+            .map(|p| {
+                other_points
+                    .iter()
+                    .map(|x| crate::poly_ops::evaluate_at_points(p, &[*x])[0])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let precomp = M1Precomp::from_inner(
+            s.clone(),
+            ark_std::vec![points.to_vec(), other_points.to_vec()],
+        )
+        .unwrap();
+        let all_evals = [evals.clone(), other_evals];
+        let mut transcripts = [Transcript::new(b"a"), Transcript::new(b"b")];
+        let proofs = precomp
+            .open_all(&mut transcripts, &all_evals, &polys)
+            .unwrap();
+        let mut transcripts = [Transcript::new(b"a"), Transcript::new(b"b")];
+        let given = precomp
+            .open_all_with_commits(&mut transcripts, &commits, &all_evals, &polys)
+            .unwrap();
+        assert_eq!(
+            proofs.iter().map(|p| p.0).collect::<Vec<_>>(),
+            given.iter().map(|p| p.0).collect::<Vec<_>>()
+        );
+
+        let m2 = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, &mut test_rng())
+            .with_transcript_encoding(TranscriptEncoding::v1());
+        let commits = polys
+            .iter()
+            .map(|p| m2.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let proof = m2
+            .open(&mut Transcript::new(b"commits"), &evals, &polys, &points)
+            .unwrap();
+        let given = m2
+            .open_with_commits(
+                &mut Transcript::new(b"commits"),
+                &commits,
+                &evals,
+                &polys,
+                &points,
+            )
+            .unwrap();
+        assert_eq!((proof.0, proof.1), (given.0, given.1));
+    }
+}
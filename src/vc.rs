@@ -0,0 +1,217 @@
+//! Vector commitments on top of the polynomial commitment schemes. A vector of length $n$ is
+//! treated as the evaluations of a polynomial over the size $n$ subgroup, so position $i$ holds
+//! the evaluation at $\omega^i$. Committing goes through [`Committer::commit_evals`], which uses a
+//! lagrange basis when the setup has one for the domain, and opening positions is a multiproof
+//! at the matching roots of unity.
+use ark_ec::pairing::Pairing;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{vec, vec::Vec};
+
+use crate::{
+    poly_ops::check_domain_size,
+    traits::{Committer, PolyMultiProofNoPrecomp, TranscriptProtocol},
+    Commitment, Error,
+};
+
+/// Commits to vectors of a fixed power of two length and opens them at positions
+#[derive(Clone)]
+pub struct VectorCommitment<E: Pairing, S> {
+    /// The polynomial commitment scheme underneath
+    pub srs: S,
+    domain: Radix2EvaluationDomain<E::ScalarField>,
+}
+
+impl<E: Pairing, S: Committer<E> + PolyMultiProofNoPrecomp<E>> VectorCommitment<E, S> {
+    /// Commit to vectors of length `len`, which must be a power of two
+    pub fn new(srs: S, len: usize) -> Result<Self, Error> {
+        if !len.is_power_of_two() {
+            return Err(Error::NotPowerOfTwo(len));
+        }
+        check_domain_size::<E::ScalarField>(len)?;
+        let domain =
+            Radix2EvaluationDomain::new(len).ok_or(Error::DomainConstructionFailed(len))?;
+        Ok(Self { srs, domain })
+    }
+
+    /// The length of the committed vectors
+    pub fn len(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// Always false, vectors have at least one element
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The domain the vectors are evaluations over
+    pub fn domain(&self) -> &Radix2EvaluationDomain<E::ScalarField> {
+        &self.domain
+    }
+
+    /// The points each of `positions` is an evaluation at, failing if any are out of range or
+    /// repeated
+    pub fn positions_to_points(&self, positions: &[usize]) -> Result<Vec<E::ScalarField>, Error> {
+        let len = self.len();
+        let mut seen = vec![false; len];
+        positions
+            .iter()
+            .map(|&idx| {
+                match seen.get_mut(idx) {
+                    None => return Err(Error::PositionOutOfRange { idx, len }),
+                    Some(true) => return Err(Error::DuplicatePosition(idx)),
+                    Some(s) => *s = true,
+                }
+                Ok(self.domain.element(idx))
+            })
+            .collect()
+    }
+
+    /// Commit to `vector`
+    pub fn commit(&self, vector: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        self.srs.commit_evals(vector, &self.domain)
+    }
+
+    /// Prove the values of every vector in `vectors` at `positions` with a single proof
+    pub fn prove_positions(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        vectors: &[impl AsRef<[E::ScalarField]>],
+        positions: &[usize],
+    ) -> Result<S::Proof, Error> {
+        let points = self.positions_to_points(positions)?;
+        let polys = vectors
+            .iter()
+            .map(|v| {
+                let v = v.as_ref();
+                if v.len() != self.len() {
+                    return Err(Error::InvalidInputLength {
+                        expected: self.len(),
+                        got: v.len(),
+                    });
+                }
+                Ok(self.domain.ifft(v))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let values = vectors
+            .iter()
+            .map(|v| positions.iter().map(|&i| v.as_ref()[i]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        self.srs.open(transcript, &values, &polys, &points)
+    }
+
+    /// Verify that `values[i]` holds the elements at `positions` of the vector committed to by
+    /// `commits[i]`
+    pub fn verify_positions(
+        &self,
+        transcript: &mut impl TranscriptProtocol,
+        commits: &[Commitment<E>],
+        positions: &[usize],
+        values: &[impl AsRef<[E::ScalarField]>],
+        proof: &S::Proof,
+    ) -> Result<bool, Error> {
+        let points = self.positions_to_points(positions)?;
+        self.srs.verify(transcript, commits, &points, values, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    use super::VectorCommitment;
+    use crate::{
+        method1::M1NoPrecomp,
+        method2::M2NoPrecomp,
+        msm::blst::BlstMSMEngine,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+
+    fn check_positions<S: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>>(
+        vc: &VectorCommitment<Bls12_381, S>,
+    ) {
+        let vectors = (0..3)
+            .map(|_| {
+                (0..vc.len())
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = vectors
+            .iter()
+            .map(|v| vc.commit(v).unwrap())
+            .collect::<Vec<_>>();
+        let positions = [3, 0, 17, 63];
+        let values = vectors
+            .iter()
+            .map(|v| positions.iter().map(|&i| v[i]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let proof = vc
+            .prove_positions(&mut Transcript::new(b"vc"), &vectors, &positions)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            vc.verify_positions(
+                &mut Transcript::new(b"vc"),
+                &commits,
+                &positions,
+                &values,
+                &proof
+            )
+        );
+        // The values are tied to their positions
+        assert_eq!(
+            Ok(false),
+            vc.verify_positions(
+                &mut Transcript::new(b"vc"),
+                &commits,
+                &[0, 3, 17, 63],
+                &values,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_vector_commitment() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let vc = VectorCommitment::new(s.clone(), 64).unwrap();
+        assert_eq!(64, vc.len());
+        check_positions(&vc);
+        // With a lagrange basis the commitments are the same
+        let domain = *vc.domain();
+        let with_basis = VectorCommitment::new(s.with_lagrange_basis(domain).unwrap(), 64).unwrap();
+        check_positions(&with_basis);
+        let v = (0..64)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(vc.commit(&v).unwrap().0, with_basis.commit(&v).unwrap().0);
+
+        let s = M2NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, &mut test_rng());
+        check_positions(&VectorCommitment::new(s, 64).unwrap());
+
+        assert_eq!(
+            Err(Error::PositionOutOfRange { idx: 64, len: 64 }),
+            vc.positions_to_points(&[1, 64])
+        );
+        assert_eq!(
+            Err(Error::DuplicatePosition(1)),
+            vc.positions_to_points(&[1, 2, 1])
+        );
+        assert_eq!(
+            Some(Error::NotPowerOfTwo(48)),
+            VectorCommitment::<Bls12_381, _>::new(vc.srs.clone(), 48).err()
+        );
+        assert_eq!(
+            Some(Error::InvalidInputLength {
+                expected: 64,
+                got: 63
+            }),
+            vc.prove_positions(&mut Transcript::new(b"vc"), &[&v[..63]], &[0])
+                .err()
+        );
+    }
+}
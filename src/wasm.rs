@@ -0,0 +1,499 @@
+//! A WebAssembly API for method 1 over BLS12-381, enabled with the `wasm` feature.
+//!
+//! [`WasmM1`] takes and returns byte buffers in the same layout as the C API: scalars are
+//! [`SCALAR_BYTES`] bytes little endian, and G1 points (commitments and proofs) are [`G1_BYTES`]
+//! bytes, in the arkworks compressed serialization. Lists are concatenated, and matrices are
+//! row-major with one row per polynomial. Proofs use the same transcript as the C API, so a proof
+//! from one verifies with the other.
+//!
+//! On `wasm32` the module exports these calls to JavaScript, and `js/poly_multiproof.mjs` wraps
+//! the exports in a `Setup` class whose methods take and return `Uint8Array`s, so no
+//! `wasm-bindgen` glue is needed. Build the module with the size-optimized `wasm` profile:
+//! ```bash
+//! cargo rustc --profile wasm --no-default-features --features wasm \
+//!     --target wasm32-unknown-unknown --crate-type cdylib
+//! ```
+//! and load it with
+//! ```js
+//! import { Setup } from "./js/poly_multiproof.mjs";
+//! const setup = await Setup.load(wasmBytes, setupBytes);
+//! const commit = setup.commit(coeffs);
+//! ```
+//! `node --test js/` then tests the wrapper against the module.
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::{
+    method1::{M1NoPrecomp, Proof},
+    msm::ArkMSMEngine,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Error,
+};
+
+/// The size of a serialized scalar
+pub const SCALAR_BYTES: usize = 32;
+/// The size of a serialized G1 point
+pub const G1_BYTES: usize = 48;
+
+/// A method 1 setup over BLS12-381 with a byte buffer API
+pub struct WasmM1(M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>);
+
+impl WasmM1 {
+    /// Load a setup from its compressed serialization, checking that all its points are valid
+    pub fn load(setup: &[u8]) -> Result<Self, Error> {
+        Ok(Self(M1NoPrecomp::deserialize_compressed(setup)?))
+    }
+
+    /// Commit to the polynomial with the coefficients `coeffs`, returning the commitment
+    pub fn commit(&self, coeffs: &[u8]) -> Result<Vec<u8>, Error> {
+        to_bytes(&self.0.commit(scalars(coeffs)?)?.0)
+    }
+
+    /// Open `n_polys` polynomials of the same length, concatenated in `polys`, at `points`,
+    /// returning the proof. `evals` holds the evaluations of each polynomial at the points. The
+    /// transcript is seeded with `domain`, which must match the one given to [`WasmM1::verify`].
+    pub fn open(
+        &self,
+        domain: &[u8],
+        evals: &[u8],
+        polys: &[u8],
+        n_polys: usize,
+        points: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let (evals, polys) = (scalars(evals)?, scalars(polys)?);
+        let evals = rows(&evals, n_polys)?;
+        let polys = rows(&polys, n_polys)?;
+        let proof = self
+            .0
+            .open(&mut transcript(domain), &evals, &polys, &scalars(points)?)?;
+        to_bytes(&proof.0)
+    }
+
+    /// Verify a proof from [`WasmM1::open`] against the concatenated `commits`
+    pub fn verify(
+        &self,
+        domain: &[u8],
+        commits: &[u8],
+        evals: &[u8],
+        points: &[u8],
+        proof: &[u8],
+    ) -> Result<bool, Error> {
+        let commits = g1s(commits)?
+            .into_iter()
+            .map(Commitment)
+            .collect::<Vec<_>>();
+        let evals = scalars(evals)?;
+        let evals = rows(&evals, commits.len())?;
+        let proof = G1Affine::deserialize_compressed(proof)?;
+        self.0.verify(
+            &mut transcript(domain),
+            &commits,
+            &scalars(points)?,
+            &evals,
+            &Proof(proof),
+        )
+    }
+}
+
+fn scalars(data: &[u8]) -> Result<Vec<Fr>, Error> {
+    deserialize_all(data, SCALAR_BYTES)
+}
+
+fn g1s(data: &[u8]) -> Result<Vec<G1Affine>, Error> {
+    deserialize_all(data, G1_BYTES)
+}
+
+fn deserialize_all<T: CanonicalDeserialize>(data: &[u8], size: usize) -> Result<Vec<T>, Error> {
+    let chunks = data.chunks_exact(size);
+    if !chunks.remainder().is_empty() {
+        return Err(Error::SerializationError);
+    }
+    chunks.map(|b| Ok(T::deserialize_compressed(b)?)).collect()
+}
+
+fn to_bytes(item: &impl CanonicalSerialize) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    item.serialize_compressed(&mut out)?;
+    Ok(out)
+}
+
+/// The same transcript as the C API's
+fn transcript(domain: &[u8]) -> Transcript {
+    let mut t = Transcript::new(b"poly-multiproof capi");
+    t.append_message(b"domain", domain);
+    t
+}
+
+/// Split a flat row-major matrix into `n_rows` rows of the same length
+fn rows(flat: &[Fr], n_rows: usize) -> Result<Vec<&[Fr]>, Error> {
+    let row_len = flat.len().checked_div(n_rows).unwrap_or(0);
+    if row_len * n_rows != flat.len() {
+        return Err(Error::InvalidInputLength {
+            expected: row_len * n_rows,
+            got: flat.len(),
+        });
+    }
+    Ok((0..n_rows)
+        .map(|i| &flat[i * row_len..(i + 1) * row_len])
+        .collect())
+}
+
+/// The exports wrapped by `js/poly_multiproof.mjs`. Each call returns 0 on success or the
+/// [`Error::code`] of the failure, and writes its result to an out pointer. Buffers are
+/// allocated in the module's memory with [`exports::pmp_alloc`] and freed with
+/// [`exports::pmp_free`]. The functions are only exported unmangled on `wasm32`, elsewhere
+/// they're plain functions so the tests can call them.
+#[allow(unsafe_code)]
+pub mod exports {
+    // std has the allocator and panic handler for wasm32. The `std` feature can't be used for
+    // them, since it enables rand's OS rng, which doesn't build for wasm32-unknown-unknown.
+    #[cfg(target_arch = "wasm32")]
+    extern crate std;
+
+    use ark_std::{boxed::Box, ptr, slice, vec};
+
+    use super::WasmM1;
+    use crate::Error;
+
+    /// Allocate `len` zeroed bytes for the caller to write an input to or read an output from
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    pub extern "C" fn pmp_alloc(len: usize) -> *mut u8 {
+        // A boxed slice is allocated with exactly `len` bytes, so `pmp_free` can rebuild it from
+        // the length alone
+        Box::into_raw(vec![0u8; len].into_boxed_slice()) as *mut u8
+    }
+
+    /// Free `len` bytes from [`pmp_alloc`]
+    ///
+    /// # Safety
+    /// `ptr` must be from a call to [`pmp_alloc`] with `len`, and not already freed.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    pub unsafe extern "C" fn pmp_free(ptr: *mut u8, len: usize) {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+
+    /// Load a setup, writing a pointer to it to `setup_out`. It must be freed with
+    /// [`pmp_setup_free`].
+    ///
+    /// # Safety
+    /// `data` must point to `len` readable bytes and `setup_out` to a writable pointer.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    pub unsafe extern "C" fn pmp_setup_load(
+        data: *const u8,
+        len: usize,
+        setup_out: *mut *mut WasmM1,
+    ) -> u32 {
+        status(WasmM1::load(bytes(data, len)), |s| {
+            *setup_out = Box::into_raw(Box::new(s))
+        })
+    }
+
+    /// Free a setup from [`pmp_setup_load`]
+    ///
+    /// # Safety
+    /// `setup` must be from [`pmp_setup_load`] and not already freed.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    pub unsafe extern "C" fn pmp_setup_free(setup: *mut WasmM1) {
+        drop(Box::from_raw(setup));
+    }
+
+    /// [`WasmM1::commit`], writing [`super::G1_BYTES`] bytes to `out`
+    ///
+    /// # Safety
+    /// `setup` must be a live setup, the inputs must point to their lengths of readable bytes,
+    /// and `out` to [`super::G1_BYTES`] writable bytes.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    pub unsafe extern "C" fn pmp_commit(
+        setup: *const WasmM1,
+        coeffs: *const u8,
+        coeffs_len: usize,
+        out: *mut u8,
+    ) -> u32 {
+        let res = (*setup).commit(bytes(coeffs, coeffs_len));
+        status(res, |c| write(&c, out))
+    }
+
+    /// [`WasmM1::open`], writing [`super::G1_BYTES`] bytes to `out`
+    ///
+    /// # Safety
+    /// `setup` must be a live setup, the inputs must point to their lengths of readable bytes,
+    /// and `out` to [`super::G1_BYTES`] writable bytes.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe extern "C" fn pmp_open(
+        setup: *const WasmM1,
+        domain: *const u8,
+        domain_len: usize,
+        evals: *const u8,
+        evals_len: usize,
+        polys: *const u8,
+        polys_len: usize,
+        n_polys: usize,
+        points: *const u8,
+        points_len: usize,
+        out: *mut u8,
+    ) -> u32 {
+        let res = (*setup).open(
+            bytes(domain, domain_len),
+            bytes(evals, evals_len),
+            bytes(polys, polys_len),
+            n_polys,
+            bytes(points, points_len),
+        );
+        status(res, |p| write(&p, out))
+    }
+
+    /// [`WasmM1::verify`], writing 1 to `valid_out` if the proof is valid and 0 otherwise
+    ///
+    /// # Safety
+    /// `setup` must be a live setup, the inputs must point to their lengths of readable bytes,
+    /// and `valid_out` to a writable byte.
+    #[cfg_attr(target_arch = "wasm32", no_mangle)]
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe extern "C" fn pmp_verify(
+        setup: *const WasmM1,
+        domain: *const u8,
+        domain_len: usize,
+        commits: *const u8,
+        commits_len: usize,
+        evals: *const u8,
+        evals_len: usize,
+        points: *const u8,
+        points_len: usize,
+        proof: *const u8,
+        proof_len: usize,
+        valid_out: *mut u8,
+    ) -> u32 {
+        let res = (*setup).verify(
+            bytes(domain, domain_len),
+            bytes(commits, commits_len),
+            bytes(evals, evals_len),
+            bytes(points, points_len),
+            bytes(proof, proof_len),
+        );
+        status(res, |v| *valid_out = v as u8)
+    }
+
+    unsafe fn bytes<'a>(data: *const u8, len: usize) -> &'a [u8] {
+        if len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(data, len)
+        }
+    }
+
+    unsafe fn write(data: &[u8], out: *mut u8) {
+        slice::from_raw_parts_mut(out, data.len()).copy_from_slice(data);
+    }
+
+    fn status<T>(res: Result<T, Error>, ok: impl FnOnce(T)) -> u32 {
+        match res {
+            Ok(v) => {
+                ok(v);
+                0
+            }
+            Err(e) => e.code(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::vec::Vec;
+
+    use super::*;
+    use crate::test_rng;
+
+    fn to_bytes(items: &[impl CanonicalSerialize]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for i in items {
+            i.serialize_compressed(&mut out).unwrap();
+        }
+        out
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// A setup, two polynomials opened at four points, and everything derived from them, as
+    /// bytes
+    struct Fixture {
+        setup: Vec<u8>,
+        polys: Vec<u8>,
+        points: Vec<u8>,
+        evals: Vec<u8>,
+        commits: Vec<u8>,
+    }
+
+    fn fixture() -> Fixture {
+        // Seeded, so the fixture is the same on every run
+        let rng = &mut ark_std::test_rng();
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 4, rng);
+        let points = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(40, rng))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .flat_map(|p| points.iter().map(|x| p.evaluate(x)))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).unwrap().0)
+            .collect::<Vec<_>>();
+        let mut setup = Vec::new();
+        s.serialize_compressed(&mut setup).unwrap();
+        Fixture {
+            setup,
+            polys: to_bytes(
+                &polys
+                    .iter()
+                    .flat_map(|p| p.coeffs.clone())
+                    .collect::<Vec<_>>(),
+            ),
+            points: to_bytes(&points),
+            evals: to_bytes(&evals),
+            commits: to_bytes(&commits),
+        }
+    }
+
+    #[test]
+    fn test_exports() {
+        use super::exports::*;
+        use core::ptr;
+
+        // Copy each input into a buffer from `pmp_alloc`, as the JS wrapper does
+        let alloc = |data: &[u8]| {
+            let ptr = pmp_alloc(data.len());
+            unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len()) };
+            (ptr, data.len())
+        };
+        let f = fixture();
+        let mut setup = ptr::null_mut();
+        let (sp, sl) = alloc(&f.setup);
+        assert_eq!(0, unsafe { pmp_setup_load(sp, sl, &mut setup) });
+
+        let [(d, dl), (c, cl), (e, el), (p, pl), (z, zl)] = [
+            b"test".as_slice(),
+            &f.commits,
+            &f.evals,
+            &f.polys,
+            &f.points,
+        ]
+        .map(alloc);
+        let proof = pmp_alloc(G1_BYTES);
+        assert_eq!(0, unsafe {
+            pmp_open(setup, d, dl, e, el, p, pl, 2, z, zl, proof)
+        });
+        let mut valid = 0;
+        assert_eq!(0, unsafe {
+            pmp_verify(
+                setup, d, dl, c, cl, e, el, z, zl, proof, G1_BYTES, &mut valid,
+            )
+        });
+        assert_eq!(1, valid);
+        assert_eq!(0, unsafe {
+            pmp_verify(
+                setup, d, 1, c, cl, e, el, z, zl, proof, G1_BYTES, &mut valid,
+            )
+        });
+        assert_eq!(0, valid);
+
+        // The first polynomial's commitment, and the error code of a buffer of partial scalars
+        let commit = pmp_alloc(G1_BYTES);
+        assert_eq!(0, unsafe { pmp_commit(setup, p, pl / 2, commit) });
+        let commit_bytes = unsafe { core::slice::from_raw_parts(commit, G1_BYTES) };
+        assert_eq!(&f.commits[..G1_BYTES], commit_bytes);
+        assert_eq!(Error::SerializationError.code(), unsafe {
+            pmp_commit(setup, p, 5, commit)
+        });
+
+        unsafe {
+            pmp_setup_free(setup);
+            for (ptr, len) in [(sp, sl), (d, dl), (c, cl), (e, el), (p, pl), (z, zl)] {
+                pmp_free(ptr, len);
+            }
+            pmp_free(proof, G1_BYTES);
+            pmp_free(commit, G1_BYTES);
+            // Empty buffers are allowed too
+            pmp_free(pmp_alloc(0), 0);
+        }
+    }
+
+    #[test]
+    fn test_js_fixture() {
+        // The fixture js/poly_multiproof.test.mjs checks the module against. The proof is
+        // deterministic, so the module must make the same one.
+        let f = fixture();
+        let wasm = WasmM1::load(&f.setup).unwrap();
+        let proof = wasm
+            .open(b"test", &f.evals, &f.polys, 2, &f.points)
+            .unwrap();
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "setup": hex(&f.setup),
+            "polys": hex(&f.polys),
+            "n_polys": 2,
+            "points": hex(&f.points),
+            "evals": hex(&f.evals),
+            "commits": hex(&f.commits),
+            "domain": "test",
+            "proof": hex(&proof),
+        }))
+        .unwrap();
+        assert_eq!(include_str!("../js/fixture.json").trim_end(), json);
+    }
+
+    #[test]
+    fn test_open_verify() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        let mut setup = Vec::new();
+        s.serialize_compressed(&mut setup).unwrap();
+        let wasm = WasmM1::load(&setup).unwrap();
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let (coeffs, evals, commits) = crate::testing::gen_openings(&s, 2, &points);
+        let points = to_bytes(&points);
+        let evals = to_bytes(&evals.concat());
+        let polys = to_bytes(&coeffs.concat());
+        let commits = to_bytes(&commits.iter().map(|c| c.0).collect::<Vec<_>>());
+        for (c, expected) in coeffs.iter().zip(commits.chunks(G1_BYTES)) {
+            assert_eq!(expected, wasm.commit(&to_bytes(c)).unwrap());
+        }
+
+        let proof = wasm.open(b"test", &evals, &polys, 2, &points).unwrap();
+        assert_eq!(G1_BYTES, proof.len());
+        let verify =
+            |domain: &[u8], commits: &[u8]| wasm.verify(domain, commits, &evals, &points, &proof);
+        assert_eq!(Ok(true), verify(b"test", &commits));
+        assert_eq!(Ok(false), verify(b"other", &commits));
+        let swapped = [&commits[G1_BYTES..], &commits[..G1_BYTES]].concat();
+        assert_eq!(Ok(false), verify(b"test", &swapped));
+
+        // Buffers which aren't whole scalars or points, and rows of different lengths
+        assert_eq!(
+            Err(Error::SerializationError),
+            wasm.commit(&polys[1..]).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SerializationError),
+            verify(b"test", &commits[1..]).map(|_| ())
+        );
+        let uneven = Err(Error::InvalidInputLength {
+            expected: 9,
+            got: 10,
+        });
+        let ten = to_bytes(&[Fr::from(1u64); 10]);
+        assert_eq!(
+            uneven,
+            wasm.open(b"test", &ten, &polys, 3, &points).map(|_| ())
+        );
+        assert!(WasmM1::load(&setup[1..]).is_err());
+    }
+}